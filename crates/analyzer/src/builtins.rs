@@ -70,8 +70,11 @@ pub enum Intrinsic {
     __mload,          // (p)
     __mstore,         // (p, v) -> ()
     __mstore8,        // (p, v) -> ()
+    __mcopy,          // (t, f, s) -> ()
     __sload,          // (p)
     __sstore,         // (p, v) -> ()
+    __tload,          // (p)
+    __tstore,         // (p, v) -> ()
     __msize,          // ()
     __gas,            // ()
     __address,        // ()
@@ -125,16 +128,16 @@ impl Intrinsic {
             | __caller | __callvalue | __calldatasize | __codesize | __returndatasize
             | __invalid | __chainid => 0,
 
-            __not | __iszero | __pop | __mload | __balance | __sload | __calldataload
+            __not | __iszero | __pop | __mload | __balance | __sload | __tload | __calldataload
             | __extcodesize | __extcodehash | __selfdestruct | __blockhash => 1,
 
             __add | __sub | __mul | __div | __sdiv | __mod | __smod | __exp | __lt | __gt
             | __slt | __sgt | __eq | __and | __or | __xor | __byte | __shl | __shr | __sar
-            | __signextend | __keccak256 | __mstore | __mstore8 | __sstore | __return
-            | __revert | __log0 => 2,
+            | __signextend | __keccak256 | __mstore | __mstore8 | __sstore | __tstore
+            | __return | __revert | __log0 => 2,
 
-            __addmod | __mulmod | __calldatacopy | __codecopy | __returndatacopy | __create
-            | __log1 => 3,
+            __addmod | __mulmod | __mcopy | __calldatacopy | __codecopy | __returndatacopy
+            | __create | __log1 => 3,
             __extcodecopy | __create2 | __log2 => 4,
             __log3 => 5,
             __delegatecall | __staticcall | __log4 => 6,
@@ -145,9 +148,10 @@ impl Intrinsic {
     pub fn return_type(&self) -> Base {
         use Intrinsic::*;
         match self {
-            __stop | __pop | __mstore | __mstore8 | __sstore | __calldatacopy | __codecopy
-            | __extcodecopy | __returndatacopy | __return | __revert | __selfdestruct
-            | __invalid | __log0 | __log1 | __log2 | __log3 | __log4 => Base::Unit,
+            __stop | __pop | __mstore | __mstore8 | __mcopy | __sstore | __tstore
+            | __calldatacopy | __codecopy | __extcodecopy | __returndatacopy | __return
+            | __revert | __selfdestruct | __invalid | __log0 | __log1 | __log2 | __log3
+            | __log4 => Base::Unit,
             _ => Base::u256(),
         }
     }
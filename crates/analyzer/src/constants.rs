@@ -1,4 +1,8 @@
 pub const EMITTABLE_TRAIT_NAME: &str = "Emittable";
 pub const EMIT_FN_NAME: &str = "emit";
+pub const ALLOW: &str = "allow";
 pub const INDEXED: &str = "indexed";
+pub const INLINE: &str = "inline";
+pub const NOINLINE: &str = "noinline";
+pub const TEST: &str = "test";
 pub const MAX_INDEXED_EVENT_FIELDS: usize = 3;
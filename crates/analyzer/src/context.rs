@@ -18,8 +18,8 @@ use crate::{
     errors::{self, IncompleteItem, TypeError},
     namespace::items::ModuleId,
 };
-use fe_common::diagnostics::Diagnostic;
 pub use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{Diagnostic, ErrorCode};
 use fe_common::Span;
 use fe_parser::ast;
 use fe_parser::node::{Node, NodeId};
@@ -62,8 +62,14 @@ pub trait AnalyzerContext {
     fn add_diagnostic(&self, diag: Diagnostic);
     fn db(&self) -> &dyn AnalyzerDb;
 
-    fn error(&self, message: &str, label_span: Span, label: &str) -> DiagnosticVoucher {
-        self.register_diag(errors::error(message, label_span, label))
+    fn error(
+        &self,
+        code: ErrorCode,
+        message: &str,
+        label_span: Span,
+        label: &str,
+    ) -> DiagnosticVoucher {
+        self.register_diag(errors::error(code, message, label_span, label))
     }
 
     /// Attribute contextual information to an expression node.
@@ -183,11 +189,12 @@ pub trait AnalyzerContext {
 
     fn fancy_error(
         &self,
+        code: ErrorCode,
         message: &str,
         labels: Vec<Label>,
         notes: Vec<String>,
     ) -> DiagnosticVoucher {
-        self.register_diag(errors::fancy_error(message, labels, notes))
+        self.register_diag(errors::fancy_error(code, message, labels, notes))
     }
 
     fn duplicate_name_error(
@@ -1,3 +1,4 @@
+use crate::constants::ALLOW;
 use crate::context::AnalyzerContext;
 use crate::db::{Analysis, AnalyzerDb};
 use crate::errors;
@@ -8,7 +9,7 @@ use crate::namespace::items::{
 use crate::namespace::scopes::ItemScope;
 use crate::namespace::types::{self, Type};
 use crate::traversal::types::type_desc;
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_parser::ast;
 use indexmap::map::{Entry, IndexMap};
 use smol_str::SmolStr;
@@ -60,6 +61,7 @@ pub fn contract_function_map(
         if let Ok(ret_ty) = func_sig.signature(db).return_type {
             if func.is_public(db) && !ret_ty.is_encodable(db).unwrap_or(false) {
                 scope.fancy_error(
+                    ErrorCode::InvalidContract,
                     "can't return unencodable type from public contract function",
                     vec![Label::primary(
                         func_sig
@@ -80,6 +82,7 @@ pub fn contract_function_map(
             if let Ok(param_ty) = param.typ {
                 if func.is_public(db) && !param_ty.is_encodable(db).unwrap_or(false) {
                     scope.fancy_error(
+                        ErrorCode::InvalidContract,
                         "can't use unencodable type as a public contract function argument",
                         vec![Label::primary(
                             func_sig.data(db).ast.kind.args[i].kind.typ_span().unwrap(),
@@ -150,6 +153,7 @@ pub fn contract_init_function(
             labels.push(Label::secondary(dupe_span, "`__init__` redefined here"));
         }
         diagnostics.push(errors::fancy_error(
+            ErrorCode::DuplicateDefinition,
             format!(
                 "`fn __init__()` is defined multiple times in `contract {}`",
                 contract.name(db),
@@ -164,6 +168,7 @@ pub fn contract_init_function(
         // Return type is checked in `queries::functions::function_signature`.
         if !id.is_public(db) {
             diagnostics.push(errors::fancy_error(
+                ErrorCode::InvalidContract,
                 "`__init__` function is not public",
                 vec![Label::primary(span, "`__init__` function must be public")],
                 vec![
@@ -202,6 +207,7 @@ pub fn contract_call_function(
             labels.push(Label::secondary(dupe_span, "`__call__` redefined here"));
         }
         diagnostics.push(errors::fancy_error(
+            ErrorCode::DuplicateDefinition,
             format!(
                 "`fn __call__()` is defined multiple times in `contract {}`",
                 contract.name(db),
@@ -216,6 +222,7 @@ pub fn contract_call_function(
         // Return type is checked in `queries::functions::function_signature`.
         if !id.is_public(db) {
             diagnostics.push(errors::fancy_error(
+                ErrorCode::InvalidContract,
                 "`__call__` function is not public",
                 vec![Label::primary(span, "`__call__` function must be public")],
                 vec![
@@ -231,6 +238,7 @@ pub fn contract_call_function(
             let name = func.name(db);
             if func.is_public(db) && name != "__init__" && name != "__call__" {
                 diagnostics.push(errors::fancy_error(
+                    ErrorCode::InvalidContract,
                     "`pub` not allowed if `__call__` is defined",
                     vec![
                         Label::primary(func.name_span(db), format!("`{name}` can't be public")),
@@ -279,6 +287,17 @@ pub fn contract_field_map(
     for field in db.contract_all_fields(contract).iter() {
         let node = &field.data(db).ast;
 
+        for attribute in node.kind.attributes.iter() {
+            if attribute.kind.name.kind != ALLOW {
+                scope.error(
+                    ErrorCode::InvalidContract,
+                    "Invalid attribute",
+                    attribute.span,
+                    "illegal name. Only `allow` is supported.",
+                );
+            }
+        }
+
         match map.entry(node.name().into()) {
             Entry::Occupied(entry) => {
                 scope.duplicate_name_error(
@@ -1,3 +1,4 @@
+use fe_common::diagnostics::ErrorCode;
 use std::{rc::Rc, str::FromStr};
 
 use fe_parser::ast;
@@ -84,6 +85,7 @@ pub fn enum_variant_kind(
                     |ast_ty| match type_desc(&mut scope, ast_ty, self_ty.clone()) {
                         Ok(ty) if ty.has_fixed_size(db) => Ok(ty),
                         Ok(_) => Err(TypeError::new(scope.error(
+                            ErrorCode::InvalidType,
                             "enum variant type must have a fixed size",
                             variant_data.ast.span,
                             "this can't be used as an struct field",
@@ -142,6 +144,7 @@ pub fn enum_function_map(
 
         if builtins::ValueMethod::from_str(def_name).is_ok() {
             scope.error(
+                ErrorCode::NameConflict,
                 &format!("function name `{def_name}` conflicts with built-in function"),
                 func.name_span(db),
                 &format!("`{def_name}` is a built-in function"),
@@ -229,6 +232,7 @@ pub fn enum_cycle(
     let enum_name = enum_.name(db);
     let enum_span = enum_.span(db);
     scope.error(
+        ErrorCode::InvalidType,
         &format!("recursive enum `{enum_name}`"),
         enum_span,
         &format!("enum `{enum_name}` has infinite size due to recursive definition",),
@@ -1,3 +1,4 @@
+use crate::constants::{ALLOW, INLINE, NOINLINE, TEST};
 use crate::context::{AnalyzerContext, CallType, FunctionBody};
 use crate::db::{Analysis, AnalyzerDb};
 use crate::display::Displayable;
@@ -9,7 +10,7 @@ use crate::namespace::scopes::{BlockScope, BlockScopeType, FunctionScope, ItemSc
 use crate::namespace::types::{self, CtxDecl, Generic, SelfDecl, Type, TypeId};
 use crate::traversal::functions::traverse_statements;
 use crate::traversal::types::{type_desc, type_desc_to_trait};
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_parser::ast::{self, GenericParameter};
 use fe_parser::node::Node;
 use if_chain::if_chain;
@@ -54,6 +55,7 @@ pub fn function_signature(
 
     if !matches!(fn_parent, Item::Type(TypeDef::Struct(_))) && function.is_generic(db) {
         scope.fancy_error(
+            ErrorCode::NotYetImplemented,
             "generic function parameters aren't yet supported outside of struct functions",
             vec![Label::primary(
                 function.data(db).ast.kind.generic_params.span,
@@ -67,6 +69,7 @@ pub fn function_signature(
         for param in function.data(db).ast.kind.generic_params.kind.iter() {
             if let GenericParameter::Unbounded(val) = param {
                 scope.fancy_error(
+                    ErrorCode::NotYetImplemented,
                     "unbounded generic parameters aren't yet supported",
                     vec![Label::primary(
                         val.span,
@@ -90,6 +93,7 @@ pub fn function_signature(
             ast::FunctionArg::Self_ { mut_ }=> {
                 if matches!(fn_parent, Item::Module(_)) {
                     scope.error(
+                        ErrorCode::InvalidFunction,
                         "`self` can only be used in contract, struct, trait or impl functions",
                         arg.span,
                         "not allowed in functions defined directly in a module",
@@ -98,6 +102,7 @@ pub fn function_signature(
                     self_decl = Some(SelfDecl { span: arg.span, mut_: *mut_ });
                     if index != 0 {
                         scope.error(
+                            ErrorCode::InvalidFunction,
                             "`self` is not the first parameter",
                             arg.span,
                             "`self` may only be used as the first parameter",
@@ -112,6 +117,7 @@ pub fn function_signature(
                         if let Some(mut_span) = mut_ {
                             if typ.is_primitive(db) {
                                 Err(TypeError::new(scope.error(
+                                    ErrorCode::InvalidFunction,
                                     "primitive type function parameters cannot be `mut`",
                                     *mut_span + typedesc.span,
                                     &format!("`{}` type can't be used as a `mut` function parameter",
@@ -124,6 +130,7 @@ pub fn function_signature(
                         }
                     }
                     _ => Err(TypeError::new(scope.error(
+                        ErrorCode::InvalidType,
                         "function parameter types must have fixed size",
                         typedesc.span,
                         &format!("`{}` type can't be used as a function parameter", typ.display(db)),
@@ -133,6 +140,7 @@ pub fn function_signature(
                 if let Some(context_type) = scope.get_context_type() {
                     if arg.name() == "ctx" &&  typ.as_ref().map(|val| val.deref(db)) != Ok(context_type) {
                         scope.error(
+                            ErrorCode::NameConflict,
                             "`ctx` is reserved for instances of `Context`",
                             arg.span,
                             "`ctx` must be an instance of `Context`",
@@ -142,18 +150,21 @@ pub fn function_signature(
                     if typ.as_ref().map(|val| val.deref(db)) == Ok(context_type) {
                         if arg.name() != "ctx" {
                             scope.error(
+                                ErrorCode::InvalidFunction,
                                 "invalid `Context` instance name",
                                 arg.span,
                                 "instances of `Context` must be named `ctx`",
                             );
                         } else if self_decl.is_some() && index != 1 {
                             scope.error(
+                                ErrorCode::InvalidFunction,
                                 "invalid parameter order",
                                 arg.span,
                                 "`ctx: Context` must be placed after the `self` parameter",
                             );
                         } else if self_decl.is_none() && index != 0 {
                             scope.error(
+                                ErrorCode::InvalidFunction,
                                 "invalid parameter order",
                                 arg.span,
                                 "`ctx: Context` must be the first parameter",
@@ -172,6 +183,7 @@ pub fn function_signature(
                         then {
                             let dup_arg: &Node<ast::FunctionArg> = &def.kind.args[*dup_idx];
                             scope.fancy_error(
+                                ErrorCode::DuplicateDefinition,
                                 &format!("duplicate parameter labels in function `{}`", def.kind.name.kind),
                                 vec![
                                     Label::primary(dup_arg.span, "the label `{}` was first used here"),
@@ -225,6 +237,7 @@ pub fn function_signature(
                 // `__init__` and `__call__` must not return any type other than `()`.
                 if type_node.kind != ast::TypeDesc::Unit {
                     scope.fancy_error(
+                        ErrorCode::MismatchedTypes,
                         &format!("`{fn_name}` function has incorrect return type"),
                         vec![Label::primary(type_node.span, "return type should be `()`")],
                         vec![
@@ -243,6 +256,7 @@ pub fn function_signature(
                 match type_desc(&mut scope, type_node, self_ty)? {
                     typ if typ.has_fixed_size(scope.db()) => Ok(typ),
                     _ => Err(TypeError::new(scope.error(
+                        ErrorCode::InvalidType,
                         "function return type must have a fixed size",
                         type_node.span,
                         "this can't be returned from a function",
@@ -309,6 +323,7 @@ pub fn function_body(db: &dyn AnalyzerDb, function: FunctionId) -> Analysis<Rc<F
     if let Ok(return_type) = &function.signature(db).return_type {
         if !return_type.typ(db).is_unit() && !all_paths_return_or_revert(&def.body) {
             scope.fancy_error(
+                ErrorCode::MissingReturn,
                 "function body is missing a return or revert statement",
                 vec![
                     Label::primary(
@@ -325,6 +340,29 @@ pub fn function_body(db: &dyn AnalyzerDb, function: FunctionId) -> Analysis<Rc<F
         }
     }
 
+    if function.is_inline(db) && function.is_noinline(db) {
+        scope.fancy_error(
+            ErrorCode::InvalidFunction,
+            "conflicting inline attributes",
+            vec![Label::primary(
+                function.name_span(db),
+                "this function is marked both `#inline` and `#noinline`",
+            )],
+            vec!["Hint: remove one of the attributes".into()],
+        );
+    }
+
+    for attribute in function.attributes(db) {
+        if ![INLINE, NOINLINE, TEST, ALLOW].contains(&attribute.kind.name.kind.as_str()) {
+            scope.error(
+                ErrorCode::InvalidFunction,
+                "Invalid attribute",
+                attribute.span,
+                "illegal name. Only `inline`, `noinline`, `test` and `allow` supported.",
+            );
+        }
+    }
+
     let mut block_scope = BlockScope::new(
         &scope,
         if function.is_unsafe(db) {
@@ -10,7 +10,7 @@ use crate::namespace::scopes::ItemScope;
 use crate::namespace::types::{self, TypeId};
 use crate::traversal::{const_expr, expressions, types::type_desc};
 use crate::AnalyzerDb;
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_common::files::Utf8Path;
 use fe_common::Span;
 use fe_parser::{ast, node::Node};
@@ -177,6 +177,7 @@ pub fn module_item_map(
             if function.is_test(db) {
                 if !sig_ast.generic_params.kind.is_empty() {
                     diagnostics.push(errors::fancy_error(
+                        ErrorCode::InvalidFunction,
                         "generic parameters are not supported on test functions",
                         vec![Label::primary(
                             sig_ast.generic_params.span,
@@ -189,6 +190,7 @@ pub fn module_item_map(
                 if let Some(arg) = sig_ast.args.first() {
                     if arg.name() != "ctx" {
                         diagnostics.push(errors::fancy_error(
+                            ErrorCode::InvalidFunction,
                             "function parameters other than `ctx` are not supported on test functions",
                             vec![Label::primary(arg.span, "invalid function parameter")],
                             vec!["Hint: remove the parameter".into()],
@@ -199,6 +201,7 @@ pub fn module_item_map(
                 for arg in sig_ast.args.iter().skip(1) {
                     if arg.name() != "ctx" {
                         diagnostics.push(errors::fancy_error(
+                            ErrorCode::InvalidFunction,
                             "function parameters other than `ctx` are not supported on test functions",
                             vec![Label::primary(arg.span, "invalid function parameter")],
                             vec!["Hint: remove the parameter".into()],
@@ -213,6 +216,7 @@ pub fn module_item_map(
             let kind = item.item_kind_display_name();
             let other_kind = global_item.item_kind_display_name();
             diagnostics.push(errors::error(
+                ErrorCode::NameConflict,
                 format!("{kind} name conflicts with the {other_kind} named \"{item_name}\""),
                 item.name_span(db)
                     .expect("user defined item is missing a name span"),
@@ -250,6 +254,7 @@ pub fn module_item_map(
                     ));
                 } else {
                     diagnostics.push(errors::fancy_error(
+                        ErrorCode::DuplicateDefinition,
                         format!(
                             "a {} named \"{}\" has already been defined",
                             entry.get().item_kind_display_name(),
@@ -372,6 +377,7 @@ pub fn module_constant_type(
     match &typ {
         Ok(typ) if !typ.is_primitive(db) => {
             scope.error(
+                ErrorCode::NotYetImplemented,
                 "Non-primitive types not yet supported for constants",
                 constant.data(db).ast.kind.typ.span,
                 &format!(
@@ -407,6 +413,7 @@ pub fn module_constant_type_cycle(
 ) -> Analysis<Result<TypeId, TypeError>> {
     let context = ItemScope::new(db, constant.data(db).module);
     let err = Err(TypeError::new(context.error(
+        ErrorCode::ConstEval,
         "recursive constant value definition",
         constant.data(db).ast.span,
         "",
@@ -467,6 +474,7 @@ pub fn module_constant_value_cycle(
 ) -> Analysis<Result<Constant, ConstEvalError>> {
     let context = ItemScope::new(db, constant.data(db).module);
     let err = Err(ConstEvalError::new(context.error(
+        ErrorCode::ConstEval,
         "recursive constant value definition",
         constant.data(db).ast.span,
         "",
@@ -498,6 +506,7 @@ pub fn module_used_item_map(
                 for (name, (name_span, item)) in items.value.iter() {
                     if !item.is_public(db) {
                         diagnostics.push(errors::error(
+                            ErrorCode::PrivateItem,
                             format!("{} {} is private", item.item_kind_display_name(), name,),
                             *name_span,
                             name.as_str(),
@@ -528,6 +537,7 @@ pub fn module_used_item_map(
                 let other_kind = global_item.item_kind_display_name();
 
                 diagnostics.push(errors::error(
+                    ErrorCode::NameConflict,
                     format!("import name conflicts with the {other_kind} named \"{name}\""),
                     name_span,
                     format!("`{name}` is already defined"),
@@ -663,6 +673,7 @@ fn resolve_use_tree(
                     .collect(),
                 Some(named_thing) => {
                     diagnostics.push(errors::error(
+                        ErrorCode::InvalidImport,
                         format!(
                             "cannot glob import from {}",
                             named_thing.item_kind_display_name()
@@ -708,6 +719,7 @@ fn resolve_use_tree(
                 }
                 Some(item) => {
                     diagnostics.push(errors::error(
+                        ErrorCode::InvalidImport,
                         format!("cannot glob import from {}", item.item_kind_display_name()),
                         prefix.segments.last().unwrap().span,
                         "prefix item must be a module",
@@ -735,6 +747,7 @@ fn resolve_use_tree(
                 }
                 Some(named_thing) => {
                     diagnostics.push(errors::error(
+                        ErrorCode::InvalidImport,
                         format!(
                             "cannot import non-item {}",
                             named_thing.item_kind_display_name(),
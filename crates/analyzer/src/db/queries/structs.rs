@@ -1,5 +1,5 @@
 use crate::builtins;
-use crate::constants::MAX_INDEXED_EVENT_FIELDS;
+use crate::constants::{ALLOW, INDEXED, MAX_INDEXED_EVENT_FIELDS};
 use crate::context::AnalyzerContext;
 use crate::db::Analysis;
 use crate::errors::TypeError;
@@ -11,6 +11,7 @@ use crate::namespace::scopes::ItemScope;
 use crate::namespace::types::{Type, TypeId};
 use crate::traversal::types::type_desc;
 use crate::AnalyzerDb;
+use fe_common::diagnostics::ErrorCode;
 use fe_common::utils::humanize::pluralize_conditionally;
 use fe_parser::{ast, Label};
 use indexmap::map::{Entry, IndexMap};
@@ -50,15 +51,15 @@ pub fn struct_field_map(
             indexed_count += 1;
         }
 
-        // Multiple attributes are currently still rejected by the parser so we only
-        // need to check the name here
-        if !field.attributes(db).is_empty() && !field.is_indexed(db) {
-            let span = field.data(db).ast.kind.attributes.first().unwrap().span;
-            scope.error(
-                "Invalid attribute",
-                span,
-                "illegal name. Only `indexed` supported.",
-            );
+        for attribute in node.kind.attributes.iter() {
+            if ![INDEXED, ALLOW].contains(&attribute.kind.name.kind.as_str()) {
+                scope.error(
+                    ErrorCode::InvalidContract,
+                    "Invalid attribute",
+                    attribute.span,
+                    "illegal name. Only `indexed` and `allow` are supported.",
+                );
+            }
         }
 
         match fields.entry(node.name().into()) {
@@ -87,6 +88,7 @@ pub fn struct_field_map(
         labels.last_mut().unwrap().message = format!("{indexed_count} indexed fields");
 
         scope.fancy_error(
+            ErrorCode::InvalidContract,
             &format!(
                 "more than three indexed fields in `event {}`",
                 struct_.name(db)
@@ -136,6 +138,7 @@ pub fn struct_field_type(
             }
             t if t.has_fixed_size(db) => Ok(typ),
             _ => Err(TypeError::new(scope.error(
+                ErrorCode::InvalidType,
                 "struct field type must have a fixed size",
                 field_data.ast.span,
                 "this can't be used as an struct field",
@@ -192,6 +195,7 @@ pub fn struct_function_map(
 
         if builtins::ValueMethod::from_str(def_name).is_ok() {
             scope.error(
+                ErrorCode::NameConflict,
                 &format!("function name `{def_name}` conflicts with built-in function"),
                 func.name_span(db),
                 &format!("`{def_name}` is a built-in function"),
@@ -264,6 +268,7 @@ pub fn struct_cycle(
     let scope = ItemScope::new(db, struct_.module(db));
     let struct_data = &struct_.data(db).ast;
     scope.error(
+        ErrorCode::InvalidType,
         &format!("recursive struct `{}`", struct_data.name()),
         struct_data.kind.name.span,
         &format!(
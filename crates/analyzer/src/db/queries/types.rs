@@ -1,3 +1,4 @@
+use fe_common::diagnostics::ErrorCode;
 use std::rc::Rc;
 
 use smol_str::SmolStr;
@@ -64,6 +65,7 @@ pub fn type_alias_type_cycle(
 ) -> Analysis<Result<types::TypeId, TypeError>> {
     let context = TempContext::default();
     let err = Err(TypeError::new(context.error(
+        ErrorCode::InvalidType,
         "recursive type definition",
         alias.data(db).ast.span,
         "",
@@ -1,7 +1,7 @@
 //! Semantic errors.
 
 use crate::context::{DiagnosticVoucher, NamedThing};
-use fe_common::diagnostics::{Diagnostic, Label, Severity};
+use fe_common::diagnostics::{Diagnostic, ErrorCode, Label, Severity};
 use fe_common::Span;
 use std::fmt::Display;
 
@@ -22,7 +22,7 @@ use std::fmt::Display;
 /// by calling an error function on an [`AnalyzerContext`](crate::context::AnalyzerContext).
 /// Please don't try to work around this restriction.
 ///
-/// Example: `TypeError::new(context.error("something is wrong", some_span, "this thing"))`
+/// Example: `TypeError::new(context.error(ErrorCode::InvalidType, "something is wrong", some_span, "this thing"))`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct TypeError(DiagnosticVoucher);
 impl TypeError {
@@ -178,17 +178,29 @@ impl From<IncompleteItem> for TypeError {
     }
 }
 
-pub fn error(message: impl Into<String>, label_span: Span, label: impl Into<String>) -> Diagnostic {
-    fancy_error(message, vec![Label::primary(label_span, label)], vec![])
+pub fn error(
+    code: ErrorCode,
+    message: impl Into<String>,
+    label_span: Span,
+    label: impl Into<String>,
+) -> Diagnostic {
+    fancy_error(
+        code,
+        message,
+        vec![Label::primary(label_span, label)],
+        vec![],
+    )
 }
 
 pub fn fancy_error(
+    code: ErrorCode,
     message: impl Into<String>,
     labels: Vec<Label>,
     notes: Vec<String>,
 ) -> Diagnostic {
     Diagnostic {
         severity: Severity::Error,
+        code: Some(code),
         message: message.into(),
         labels,
         notes,
@@ -202,6 +214,7 @@ pub fn type_error(
     actual: impl Display,
 ) -> Diagnostic {
     error(
+        ErrorCode::MismatchedTypes,
         message,
         span,
         format!("this has type `{actual}`; expected type `{expected}`"),
@@ -210,6 +223,7 @@ pub fn type_error(
 
 pub fn not_yet_implemented(feature: impl Display, span: Span) -> Diagnostic {
     error(
+        ErrorCode::NotYetImplemented,
         format!("feature not yet implemented: {feature}"),
         span,
         "not yet implemented",
@@ -223,6 +237,7 @@ pub fn duplicate_name_error(
     duplicate: Span,
 ) -> Diagnostic {
     fancy_error(
+        ErrorCode::DuplicateDefinition,
         message,
         vec![
             Label::primary(original, format!("`{name}` first defined here")),
@@ -241,6 +256,7 @@ pub fn name_conflict_error(
 ) -> Diagnostic {
     if let Some(original_span) = original_span {
         fancy_error(
+            ErrorCode::NameConflict,
             format!(
                 "{} name `{}` conflicts with previously defined {}",
                 name_kind,
@@ -255,6 +271,7 @@ pub fn name_conflict_error(
         )
     } else {
         fancy_error(
+            ErrorCode::NameConflict,
             format!(
                 "{} name `{}` conflicts with built-in {}",
                 name_kind,
@@ -276,6 +293,7 @@ pub fn name_conflict_error(
 
 pub fn to_mem_error(span: Span) -> Diagnostic {
     fancy_error(
+        ErrorCode::StorageValue,
         "value must be copied to memory",
         vec![Label::primary(span, "this value is in storage")],
         vec![
@@ -287,6 +305,7 @@ pub fn to_mem_error(span: Span) -> Diagnostic {
 }
 pub fn self_contract_type_error(span: Span, typ: &dyn Display) -> Diagnostic {
     fancy_error(
+        ErrorCode::MismatchedTypes,
         format!("`self` can't be used where a contract of type `{typ}` is expected",),
         vec![Label::primary(span, "cannot use `self` here")],
         vec![format!(
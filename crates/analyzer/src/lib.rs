@@ -10,6 +10,7 @@ pub mod context;
 pub mod db;
 pub mod display;
 pub mod errors;
+pub mod lints;
 pub mod namespace;
 
 mod operations;
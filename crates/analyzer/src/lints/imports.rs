@@ -0,0 +1,257 @@
+//! The `unused_imports` lint. An import is used if a name or path in the
+//! module resolves to the imported item through it, or if another module of
+//! the ingot imports the name from this module or refers to it by a path
+//! through this module.
+
+use super::{allows, module_functions, BodyVisitor, Lint};
+use crate::context::NamedThing;
+use crate::namespace::items::{ContractId, Item, ModuleId, TypeDef};
+use crate::AnalyzerDb;
+use fe_common::diagnostics::{Diagnostic, Label};
+use fe_common::Span;
+use fe_parser::ast::{self, UseTree, VariantKind};
+use fe_parser::node::Node;
+use smol_str::SmolStr;
+use std::collections::HashSet;
+
+/// Warns about the items of `use` statements that aren't referred to in the
+/// module, nor imported from it by another module of the ingot. Names that a
+/// glob imports aren't checked.
+pub(super) fn unused_imports(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let used_items = module.used_items(db);
+    if used_items.is_empty() {
+        return;
+    }
+
+    let mut used = HashSet::new();
+    for (path, contract) in item_refs(db, module) {
+        let name = &path[0];
+        let resolved = match contract {
+            Some(contract) => contract.resolve_name(db, name),
+            None => module.resolve_name(db, name),
+        };
+        if let (Ok(Some(NamedThing::Item(item))), Some((_, imported))) =
+            (resolved, used_items.get(name))
+        {
+            if item == *imported {
+                used.insert(name.clone());
+            }
+        }
+    }
+
+    for other in module.ingot(db).all_modules(db).iter() {
+        if *other == module {
+            continue;
+        }
+        for stmt in other.ast(db).body.iter() {
+            if let ast::ModuleStmt::Use(use_stmt) = stmt {
+                names_imported_from(db, module, *other, true, &use_stmt.kind.tree, &mut used);
+            }
+        }
+        for (path, _) in item_refs(db, *other) {
+            let first = match other.resolve_name(db, &path[0]) {
+                Ok(Some(NamedThing::Item(item))) => Some(item),
+                _ => None,
+            };
+            for idx in 1..path.len() {
+                if let Some(NamedThing::Item(Item::Module(prefix))) =
+                    resolve_segments(db, first, &path[1..idx])
+                {
+                    if prefix == module {
+                        used.insert(path[idx].clone());
+                    }
+                }
+            }
+        }
+    }
+
+    let skipped_spans = skipped_spans(db, module);
+    for (name, (span, _)) in used_items.iter() {
+        if used.contains(name) || skipped_spans.iter().any(|outer| contains(*outer, *span)) {
+            continue;
+        }
+
+        diagnostics.push(Lint::UnusedImports.warning(
+            format!("unused import `{name}`"),
+            vec![Label::primary(*span, "this is never used")],
+        ));
+    }
+}
+
+/// Returns the names and paths in the module that refer to items, along with
+/// the contract of the function they're in, if any, as contract functions
+/// shadow the items of the module.
+fn item_refs(db: &dyn AnalyzerDb, module: ModuleId) -> Vec<(Vec<SmolStr>, Option<ContractId>)> {
+    let signature_refs = |sig: &ast::FunctionSignature| {
+        let mut visitor = BodyVisitor::default();
+        visitor.scopes.push(vec![]);
+        visitor.visit_signature(sig);
+        visitor
+    };
+
+    let mut refs = vec![];
+    for function in module_functions(db, module) {
+        let data = function.data(db);
+        let mut visitor = signature_refs(&data.ast.kind.sig.kind);
+        visitor.visit_block(&data.ast.kind.body);
+
+        let contract = match function.parent(db) {
+            Item::Type(TypeDef::Contract(contract)) => Some(contract),
+            _ => None,
+        };
+        refs.extend(visitor.item_refs.into_iter().map(|path| (path, contract)));
+    }
+
+    let mut visitor = BodyVisitor::default();
+    let visit_fields = |visitor: &mut BodyVisitor, fields: &[Node<ast::Field>]| {
+        for field in fields {
+            visitor.visit_type(&field.kind.typ);
+            visitor.visit_opt_expr(&field.kind.value);
+        }
+    };
+    for stmt in module.ast(db).body.iter() {
+        match stmt {
+            ast::ModuleStmt::TypeAlias(alias) => visitor.visit_type(&alias.kind.typ),
+            ast::ModuleStmt::Constant(constant) => {
+                visitor.visit_type(&constant.kind.typ);
+                visitor.visit_expr(&constant.kind.value);
+            }
+            ast::ModuleStmt::Contract(contract) => {
+                visit_fields(&mut visitor, &contract.kind.fields)
+            }
+            ast::ModuleStmt::Struct(struct_) => visit_fields(&mut visitor, &struct_.kind.fields),
+            ast::ModuleStmt::Enum(enum_) => {
+                for variant in enum_.kind.variants.iter() {
+                    if let VariantKind::Tuple(types) = &variant.kind.kind {
+                        for typ in types {
+                            visitor.visit_type(typ)
+                        }
+                    }
+                }
+            }
+            ast::ModuleStmt::Trait(trait_) => {
+                for sig in trait_.kind.functions.iter() {
+                    visitor
+                        .item_refs
+                        .extend(signature_refs(&sig.kind).item_refs)
+                }
+            }
+            ast::ModuleStmt::Impl(impl_) => {
+                visitor
+                    .item_refs
+                    .push(vec![impl_.kind.impl_trait.kind.clone()]);
+                visitor.visit_type(&impl_.kind.receiver);
+            }
+            _ => {}
+        }
+    }
+    refs.extend(visitor.item_refs.into_iter().map(|path| (path, None)));
+    refs
+}
+
+/// Adds the names that `tree` imports from `module` to `names`. The paths of
+/// the tree are relative to `scope`, which is the module of the `use`
+/// statement if `top_level`, or the module of the prefix of a nested tree.
+fn names_imported_from(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+    scope: ModuleId,
+    top_level: bool,
+    tree: &Node<UseTree>,
+    names: &mut HashSet<SmolStr>,
+) {
+    // Mirrors `resolve_use_tree`: the first segment of a top-level path can't
+    // be an imported item.
+    let resolve_module = |segments: &[Node<SmolStr>]| -> Option<ModuleId> {
+        let (first, rest) = match segments.split_first() {
+            Some(split) => split,
+            None if top_level => return None,
+            None => return Some(scope),
+        };
+        let first = if top_level {
+            scope.non_used_internal_items(db).get(&first.kind).copied()
+        } else {
+            scope.items(db).get(&first.kind).copied()
+        };
+        let rest: Vec<_> = rest.iter().map(|segment| segment.kind.clone()).collect();
+        match resolve_segments(db, first, &rest) {
+            Some(NamedThing::Item(Item::Module(module))) => Some(module),
+            _ => None,
+        }
+    };
+
+    match &tree.kind {
+        UseTree::Glob { prefix } => {
+            if resolve_module(&prefix.segments) == Some(module) {
+                names.extend(module.used_items(db).keys().cloned())
+            }
+        }
+        UseTree::Nested { prefix, children } => {
+            if let Some(prefix_module) = resolve_module(&prefix.segments) {
+                for child in children {
+                    names_imported_from(db, module, prefix_module, false, child, names)
+                }
+            }
+        }
+        UseTree::Simple { path, .. } => {
+            if let Some((name, prefix)) = path.segments.split_last() {
+                if resolve_module(prefix) == Some(module) {
+                    names.insert(name.kind.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Resolves the segments after the first one, which resolved to `first`.
+fn resolve_segments(
+    db: &dyn AnalyzerDb,
+    first: Option<Item>,
+    rest: &[SmolStr],
+) -> Option<NamedThing> {
+    rest.iter()
+        .try_fold(NamedThing::Item(first?), |thing, segment| {
+            thing.resolve_path_segment(db, segment)
+        })
+}
+
+/// The spans of the `use` statements that `#allow(unused_imports)` is in
+/// front of, and of the glob imports.
+fn skipped_spans(db: &dyn AnalyzerDb, module: ModuleId) -> Vec<Span> {
+    let mut spans = vec![];
+    let mut attributes = vec![];
+    for stmt in module.ast(db).body.iter() {
+        match stmt {
+            ast::ModuleStmt::Attribute(attribute) => attributes.push(attribute.clone()),
+            ast::ModuleStmt::Use(use_stmt) => {
+                if allows(&attributes, Lint::UnusedImports) {
+                    spans.push(use_stmt.span);
+                }
+                glob_spans(&use_stmt.kind.tree, &mut spans);
+                attributes = vec![];
+            }
+            _ => attributes = vec![],
+        }
+    }
+    spans
+}
+
+fn glob_spans(tree: &Node<UseTree>, spans: &mut Vec<Span>) {
+    match &tree.kind {
+        UseTree::Glob { .. } => spans.push(tree.span),
+        UseTree::Nested { children, .. } => {
+            for child in children {
+                glob_spans(child, spans)
+            }
+        }
+        UseTree::Simple { .. } => {}
+    }
+}
+
+fn contains(outer: Span, inner: Span) -> bool {
+    outer.file_id == inner.file_id && outer.start <= inner.start && inner.end <= outer.end
+}
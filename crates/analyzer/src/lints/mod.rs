@@ -0,0 +1,628 @@
+//! Lints: warnings about code that is valid, but likely a mistake, such as an
+//! unused variable or a statement that can never run. Each lint can be turned
+//! off for an item by putting `#allow(lint_name)` in front of it.
+//!
+//! The lints that need the control flow of a function are implemented on the
+//! MIR, in `fe_mir::lints`.
+
+mod imports;
+mod security;
+
+use crate::constants::ALLOW;
+use crate::namespace::items::{ContractId, FunctionId, Item, ModuleId, TypeDef};
+use crate::AnalyzerDb;
+use fe_common::diagnostics::{Diagnostic, ErrorCode, Label, Severity};
+use fe_common::Span;
+use fe_parser::ast::{self, Expr, FuncStmt, GenericArg, Pattern, TypeDesc, VarDeclTarget};
+use fe_parser::node::Node;
+use smol_str::SmolStr;
+use std::collections::HashSet;
+use std::str::FromStr;
+use strum::{EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumString, IntoStaticStr, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum Lint {
+    /// A `let` binding that is never read.
+    UnusedVariables,
+    /// A `use` item that is never referred to.
+    UnusedImports,
+    /// A private function that is never called.
+    DeadCode,
+    /// Statements after a `return` or `revert`.
+    UnreachableCode,
+    /// A contract field that is never read.
+    UnreadFields,
+    /// Contract state that is written after a call that may reenter the
+    /// contract. Implemented in `fe_mir::lints`.
+    StateWriteAfterExternalCall,
+    /// The success flag of a raw call that is discarded.
+    UncheckedCallResult,
+    /// `tx_origin()` compared with an address, as for authorization.
+    TxOriginAuth,
+    /// An `ec_recover` result that isn't checked to be non-zero.
+    UncheckedEcRecover,
+    /// A product of a quotient, which loses the precision the division
+    /// truncated.
+    DivideBeforeMultiply,
+}
+
+impl Lint {
+    /// The name of the lint, as given to `#allow(..)`.
+    pub fn name(self) -> &'static str {
+        self.into()
+    }
+
+    /// The code of the warnings of this lint.
+    pub fn code(self) -> ErrorCode {
+        match self {
+            Lint::UnusedVariables => ErrorCode::UnusedVariable,
+            Lint::UnusedImports => ErrorCode::UnusedImport,
+            Lint::DeadCode => ErrorCode::DeadCode,
+            Lint::UnreachableCode => ErrorCode::UnreachableCode,
+            Lint::UnreadFields => ErrorCode::UnreadField,
+            Lint::StateWriteAfterExternalCall => ErrorCode::StateWriteAfterExternalCall,
+            Lint::UncheckedCallResult => ErrorCode::UncheckedCallResult,
+            Lint::TxOriginAuth => ErrorCode::TxOriginAuth,
+            Lint::UncheckedEcRecover => ErrorCode::UncheckedEcRecover,
+            Lint::DivideBeforeMultiply => ErrorCode::DivideBeforeMultiply,
+        }
+    }
+
+    /// Creates the warning of this lint.
+    pub fn warning(self, message: String, labels: Vec<Label>) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            code: Some(self.code()),
+            message,
+            labels,
+            notes: vec![format!(
+                "Note: `#allow({})` in front of the item turns this warning off",
+                self.name()
+            )],
+        }
+    }
+}
+
+/// Returns the lint names listed by an `allow(..)` attribute, or nothing if
+/// the attribute is another one.
+fn allowed_lint_names(attribute: &ast::Attribute) -> &[Node<SmolStr>] {
+    if attribute.name.kind == ALLOW {
+        &attribute.args
+    } else {
+        &[]
+    }
+}
+
+fn allows(attributes: &[Node<ast::Attribute>], lint: Lint) -> bool {
+    attributes.iter().any(|attribute| {
+        allowed_lint_names(&attribute.kind)
+            .iter()
+            .any(|name| name.kind == lint.name())
+    })
+}
+
+fn item_attributes(db: &dyn AnalyzerDb, item: Item) -> Vec<Node<ast::Attribute>> {
+    item.attributes(db)
+        .iter()
+        .map(|attribute| attribute.data(db).ast.clone())
+        .collect()
+}
+
+/// Returns `true` if the lint is turned off for the function, or for the
+/// contract, struct or enum it's defined in.
+pub fn is_allowed_in_function(db: &dyn AnalyzerDb, function: FunctionId, lint: Lint) -> bool {
+    allows(&function.attributes(db), lint)
+        || allows(&item_attributes(db, function.parent(db)), lint)
+}
+
+/// Returns the warnings of all lints for the module.
+pub fn module_lints(db: &dyn AnalyzerDb, module: ModuleId) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    unknown_lints(db, module, &mut diagnostics);
+    imports::unused_imports(db, module, &mut diagnostics);
+
+    let functions = module_functions(db, module);
+    for function in functions.iter() {
+        function_lints(db, *function, &mut diagnostics);
+    }
+    dead_code(db, &functions, &mut diagnostics);
+
+    for item in module.all_items(db).iter() {
+        if let Item::Type(TypeDef::Contract(contract)) = item {
+            unread_fields(db, *contract, &mut diagnostics);
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| {
+        diagnostic
+            .labels
+            .first()
+            .map(|label| label.span.start)
+            .unwrap_or_default()
+    });
+    diagnostics
+}
+
+/// All functions defined in the module, including the ones of its contracts,
+/// structs, enums and `impl` blocks.
+fn module_functions(db: &dyn AnalyzerDb, module: ModuleId) -> Vec<FunctionId> {
+    let mut functions = vec![];
+    for item in module.all_items(db).iter() {
+        match item {
+            Item::Function(function) => functions.push(*function),
+            Item::Type(TypeDef::Contract(id)) => functions.extend(id.all_functions(db).iter()),
+            Item::Type(TypeDef::Struct(id)) => functions.extend(id.all_functions(db).iter()),
+            Item::Type(TypeDef::Enum(id)) => functions.extend(id.all_functions(db).iter()),
+            _ => {}
+        }
+    }
+    for impl_ in module.all_impls(db).iter() {
+        functions.extend(impl_.all_functions(db).iter());
+    }
+    functions
+}
+
+fn unknown_lints(db: &dyn AnalyzerDb, module: ModuleId, diagnostics: &mut Vec<Diagnostic>) {
+    let mut attributes = vec![];
+    let function_attributes = |functions: &[Node<ast::Function>]| -> Vec<Node<ast::Attribute>> {
+        functions
+            .iter()
+            .flat_map(|function| function.kind.attributes.iter().cloned())
+            .collect()
+    };
+    for stmt in module.ast(db).body.iter() {
+        match stmt {
+            ast::ModuleStmt::Attribute(attribute) => attributes.push(attribute.clone()),
+            ast::ModuleStmt::Contract(contract) => {
+                for field in contract.kind.fields.iter() {
+                    attributes.extend(field.kind.attributes.iter().cloned());
+                }
+                for ast::ContractStmt::Function(function) in contract.kind.body.iter() {
+                    attributes.extend(function.kind.attributes.iter().cloned());
+                }
+            }
+            ast::ModuleStmt::Struct(struct_) => {
+                for field in struct_.kind.fields.iter() {
+                    attributes.extend(field.kind.attributes.iter().cloned());
+                }
+                attributes.extend(function_attributes(&struct_.kind.functions))
+            }
+            ast::ModuleStmt::Enum(enum_) => {
+                attributes.extend(function_attributes(&enum_.kind.functions))
+            }
+            ast::ModuleStmt::Impl(impl_) => {
+                attributes.extend(function_attributes(&impl_.kind.functions))
+            }
+            _ => {}
+        }
+    }
+
+    for attribute in attributes {
+        for name in allowed_lint_names(&attribute.kind) {
+            if Lint::from_str(&name.kind).is_err() {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    code: Some(ErrorCode::UnknownLint),
+                    message: format!("unknown lint `{}`", name.kind),
+                    labels: vec![Label::primary(name.span, "")],
+                    notes: vec![format!(
+                        "Note: the lints are {}",
+                        Lint::iter()
+                            .map(|lint| format!("`{}`", lint.name()))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )],
+                });
+            }
+        }
+    }
+}
+
+fn function_lints(db: &dyn AnalyzerDb, function: FunctionId, diagnostics: &mut Vec<Diagnostic>) {
+    let mut visitor = BodyVisitor::default();
+    visitor.visit_block(&function.data(db).ast.kind.body);
+
+    if !is_allowed_in_function(db, function, Lint::UnusedVariables) {
+        for (name, span, _) in visitor.variables.iter().filter(|(_, _, read)| !read) {
+            diagnostics.push(Lint::UnusedVariables.warning(
+                format!("unused variable `{name}`"),
+                vec![Label::primary(
+                    *span,
+                    format!("`{name}` is never read. Prefix it with `_` if that's intended"),
+                )],
+            ));
+        }
+    }
+
+    if !is_allowed_in_function(db, function, Lint::UnreachableCode) {
+        for (exit_span, unreachable_span) in visitor.unreachable.iter() {
+            diagnostics.push(Lint::UnreachableCode.warning(
+                "unreachable code".into(),
+                vec![
+                    Label::primary(*unreachable_span, "this code never runs"),
+                    Label::secondary(*exit_span, "because the function exits here"),
+                ],
+            ));
+        }
+    }
+
+    security::function_lints(db, function, diagnostics);
+}
+
+/// Warns about the private functions that no function of the module calls.
+fn dead_code(db: &dyn AnalyzerDb, functions: &[FunctionId], diagnostics: &mut Vec<Diagnostic>) {
+    let called: HashSet<_> = functions
+        .iter()
+        .flat_map(|function| {
+            function
+                .body(db)
+                .calls
+                .values()
+                .filter_map(|call| call.function())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    for function in functions {
+        if called.contains(function)
+            || function.is_public(db)
+            || function.is_test(db)
+            || function.is_constructor(db)
+            || function.name(db) == "__call__"
+            // The functions of an `impl` block implement the methods of its trait.
+            || matches!(function.parent(db), Item::Impl(_))
+            || is_allowed_in_function(db, *function, Lint::DeadCode)
+        {
+            continue;
+        }
+
+        diagnostics.push(Lint::DeadCode.warning(
+            format!("function `{}` is never used", function.name(db)),
+            vec![Label::primary(function.name_span(db), "")],
+        ));
+    }
+}
+
+/// Warns about the private fields of a contract that its functions only write
+/// to.
+fn unread_fields(db: &dyn AnalyzerDb, contract: ContractId, diagnostics: &mut Vec<Diagnostic>) {
+    let mut read_fields = HashSet::new();
+    for function in contract.all_functions(db).iter() {
+        let mut visitor = BodyVisitor::default();
+        visitor.visit_block(&function.data(db).ast.kind.body);
+        read_fields.extend(visitor.read_fields);
+    }
+
+    let contract_attributes = item_attributes(db, Item::Type(TypeDef::Contract(contract)));
+    for field in db.contract_all_fields(contract).iter() {
+        let ast = &field.data(db).ast.kind;
+        if ast.is_pub
+            || read_fields.contains(&ast.name.kind)
+            || allows(&ast.attributes, Lint::UnreadFields)
+            || allows(&contract_attributes, Lint::UnreadFields)
+        {
+            continue;
+        }
+
+        diagnostics.push(Lint::UnreadFields.warning(
+            format!("field `{}` is never read", ast.name.kind),
+            vec![Label::primary(
+                ast.name.span,
+                format!("no function of `{}` reads this field", contract.name(db)),
+            )],
+        ));
+    }
+}
+
+/// Walks a function body, keeping track of the variables in scope and which of
+/// them are read.
+#[derive(Default)]
+struct BodyVisitor {
+    /// The names in scope. Only `let` bindings have an index into
+    /// `variables`, as the other names aren't linted.
+    scopes: Vec<Vec<(SmolStr, Option<usize>)>>,
+    /// The `let` bindings and whether they are read.
+    variables: Vec<(SmolStr, Span, bool)>,
+    /// The contract fields that are read through `self`.
+    read_fields: HashSet<SmolStr>,
+    /// The segments of the names and paths that refer to items, i.e. the
+    /// names that no binding in scope shadows, the paths and the type names.
+    item_refs: Vec<Vec<SmolStr>>,
+    /// The span of each `return` or `revert` that's followed by statements,
+    /// along with the span of those statements.
+    unreachable: Vec<(Span, Span)>,
+}
+
+impl BodyVisitor {
+    fn visit_block(&mut self, stmts: &[Node<FuncStmt>]) {
+        self.scopes.push(vec![]);
+        for stmt in stmts {
+            self.visit_stmt(stmt);
+        }
+        self.scopes.pop();
+
+        let exit = stmts.iter().position(|stmt| {
+            matches!(stmt.kind, FuncStmt::Return { .. } | FuncStmt::Revert { .. })
+        });
+        if let Some(idx) = exit {
+            if let Some(last) = stmts.last().filter(|_| idx + 1 < stmts.len()) {
+                self.unreachable
+                    .push((stmts[idx].span, stmts[idx + 1].span + last.span));
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &SmolStr, span: Option<Span>) {
+        let idx = match span {
+            Some(span) if !name.starts_with('_') => {
+                self.variables.push((name.clone(), span, false));
+                Some(self.variables.len() - 1)
+            }
+            _ => None,
+        };
+        self.scopes
+            .last_mut()
+            .expect("no scope")
+            .push((name.clone(), idx));
+    }
+
+    fn read(&mut self, name: &str) {
+        let binding = self
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(other, _)| other == name);
+        match binding {
+            Some((_, Some(idx))) => self.variables[*idx].2 = true,
+            Some((_, None)) => {}
+            None => self.item_refs.push(vec![name.into()]),
+        }
+    }
+
+    fn refer_to_path(&mut self, path: &ast::Path) {
+        self.item_refs.push(
+            path.segments
+                .iter()
+                .map(|segment| segment.kind.clone())
+                .collect(),
+        )
+    }
+
+    /// Declares the parameters of a function and visits the types of its
+    /// signature.
+    fn visit_signature(&mut self, sig: &ast::FunctionSignature) {
+        for param in sig.generic_params.kind.iter() {
+            if let ast::GenericParameter::Bounded { bound, .. } = param {
+                self.visit_type(bound)
+            }
+        }
+        for arg in sig.args.iter() {
+            if let ast::FunctionArg::Regular { name, typ, .. } = &arg.kind {
+                self.visit_type(typ);
+                self.declare(&name.kind, None);
+            }
+        }
+        if let Some(return_type) = &sig.return_type {
+            self.visit_type(return_type)
+        }
+    }
+
+    fn visit_type(&mut self, typ: &Node<TypeDesc>) {
+        match &typ.kind {
+            TypeDesc::Base { base } => self.item_refs.push(vec![base.clone()]),
+            TypeDesc::Path(path) => self.refer_to_path(path),
+            TypeDesc::Tuple { items } => {
+                for item in items.iter() {
+                    self.visit_type(item)
+                }
+            }
+            TypeDesc::Generic { base, args } => {
+                self.item_refs.push(vec![base.kind.clone()]);
+                for arg in args.kind.iter() {
+                    self.visit_generic_arg(arg)
+                }
+            }
+            TypeDesc::Unit | TypeDesc::SelfType => {}
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Node<FuncStmt>) {
+        match &stmt.kind {
+            FuncStmt::Return { value } => self.visit_opt_expr(value),
+            FuncStmt::VarDecl {
+                target, typ, value, ..
+            } => {
+                self.visit_type(typ);
+                self.visit_opt_expr(value);
+                self.declare_target(target);
+            }
+            FuncStmt::ConstantDecl { name, typ, value } => {
+                self.visit_type(typ);
+                self.visit_expr(value);
+                self.declare(&name.kind, None);
+            }
+            FuncStmt::Assign { target, value } => {
+                self.visit_assign_target(target);
+                self.visit_expr(value);
+            }
+            FuncStmt::AugAssign { target, value, .. } => {
+                self.visit_expr(target);
+                self.visit_expr(value);
+            }
+            FuncStmt::For { target, iter, body } => {
+                self.visit_expr(iter);
+                self.scopes.push(vec![]);
+                self.declare(&target.kind, None);
+                self.visit_block(body);
+                self.scopes.pop();
+            }
+            FuncStmt::While { test, body } => {
+                self.visit_expr(test);
+                self.visit_block(body);
+            }
+            FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.visit_expr(test);
+                self.visit_block(body);
+                self.visit_block(or_else);
+            }
+            FuncStmt::Match { expr, arms } => {
+                self.visit_expr(expr);
+                for arm in arms {
+                    self.scopes.push(vec![]);
+                    self.declare_pattern(&arm.kind.pat);
+                    self.visit_block(&arm.kind.body);
+                    self.scopes.pop();
+                }
+            }
+            FuncStmt::Assert { test, msg } => {
+                self.visit_expr(test);
+                self.visit_opt_expr(msg);
+            }
+            FuncStmt::Expr { value } => self.visit_expr(value),
+            FuncStmt::Revert { error } => self.visit_opt_expr(error),
+            FuncStmt::Unsafe(body) => self.visit_block(body),
+            FuncStmt::Break | FuncStmt::Continue => {}
+        }
+    }
+
+    fn declare_target(&mut self, target: &Node<VarDeclTarget>) {
+        match &target.kind {
+            VarDeclTarget::Name(name) => self.declare(name, Some(target.span)),
+            VarDeclTarget::Tuple(elts) => {
+                for elt in elts {
+                    self.declare_target(elt)
+                }
+            }
+        }
+    }
+
+    /// Declares the bindings of a pattern. A name that resolves to an item,
+    /// such as an imported enum variant, is a reference to it rather than a
+    /// binding, so the names are also recorded as item references.
+    fn declare_pattern(&mut self, pattern: &Node<Pattern>) {
+        match &pattern.kind {
+            Pattern::Path(path) => {
+                self.refer_to_path(&path.kind);
+                if path.kind.segments.len() == 1 {
+                    self.declare(&path.kind.segments[0].kind, None)
+                }
+            }
+            Pattern::PathTuple(path, elts) => {
+                self.refer_to_path(&path.kind);
+                for elt in elts {
+                    self.declare_pattern(elt)
+                }
+            }
+            Pattern::Tuple(elts) | Pattern::Or(elts) => {
+                for elt in elts {
+                    self.declare_pattern(elt)
+                }
+            }
+            Pattern::PathStruct { path, fields, .. } => {
+                self.refer_to_path(&path.kind);
+                for (_, pattern) in fields {
+                    self.declare_pattern(pattern)
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Visits the target of an assignment. The variable or field that is
+    /// assigned to, or into, isn't read by it.
+    fn visit_assign_target(&mut self, target: &Node<Expr>) {
+        match &target.kind {
+            Expr::Name(_) => {}
+            Expr::Attribute { value, .. } if is_self(value) => {}
+            Expr::Attribute { value, .. } => self.visit_assign_target(value),
+            Expr::Subscript { value, index } => {
+                self.visit_assign_target(value);
+                self.visit_expr(index);
+            }
+            _ => self.visit_expr(target),
+        }
+    }
+
+    fn visit_opt_expr(&mut self, expr: &Option<Node<Expr>>) {
+        if let Some(expr) = expr {
+            self.visit_expr(expr)
+        }
+    }
+
+    fn visit_generic_arg(&mut self, arg: &GenericArg) {
+        match arg {
+            GenericArg::TypeDesc(typ) => self.visit_type(typ),
+            GenericArg::ConstExpr(expr) => self.visit_expr(expr),
+            GenericArg::Int(_) => {}
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Node<Expr>) {
+        match &expr.kind {
+            Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                self.visit_expr(if_expr);
+                self.visit_expr(test);
+                self.visit_expr(else_expr);
+            }
+            Expr::BoolOperation { left, right, .. }
+            | Expr::BinOperation { left, right, .. }
+            | Expr::CompOperation { left, right, .. } => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            }
+            Expr::UnaryOperation { operand, .. } => self.visit_expr(operand),
+            Expr::Attribute { value, attr } => {
+                if is_self(value) {
+                    self.read_fields.insert(attr.kind.clone());
+                } else {
+                    self.visit_expr(value);
+                }
+            }
+            Expr::Subscript { value, index } => {
+                self.visit_expr(value);
+                self.visit_expr(index);
+            }
+            Expr::Call {
+                func,
+                generic_args,
+                args,
+            } => {
+                self.visit_expr(func);
+                if let Some(generic_args) = generic_args {
+                    for arg in generic_args.kind.iter() {
+                        self.visit_generic_arg(arg);
+                    }
+                }
+                for arg in args.kind.iter() {
+                    self.visit_expr(&arg.kind.value);
+                }
+            }
+            Expr::List { elts } | Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.visit_expr(elt);
+                }
+            }
+            Expr::Repeat { value, len } => {
+                self.visit_expr(value);
+                self.visit_generic_arg(&len.kind);
+            }
+            Expr::Name(name) => self.read(name),
+            Expr::Path(path) => self.refer_to_path(path),
+            Expr::Bool(_) | Expr::Num(_) | Expr::Str(_) | Expr::Unit => {}
+        }
+    }
+}
+
+fn is_self(expr: &Node<Expr>) -> bool {
+    matches!(&expr.kind, Expr::Name(name) if name == "self")
+}
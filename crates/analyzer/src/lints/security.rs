@@ -0,0 +1,373 @@
+//! Lints for patterns that are known to cause vulnerabilities in contracts.
+//! They look up the functions that calls resolve to in the `calls` of the
+//! [`FunctionBody`](crate::context::FunctionBody).
+
+use super::{is_allowed_in_function, Lint};
+use crate::context::FunctionBody;
+use crate::namespace::items::{FunctionId, Item, TypeDef};
+use crate::AnalyzerDb;
+use fe_common::diagnostics::{Diagnostic, Label};
+use fe_parser::ast::{BinOperator, Expr, FuncStmt, GenericArg, VarDeclTarget};
+use fe_parser::node::Node;
+use smol_str::SmolStr;
+use std::collections::HashMap;
+
+/// The `std` functions that return whether a call to another account
+/// succeeded, as `(parent, name)`.
+const RAW_CALLS: &[(&str, &str)] = &[
+    ("Context", "raw_call"),
+    ("evm", "call"),
+    ("evm", "call_code"),
+    ("evm", "delegate_call"),
+    ("evm", "static_call"),
+    ("evm", "call_2"),
+    ("evm", "call_code_2"),
+    ("evm", "delegate_call_2"),
+    ("evm", "static_call_2"),
+];
+
+pub(super) fn function_lints(
+    db: &dyn AnalyzerDb,
+    function: FunctionId,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let body = function.body(db);
+    let stmts = &function.data(db).ast.kind.body;
+    let lints = FunctionLints {
+        db,
+        body: &body,
+        stmts,
+    };
+
+    let allowed = |lint| is_allowed_in_function(db, function, lint);
+    if !allowed(Lint::UncheckedCallResult) {
+        diagnostics.extend(lints.unchecked_call_results());
+    }
+    if !allowed(Lint::TxOriginAuth) {
+        diagnostics.extend(lints.tx_origin_auth());
+    }
+    if !allowed(Lint::UncheckedEcRecover) {
+        diagnostics.extend(lints.unchecked_ec_recover());
+    }
+    if !allowed(Lint::DivideBeforeMultiply) {
+        diagnostics.extend(lints.divide_before_multiply());
+    }
+}
+
+struct FunctionLints<'a> {
+    db: &'a dyn AnalyzerDb,
+    body: &'a FunctionBody,
+    stmts: &'a [Node<FuncStmt>],
+}
+
+impl<'a> FunctionLints<'a> {
+    /// Returns `true` if `expr` is a call of the `std` function `name`, which
+    /// is defined in the module or struct `parent`.
+    fn calls_std(&self, expr: &Node<Expr>, parent: &str, name: &str) -> bool {
+        let func = match &expr.kind {
+            Expr::Call { func, .. } => func,
+            _ => return false,
+        };
+        let function = match self
+            .body
+            .calls
+            .get(&func.id)
+            .and_then(|call| call.function())
+        {
+            Some(function) => function,
+            None => return false,
+        };
+        let db = self.db;
+        function.name(db) == name
+            && function.module(db).ingot(db).name(db) == "std"
+            && match function.parent(db) {
+                Item::Type(TypeDef::Struct(struct_)) => struct_.name(db) == parent,
+                Item::Module(module) => module.name(db) == parent,
+                _ => false,
+            }
+    }
+
+    /// Returns the names that `let` statements bind to expressions for which
+    /// `pred` holds, along with those expressions.
+    fn bound_names(&self, pred: impl Fn(&Node<Expr>) -> bool) -> HashMap<SmolStr, &'a Node<Expr>> {
+        let mut names = HashMap::new();
+        for_each_stmt(self.stmts, &mut |stmt| {
+            if let FuncStmt::VarDecl {
+                target,
+                value: Some(value),
+                ..
+            } = &stmt.kind
+            {
+                if let VarDeclTarget::Name(name) = &target.kind {
+                    if pred(value) {
+                        names.insert(name.clone(), value);
+                    }
+                }
+            }
+        });
+        names
+    }
+
+    fn unchecked_call_results(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for_each_stmt(self.stmts, &mut |stmt| {
+            if let FuncStmt::Expr { value } = &stmt.kind {
+                if let Some((_, name)) = RAW_CALLS
+                    .iter()
+                    .find(|(parent, name)| self.calls_std(value, parent, name))
+                {
+                    diagnostics.push(Lint::UncheckedCallResult.warning(
+                        format!("the result of `{name}` is not checked"),
+                        vec![Label::primary(
+                            value.span,
+                            "this returns `false` if the call fails",
+                        )],
+                    ));
+                }
+            }
+        });
+        diagnostics
+    }
+
+    fn tx_origin_auth(&self) -> Vec<Diagnostic> {
+        let is_origin = |expr: &Node<Expr>| self.calls_std(expr, "Context", "tx_origin");
+        let origins = self.bound_names(is_origin);
+
+        let mut diagnostics = vec![];
+        for_each_expr(self.stmts, &mut |expr| {
+            if let Expr::CompOperation { left, right, .. } = &expr.kind {
+                if [left, right].iter().any(|operand| {
+                    is_origin(operand)
+                        || matches!(&operand.kind, Expr::Name(name) if origins.contains_key(name))
+                }) {
+                    let mut diagnostic = Lint::TxOriginAuth.warning(
+                        "`tx_origin()` is used for authorization".into(),
+                        vec![Label::primary(
+                            expr.span,
+                            "this compares the origin of the transaction",
+                        )],
+                    );
+                    diagnostic.notes.insert(
+                        0,
+                        "Note: the origin is the account that started the transaction, which \
+                         may have been tricked into calling a malicious contract that calls \
+                         this one. Use `msg_sender()` instead."
+                            .into(),
+                    );
+                    diagnostics.push(diagnostic);
+                }
+            }
+        });
+        diagnostics
+    }
+
+    fn unchecked_ec_recover(&self) -> Vec<Diagnostic> {
+        let is_recover = |expr: &Node<Expr>| self.calls_std(expr, "precompiles", "ec_recover");
+        let signers = self.bound_names(is_recover);
+
+        let mut checked = vec![];
+        for_each_expr(self.stmts, &mut |expr| {
+            if let Expr::CompOperation { left, right, .. } = &expr.kind {
+                for (operand, other) in [(left, right), (right, left)] {
+                    if let Expr::Name(name) = &operand.kind {
+                        if is_zero_address(other) && signers.contains_key(name) {
+                            checked.push(name.clone());
+                        }
+                    }
+                }
+            }
+        });
+
+        let mut diagnostics = vec![];
+        for_each_expr(self.stmts, &mut |expr| {
+            if !is_recover(expr) {
+                return;
+            }
+            let is_checked = signers
+                .iter()
+                .any(|(name, value)| value.id == expr.id && checked.contains(name));
+            if !is_checked {
+                let mut diagnostic = Lint::UncheckedEcRecover.warning(
+                    "the result of `ec_recover` is not checked to be non-zero".into(),
+                    vec![Label::primary(
+                        expr.span,
+                        "this returns the zero address for an invalid signature",
+                    )],
+                );
+                diagnostic.notes.insert(
+                    0,
+                    "Hint: bind the result and reject the zero address, as in \
+                     `assert signer != address(0)`"
+                        .into(),
+                );
+                diagnostics.push(diagnostic);
+            }
+        });
+        diagnostics
+    }
+
+    fn divide_before_multiply(&self) -> Vec<Diagnostic> {
+        let is_quotient = |expr: &Node<Expr>| {
+            matches!(
+                &expr.kind,
+                Expr::BinOperation { op, .. } if op.kind == BinOperator::Div
+            )
+        };
+        let quotients = self.bound_names(is_quotient);
+
+        let mut diagnostics = vec![];
+        for_each_expr(self.stmts, &mut |expr| {
+            if let Expr::BinOperation { left, op, right } = &expr.kind {
+                if op.kind != BinOperator::Mult {
+                    return;
+                }
+                let quotient = [left, right].into_iter().find_map(|operand| {
+                    if is_quotient(operand) {
+                        Some(operand.span)
+                    } else if let Expr::Name(name) = &operand.kind {
+                        quotients.get(name).map(|value| value.span)
+                    } else {
+                        None
+                    }
+                });
+                if let Some(quotient) = quotient {
+                    let mut diagnostic = Lint::DivideBeforeMultiply.warning(
+                        "division before multiplication".into(),
+                        vec![
+                            Label::primary(quotient, "this division truncates its result"),
+                            Label::secondary(expr.span, "which is then multiplied"),
+                        ],
+                    );
+                    diagnostic.notes.insert(
+                        0,
+                        "Hint: multiply first, as in `a * c / b`, to keep the precision".into(),
+                    );
+                    diagnostics.push(diagnostic);
+                }
+            }
+        });
+        diagnostics
+    }
+}
+
+/// Returns `true` if `expr` is `address(0)`.
+fn is_zero_address(expr: &Node<Expr>) -> bool {
+    match &expr.kind {
+        Expr::Call { func, args, .. } => {
+            matches!(&func.kind, Expr::Name(name) if name == "address")
+                && matches!(
+                    args.kind.as_slice(),
+                    [arg] if matches!(&arg.kind.value.kind, Expr::Num(num) if is_zero(num))
+                )
+        }
+        _ => false,
+    }
+}
+
+fn is_zero(num: &str) -> bool {
+    let digits = ["0x", "0o", "0b"]
+        .iter()
+        .find_map(|prefix| num.strip_prefix(prefix))
+        .unwrap_or(num);
+    digits.chars().all(|c| c == '0' || c == '_')
+}
+
+/// Calls `f` with each statement in `stmts`, including the nested ones.
+fn for_each_stmt<'a>(stmts: &'a [Node<FuncStmt>], f: &mut dyn FnMut(&'a Node<FuncStmt>)) {
+    for stmt in stmts {
+        f(stmt);
+        match &stmt.kind {
+            FuncStmt::For { body, .. } | FuncStmt::While { body, .. } | FuncStmt::Unsafe(body) => {
+                for_each_stmt(body, f)
+            }
+            FuncStmt::If { body, or_else, .. } => {
+                for_each_stmt(body, f);
+                for_each_stmt(or_else, f);
+            }
+            FuncStmt::Match { arms, .. } => {
+                for arm in arms {
+                    for_each_stmt(&arm.kind.body, f)
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` with each expression in `stmts`, including the nested ones.
+fn for_each_expr<'a>(stmts: &'a [Node<FuncStmt>], f: &mut dyn FnMut(&'a Node<Expr>)) {
+    for_each_stmt(stmts, &mut |stmt| {
+        let exprs: Vec<&Node<Expr>> = match &stmt.kind {
+            FuncStmt::Return { value } => value.iter().collect(),
+            FuncStmt::VarDecl { value, .. } => value.iter().collect(),
+            FuncStmt::ConstantDecl { value, .. } => vec![value],
+            FuncStmt::Assign { target, value } | FuncStmt::AugAssign { target, value, .. } => {
+                vec![target, value]
+            }
+            FuncStmt::For { iter, .. } => vec![iter],
+            FuncStmt::While { test, .. } | FuncStmt::If { test, .. } => vec![test],
+            FuncStmt::Match { expr, .. } => vec![expr],
+            FuncStmt::Assert { test, msg } => std::iter::once(test).chain(msg).collect(),
+            FuncStmt::Expr { value } => vec![value],
+            FuncStmt::Revert { error } => error.iter().collect(),
+            FuncStmt::Break | FuncStmt::Continue | FuncStmt::Unsafe(_) => vec![],
+        };
+        for expr in exprs {
+            walk_expr(expr, f);
+        }
+    });
+}
+
+fn walk_expr<'a>(expr: &'a Node<Expr>, f: &mut dyn FnMut(&'a Node<Expr>)) {
+    f(expr);
+    match &expr.kind {
+        Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => {
+            walk_expr(if_expr, f);
+            walk_expr(test, f);
+            walk_expr(else_expr, f);
+        }
+        Expr::BoolOperation { left, right, .. }
+        | Expr::BinOperation { left, right, .. }
+        | Expr::CompOperation { left, right, .. } => {
+            walk_expr(left, f);
+            walk_expr(right, f);
+        }
+        Expr::UnaryOperation { operand, .. } => walk_expr(operand, f),
+        Expr::Attribute { value, .. } => walk_expr(value, f),
+        Expr::Subscript { value, index } => {
+            walk_expr(value, f);
+            walk_expr(index, f);
+        }
+        Expr::Call {
+            func,
+            generic_args,
+            args,
+        } => {
+            walk_expr(func, f);
+            for arg in generic_args.iter().flat_map(|args| args.kind.iter()) {
+                if let GenericArg::ConstExpr(expr) = arg {
+                    walk_expr(expr, f);
+                }
+            }
+            for arg in args.kind.iter() {
+                walk_expr(&arg.kind.value, f);
+            }
+        }
+        Expr::List { elts } | Expr::Tuple { elts } => {
+            for elt in elts {
+                walk_expr(elt, f);
+            }
+        }
+        Expr::Repeat { value, .. } => walk_expr(value, f),
+        Expr::Bool(_)
+        | Expr::Name(_)
+        | Expr::Path(_)
+        | Expr::Num(_)
+        | Expr::Str(_)
+        | Expr::Unit => {}
+    }
+}
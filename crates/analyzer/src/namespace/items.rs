@@ -1,13 +1,14 @@
-use crate::constants::{EMITTABLE_TRAIT_NAME, INDEXED};
+use crate::constants::{EMITTABLE_TRAIT_NAME, INDEXED, INLINE, NOINLINE, TEST};
 use crate::context::{self, Analysis, Constant, NamedThing};
 use crate::display::{DisplayWithDb, Displayable};
 use crate::errors::{self, IncompleteItem, TypeError};
+use crate::lints;
 use crate::namespace::types::{self, GenericType, Type, TypeId};
 use crate::traversal::pragma::check_pragma_version;
 use crate::AnalyzerDb;
 use crate::{builtins, errors::ConstEvalError};
-use fe_common::diagnostics::Diagnostic;
 use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{Diagnostic, ErrorCode};
 use fe_common::files::{common_prefix, Utf8Path};
 use fe_common::utils::files::{BuildFiles, ProjectMode};
 use fe_common::{impl_intern_key, FileKind, SourceFileId};
@@ -222,6 +223,7 @@ impl Item {
                     return Analysis {
                         value: None,
                         diagnostics: Rc::new([errors::error(
+                            ErrorCode::UnresolvedName,
                             "unresolved path item",
                             node.span,
                             "not found",
@@ -461,6 +463,15 @@ impl IngotId {
         diagnostics
     }
 
+    /// Returns the lint warnings of the modules of the ingot. Only meaningful
+    /// if there are no errors.
+    pub fn lint_diagnostics(&self, db: &dyn AnalyzerDb) -> Vec<Diagnostic> {
+        self.all_modules(db)
+            .iter()
+            .flat_map(|module| module.lint_diagnostics(db))
+            .collect()
+    }
+
     pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
         if self.root_module(db).is_none() {
             let file_name = match self.data(db).mode {
@@ -468,13 +479,16 @@ impl IngotId {
                 IngotMode::Main => "main",
                 IngotMode::StandaloneModule => unreachable!(), // always has a root module
             };
-            sink.push(&Diagnostic::error(format!(
-                "The ingot named \"{}\" is missing a `{}` module. \
+            sink.push(&Diagnostic {
+                code: Some(ErrorCode::ProjectLayout),
+                ..Diagnostic::error(format!(
+                    "The ingot named \"{}\" is missing a `{}` module. \
                  \nPlease add a `src/{}.fe` file to the base directory.",
-                self.name(db),
-                file_name,
-                file_name,
-            )));
+                    self.name(db),
+                    file_name,
+                    file_name,
+                ))
+            });
         }
         for module in self.all_modules(db).iter() {
             module.sink_diagnostics(db, sink)
@@ -666,6 +680,7 @@ impl ModuleId {
             Analysis {
                 value: None,
                 diagnostics: Rc::new([errors::error(
+                    ErrorCode::UnresolvedName,
                     "unresolved path item",
                     first_segment.span,
                     "not found",
@@ -689,6 +704,7 @@ impl ModuleId {
             Analysis {
                 value: None,
                 diagnostics: Rc::new([errors::error(
+                    ErrorCode::UnresolvedName,
                     "unresolved path item",
                     first_segment.span,
                     "not found",
@@ -796,6 +812,12 @@ impl ModuleId {
         diagnostics
     }
 
+    /// Returns the lint warnings of the module. Only meaningful if there are
+    /// no errors.
+    pub fn lint_diagnostics(&self, db: &dyn AnalyzerDb) -> Vec<Diagnostic> {
+        lints::module_lints(db, *self)
+    }
+
     pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
         let data = self.data(db);
         if let ModuleSource::File(_) = data.source {
@@ -1393,7 +1415,37 @@ impl FunctionId {
         Item::Function(*self)
             .attributes(db)
             .iter()
-            .any(|attribute| attribute.name(db) == "test")
+            .any(|attribute| attribute.name(db) == TEST)
+    }
+
+    /// Returns the attributes of the function. These precede the function
+    /// either as module level attributes or, for a function defined in a
+    /// struct, contract, enum or impl block, in its definition.
+    pub fn attributes(&self, db: &dyn AnalyzerDb) -> Vec<Node<ast::Attribute>> {
+        let mut attributes: Vec<_> = Item::Function(*self)
+            .attributes(db)
+            .iter()
+            .map(|attribute| attribute.data(db).ast.clone())
+            .collect();
+        attributes.extend(self.data(db).ast.kind.attributes.iter().cloned());
+        attributes
+    }
+
+    /// Returns `true` if the function has an attribute named `name`.
+    pub fn has_attribute(&self, db: &dyn AnalyzerDb, name: &str) -> bool {
+        self.attributes(db)
+            .iter()
+            .any(|attribute| attribute.kind.name.kind == name)
+    }
+
+    /// Returns `true` if the function is marked `#inline`.
+    pub fn is_inline(&self, db: &dyn AnalyzerDb) -> bool {
+        self.has_attribute(db, INLINE)
+    }
+
+    /// Returns `true` if the function is marked `#noinline`.
+    pub fn is_noinline(&self, db: &dyn AnalyzerDb) -> bool {
+        self.has_attribute(db, NOINLINE)
     }
 }
 
@@ -1531,13 +1583,14 @@ impl StructFieldId {
     pub fn data(&self, db: &dyn AnalyzerDb) -> Rc<StructField> {
         db.lookup_intern_struct_field(*self)
     }
+    /// Returns the names of the attributes of the field.
     pub fn attributes(&self, db: &dyn AnalyzerDb) -> Vec<SmolStr> {
         self.data(db)
             .ast
             .kind
             .attributes
             .iter()
-            .map(|node| node.kind.clone())
+            .map(|node| node.kind.name.kind.clone())
             .collect()
     }
 
@@ -1559,7 +1612,7 @@ impl StructFieldId {
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Attribute {
-    pub ast: Node<SmolStr>,
+    pub ast: Node<ast::Attribute>,
     pub module: ModuleId,
 }
 #[derive(Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
@@ -1574,7 +1627,7 @@ impl AttributeId {
         self.data(db).ast.span
     }
     pub fn name(self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.kind.to_owned()
+        self.data(db).ast.kind.name.kind.clone()
     }
 
     pub fn module(self, db: &dyn AnalyzerDb) -> ModuleId {
@@ -1845,6 +1898,7 @@ impl ImplId {
 
         if !is_allowed {
             sink.push(&errors::fancy_error(
+                ErrorCode::InvalidImpl,
                 "illegal `impl`. Either type or trait must be in the same ingot as the `impl`",
                 vec![Label::primary(
                     self.data(db).ast.span,
@@ -1866,6 +1920,7 @@ impl ImplId {
             | Type::SelfContract(_)
             | Type::Generic(_)
             | Type::SelfType(_) => sink.push(&errors::fancy_error(
+                ErrorCode::InvalidImpl,
                 format!(
                     "`impl` blocks aren't allowed for {}",
                     self.data(db).receiver.display(db)
@@ -1892,6 +1947,7 @@ impl ImplId {
             let trait_module_name = self.trait_id(db).module(db).name(db);
             let trait_name = self.trait_id(db).name(db);
             sink.push(&errors::fancy_error(
+                     ErrorCode::PrivateItem,
                      format!(
                          "the trait `{trait_name}` is private",
                      ),
@@ -1921,6 +1977,7 @@ impl ImplId {
                         continue;
                     } else {
                         sink.push(&errors::fancy_error(
+                            ErrorCode::InvalidImpl,
                             format!(
                                 "method `{}` has incompatible parameters for `{}` of trait `{}`",
                                 impl_fn.name(db),
@@ -1952,6 +2009,7 @@ impl ImplId {
                 if !self.can_stand_in_for(db, impl_fn_return_ty, trait_fn_return_ty) {
                     // TODO: This could be a nicer, more detailed report
                     sink.push(&errors::fancy_error(
+                        ErrorCode::InvalidImpl,
                         format!(
                             "method `{}` has an incompatible return type for `{}` of trait `{}`",
                             impl_fn.name(db),
@@ -1989,6 +2047,7 @@ impl ImplId {
                             )
                         };
                     sink.push(&errors::fancy_error(
+                        ErrorCode::InvalidImpl,
                         format!(
                             "method `{}` has a `self` declaration in the {}, but not in the `{}`",
                             impl_fn.name(db),
@@ -2010,6 +2069,7 @@ impl ImplId {
                 }
             } else {
                 sink.push(&errors::fancy_error(
+                    ErrorCode::InvalidImpl,
                     format!(
                         "method `{}` is not a member of trait `{}`",
                         impl_fn.name(db),
@@ -2027,6 +2087,7 @@ impl ImplId {
         for trait_fn in self.trait_id(db).all_functions(db).iter() {
             if self.function(db, &trait_fn.name(db)).is_none() {
                 sink.push(&errors::fancy_error(
+                    ErrorCode::InvalidImpl,
                     format!(
                         "not all members of trait `{}` implemented, missing: `{}`",
                         self.trait_id(db).name(db),
@@ -9,7 +9,7 @@ use crate::namespace::items::{Item, TypeDef};
 use crate::namespace::types::{Type, TypeId};
 use crate::pattern_analysis::PatternMatrix;
 use crate::AnalyzerDb;
-use fe_common::diagnostics::Diagnostic;
+use fe_common::diagnostics::{Diagnostic, ErrorCode};
 use fe_common::Span;
 use fe_parser::{ast, node::NodeId, Label};
 use fe_parser::{ast::Expr, node::Node};
@@ -136,7 +136,12 @@ impl<'a> AnalyzerContext for ItemScope<'a> {
             check_visibility(self, &named_thing, span);
             Ok(named_thing)
         } else {
-            let err = self.error("unresolved path item", span, "not found");
+            let err = self.error(
+                ErrorCode::UnresolvedName,
+                "unresolved path item",
+                span,
+                "not found",
+            );
             Err(FatalError::new(err))
         }
     }
@@ -389,7 +394,12 @@ impl<'a> AnalyzerContext for FunctionScope<'a> {
             check_visibility(self, &named_thing, span);
             Ok(named_thing)
         } else {
-            let err = self.error("unresolved path item", span, "not found");
+            let err = self.error(
+                ErrorCode::UnresolvedName,
+                "unresolved path item",
+                span,
+                "not found",
+            );
             Err(FatalError::new(err))
         }
     }
@@ -602,6 +612,7 @@ impl<'a, 'b> BlockScope<'a, 'b> {
         match self.resolve_name(name, span) {
             Ok(Some(NamedThing::SelfValue { .. })) => {
                 let err = self.error(
+                    ErrorCode::NameConflict,
                     "`self` can't be used as a variable name",
                     span,
                     "expected a name, found keyword `self`",
@@ -612,6 +623,7 @@ impl<'a, 'b> BlockScope<'a, 'b> {
             Ok(Some(named_item)) => {
                 if named_item.is_builtin() {
                     let err = self.error(
+                        ErrorCode::NameConflict,
                         &format!(
                             "variable name conflicts with built-in {}",
                             named_item.item_kind_display_name(),
@@ -672,6 +684,7 @@ pub fn check_visibility(context: &dyn AnalyzerContext, named_thing: &NamedThing,
             let item_span = item.name_span(context.db()).unwrap_or(span);
             let item_kind_name = item.item_kind_display_name();
             context.fancy_error(
+                ErrorCode::PrivateItem,
                 &format!("the {item_kind_name} `{item_name}` is private",),
                 vec![
                     Label::primary(span, format!("this {item_kind_name} is not `pub`")),
@@ -5,7 +5,7 @@ use crate::namespace::types::{Type, TypeId};
 use crate::operations;
 use crate::traversal::expressions;
 use crate::traversal::utils::add_bin_operations_errors;
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_parser::ast as fe;
 use fe_parser::node::{Node, Span};
 use smol_str::SmolStr;
@@ -43,6 +43,7 @@ fn assignment_lhs_type(
                 ));
             }
             scope.fancy_error(
+                ErrorCode::NotMutable,
                 &format!("cannot modify `{}`, as it is not mutable", &target.kind),
                 labels,
                 vec![],
@@ -109,6 +110,7 @@ fn bad_assign_target_error(
     msg: &str,
 ) -> DiagnosticVoucher {
     scope.fancy_error(
+        ErrorCode::InvalidExpression,
         msg,
         vec![Label::primary(expr.span, "")],
         vec!["The left side of an assignment can be a variable name, attribute, subscript, or tuple.".into()]
@@ -1,7 +1,7 @@
 use super::call_args::LabeledParameter;
 use crate::context::{AnalyzerContext, NamedThing};
 use crate::namespace::types::{Type, TypeId};
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_parser::ast;
 use fe_parser::node::{Node, Span};
 use smallvec::{smallvec, SmallVec};
@@ -69,6 +69,7 @@ pub fn check_fn_call_arg_borrows(
                 if let Some((_, other_span)) = other_vars.iter().find(|(nt, _)| nt == var) {
                     let name = var.name(context.db());
                     context.fancy_error(
+                        ErrorCode::BorrowConflict,
                         &format!("borrow conflict in call to fn `{fn_name}`"),
                         vec![
                             Label::primary(*var_span, format!("`{name}` is used mutably here")),
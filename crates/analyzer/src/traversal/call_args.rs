@@ -4,7 +4,10 @@ use crate::context::{AnalyzerContext, DiagnosticVoucher};
 use crate::display::Displayable;
 use crate::errors::{self, FatalError, TypeCoercionError, TypeError};
 use crate::namespace::types::{FunctionParam, Generic, Type, TypeId};
-use fe_common::{diagnostics::Label, utils::humanize::pluralize_conditionally};
+use fe_common::{
+    diagnostics::{ErrorCode, Label},
+    utils::humanize::pluralize_conditionally,
+};
 use fe_common::{Span, Spanned};
 use fe_parser::ast as fe;
 use fe_parser::node::Node;
@@ -85,6 +88,7 @@ pub fn validate_arg_count(
         }
 
         Some(context.fancy_error(
+            ErrorCode::InvalidArguments,
             &format!(
                 "`{}` expects {} {}, but {} {} provided",
                 name,
@@ -127,6 +131,7 @@ pub fn validate_named_args(
                         vec![]
                     };
                     context.fancy_error(
+                        ErrorCode::InvalidArguments,
                         "argument label mismatch",
                         vec![Label::primary(
                             actual_label.span,
@@ -140,6 +145,7 @@ pub fn validate_named_args(
                 fe::Expr::Name(var_name) if var_name == expected_label => {}
                 _ => {
                     context.fancy_error(
+                            ErrorCode::InvalidArguments,
                             "missing argument label",
                             vec![Label::primary(
                                 Span::new(arg_val.span.file_id, arg_val.span.start, arg_val.span.start),
@@ -153,6 +159,7 @@ pub fn validate_named_args(
             },
             (None, Some(actual_label)) => {
                 context.error(
+                    ErrorCode::InvalidArguments,
                     "argument should not be labeled",
                     actual_label.span,
                     "remove this label",
@@ -169,6 +176,7 @@ pub fn validate_named_args(
                 for bound in bounds.iter() {
                     if !bound.is_implemented_for(context.db(), arg_type) {
                         context.error(
+                            ErrorCode::MismatchedTypes,
                             &format!(
                                 "the trait bound `{}: {}` is not satisfied",
                                 arg_type.display(context.db()),
@@ -221,7 +229,12 @@ pub fn validate_named_args(
             } else {
                 format!("`{name}` argument at position {index} must be mutable")
             };
-            context.error(&msg, arg.kind.value.span, "is not `mut`");
+            context.error(
+                ErrorCode::NotMutable,
+                &msg,
+                arg.kind.value.span,
+                "is not `mut`",
+            );
         }
     }
     Ok(())
@@ -1,6 +1,7 @@
 //! This module provides evaluator for constant expression to resolve const
 //! generics.
 
+use fe_common::diagnostics::ErrorCode;
 use num_bigint::BigInt;
 use num_traits::{One, ToPrimitive, Zero};
 
@@ -323,6 +324,7 @@ impl Constant {
 
 fn not_const_error(context: &mut dyn AnalyzerContext, span: Span) -> ConstEvalError {
     ConstEvalError::new(context.error(
+        ErrorCode::ConstEval,
         "expression is not a constant",
         span,
         "expression is required to be constant here",
@@ -331,6 +333,7 @@ fn not_const_error(context: &mut dyn AnalyzerContext, span: Span) -> ConstEvalEr
 
 fn overflow_error(context: &mut dyn AnalyzerContext, span: Span) -> ConstEvalError {
     ConstEvalError::new(context.error(
+        ErrorCode::ConstEval,
         "overflow error",
         span,
         "overflow occurred during constant evaluation",
@@ -339,6 +342,7 @@ fn overflow_error(context: &mut dyn AnalyzerContext, span: Span) -> ConstEvalErr
 
 fn zero_division_error(context: &mut dyn AnalyzerContext, span: Span) -> ConstEvalError {
     ConstEvalError::new(context.error(
+        ErrorCode::ConstEval,
         "zero division error",
         span,
         "zero division occurred during constant evaluation",
@@ -4,7 +4,10 @@ use crate::errors::{self, FatalError, TypeCoercionError};
 use crate::namespace::scopes::BlockScope;
 use crate::namespace::types::{Type, TypeId};
 use crate::traversal::{const_expr, expressions, types};
-use fe_common::{diagnostics::Label, utils::humanize::pluralize_conditionally};
+use fe_common::{
+    diagnostics::{ErrorCode, Label},
+    utils::humanize::pluralize_conditionally,
+};
 use fe_parser::ast as fe;
 use fe_parser::node::Node;
 
@@ -28,6 +31,7 @@ pub fn var_decl(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
     let declared_type = types::type_desc(scope, typ, self_ty)?;
     if let Type::Map(_) = declared_type.typ(scope.db()) {
         return Err(FatalError::new(scope.error(
+            ErrorCode::InvalidType,
             "invalid variable type",
             typ.span,
             "`Map` type can only be used as a contract field",
@@ -62,6 +66,7 @@ pub fn var_decl(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
         Type::Array(_) | Type::Struct(_) | Type::Tuple(_)
     ) {
         scope.error(
+            ErrorCode::InvalidExpression,
             "uninitialized variable",
             target.span,
             &format!(
@@ -92,6 +97,7 @@ pub fn const_decl(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(
             _ => {
                 // If this conversion fails, the type must be a map (for now at least)
                 return Err(FatalError::new(scope.error(
+                    ErrorCode::InvalidType,
                     "invalid constant type",
                     typ.span,
                     "`Map` type can only be used as a contract field",
@@ -144,6 +150,7 @@ fn add_var(
                     let items_ty_len = items_ty.len();
                     if items.len() != items_ty_len {
                         return Err(FatalError::new(scope.fancy_error(
+                            ErrorCode::InvalidExpression,
                             "invalid declaration",
                             vec![Label::primary(target.span, "")],
                             vec![format!(
@@ -161,6 +168,7 @@ fn add_var(
                     Ok(())
                 }
                 _ => Err(FatalError::new(scope.fancy_error(
+                    ErrorCode::InvalidExpression,
                     "invalid declaration",
                     vec![Label::primary(target.span, "")],
                     vec![format!(
@@ -18,7 +18,7 @@ use crate::traversal::types::{
 };
 use crate::traversal::utils::add_bin_operations_errors;
 
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_common::{numeric, Span};
 use fe_parser::ast as fe;
 use fe_parser::ast::GenericArg;
@@ -168,6 +168,7 @@ fn expr_list(
                 }
                 Err(TypeCoercionError::Incompatible) => {
                     context.fancy_error(
+                        ErrorCode::MismatchedTypes,
                         "array elements must have same type",
                         vec![
                             Label::primary(
@@ -225,6 +226,7 @@ fn expr_repeat(
     let size = match &len.kind {
         GenericArg::Int(size) => Ok(size.kind),
         GenericArg::TypeDesc(_) => Err(context.fancy_error(
+            ErrorCode::ConstEval,
             "expected a constant u256 value",
             vec![Label::primary(len.span, "Array length")],
             vec!["Note: Array length must be a constant u256".to_string()],
@@ -235,6 +237,7 @@ fn expr_repeat(
                 Ok(len.to_usize().unwrap())
             } else {
                 Err(context.fancy_error(
+                    ErrorCode::ConstEval,
                     "expected a constant u256 value",
                     vec![Label::primary(len.span, "Array length")],
                     vec!["Note: Array length must be a constant u256".to_string()],
@@ -289,6 +292,7 @@ fn expr_tuple(
         if !&types.iter().all(|id| id.has_fixed_size(context.db())) {
             // TODO: doesn't need to be fatal if expected.is_some()
             return Err(FatalError::new(context.error(
+                ErrorCode::InvalidType,
                 "variable size types can not be part of tuples",
                 exp.span,
                 "",
@@ -345,6 +349,7 @@ fn expr_named_thing(
             if let Some(target) = parent {
                 if decl.is_none() {
                     context.fancy_error(
+                        ErrorCode::UnresolvedName,
                         "`self` is not defined",
                         vec![Label::primary(exp.span, "undefined value")],
                         if let Item::Function(func_id) = context.parent() {
@@ -370,6 +375,7 @@ fn expr_named_thing(
                     // This can only happen when trait methods can implement a default body
                     Item::Trait(id) => {
                         return Err(FatalError::new(context.fancy_error(
+                            ErrorCode::InvalidExpression,
                             &format!(
                                 "`{}` is a trait, and can't be used as an expression",
                                 exp.kind
@@ -397,6 +403,7 @@ fn expr_named_thing(
                 Ok(self_typ)
             } else {
                 Err(context.fancy_error(
+                    ErrorCode::InvalidFunction,
                     "`self` can only be used in contract, struct, trait or impl functions",
                     vec![Label::primary(
                         exp.span,
@@ -419,6 +426,7 @@ fn expr_named_thing(
             if let Ok(EnumVariantKind::Tuple(_)) = variant.kind(context.db()) {
                 let name = variant.name_with_parent(context.db());
                 context.fancy_error(
+                    ErrorCode::InvalidExpression,
                     &format!(
                         "`{}` is not a unit variant",
                         variant.name_with_parent(context.db()),
@@ -441,6 +449,7 @@ fn expr_named_thing(
             let item_kind = item.item_kind_display_name();
             let diag = if let Some(def_span) = item.name_span(context.db()) {
                 context.fancy_error(
+                    ErrorCode::InvalidExpression,
                     &format!(
                         "`{}` is a {} name, and can't be used as an expression",
                         exp.kind, item_kind
@@ -456,6 +465,7 @@ fn expr_named_thing(
                 )
             } else {
                 context.error(
+                    ErrorCode::InvalidExpression,
                     &format!(
                         "`{}` is a built-in {} name, and can't be used as an expression",
                         exp.kind, item_kind
@@ -467,6 +477,7 @@ fn expr_named_thing(
             Err(diag)
         }
         None => Err(context.error(
+            ErrorCode::UnresolvedName,
             &format!("cannot find value `{}` in this scope", exp.kind),
             exp.span,
             "undefined",
@@ -491,11 +502,17 @@ fn expr_str(
 ) -> Result<ExpressionAttributes, FatalError> {
     if let fe::Expr::Str(string) = &exp.kind {
         if !is_valid_string(string) {
-            context.error("String contains invalid byte sequence", exp.span, "");
+            context.error(
+                ErrorCode::InvalidExpression,
+                "String contains invalid byte sequence",
+                exp.span,
+                "",
+            );
         };
 
         if !context.is_in_function() {
             context.fancy_error(
+                ErrorCode::InvalidExpression,
                 "string literal can't be used outside function",
                 vec![Label::primary(exp.span, "string type is used here")],
                 vec!["Note: string literal can be used only inside function".into()],
@@ -560,6 +577,7 @@ fn expr_num(
     if expected_type == Some(TypeId::address(context.db())) {
         if num < BigInt::zero() && num > types::address_max() {
             context.error(
+                ErrorCode::ConstEval,
                 "literal out of range for `address` type",
                 exp.span,
                 "does not fit into type `address`",
@@ -591,6 +609,7 @@ fn expr_subscript(
             Err(err) => {
                 let diag = match err {
                     IndexingError::NotSubscriptable => context.fancy_error(
+                        ErrorCode::InvalidOperation,
                         &format!(
                             "`{}` type is not subscriptable",
                             value_ty.display(context.db())
@@ -599,6 +618,7 @@ fn expr_subscript(
                         vec!["Note: Only arrays and maps are subscriptable".into()],
                     ),
                     IndexingError::WrongIndexType => context.fancy_error(
+                        ErrorCode::InvalidOperation,
                         &format!(
                             "can not subscript {} with type {}",
                             value_ty.display(context.db()),
@@ -667,6 +687,7 @@ fn field_type(
         Type::SelfContract(id) => match id.field_type(context.db(), field_name) {
             Some(typ) => Ok(typ?.make_sptr(context.db())),
             None => Err(FatalError::new(context.fancy_error(
+                ErrorCode::UnresolvedName,
                 &format!("No field `{field_name}` exists on this contract"),
                 vec![Label::primary(field_span, "undefined field")],
                 vec![],
@@ -678,6 +699,7 @@ fn field_type(
                 if !context.root_item().is_struct(&struct_) && !struct_field.is_public(context.db())
                 {
                     context.fancy_error(
+                        ErrorCode::PrivateItem,
                         &format!(
                             "Can not access private field `{}` on struct `{}`",
                             field_name,
@@ -690,6 +712,7 @@ fn field_type(
                 Ok(struct_field.typ(context.db())?)
             } else {
                 Err(FatalError::new(context.fancy_error(
+                    ErrorCode::UnresolvedName,
                     &format!(
                         "No field `{}` exists on struct `{}`",
                         field_name,
@@ -703,6 +726,7 @@ fn field_type(
         Type::Tuple(tuple) => {
             let item_index = tuple_item_index(field_name).ok_or_else(||
                     FatalError::new(context.fancy_error(
+                        ErrorCode::UnresolvedName,
                         &format!("No field `{field_name}` exists on this tuple"),
                         vec![
                             Label::primary(
@@ -715,6 +739,7 @@ fn field_type(
 
             tuple.items.get(item_index).copied().ok_or_else(|| {
                 FatalError::new(context.fancy_error(
+                    ErrorCode::UnresolvedName,
                     &format!("No field `item{item_index}` exists on this tuple"),
                     vec![Label::primary(field_span, "unknown field")],
                     vec![format!(
@@ -725,6 +750,7 @@ fn field_type(
             })
         }
         _ => Err(FatalError::new(context.fancy_error(
+            ErrorCode::UnresolvedName,
             &format!(
                 "No field `{}` exists on type {}",
                 field_name,
@@ -809,6 +835,7 @@ fn expr_unary_operation(
 
     let emit_err = |context: &mut dyn AnalyzerContext, expected| {
         context.error(
+            ErrorCode::InvalidOperation,
             &format!(
                 "cannot apply unary operator `{}` to type `{}`",
                 op.kind,
@@ -836,6 +863,7 @@ fn expr_unary_operation(
                 }
                 if !expected_int_type.is_signed() {
                     context.error(
+                        ErrorCode::InvalidOperation,
                         "Can not apply unary operator",
                         op.span + operand.span,
                         &format!(
@@ -884,6 +912,7 @@ fn expr_call(
         _ => {
             let expression = expr(context, func, None)?;
             let diag = context.fancy_error(
+                ErrorCode::InvalidCall,
                 &format!(
                     "`{}` type is not callable",
                     expression.typ.display(context.db())
@@ -914,6 +943,7 @@ fn expr_call(
             ))
         }
         context.fancy_error(
+            ErrorCode::Unsafe,
             &format!("unsafe function `{}` can only be called in an unsafe function or block",
                      &fn_name),
             labels,
@@ -925,6 +955,7 @@ fn expr_call(
         context.add_call(func, call_type);
     } else {
         context.error(
+            ErrorCode::InvalidCall,
             "calling function outside function",
             func.span,
             "function can only be called inside function",
@@ -955,6 +986,7 @@ fn expr_call_name<T: std::fmt::Display>(
             {
                 // TODO: this doesn't have to be fatal
                 FatalError::new(context.fancy_error(
+                    ErrorCode::InvalidCall,
                     &format!("`{name}` must be called via `self`"),
                     vec![
                         Label::primary(
@@ -972,6 +1004,7 @@ fn expr_call_name<T: std::fmt::Display>(
                 ))
             } else {
                 FatalError::new(context.error(
+                    ErrorCode::UnresolvedName,
                     &format!("`{name}` is not defined"),
                     func.span,
                     &format!("`{name}` has not been defined in this context"),
@@ -979,6 +1012,7 @@ fn expr_call_name<T: std::fmt::Display>(
             }
         } else {
             FatalError::new(context.error(
+                ErrorCode::InvalidCall,
                 "calling function outside function",
                 func.span,
                 "function can only be called inside function",
@@ -1049,6 +1083,7 @@ fn validate_has_no_conflicting_trait_in_scope<T: std::fmt::Display>(
             .collect::<Vec<_>>();
 
             return Err(FatalError::new(context.fancy_error(
+                ErrorCode::UnresolvedName,
                 "multiple applicable items in scope",
                 labels,
                 vec![
@@ -1080,6 +1115,7 @@ fn expr_call_trait_associated_function<T: std::fmt::Display>(
 
         if in_scope_candidates.len() > 1 {
             context.fancy_error(
+                ErrorCode::UnresolvedName,
                 "multiple applicable items in scope",
                 in_scope_candidates
                     .iter()
@@ -1105,6 +1141,7 @@ fn expr_call_trait_associated_function<T: std::fmt::Display>(
             return expr_call_pure(context, fun, func.span, generic_args, args);
         } else if in_scope_candidates.is_empty() && !candidates.is_empty() {
             context.fancy_error(
+                ErrorCode::UnresolvedName,
                 "Applicable items exist but are not in scope",
                 candidates.iter().enumerate().map(|(idx, (fun, _impl ))| {
                     Label::primary(fun.name_span(context.db()), format!(
@@ -1129,6 +1166,7 @@ fn expr_call_trait_associated_function<T: std::fmt::Display>(
     context.resolve_path(path, func.span)?;
 
     Err(FatalError::new(context.error(
+        ErrorCode::UnresolvedName,
         "unresolved path item",
         func.span,
         "not found",
@@ -1155,6 +1193,7 @@ fn expr_call_named_thing<T: std::fmt::Display>(
         NamedThing::Item(Item::Type(def)) => {
             if let Some(args) = generic_args {
                 context.fancy_error(
+                    ErrorCode::InvalidType,
                     &format!("`{}` type is not generic", func.kind),
                     vec![Label::primary(
                         args.span,
@@ -1172,6 +1211,7 @@ fn expr_call_named_thing<T: std::fmt::Display>(
             expr_call_type_constructor(context, concrete_type, func.span, args)
         }
         NamedThing::Item(Item::Constant(id)) => Err(FatalError::new(context.error(
+            ErrorCode::InvalidCall,
             &format!("`{}` is not callable", func.kind),
             func.span,
             &format!(
@@ -1181,6 +1221,7 @@ fn expr_call_named_thing<T: std::fmt::Display>(
             ),
         ))),
         NamedThing::Item(Item::Trait(_)) => Err(FatalError::new(context.error(
+            ErrorCode::InvalidCall,
             &format!("`{}` is not callable", func.kind),
             func.span,
             &format!(
@@ -1191,6 +1232,7 @@ fn expr_call_named_thing<T: std::fmt::Display>(
         NamedThing::Item(Item::Impl(_)) => unreachable!(),
         NamedThing::Item(Item::Attribute(_)) => unreachable!(),
         NamedThing::Item(Item::Ingot(_)) => Err(FatalError::new(context.error(
+            ErrorCode::InvalidCall,
             &format!("`{}` is not callable", func.kind),
             func.span,
             &format!(
@@ -1199,6 +1241,7 @@ fn expr_call_named_thing<T: std::fmt::Display>(
             ),
         ))),
         NamedThing::Item(Item::Module(_)) => Err(FatalError::new(context.error(
+            ErrorCode::InvalidCall,
             &format!("`{}` is not callable", func.kind),
             func.span,
             &format!(
@@ -1213,12 +1256,14 @@ fn expr_call_named_thing<T: std::fmt::Display>(
 
         // Nothing else is callable (for now at least)
         NamedThing::SelfValue { .. } => Err(FatalError::new(context.error(
+            ErrorCode::InvalidCall,
             "`self` is not callable",
             func.span,
             "can't be used as a function",
         ))),
 
         NamedThing::Variable { typ, span, .. } => Err(FatalError::new(context.fancy_error(
+            ErrorCode::InvalidCall,
             &format!("`{}` is not callable", func.kind),
             vec![
                 Label::secondary(
@@ -1244,6 +1289,7 @@ fn expr_call_builtin_function(
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     if let Some(args) = generic_args {
         context.error(
+            ErrorCode::InvalidType,
             &format!(
                 "`{}` function does not expect generic arguments",
                 function.as_ref()
@@ -1265,6 +1311,7 @@ fn expr_call_builtin_function(
                     Type::Array(Array { inner, .. }) if inner.typ(context.db()) == Type::u8() => {}
                     _ => {
                         context.fancy_error(
+                            ErrorCode::MismatchedTypes,
                             &format!(
                                 "`{}` can not be used as an argument to `{}`",
                                 arg_typ.display(context.db()),
@@ -1294,6 +1341,7 @@ fn expr_call_intrinsic(
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     if let Some(args) = generic_args {
         context.error(
+            ErrorCode::InvalidType,
             &format!(
                 "`{}` function does not expect generic arguments",
                 function.as_ref()
@@ -1348,6 +1396,7 @@ fn validate_visibility_of_called_fn(
     let is_called_from_same_item = fn_parent == context.parent_function().parent(context.db());
     if !called_fn.is_public(context.db()) && !is_called_from_same_item {
         context.fancy_error(
+            ErrorCode::PrivateItem,
             &format!(
                 "the function `{}` on `{} {}` is private",
                 name,
@@ -1386,6 +1435,7 @@ fn expr_call_pure(
     let fn_name = function.name(context.db());
     if let Some(args) = generic_args {
         context.fancy_error(
+            ErrorCode::InvalidType,
             &format!("`{fn_name}` function is not generic"),
             vec![Label::primary(
                 args.span,
@@ -1397,6 +1447,7 @@ fn expr_call_pure(
 
     if function.is_test(context.db()) {
         context.fancy_error(
+            ErrorCode::InvalidCall,
             &format!("`{fn_name}` is a test function"),
             vec![Label::primary(call_span, "test functions are not callable")],
             vec![],
@@ -1432,6 +1483,7 @@ fn expr_call_type_constructor(
         | Type::Map(_)
         | Type::Generic(_) => {
             return Err(FatalError::new(context.error(
+                ErrorCode::InvalidCall,
                 &format!("`{}` type is not callable", typ.display(context.db())),
                 into_span,
                 "",
@@ -1483,6 +1535,7 @@ fn expr_call_struct_constructor(
             .collect();
 
         context.fancy_error(
+            ErrorCode::PrivateItem,
             &format!(
                 "Can not call private constructor of struct `{name}` "
             ),
@@ -1521,6 +1574,7 @@ fn expr_call_enum_constructor(
             let name = variant.name_with_parent(context.db());
             let label = Label::primary(name_span, format! {"`{name}` is a unit variant"});
             context.fancy_error(
+                ErrorCode::InvalidCall,
                 &format!("Can not call a unit variant `{name}`",),
                 vec![label],
                 vec![format!(
@@ -1580,6 +1634,7 @@ fn expr_call_method(
 
     match obj_type.function_sigs(context.db(), &field.kind).as_ref() {
         [] => Err(FatalError::new(context.fancy_error(
+            ErrorCode::UnresolvedName,
             &format!(
                 "No function `{}` exists on type `{}`",
                 &field.kind,
@@ -1601,6 +1656,7 @@ fn expr_call_method(
                 };
 
                 context.fancy_error(
+                    ErrorCode::InvalidCall,
                     &format!("`{}` must be called without `self`", &field.kind),
                     vec![Label::primary(field.span, "function does not take self")],
                     vec![format!(
@@ -1614,6 +1670,7 @@ fn expr_call_method(
             let mut_self = matches!(sig.self_decl.map(|d| d.is_mut()), Some(true));
             if mut_self && !target_attributes.typ.is_mut(context.db()) {
                 context.error(
+                    ErrorCode::NotMutable,
                     &format!("`{}` takes `mut self`", &field.kind),
                     target.span,
                     "this is not mutable",
@@ -1663,6 +1720,7 @@ fn expr_call_method(
                         ) {
                             let kind = obj_type.kind_display_name(context.db());
                             context.fancy_error(
+                                ErrorCode::StorageValue,
                                 &format!("{kind} functions can only be called on {kind} in memory"),
                                 vec![
                                     Label::primary(target.span, "this value is in storage"),
@@ -1692,6 +1750,7 @@ fn expr_call_method(
         }
         [first, second, ..] => {
             context.fancy_error(
+                ErrorCode::UnresolvedName,
                 "multiple applicable items in scope",
                 vec![
                     Label::primary(
@@ -1737,6 +1796,7 @@ fn validate_trait_in_scope(
         .is_in_scope(context.db(), Item::Trait(treit))
     {
         context.fancy_error(
+            ErrorCode::UnresolvedName,
             &format!(
                 "No method named `{}` found for type `{}` in the current scope",
                 called_fn.name(context.db()),
@@ -1783,6 +1843,7 @@ fn expr_call_builtin_value_method(
                 let inner = ty.deref(context.db());
                 if inner.is_primitive(context.db()) {
                     context.fancy_error(
+                        ErrorCode::StorageValue,
                         "`to_mem()` called on primitive type",
                         vec![
                             Label::primary(
@@ -1795,6 +1856,7 @@ fn expr_call_builtin_value_method(
                     );
                 } else if inner.is_map(context.db()) {
                     context.fancy_error(
+                        ErrorCode::StorageValue,
                         "`to_mem()` called on a Map",
                         vec![
                             Label::primary(value.span, "Maps can not be copied to memory"),
@@ -1806,6 +1868,7 @@ fn expr_call_builtin_value_method(
                     // TODO: this restriction should be removed
                 } else if ty.is_generic(context.db()) {
                     context.fancy_error(
+                        ErrorCode::StorageValue,
                         "`to_mem()` called on generic type",
                         vec![
                             Label::primary(value.span, "this value can not be copied to memory"),
@@ -1819,6 +1882,7 @@ fn expr_call_builtin_value_method(
                 return Ok((value_attrs, calltype));
             } else {
                 context.fancy_error(
+                    ErrorCode::StorageValue,
                     "`to_mem()` called on value in memory",
                     vec![
                         Label::primary(value.span, "this value is already in memory"),
@@ -1866,6 +1930,7 @@ fn abi_encoded_type(
             .id(context.db()),
         )),
         _ => Err(FatalError::new(context.fancy_error(
+            ErrorCode::InvalidCall,
             &format!(
                 "value of type `{}` does not support `abi_encode()`",
                 ty.display(context.db())
@@ -1892,6 +1957,7 @@ fn expr_call_type_attribute(
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     if let Some(generic_args) = generic_args {
         context.error(
+            ErrorCode::InvalidType,
             "unexpected generic argument list",
             generic_args.span,
             "unexpected",
@@ -1909,6 +1975,7 @@ fn expr_call_type_attribute(
         if let Ok(function) = ContractTypeMethod::from_str(&field.kind) {
             if context.root_item() == Item::Type(TypeDef::Contract(contract)) {
                 context.fancy_error(
+                        ErrorCode::InvalidCall,
                         &format!("`{contract}.{}(...)` called within `{contract}` creates an illegal circular dependency", function.as_ref(), contract=&target_name),
                         vec![Label::primary(field.span, "Contract creation")],
                         vec![format!("Note: Consider using a dedicated factory contract to create instances of `{}`", &target_name)]);
@@ -1929,6 +1996,7 @@ fn expr_call_type_attribute(
                         if let Some(ctx_type) = context.get_context_type() {
                             if attrs.typ != Type::Mut(ctx_type).id(context.db()) {
                                 context.fancy_error(
+                                    ErrorCode::MismatchedTypes,
                                     &format!(
                                         "incorrect type for argument to `{}.{}`",
                                         &target_name,
@@ -1946,6 +2014,7 @@ fn expr_call_type_attribute(
                             }
                         } else {
                             context.fancy_error(
+                                ErrorCode::UnresolvedName,
                                 "`Context` is not defined",
                                 vec![
                                     Label::primary(
@@ -1966,6 +2035,7 @@ fn expr_call_type_attribute(
                         }
                     } else if !attrs.typ.is_integer(context.db()) {
                         context.fancy_error(
+                            ErrorCode::MismatchedTypes,
                             &format!(
                                 "incorrect type for argument to `{}.{}`",
                                 &target_name,
@@ -1993,6 +2063,7 @@ fn expr_call_type_attribute(
     if let Some(sig) = target_type.function_sig(context.db(), &field.kind) {
         if sig.takes_self(context.db()) {
             return Err(FatalError::new(context.fancy_error(
+                ErrorCode::InvalidCall,
                 &format!(
                     "`{}` function `{}` must be called on an instance of `{}`",
                     &target_name, &field.kind, &target_name,
@@ -2008,6 +2079,7 @@ fn expr_call_type_attribute(
             )));
         } else {
             context.fancy_error(
+                ErrorCode::InvalidCall,
                 "Static functions need to be called with `::` not `.`",
                 vec![Label::primary(
                     field.span,
@@ -2036,6 +2108,7 @@ fn expr_call_type_attribute(
     }
 
     Err(FatalError::new(context.fancy_error(
+        ErrorCode::UnresolvedName,
         &format!(
             "No function `{}` exists on type `{}`",
             &field.kind,
@@ -2067,6 +2140,7 @@ fn expect_no_label_on_arg(
         .and_then(|arg| arg.kind.label.as_ref())
     {
         context.error(
+            ErrorCode::InvalidArguments,
             "argument should not be labeled",
             label.span,
             "remove this label",
@@ -2088,6 +2162,7 @@ fn check_for_call_to_special_fns(
             "Note: `__call__` is not part of the contract's interface, and can't be called."
         };
         Err(FatalError::new(context.fancy_error(
+            ErrorCode::InvalidCall,
             &format!("`{name}()` is not directly callable"),
             vec![Label::primary(span, "")],
             vec![label.into()],
@@ -2105,6 +2180,7 @@ fn validate_numeric_literal_fits_type(
 ) {
     if !int_type.fits(num) {
         context.error(
+            ErrorCode::ConstEval,
             &format!("literal out of range for `{int_type}`"),
             span,
             &format!("does not fit into type `{int_type}`"),
@@ -2123,6 +2199,7 @@ fn expr_comp_operation(
             expect_expr_type(context, right, left_ty, false)?;
         } else {
             context.error(
+                ErrorCode::InvalidOperation,
                 &format!(
                     "`{}` type can't be compared with the `{}` operator",
                     left_ty.display(context.db()),
@@ -2160,6 +2237,7 @@ fn expr_ternary(
             if try_coerce_type(context, Some(else_expr), else_attr.typ, if_expr_ty, false).is_err()
             {
                 context.fancy_error(
+                    ErrorCode::MismatchedTypes,
                     "`if` and `else` values must have same type",
                     vec![
                         Label::primary(
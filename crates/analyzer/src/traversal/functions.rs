@@ -6,7 +6,7 @@ use crate::namespace::scopes::{BlockScope, BlockScopeType};
 use crate::namespace::types::{Type, TypeId};
 use crate::pattern_analysis::PatternMatrix;
 use crate::traversal::{assignments, declarations, expressions, types};
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_parser::ast::{self as fe, LiteralPattern, Pattern};
 use fe_parser::node::{Node, Span};
 use indexmap::map::Entry;
@@ -91,6 +91,7 @@ fn loop_flow_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) {
             _ => unreachable!(),
         };
         scope.error(
+            ErrorCode::InvalidExpression,
             &format!("`{stmt_name}` outside of a loop"),
             stmt.span,
             &format!("`{stmt_name}` can only be used inside of a `for` or `while` loop"),
@@ -170,6 +171,7 @@ fn match_pattern(
         Pattern::WildCard => Ok(IndexMap::new()),
 
         Pattern::Rest => Err(FatalError::new(scope.error(
+            ErrorCode::InvalidExpression,
             "`..` is not allowed here",
             pat.span,
             "rest pattern is only allowed in tuple pattern",
@@ -196,6 +198,7 @@ fn match_pattern(
                     expected_type.display(scope.db())
                 );
                 return Err(FatalError::new(scope.fancy_error(
+                    ErrorCode::InvalidPattern,
                     "mismatched types",
                     vec![Label::primary(pat.span, label_msg)],
                     vec![],
@@ -213,6 +216,7 @@ fn match_pattern(
                 if kind != EnumVariantKind::Unit {
                     let variant_kind_name = kind.display_name();
                     let err = scope.fancy_error(
+                        ErrorCode::InvalidPattern,
                         "expected an unit variant",
                         vec![
                             Label::primary(
@@ -258,6 +262,7 @@ fn match_pattern(
             None => {
                 let path = &path.kind;
                 let err = scope.fancy_error(
+                    ErrorCode::InvalidPattern,
                     &format! {"failed to resolve `{path}`"},
                     vec![Label::primary(
                         pat.span,
@@ -270,6 +275,7 @@ fn match_pattern(
 
             _ => {
                 let err = scope.fancy_error(
+                    ErrorCode::InvalidPattern,
                     "expected enum variant or variable",
                     vec![Label::primary(
                         pat.span,
@@ -286,6 +292,7 @@ fn match_pattern(
                 NamedThing::EnumVariant(variant) => variant,
                 _ => {
                     let err = scope.fancy_error(
+                        ErrorCode::InvalidPattern,
                         "expected enum variant",
                         vec![Label::primary(path.span, "expected enum variant here")],
                         vec![],
@@ -306,6 +313,7 @@ fn match_pattern(
                 EnumVariantKind::Unit => {
                     let variant_kind_name = variant_kind.display_name();
                     let err = scope.fancy_error(
+                        ErrorCode::InvalidPattern,
                         "expected a tuple variant",
                         vec![
                             Label::primary(
@@ -337,6 +345,7 @@ fn match_pattern(
                 }
                 _ => {
                     let err = scope.fancy_error(
+                        ErrorCode::InvalidPattern,
                         "expected struct type",
                         vec![Label::primary(
                             pat.span,
@@ -376,6 +385,7 @@ fn match_pattern(
                 for (subpat, binds) in subpat_binds.iter() {
                     if !binds.contains_key(var) {
                         err = Some(scope.fancy_error(
+                            ErrorCode::InvalidPattern,
                             &format!("variable `{var}` is not bound in all sub patterns"),
                             vec![Label::primary(
                                 subpat.span,
@@ -441,6 +451,7 @@ fn struct_pattern(
         match pat_fields.entry(name.kind.clone()) {
             Entry::Occupied(entry) => {
                 let err = scope.fancy_error(
+                    ErrorCode::InvalidPattern,
                     &format!("duplicate field `{}` bound in the pattern", name.kind),
                     vec![
                         Label::primary(name.span, "multiple uses here"),
@@ -475,6 +486,7 @@ fn struct_pattern(
         let pat = match pat_fields.remove(f_name) {
             Some((_, span)) if !field.is_public(scope.db()) => {
                 let err = scope.fancy_error(
+                    ErrorCode::PrivateItem,
                     &format!("field `{f_name}` is not public field"),
                     vec![
                         Label::primary(span, format!("`{f_name}` is not public")),
@@ -492,6 +504,7 @@ fn struct_pattern(
                     Node::new(Pattern::WildCard, dummy_span)
                 } else {
                     let err = scope.fancy_error(
+                        ErrorCode::InvalidPattern,
                         &format!("missing field `{f_name}` in the pattern"),
                         vec![Label::primary(pat_span, "missing field")],
                         vec![],
@@ -523,6 +536,7 @@ fn tuple_pattern(
         if pat.kind.is_rest() {
             if rest_pat_pos.is_some() {
                 let err = scope.fancy_error(
+                    ErrorCode::InvalidPattern,
                     "multiple rest patterns are not allowed",
                     vec![
                         Label::primary(pat.span, "multiple rest patterns are not allowed"),
@@ -549,7 +563,12 @@ fn tuple_pattern(
             ));
         }
 
-        let err = scope.fancy_error("the number of tuple variant mismatch", labels, vec![]);
+        let err = scope.fancy_error(
+            ErrorCode::InvalidPattern,
+            "the number of tuple variant mismatch",
+            labels,
+            vec![],
+        );
         Err(FatalError::new(err))
     };
 
@@ -583,6 +602,7 @@ fn collect_binds_from_pat_vec(
                 Entry::Occupied(entry) => {
                     let original = entry.get();
                     let err = scope.fancy_error(
+                        ErrorCode::InvalidPattern,
                         "same variable appears in the same pattern",
                         vec![
                             Label::primary(
@@ -630,6 +650,7 @@ fn unsafe_block(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
         fe::FuncStmt::Unsafe(body) => {
             if scope.inherits_type(BlockScopeType::Unsafe) {
                 scope.error(
+                    ErrorCode::Unsafe,
                     "unnecessary `unsafe` block",
                     stmt.span,
                     "this `unsafe` block is nested inside another `unsafe` context",
@@ -665,6 +686,7 @@ fn assert(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), Fatal
                 }
                 _ => {
                     scope.error(
+                        ErrorCode::MismatchedTypes,
                         "`assert` reason must be a string",
                         msg.span,
                         &format!(
@@ -688,6 +710,7 @@ fn revert(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), Fatal
             let error_attr = expressions::expr(scope, error_expr, None)?;
             if !error_attr.typ.deref(scope.db()).is_struct(scope.db()) {
                 scope.error(
+                    ErrorCode::MismatchedTypes,
                     "`revert` error must be a struct",
                     error_expr.span,
                     &format!(
@@ -697,6 +720,7 @@ fn revert(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), Fatal
                 );
             } else if error_attr.typ.is_sptr(scope.db()) {
                 scope.fancy_error(
+                    ErrorCode::StorageValue,
                     "`revert` value must be copied to memory",
                     vec![Label::primary(error_expr.span, "this value is in storage")],
                     vec!["Hint: values located in storage can be copied to memory using the `to_mem` function.".into(),
@@ -728,6 +752,7 @@ fn func_return(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
             }
             Err(TypeCoercionError::Incompatible) => {
                 scope.error(
+                    ErrorCode::MismatchedTypes,
                     &format!(
                         "expected function to return `{}` but was `{}`",
                         expected_type.display(scope.db()),
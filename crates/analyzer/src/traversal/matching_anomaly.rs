@@ -1,3 +1,4 @@
+use fe_common::diagnostics::ErrorCode;
 use std::fmt::Write;
 
 use fe_common::Span;
@@ -21,6 +22,7 @@ pub(super) fn check_match_exhaustiveness(
 ) -> Result<(), FatalError> {
     if arms.is_empty() {
         let err = scope.fancy_error(
+            ErrorCode::InvalidPattern,
             "patterns is not exhaustive",
             vec![Label::primary(
                 match_span,
@@ -35,6 +37,7 @@ pub(super) fn check_match_exhaustiveness(
     match pattern_matrix.find_non_exhaustiveness(scope.db()) {
         Some(pats) => {
             let err = scope.fancy_error(
+                ErrorCode::InvalidPattern,
                 "patterns is not exhaustive",
                 vec![Label::primary(
                     match_span,
@@ -56,6 +59,7 @@ pub(super) fn check_unreachable_pattern(
 ) -> Result<(), FatalError> {
     if arms.is_empty() {
         let err = scope.fancy_error(
+            ErrorCode::InvalidPattern,
             "patterns is not exhaustive",
             vec![Label::primary(
                 match_span,
@@ -71,6 +75,7 @@ pub(super) fn check_unreachable_pattern(
     for (i, arms) in arms.iter().enumerate() {
         if !pattern_matrix.is_row_useful(scope.db(), i) {
             let err = scope.fancy_error(
+                ErrorCode::InvalidPattern,
                 "unreachable pattern ",
                 vec![Label::primary(
                     arms.kind.pat.span,
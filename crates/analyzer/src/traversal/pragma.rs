@@ -1,5 +1,5 @@
 use crate::errors;
-use fe_common::diagnostics::{Diagnostic, Label};
+use fe_common::diagnostics::{Diagnostic, ErrorCode, Label};
 use fe_parser::ast;
 use fe_parser::node::Node;
 use semver::{Version, VersionReq};
@@ -16,6 +16,7 @@ pub fn check_pragma_version(stmt: &Node<ast::Pragma>) -> Option<Diagnostic> {
         None
     } else {
         Some(errors::fancy_error(
+            ErrorCode::VersionMismatch,
             format!(
                 "The current compiler version {actual_version} doesn't match the specified requirement"
             ),
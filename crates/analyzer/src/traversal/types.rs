@@ -11,7 +11,7 @@ use crate::namespace::types::{
     TypeId,
 };
 use crate::traversal::call_args::validate_arg_count;
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_common::utils::humanize::pluralize_conditionally;
 use fe_common::Spanned;
 use fe_parser::ast;
@@ -44,6 +44,7 @@ pub fn try_cast_type(
         (Type::String(from_str), Type::String(into_str)) => {
             if from_str.max_size > into_str.max_size {
                 context.error(
+                    ErrorCode::InvalidType,
                     "string capacity exceeded",
                     from_expr.span,
                     &format!(
@@ -65,6 +66,7 @@ pub fn try_cast_type(
 
             if sign_differs && size_differs {
                 context.error(
+                        ErrorCode::InvalidCast,
                         "Casting between numeric values can change the sign or size but not both at once",
                         from_expr.span,
                         &format!("can not cast from `{}` to `{}` in a single step",
@@ -76,6 +78,7 @@ pub fn try_cast_type(
         (Type::Base(Base::Address), Type::Base(Base::Numeric(into))) => {
             if into != Integer::U256 {
                 context.error(
+                    ErrorCode::InvalidCast,
                     &format!("can't cast `address` to `{into}`"),
                     into_span,
                     "try `u256` here",
@@ -84,6 +87,7 @@ pub fn try_cast_type(
         }
         (Type::SelfContract(_), Type::Base(Base::Address)) => {
             context.error(
+                ErrorCode::InvalidCast,
                 "`self` address must be retrieved via `Context` object",
                 into_span + from_expr.span,
                 "use `ctx.self_address()` here",
@@ -101,6 +105,7 @@ pub fn try_cast_type(
 
         _ => {
             context.error(
+                ErrorCode::MismatchedTypes,
                 &format!(
                     "incorrect type for argument to `{}`",
                     into.display(context.db())
@@ -339,6 +344,7 @@ pub fn apply_generic_type_args(
 
     let args = args.ok_or_else(|| {
         TypeError::new(context.fancy_error(
+            ErrorCode::InvalidType,
             &format!(
                 "missing generic {} for type `{}`",
                 pluralize_conditionally("argument", params.len()),
@@ -377,6 +383,7 @@ pub fn apply_generic_type_args(
 
             (GenericParamKind::Int, ast::GenericArg::TypeDesc(_)) => {
                 Err(TypeError::new(context.fancy_error(
+                    ErrorCode::InvalidType,
                     &format!("`{}` {} must be an integer", generic.name(), param.name),
                     vec![Label::primary(arg.span(), "expected an integer")],
                     vec![],
@@ -408,6 +415,7 @@ pub fn apply_generic_type_args(
                     Ok(GenericArg::Type(typ))
                 } else {
                     Err(TypeError::new(context.error(
+                        ErrorCode::InvalidType,
                         &format!(
                             "`{}` {} must be a primitive type",
                             generic.name(),
@@ -430,6 +438,7 @@ pub fn apply_generic_type_args(
                 GenericParamKind::PrimitiveType | GenericParamKind::AnyType,
                 ast::GenericArg::Int(_) | ast::GenericArg::ConstExpr(_),
             ) => Err(TypeError::new(context.fancy_error(
+                ErrorCode::InvalidType,
                 &format!("`{}` {} must be a type", generic.name(), param.name),
                 vec![Label::primary(arg.span(), "expected a type name")],
                 vec![],
@@ -485,6 +494,7 @@ pub fn resolve_concrete_type_named_thing<T: std::fmt::Display>(
         Some(NamedThing::Item(Item::Type(id))) => {
             if let Some(args) = generic_args {
                 context.fancy_error(
+                    ErrorCode::InvalidType,
                     &format!("`{}` type is not generic", base_desc.kind),
                     vec![Label::primary(
                         args.span,
@@ -499,6 +509,7 @@ pub fn resolve_concrete_type_named_thing<T: std::fmt::Display>(
             apply_generic_type_args(context, generic, base_desc.span, generic_args)
         }
         Some(named_thing) => Err(TypeError::new(context.fancy_error(
+            ErrorCode::InvalidType,
             &format!("`{}` is not a type name", base_desc.kind),
             if let Some(def_span) = named_thing.name_span(context.db()) {
                 vec![
@@ -528,6 +539,7 @@ pub fn resolve_concrete_type_named_thing<T: std::fmt::Display>(
             vec![],
         ))),
         None => Err(TypeError::new(context.error(
+            ErrorCode::UnresolvedName,
             "undefined type",
             base_desc.span,
             &format!("`{}` has not been defined", base_desc.kind),
@@ -555,6 +567,7 @@ pub fn type_desc(
                     Ok(typ) if typ.has_fixed_size(context.db()) => Ok(typ),
                     Err(e) => Err(e),
                     _ => Err(TypeError::new(context.error(
+                        ErrorCode::InvalidType,
                         "tuple elements must have fixed size",
                         typ.span,
                         "this can't be stored in a tuple",
@@ -572,6 +585,7 @@ pub fn type_desc(
             } else {
                 dbg!("Reporting error");
                 Err(TypeError::new(context.error(
+                    ErrorCode::InvalidType,
                     "`Self` can not be used here",
                     desc.span,
                     "",
@@ -612,11 +626,13 @@ pub fn resolve_concrete_trait_named_thing<T: std::fmt::Display>(
     match val {
         Some(NamedThing::Item(Item::Trait(treit))) => Ok(treit),
         Some(NamedThing::Item(Item::Type(ty))) => Err(TypeError::new(context.error(
+            ErrorCode::InvalidType,
             &format!("expected trait, found type `{}`", ty.name(context.db())),
             base_desc.span,
             "not a trait",
         ))),
         Some(named_thing) => Err(TypeError::new(context.fancy_error(
+            ErrorCode::InvalidType,
             &format!("`{}` is not a trait name", base_desc.kind),
             if let Some(def_span) = named_thing.name_span(context.db()) {
                 vec![
@@ -646,6 +662,7 @@ pub fn resolve_concrete_trait_named_thing<T: std::fmt::Display>(
             vec![],
         ))),
         None => Err(TypeError::new(context.error(
+            ErrorCode::UnresolvedName,
             "undefined trait",
             base_desc.span,
             &format!("`{}` has not been defined", base_desc.kind),
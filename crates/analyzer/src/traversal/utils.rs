@@ -1,4 +1,4 @@
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{ErrorCode, Label};
 use fe_common::Span;
 
 use crate::context::{AnalyzerContext, DiagnosticVoucher};
@@ -27,11 +27,13 @@ pub fn add_bin_operations_errors(
 
     match error {
         BinaryOperationError::NotEqualAndUnsigned => context.fancy_error(
+            ErrorCode::InvalidOperation,
             &format!("`{op}` operand types must be equal and unsigned"),
             vec![type_label(db, lspan, ltype), type_label(db, rspan, rtype)],
             vec![],
         ),
         BinaryOperationError::RightIsSigned => context.fancy_error(
+            ErrorCode::InvalidOperation,
             &format!("The right hand side of the `{op}` operation must be unsigned"),
             vec![Label::primary(
                 rspan,
@@ -40,6 +42,7 @@ pub fn add_bin_operations_errors(
             vec![],
         ),
         BinaryOperationError::RightTooLarge => context.fancy_error(
+            ErrorCode::InvalidOperation,
             &format!("incompatible `{op}` operand types"),
             vec![type_label(db, lspan, ltype), type_label(db, rspan, rtype)],
             vec![format!(
@@ -48,11 +51,13 @@ pub fn add_bin_operations_errors(
             )],
         ),
         BinaryOperationError::TypesNotCompatible => context.fancy_error(
+            ErrorCode::InvalidOperation,
             &format!("`{op}` operand types are not compatible"),
             vec![type_label(db, lspan, ltype), type_label(db, rspan, rtype)],
             vec![],
         ),
         BinaryOperationError::TypesNotNumeric => context.fancy_error(
+            ErrorCode::InvalidOperation,
             &format!("`{op}` operands must be numeric"),
             vec![type_label(db, lspan, ltype), type_label(db, rspan, rtype)],
             vec![],
@@ -336,6 +336,7 @@ fn build_snapshot(db: &dyn AnalyzerDb, module: items::ModuleId) -> String {
 fn new_diagnostic(labels: Vec<Label>) -> Diagnostic {
     Diagnostic {
         severity: Severity::Note,
+        code: None,
         message: String::new(),
         labels,
         notes: vec![],
@@ -420,6 +421,7 @@ fn build_display_diagnostic<T: Display>(span: Span, attributes: &T) -> Diagnosti
     let label = Label::primary(span, format!("{attributes}"));
     Diagnostic {
         severity: Severity::Note,
+        code: None,
         message: String::new(),
         labels: vec![label],
         notes: vec![],
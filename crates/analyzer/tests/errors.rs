@@ -266,6 +266,9 @@ test_file! { invalid_impl_location }
 test_file! { invalid_msg_field }
 test_file! { invalid_string_field }
 test_file! { invalid_struct_attribute }
+test_file! { invalid_function_attribute }
+test_file! { invalid_contract_field_attribute }
+test_file! { conflicting_inline_attributes }
 test_file! { invalid_struct_field }
 test_file! { invalid_tuple_field }
 test_file! { invalid_tx_field }
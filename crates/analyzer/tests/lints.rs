@@ -0,0 +1,102 @@
+//! Tests for the lint warnings
+
+use fe_analyzer::namespace::items::{Item, ModuleId, TypeDef};
+use fe_analyzer::TestDb;
+use fe_common::diagnostics::{diagnostics_string, Severity};
+
+fn warning_string(path: &str) -> String {
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, path, test_files::fixture(path));
+
+    assert!(module.diagnostics(&db).is_empty());
+    let warnings = module.lint_diagnostics(&db);
+    assert!(warnings
+        .iter()
+        .all(|warning| warning.severity == Severity::Warning));
+    diagnostics_string(&db, &warnings)
+}
+
+#[test]
+fn lint_warnings() {
+    let warnings = warning_string("lints/warnings.fe");
+    assert!(warnings.contains("unused import `evm`"));
+    assert!(warnings.contains("unused import `min`"));
+    assert!(!warnings.contains("unused import `max`"));
+    assert!(warnings.contains("function `helper` is never used"));
+    assert!(warnings.contains("function `never_called` is never used"));
+    assert!(!warnings.contains("function `used_helper` is never used"));
+    assert!(warnings.contains("warning[E0060]: unused variable `unused`"));
+    assert!(!warnings.contains("unused variable `_ignored`"));
+    assert!(!warnings.contains("unused variable `a`"));
+    assert!(warnings.contains("field `owner` is never read"));
+    assert!(!warnings.contains("field `count` is never read"));
+    assert!(warnings.contains("unreachable code"));
+    assert!(warnings.contains("`#allow(unused_variables)` in front of the item"));
+}
+
+#[test]
+fn used_imports() {
+    let warnings = warning_string("lints/imports.fe");
+    assert!(warnings.is_empty(), "{warnings}");
+}
+
+#[test]
+fn allowed_lints() {
+    let warnings = warning_string("lints/allowed.fe");
+    assert!(warnings.contains("warning[E0065]: unknown lint `unknown`"));
+    assert_eq!(warnings.matches("warning").count(), 1, "{warnings}");
+}
+
+#[test]
+fn combined_attributes() {
+    let path = "lints/attributes.fe";
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, path, test_files::fixture(path));
+    assert!(module.diagnostics(&db).is_empty());
+    assert!(module.lint_diagnostics(&db).is_empty());
+
+    let contract = module.all_contracts(&db)[0];
+    assert!(contract.function(&db, "helper").unwrap().is_inline(&db));
+    let never_called = contract.function(&db, "never_called").unwrap();
+    assert!(never_called.is_noinline(&db));
+    assert!(!never_called.is_inline(&db));
+
+    let transfer = match module.items(&db).get("Transfer") {
+        Some(Item::Type(TypeDef::Struct(struct_))) => *struct_,
+        _ => panic!("no struct `Transfer`"),
+    };
+    assert!(transfer.field(&db, "from").unwrap().is_indexed(&db));
+}
+
+#[test]
+fn security_lints() {
+    let warnings = warning_string("lints/security.fe");
+    assert_eq!(
+        warnings
+            .matches("the result of `raw_call` is not checked")
+            .count(),
+        1,
+        "{warnings}"
+    );
+    assert_eq!(
+        warnings
+            .matches("`tx_origin()` is used for authorization")
+            .count(),
+        2,
+        "{warnings}"
+    );
+    assert_eq!(
+        warnings
+            .matches("the result of `ec_recover` is not checked to be non-zero")
+            .count(),
+        1,
+        "{warnings}"
+    );
+    assert_eq!(
+        warnings.matches("division before multiplication").count(),
+        2,
+        "{warnings}"
+    );
+    assert!(warnings.contains("`#allow(divide_before_multiply)` in front of the item"));
+    assert!(warnings.contains("warning[E0070]: division before multiplication"));
+}
@@ -7,22 +7,35 @@ use fe_analyzer::{
     namespace::items::{ContractId, ModuleId},
     AnalyzerDb,
 };
-use fe_common::db::{SourceDb, SourceDbStorage, Upcast, UpcastMut};
+use fe_common::{
+    db::{SourceDb, SourceDbStorage, Upcast, UpcastMut},
+    diagnostics::Diagnostic,
+};
 use fe_mir::{
     db::{MirDb, MirDbStorage},
     ir::{FunctionBody, FunctionId, FunctionSignature, TypeId},
 };
 
+use crate::options::CodegenOptions;
+
 mod queries;
 
+pub use queries::contract::StorageField;
+
 #[salsa::query_group(CodegenDbStorage)]
 pub trait CodegenDb: MirDb + Upcast<dyn MirDb> + UpcastMut<dyn MirDb> {
+    // Set via `db.set_codegen_options`. `Db::default` sets the default options.
+    #[salsa::input]
+    fn codegen_options(&self) -> CodegenOptions;
+
     #[salsa::invoke(queries::function::legalized_signature)]
     fn codegen_legalized_signature(&self, function_id: FunctionId) -> Rc<FunctionSignature>;
     #[salsa::invoke(queries::function::legalized_body)]
     fn codegen_legalized_body(&self, function_id: FunctionId) -> Rc<FunctionBody>;
     #[salsa::invoke(queries::function::symbol_name)]
     fn codegen_function_symbol_name(&self, function_id: FunctionId) -> Rc<String>;
+    #[salsa::invoke(queries::function::unavailable_intrinsics)]
+    fn codegen_function_unavailable_intrinsics(&self, function_id: FunctionId) -> Rc<[Diagnostic]>;
 
     #[salsa::invoke(queries::types::legalized_type)]
     fn codegen_legalized_type(&self, ty: TypeId) -> TypeId;
@@ -50,6 +63,12 @@ pub trait CodegenDb: MirDb + Upcast<dyn MirDb> + UpcastMut<dyn MirDb> {
     fn codegen_contract_symbol_name(&self, contract: ContractId) -> Rc<String>;
     #[salsa::invoke(queries::contract::deployer_symbol_name)]
     fn codegen_contract_deployer_symbol_name(&self, contract: ContractId) -> Rc<String>;
+    #[salsa::invoke(queries::contract::selector_collisions)]
+    fn codegen_contract_selector_collisions(&self, contract: ContractId) -> Rc<[Diagnostic]>;
+    #[salsa::invoke(queries::contract::unavailable_intrinsics)]
+    fn codegen_contract_unavailable_intrinsics(&self, contract: ContractId) -> Rc<[Diagnostic]>;
+    #[salsa::invoke(queries::contract::storage_layout)]
+    fn codegen_contract_storage_layout(&self, contract: ContractId) -> Rc<[StorageField]>;
 
     #[salsa::invoke(queries::constant::string_symbol_name)]
     fn codegen_constant_string_symbol_name(&self, data: String) -> Rc<String>;
@@ -57,12 +76,21 @@ pub trait CodegenDb: MirDb + Upcast<dyn MirDb> + UpcastMut<dyn MirDb> {
 
 // TODO: Move this to driver.
 #[salsa::database(SourceDbStorage, AnalyzerDbStorage, MirDbStorage, CodegenDbStorage)]
-#[derive(Default)]
 pub struct Db {
     storage: salsa::Storage<Db>,
 }
 impl salsa::Database for Db {}
 
+impl Default for Db {
+    fn default() -> Self {
+        let mut db = Self {
+            storage: salsa::Storage::default(),
+        };
+        db.set_codegen_options(CodegenOptions::default());
+        db
+    }
+}
+
 impl Upcast<dyn MirDb> for Db {
     fn upcast(&self) -> &(dyn MirDb + 'static) {
         self
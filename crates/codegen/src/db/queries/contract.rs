@@ -1,8 +1,23 @@
 use std::rc::Rc;
 
-use fe_analyzer::namespace::items::ContractId;
+use fe_analyzer::namespace::{items::ContractId, types::Type};
+use fe_common::{
+    diagnostics::{Diagnostic, ErrorCode, Label, Severity},
+    utils::keccak,
+};
+use fe_mir::ir::TypeKind;
+use fxhash::FxHashMap;
+use smol_str::SmolStr;
 
-use crate::db::CodegenDb;
+use crate::{db::CodegenDb, yul::slot_size::SLOT_SIZE};
+
+use super::function::collect_unavailable_intrinsics;
+
+/// Signatures whose selectors prefix revert data. A public function sharing one
+/// of these selectors makes its return data indistinguishable from a revert
+/// reason.
+const RESERVED_SELECTOR_SIGNATURES: [&str; 3] =
+    ["Error(string)", "Error(uint256)", "Panic(uint256)"];
 
 pub fn symbol_name(db: &dyn CodegenDb, contract: ContractId) -> Rc<String> {
     let module = contract.module(db.upcast());
@@ -18,3 +33,114 @@ pub fn symbol_name(db: &dyn CodegenDb, contract: ContractId) -> Rc<String> {
 pub fn deployer_symbol_name(db: &dyn CodegenDb, contract: ContractId) -> Rc<String> {
     format!("deploy_{}", symbol_name(db, contract).as_ref()).into()
 }
+
+pub fn selector_collisions(db: &dyn CodegenDb, contract: ContractId) -> Rc<[Diagnostic]> {
+    let reserved: FxHashMap<[u8; 4], &str> = RESERVED_SELECTOR_SIGNATURES
+        .iter()
+        .map(|sig| {
+            let hash = keccak::full_as_bytes(sig.as_bytes());
+            ([hash[0], hash[1], hash[2], hash[3]], *sig)
+        })
+        .collect();
+
+    let mut diags = vec![];
+    let mut seen = FxHashMap::default();
+    for &func in contract.all_functions(db.upcast()).as_ref() {
+        let mir_func = db.mir_lowered_func_signature(func);
+        if !mir_func.linkage(db.upcast()).is_exported() || func.is_constructor(db.upcast()) {
+            continue;
+        }
+
+        let selector = db.codegen_abi_function(mir_func).selector();
+        let raw = selector.selector_raw();
+        let signature = selector.selector_signature().to_string();
+        let span = func.name_span(db.upcast());
+
+        if let Some(reserved_sig) = reserved.get(&raw) {
+            diags.push(Diagnostic {
+                severity: Severity::Error,
+                code: Some(ErrorCode::SelectorCollision),
+                message: format!(
+                    "the selector of `{signature}` collides with the reserved selector of `{reserved_sig}`"
+                ),
+                labels: vec![Label::primary(
+                    span,
+                    format!("`{signature}` has the selector `0x{}`", selector.hex()),
+                )],
+                notes: vec![format!(
+                    "`{reserved_sig}` is used to encode revert data; rename the function or change its parameters"
+                )],
+            });
+        }
+
+        if let Some((prev_sig, prev_span)) = seen.get(&raw) {
+            diags.push(Diagnostic {
+                severity: Severity::Error,
+                code: Some(ErrorCode::SelectorCollision),
+                message: format!(
+                    "function selector collision in contract `{}`",
+                    contract.name(db.upcast())
+                ),
+                labels: vec![
+                    Label::primary(
+                        span,
+                        format!("`{signature}` has the selector `0x{}`", selector.hex()),
+                    ),
+                    Label::secondary(*prev_span, format!("`{prev_sig}` has the same selector")),
+                ],
+                notes: vec!["rename one of the functions or change its parameters".into()],
+            });
+        } else {
+            seen.insert(raw, (signature, span));
+        }
+    }
+
+    diags.into()
+}
+
+pub fn unavailable_intrinsics(db: &dyn CodegenDb, contract: ContractId) -> Rc<[Diagnostic]> {
+    let module = contract.module(db.upcast());
+    let funcs = db.mir_lower_contract_all_functions(contract);
+    collect_unavailable_intrinsics(db, module, funcs.iter().copied()).into()
+}
+
+/// Where a contract field is kept in storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageField {
+    pub name: SmolStr,
+    /// The field's type as MIR prints it.
+    pub ty: String,
+    /// The storage slot the field starts in.
+    pub slot: usize,
+    /// The byte offset of the field in its first slot.
+    pub offset: usize,
+    /// The size of the field in bytes. Maps take up a single slot, and their
+    /// entries are kept at the hash of the key and that slot.
+    pub size: usize,
+}
+
+pub fn storage_layout(db: &dyn CodegenDb, contract: ContractId) -> Rc<[StorageField]> {
+    let ty = Type::SelfContract(contract).id(db.upcast());
+    let ty = db.codegen_legalized_type(db.mir_lowered_type(ty));
+    let fields = match &ty.data(db.upcast()).kind {
+        TypeKind::Contract(def) => def.fields.clone(),
+        _ => unreachable!("a contract is lowered to a contract type"),
+    };
+
+    // The contract's storage starts at offset 0, see
+    // `legalize::body::make_storage_ptr`.
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (name, field_ty))| {
+            let offset = ty.aggregate_elem_offset(db.upcast(), idx, SLOT_SIZE);
+            StorageField {
+                name,
+                ty: field_ty.as_string(db.upcast()),
+                slot: offset / SLOT_SIZE,
+                offset: offset % SLOT_SIZE,
+                size: field_ty.size_of(db.upcast(), SLOT_SIZE),
+            }
+        })
+        .collect()
+}
@@ -3,15 +3,23 @@ use std::rc::Rc;
 use fe_analyzer::{
     display::Displayable,
     namespace::{
-        items::Item,
+        items::{Item, ModuleId},
         types::{Type, TypeId},
     },
 };
-use fe_mir::ir::{FunctionBody, FunctionId, FunctionSignature};
+use fe_common::{
+    diagnostics::{Diagnostic, ErrorCode, Label, Severity},
+    Span,
+};
+use fe_mir::{
+    ir::{inst::InstKind, FunctionBody, FunctionId, FunctionSignature},
+    optimize::{Inliner, PassManager, StorageForward},
+};
+use fxhash::FxHashSet;
 use salsa::InternKey;
 use smol_str::SmolStr;
 
-use crate::{db::CodegenDb, yul::legalize};
+use crate::{db::CodegenDb, options::EvmVersion, yul::legalize};
 
 pub fn legalized_signature(db: &dyn CodegenDb, function: FunctionId) -> Rc<FunctionSignature> {
     let mut sig = function.signature(db.upcast()).as_ref().clone();
@@ -21,10 +29,87 @@ pub fn legalized_signature(db: &dyn CodegenDb, function: FunctionId) -> Rc<Funct
 
 pub fn legalized_body(db: &dyn CodegenDb, function: FunctionId) -> Rc<FunctionBody> {
     let mut body = function.body(db.upcast()).as_ref().clone();
+    let options = db.codegen_options();
+    if options.optimize_mir {
+        let mut pm = PassManager::new();
+        if options.inline_functions {
+            pm.add_pass(Inliner::new());
+        }
+        pm.add_pass(StorageForward::new())
+            .add_simplification_passes();
+        pm.run(db.upcast(), &mut body);
+    }
     legalize::legalize_func_body(db, &mut body);
     body.into()
 }
 
+pub fn unavailable_intrinsics(db: &dyn CodegenDb, function: FunctionId) -> Rc<[Diagnostic]> {
+    let module = function.module(db.upcast());
+    collect_unavailable_intrinsics(db, module, [function]).into()
+}
+
+/// Reports the intrinsics that aren't available in the targeted EVM version
+/// and are reachable from `roots`. An intrinsic used outside of `module`, e.g.
+/// in `std`, is reported at the call in `module` that reaches it.
+pub(super) fn collect_unavailable_intrinsics(
+    db: &dyn CodegenDb,
+    module: ModuleId,
+    roots: impl IntoIterator<Item = FunctionId>,
+) -> Vec<Diagnostic> {
+    let evm_version = db.codegen_options().evm_version;
+
+    let mut diags = vec![];
+    let mut reported = FxHashSet::default();
+    let mut visited = FxHashSet::default();
+    let mut worklist: Vec<(FunctionId, Option<Span>)> =
+        roots.into_iter().map(|func| (func, None)).collect();
+
+    while let Some((func, call_span)) = worklist.pop() {
+        if !visited.insert(func) {
+            continue;
+        }
+
+        let in_module = func.module(db.upcast()) == module;
+        let body = func.body(db.upcast());
+        for block in body.order.iter_block() {
+            for inst in body.order.iter_inst(block) {
+                let inst = body.store.inst_data(inst);
+                let span = if in_module {
+                    inst.source.span
+                } else {
+                    call_span.unwrap_or(inst.source.span)
+                };
+
+                match &inst.kind {
+                    InstKind::Call { func, .. } => worklist.push((*func, Some(span))),
+                    InstKind::YulIntrinsic { op, .. }
+                        if !evm_version.supports(*op) && reported.insert((*op, span)) =>
+                    {
+                        let required = EvmVersion::introducing(*op);
+                        diags.push(Diagnostic {
+                            severity: Severity::Error,
+                            code: Some(ErrorCode::UnavailableIntrinsic),
+                            message: format!(
+                                "`{op}` is not available in the `{evm_version}` EVM version"
+                            ),
+                            labels: vec![Label::primary(
+                                span,
+                                format!("`{op}` requires the `{required}` EVM version or later"),
+                            )],
+                            notes: vec![format!(
+                                "target a later EVM version with `--evm-version {required}` or `evm_version = \"{required}\"` in fe.toml"
+                            )],
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    diags
+}
+
 pub fn symbol_name(db: &dyn CodegenDb, function: FunctionId) -> Rc<String> {
     let module = function.signature(db.upcast()).module_id;
     let module_name = module.name(db.upcast());
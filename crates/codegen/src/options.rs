@@ -0,0 +1,179 @@
+use std::{fmt, path::PathBuf, str::FromStr};
+
+use fe_mir::ir::inst::YulIntrinsicOp;
+
+/// Options that control how Yul code is generated from MIR.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodegenOptions {
+    /// When a contract exports at least this many functions, the runtime
+    /// dispatcher does a binary search over the selectors instead of matching
+    /// them with a single linear `switch`. `None`, the default, always uses
+    /// the linear `switch`.
+    pub dispatcher_search_threshold: Option<usize>,
+
+    /// Release the memory allocated in a loop iteration or a function call
+    /// when it ends, if none of the allocations escape it.
+    pub reclaim_memory: bool,
+
+    /// Run the MIR optimization passes, i.e. constant folding, copy
+    /// propagation, dead code elimination and CFG simplification, on function
+    /// bodies before lowering them to Yul.
+    pub optimize_mir: bool,
+
+    /// Inline small internal functions, and those marked `#inline`, into their
+    /// callers. Only has an effect together with `optimize_mir`.
+    pub inline_functions: bool,
+
+    /// The hardfork the generated code targets. Intrinsics that were
+    /// introduced by a later hardfork are rejected with a diagnostic.
+    pub evm_version: EvmVersion,
+
+    /// The compiler that assembles the generated Yul into bytecode.
+    pub backend: Backend,
+
+    /// How many times the deployed code is expected to run, which solc's
+    /// optimizer weighs code size against execution cost with. `None` uses
+    /// solc's default.
+    pub optimizer_runs: Option<u32>,
+
+    /// The step sequence of solc's Yul optimizer. `None` uses solc's default.
+    pub yul_optimizer_steps: Option<String>,
+
+    /// Tag the Yul code lowered from each MIR instruction with the Fe source
+    /// it comes from, so that solc's source maps can be traced back to it.
+    /// Only has an effect with the `Solc` backend. See
+    /// [`crate::yul::source_map`].
+    pub source_locations: bool,
+
+    /// The solc executable the `Solc` backend runs. `None`, the default, uses
+    /// the solc linked in by the `solc-backend` feature, or the `solc` on
+    /// `PATH` in builds without it.
+    pub solc_path: Option<PathBuf>,
+}
+
+impl CodegenOptions {
+    /// A sensible threshold for `dispatcher_search_threshold`; with fewer
+    /// functions the extra comparisons cost more than they save.
+    pub const DEFAULT_DISPATCHER_SEARCH_THRESHOLD: usize = 8;
+}
+
+/// The EVM hardforks that can be targeted, in chronological order. The
+/// default is the newest one the linked solc supports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EvmVersion {
+    Istanbul,
+    Berlin,
+    London,
+    #[default]
+    Paris,
+    Shanghai,
+    Cancun,
+}
+
+impl EvmVersion {
+    pub const ALL: [EvmVersion; 6] = [
+        EvmVersion::Istanbul,
+        EvmVersion::Berlin,
+        EvmVersion::London,
+        EvmVersion::Paris,
+        EvmVersion::Shanghai,
+        EvmVersion::Cancun,
+    ];
+
+    /// The name of the hardfork as it is spelled in solc's `evmVersion`
+    /// setting.
+    pub fn name(self) -> &'static str {
+        match self {
+            EvmVersion::Istanbul => "istanbul",
+            EvmVersion::Berlin => "berlin",
+            EvmVersion::London => "london",
+            EvmVersion::Paris => "paris",
+            EvmVersion::Shanghai => "shanghai",
+            EvmVersion::Cancun => "cancun",
+        }
+    }
+
+    /// Returns the first hardfork that provides the opcode of `op`.
+    pub fn introducing(op: YulIntrinsicOp) -> EvmVersion {
+        match op {
+            YulIntrinsicOp::Basefee => EvmVersion::London,
+            YulIntrinsicOp::Prevrandao => EvmVersion::Paris,
+            YulIntrinsicOp::Tload | YulIntrinsicOp::Tstore | YulIntrinsicOp::Mcopy => {
+                EvmVersion::Cancun
+            }
+            _ => EvmVersion::Istanbul,
+        }
+    }
+
+    pub fn supports(self, op: YulIntrinsicOp) -> bool {
+        Self::introducing(op) <= self
+    }
+}
+
+impl fmt::Display for EvmVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for EvmVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|version| version.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|version| version.name()).collect();
+                format!(
+                    "unknown EVM version `{s}`, expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The compilers that can assemble Yul into EVM bytecode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// solc, which optimizes the bytecode.
+    #[default]
+    Solc,
+    /// The unoptimizing assembler of `fe_yulc::native`, which doesn't need
+    /// solc.
+    Native,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 2] = [Backend::Solc, Backend::Native];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Solc => "solc",
+            Backend::Native => "native",
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Backend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|backend| backend.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|backend| backend.name()).collect();
+                format!(
+                    "unknown backend `{s}`, expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
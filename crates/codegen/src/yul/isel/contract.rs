@@ -124,26 +124,76 @@ fn make_dispatcher(
     context: &mut Context,
     funcs: &[FunctionId],
 ) -> yul::Statement {
-    let arms = funcs
+    let mut arms = funcs
         .iter()
         .map(|func| dispatch_arm(db, context, *func))
         .collect::<Vec<_>>();
 
     if arms.is_empty() {
-        statement! { return(0, 0) }
+        return statement! { return(0, 0) };
+    }
+
+    let selector = YulVariable::new("selector");
+    let use_search = db
+        .codegen_options()
+        .dispatcher_search_threshold
+        .map_or(false, |threshold| arms.len() >= threshold);
+    let dispatch = if use_search {
+        arms.sort_by_key(|(selector, _)| *selector);
+        make_dispatch_search(&selector, arms)
     } else {
-        let selector = expression! {
-            and((shr((sub(256, 32)), (calldataload(0)))), 0xffffffff)
-        };
-        switch! {
-            switch ([selector])
-            [arms...]
-            (default { (return(0, 0)) })
-        }
+        make_dispatch_switch(&selector, arms)
+    };
+
+    // Calldata shorter than a selector is zero padded by `calldataload`, so
+    // it must not be allowed to match a (partially zero) selector.
+    block_statement! {
+        (if (lt((calldatasize()), 4)) { (return(0, 0)) })
+        (let [selector.ident()] := and((shr((sub(256, 32)), (calldataload(0)))), 0xffffffff))
+        [dispatch...]
+    }
+}
+
+/// The largest number of selectors that are matched by a single `switch` in
+/// the binary search dispatcher.
+const DISPATCH_SEARCH_LEAF_SIZE: usize = 4;
+
+fn make_dispatch_switch(
+    selector: &YulVariable,
+    arms: Vec<(u32, yul::Case)>,
+) -> Vec<yul::Statement> {
+    let arms: Vec<_> = arms.into_iter().map(|(_, arm)| arm).collect();
+    vec![switch! {
+        switch ([selector.expr()])
+        [arms...]
+        (default { (return(0, 0)) })
+    }]
+}
+
+/// Splits the sorted `arms` in half until at most `DISPATCH_SEARCH_LEAF_SIZE`
+/// selectors remain, which are then matched by a `switch`. Every arm and the
+/// default case end with `return`, so the statements following an `if` are
+/// only reached when its condition doesn't hold.
+fn make_dispatch_search(
+    selector: &YulVariable,
+    mut arms: Vec<(u32, yul::Case)>,
+) -> Vec<yul::Statement> {
+    if arms.len() <= DISPATCH_SEARCH_LEAF_SIZE {
+        return make_dispatch_switch(selector, arms);
     }
+
+    let upper = arms.split_off(arms.len() / 2);
+    let pivot = literal_expression! { (format!("{:#010x}", upper[0].0)) };
+    let lower = make_dispatch_search(selector, arms);
+    let mut stmts = vec![yul::Statement::If(yul::If {
+        expression: expression! { lt([selector.expr()], [pivot]) },
+        block: block! { [lower...] },
+    })];
+    stmts.extend(make_dispatch_search(selector, upper));
+    stmts
 }
 
-fn dispatch_arm(db: &dyn CodegenDb, context: &mut Context, func: FunctionId) -> yul::Case {
+fn dispatch_arm(db: &dyn CodegenDb, context: &mut Context, func: FunctionId) -> (u32, yul::Case) {
     context.function_dependency.insert(func);
     let func_sig = db.codegen_legalized_signature(func);
     let mut param_vars = Vec::with_capacity(func_sig.params.len());
@@ -198,13 +248,16 @@ fn dispatch_arm(db: &dyn CodegenDb, context: &mut Context, func: FunctionId) ->
     };
 
     let abi_sig = db.codegen_abi_function(func);
-    let selector = literal! { (format!("0x{}", abi_sig.selector().hex())) };
-    case! {
+    let selector = abi_sig.selector();
+    let selector_value = u32::from_be_bytes(selector.selector_raw());
+    let selector = literal! { (format!("0x{}", selector.hex())) };
+    let arm = case! {
         case [selector] {
             [decode_params...]
             [call_and_encode_return...]
         }
-    }
+    };
+    (selector_value, arm)
 }
 
 fn make_init(
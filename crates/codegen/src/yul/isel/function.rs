@@ -7,13 +7,14 @@ use fe_common::numeric::to_hex_str;
 use fe_abi::function::{AbiFunction, AbiFunctionType};
 use fe_common::db::Upcast;
 use fe_mir::{
+    analysis::{ControlFlowGraph, DomTree, EscapeAnalysis, LoopTree},
     ir::{
         self,
         constant::ConstantValue,
         inst::{BinOp, CallType, CastKind, InstKind, UnOp},
         value::AssignableValue,
-        Constant, FunctionBody, FunctionId, FunctionSignature, InstId, Type, TypeId, TypeKind,
-        Value, ValueId,
+        BasicBlockId, Constant, FunctionBody, FunctionId, FunctionSignature, InstId, Type, TypeId,
+        TypeKind, Value, ValueId,
     },
     pretty_print::PrettyPrint,
 };
@@ -26,11 +27,12 @@ use yultsur::{
 
 use crate::{
     db::CodegenDb,
+    options::Backend,
     yul::isel::inst_order::StructuralInst,
     yul::slot_size::{function_hash_type, yul_primitive_type, SLOT_SIZE},
     yul::{
         runtime::{self, RuntimeProvider},
-        YulVariable,
+        source_map, YulVariable,
     },
 };
 
@@ -55,6 +57,10 @@ struct FuncLowerHelper<'db, 'a> {
     body: &'a FunctionBody,
     ret_value: Option<yul::Identifier>,
     sink: Vec<yul::Statement>,
+    escape_analysis: EscapeAnalysis,
+    /// Whether to tag the code of each instruction with its source, see
+    /// [`source_map`].
+    source_locations: bool,
 }
 
 impl<'db, 'a> FuncLowerHelper<'db, 'a> {
@@ -83,6 +89,18 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
             None
         };
 
+        let escape_analysis = if db.codegen_options().reclaim_memory {
+            let cfg = ControlFlowGraph::compute(body);
+            let domtree = DomTree::compute(&cfg);
+            let loop_tree = LoopTree::compute(&cfg, &domtree);
+            EscapeAnalysis::compute(db.upcast(), body, &cfg, &loop_tree)
+        } else {
+            EscapeAnalysis::default()
+        };
+
+        let options = db.codegen_options();
+        let source_locations = options.source_locations && options.backend == Backend::Solc;
+
         Self {
             db,
             ctx,
@@ -92,6 +110,8 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
             body,
             ret_value,
             sink: Vec::new(),
+            escape_analysis,
+            source_locations,
         }
     }
 
@@ -124,9 +144,19 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
     fn lower_body(mut self) -> yul::Block {
         let inst_order = InstSerializer::new(self.body).serialize();
 
+        if self.escape_analysis.is_function_reclaimable() {
+            let scope_start_ptr = self.ctx.runtime.avail(self.db);
+            self.sink.push(statement! {
+                let [function_scope().ident()] := [scope_start_ptr]
+            });
+        }
+
         for inst in inst_order {
             self.lower_structural_inst(inst)
         }
+        if self.source_locations {
+            self.sink.push(source_map::marker(None));
+        }
 
         yul::Block {
             statements: self.sink,
@@ -148,8 +178,8 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
                 let switch_block = self.lower_switch(scrutinee, table, default);
                 self.sink.push(switch_block)
             }
-            StructuralInst::For { body } => {
-                let for_block = self.lower_for(body);
+            StructuralInst::For { header, body } => {
+                let for_block = self.lower_for(header, body);
                 self.sink.push(for_block)
             }
             StructuralInst::Break => self.sink.push(yul::Statement::Break),
@@ -158,6 +188,12 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
     }
 
     fn lower_inst(&mut self, inst: InstId) {
+        if self.source_locations {
+            let source = &self.body.store.inst_data(inst).source;
+            let span = (!source.is_dummy()).then_some(source.span);
+            self.sink.push(source_map::marker(span));
+        }
+
         if let Some(lhs) = self.body.store.inst_result(inst) {
             self.declare_assignable_value(lhs)
         }
@@ -375,6 +411,10 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
                     let ret_value = self.ret_value.clone().unwrap();
                     self.sink.push(statement! {[ret_value] := [arg]});
                 }
+                if self.escape_analysis.is_function_reclaimable() {
+                    let free = self.ctx.runtime.free(self.db, function_scope().expr());
+                    self.sink.push(yul::Statement::Expression(free));
+                }
                 self.sink.push(yul::Statement::Leave)
             }
 
@@ -445,6 +485,9 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
             InstKind::Jump { .. } | InstKind::Branch { .. } | InstKind::Switch { .. } => {
                 unreachable!()
             }
+
+            // Phis are lowered into copies in legalization.
+            InstKind::Phi { .. } => unreachable!(),
         }
     }
 
@@ -519,7 +562,7 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
         body
     }
 
-    fn lower_for(&mut self, body: Vec<StructuralInst>) -> yul::Statement {
+    fn lower_for(&mut self, header: BasicBlockId, body: Vec<StructuralInst>) -> yul::Statement {
         let mut body_stmts = vec![];
         std::mem::swap(&mut self.sink, &mut body_stmts);
         for inst in body {
@@ -527,12 +570,30 @@ impl<'db, 'a> FuncLowerHelper<'db, 'a> {
         }
         std::mem::swap(&mut self.sink, &mut body_stmts);
 
-        block_statement! {(
-            for {} (1) {}
+        if !self.escape_analysis.is_loop_reclaimable(header) {
+            return block_statement! {(
+                for {} (1) {}
+                {
+                    [body_stmts...]
+                }
+            )};
+        }
+
+        // Nothing allocated in an iteration outlives it, so every iteration
+        // starts, and the loop ends, with the free memory pointer the loop was
+        // entered with.
+        let scope_start = YulVariable::new(format!("$mem_scope_{}", header.index()));
+        let scope_start_ptr = self.ctx.runtime.avail(self.db);
+        let free = self.ctx.runtime.free(self.db, scope_start.expr());
+        body_stmts.insert(0, yul::Statement::Expression(free.clone()));
+        block_statement! {
+            (let [scope_start.ident()] := [scope_start_ptr])
+            (for {} (1) {}
             {
                 [body_stmts...]
-            }
-        )}
+            })
+            ([yul::Statement::Expression(free)])
+        }
     }
 
     fn lower_assign(&mut self, lhs: &AssignableValue, rhs: ValueId) -> yul::Statement {
@@ -968,6 +1029,11 @@ impl ScopedValueMap {
     }
 }
 
+/// The variable holding the free memory pointer a function was entered with.
+fn function_scope() -> YulVariable<'static> {
+    YulVariable::new("$mem_scope")
+}
+
 fn bit_mask(byte_size: usize) -> usize {
     (1 << (byte_size * 8)) - 1
 }
@@ -26,6 +26,7 @@ pub(super) enum StructuralInst {
     },
 
     For {
+        header: BasicBlockId,
         body: Vec<StructuralInst>,
     },
 
@@ -82,7 +83,10 @@ impl<'a> InstSerializer<'a> {
                 let mut body = vec![];
                 self.serialize_block(block, &mut body);
                 self.exit_scope();
-                order.push(StructuralInst::For { body });
+                order.push(StructuralInst::For {
+                    header: block,
+                    body,
+                });
 
                 match loop_exit {
                     Some(exit)
@@ -709,7 +713,7 @@ mod tests {
         insts: &mut impl Iterator<Item = StructuralInst>,
     ) -> impl Iterator<Item = StructuralInst> {
         match insts.next().unwrap() {
-            StructuralInst::For { body } => body.into_iter(),
+            StructuralInst::For { body, .. } => body.into_iter(),
             _ => panic!("expect if inst"),
         }
     }
@@ -7,9 +7,18 @@ use fe_mir::ir::{
 
 use crate::db::CodegenDb;
 
-use super::critical_edge::CriticalEdgeSplitter;
+use super::{critical_edge::CriticalEdgeSplitter, ssa::destruct_ssa};
 
 pub fn legalize_func_body(db: &dyn CodegenDb, body: &mut FunctionBody) {
+    // Only the MIR optimizations introduce phis and temporaries that are used
+    // outside of the block that defines them.
+    let has_phis = body
+        .order
+        .iter_block()
+        .any(|block| body.phis(block).next().is_some());
+    if has_phis || db.codegen_options().optimize_mir {
+        destruct_ssa(db, body);
+    }
     CriticalEdgeSplitter::new().run(body);
     legalize_func_arg(db, body);
 
@@ -1,6 +1,7 @@
 mod body;
 mod critical_edge;
 mod signature;
+mod ssa;
 
 pub use body::legalize_func_body;
 pub use signature::legalize_func_signature;
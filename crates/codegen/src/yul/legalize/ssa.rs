@@ -0,0 +1,341 @@
+use fe_mir::ir::{
+    inst::InstKind,
+    value::{AssignableValue, Local},
+    FunctionBody, Inst, SourceInfo, TypeId, Value, ValueId,
+};
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::db::CodegenDb;
+
+/// Lowers a body out of SSA form, so that every value is in scope where it's
+/// used once the body is serialized into structured control flow.
+///
+/// A phi is replaced with a copy from a new local, which is assigned the
+/// argument of the phi at the end of each predecessor. A temporary of a
+/// primitive type that's used outside of the block that defines it becomes a
+/// local as well. All these locals are declared in the entry block.
+///
+/// Temporaries of other types are left alone: such a temporary is a pointer to
+/// memory that codegen allocates where it's declared, so declaring it in the
+/// entry block would make every execution of its definition, eg. in a loop,
+/// overwrite the same memory.
+pub fn destruct_ssa(db: &dyn CodegenDb, body: &mut FunctionBody) {
+    lower_phis(body);
+    hoist_cross_block_temporaries(db, body);
+}
+
+fn lower_phis(body: &mut FunctionBody) {
+    let blocks: Vec<_> = body.order.iter_block().collect();
+    for block in blocks {
+        let phis: Vec<_> = body.phis(block).collect();
+        for phi in phis {
+            let (args, preds) = match &body.store.inst_data(phi).kind {
+                InstKind::Phi { args, blocks } => (args.clone(), blocks.clone()),
+                _ => unreachable!(),
+            };
+            let result = body.store.inst_result(phi).unwrap().value_id().unwrap();
+            let copy = declare_local(body, "$phi", body.store.value_ty(result));
+
+            for (arg, pred) in args.into_iter().zip(preds) {
+                let source = body.store.inst_data(phi).source.clone();
+                let bind = body
+                    .store
+                    .store_inst(Inst::new(InstKind::Bind { src: arg }, source));
+                body.store.map_result(bind, copy.into());
+                let terminator = body.order.terminator(&body.store, pred).unwrap();
+                body.order.insert_inst_before_inst(bind, terminator);
+            }
+            body.store.inst_data_mut(phi).kind = InstKind::Bind { src: copy };
+        }
+    }
+}
+
+fn hoist_cross_block_temporaries(db: &dyn CodegenDb, body: &mut FunctionBody) {
+    let mut def_blocks = FxHashMap::default();
+    for block in body.order.iter_block() {
+        for inst in body.order.iter_inst(block) {
+            if let Some(AssignableValue::Value(value)) = body.store.inst_result(inst) {
+                if matches!(body.store.value_data(*value), Value::Temporary { .. }) {
+                    def_blocks.insert(*value, block);
+                }
+            }
+        }
+    }
+
+    let mut hoisted = FxHashSet::default();
+    for block in body.order.iter_block() {
+        for inst in body.order.iter_inst(block) {
+            let mut values: Vec<_> = body.store.inst_data(inst).args().collect();
+            if let Some(result) = body.store.inst_result(inst) {
+                collect_assignable_uses(result, &mut values);
+            }
+            for value in values {
+                let is_cross_block =
+                    matches!(def_blocks.get(&value), Some(def_block) if *def_block != block);
+                let ty = body.store.value_ty(value);
+                if is_cross_block && ty.is_primitive(db.upcast()) && !ty.is_unit(db.upcast()) {
+                    hoisted.insert(value);
+                }
+            }
+        }
+    }
+    if hoisted.is_empty() {
+        return;
+    }
+
+    let mut hoisted: Vec<_> = hoisted.into_iter().collect();
+    hoisted.sort_by_key(|value| value.index());
+    let mut locals = FxHashMap::default();
+    for value in hoisted {
+        let local = declare_local(body, "$ssa", body.store.value_ty(value));
+        locals.insert(value, local);
+    }
+
+    let insts: Vec<_> = body
+        .order
+        .iter_block()
+        .flat_map(|block| body.order.iter_inst(block))
+        .collect();
+    for inst in insts {
+        let mut inst_data = body.store.inst_data(inst).clone();
+        for arg in inst_data.args_mut() {
+            if let Some(local) = locals.get(arg) {
+                *arg = *local;
+            }
+        }
+        body.store.replace_inst(inst, inst_data);
+
+        if let Some(mut result) = body.store.inst_result(inst).cloned() {
+            replace_assignable(&mut result, &locals);
+            body.store.map_result(inst, result);
+        }
+    }
+}
+
+/// Declares a new local at the beginning of the entry block.
+fn declare_local(body: &mut FunctionBody, name: &str, ty: TypeId) -> ValueId {
+    let local = Local::tmp_local(name.into(), ty);
+    let local = body.store.store_value(Value::Local(local));
+    let declare = body
+        .store
+        .store_inst(Inst::new(InstKind::Declare { local }, SourceInfo::dummy()));
+    let entry = body.order.entry();
+    body.order.prepend_inst(declare, entry);
+    local
+}
+
+fn collect_assignable_uses(value: &AssignableValue, uses: &mut Vec<ValueId>) {
+    match value {
+        AssignableValue::Value(_) => {}
+        AssignableValue::Aggregate { lhs, idx } => {
+            uses.push(*idx);
+            collect_assignable_uses(lhs, uses);
+        }
+        AssignableValue::Map { lhs, key } => {
+            uses.push(*key);
+            collect_assignable_uses(lhs, uses);
+        }
+    }
+}
+
+/// Replaces the hoisted values in `value`, including the value assigned to,
+/// with their locals.
+fn replace_assignable(value: &mut AssignableValue, locals: &FxHashMap<ValueId, ValueId>) {
+    match value {
+        AssignableValue::Value(value) => {
+            if let Some(local) = locals.get(value) {
+                *value = *local;
+            }
+        }
+        AssignableValue::Aggregate { lhs, idx } => {
+            if let Some(local) = locals.get(idx) {
+                *idx = *local;
+            }
+            replace_assignable(lhs, locals);
+        }
+        AssignableValue::Map { lhs, key } => {
+            if let Some(local) = locals.get(key) {
+                *key = *local;
+            }
+            replace_assignable(lhs, locals);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fe_mir::{
+        db::MirDb,
+        ir::{
+            body_builder::BodyBuilder, types::ArrayDef, BasicBlockId, FunctionId, InstId, Type,
+            TypeKind,
+        },
+    };
+
+    use super::*;
+    use crate::db::Db;
+
+    fn body_builder() -> BodyBuilder {
+        BodyBuilder::new(FunctionId(0), SourceInfo::dummy())
+    }
+
+    fn make_ty(db: &Db, kind: TypeKind) -> TypeId {
+        db.mir_intern_type(Type::new(kind, None).into())
+    }
+
+    /// Maps the result of `inst` to a new temporary.
+    fn map_to_tmp(builder: &mut BodyBuilder, inst: InstId, ty: TypeId) -> ValueId {
+        let tmp = builder.make_value(Value::Temporary { inst, ty });
+        builder.map_result(inst, tmp.into());
+        tmp
+    }
+
+    fn insts(body: &FunctionBody, block: BasicBlockId) -> Vec<InstKind> {
+        body.order
+            .iter_inst(block)
+            .map(|inst| body.store.inst_data(inst).kind.clone())
+            .collect()
+    }
+
+    fn is_local(body: &FunctionBody, value: ValueId) -> bool {
+        matches!(body.store.value_data(value), Value::Local(_))
+    }
+
+    #[test]
+    fn phi() {
+        let db = Db::default();
+        let u256 = make_ty(&db, TypeKind::U256);
+        let bool_ty = make_ty(&db, TypeKind::Bool);
+        let mut builder = body_builder();
+
+        let entry = builder.current_block();
+        let then_block = builder.make_block();
+        let merge_block = builder.make_block();
+
+        let cond = builder.make_imm_from_bool(true, bool_ty);
+        let one = builder.make_imm(1.into(), u256);
+        let two = builder.make_imm(2.into(), u256);
+        builder.branch(cond, then_block, merge_block, SourceInfo::dummy());
+
+        builder.move_to_block(then_block);
+        builder.jump(merge_block, SourceInfo::dummy());
+
+        builder.move_to_block(merge_block);
+        let phi = builder.phi(vec![one, two], vec![entry, then_block], SourceInfo::dummy());
+        let x = map_to_tmp(&mut builder, phi, u256);
+        builder.ret(x, SourceInfo::dummy());
+
+        let mut body = builder.build();
+        destruct_ssa(&db, &mut body);
+
+        // The phi reads a local that each predecessor assigns before it
+        // branches.
+        let copy = match &body.store.inst_data(phi).kind {
+            InstKind::Bind { src } => *src,
+            kind => panic!("expected the phi to be replaced, found {kind:?}"),
+        };
+        assert!(is_local(&body, copy));
+        assert!(matches!(insts(&body, entry)[0], InstKind::Declare { local } if local == copy));
+        for (pred, arg) in [(entry, one), (then_block, two)] {
+            let insts: Vec<_> = body.order.iter_inst(pred).collect();
+            let assign = insts[insts.len() - 2];
+            assert_eq!(
+                body.store.inst_data(assign).kind,
+                InstKind::Bind { src: arg }
+            );
+            assert_eq!(
+                body.store.inst_result(assign),
+                Some(&AssignableValue::from(copy))
+            );
+        }
+    }
+
+    #[test]
+    fn cross_block_primitive_temporary() {
+        let db = Db::default();
+        let u256 = make_ty(&db, TypeKind::U256);
+        let mut builder = body_builder();
+
+        let entry = builder.current_block();
+        let exit = builder.make_block();
+
+        let one = builder.make_imm(1.into(), u256);
+        let add = builder.add(one, one, SourceInfo::dummy());
+        let sum = map_to_tmp(&mut builder, add, u256);
+        let local_use = builder.mul(sum, one, SourceInfo::dummy());
+        map_to_tmp(&mut builder, local_use, u256);
+        builder.jump(exit, SourceInfo::dummy());
+
+        builder.move_to_block(exit);
+        let ret = builder.ret(sum, SourceInfo::dummy());
+
+        let mut body = builder.build();
+        destruct_ssa(&db, &mut body);
+
+        // The sum is assigned to a local, which is used in both blocks.
+        let local = match body.store.inst_result(add) {
+            Some(AssignableValue::Value(value)) => *value,
+            result => panic!("unexpected result {result:?}"),
+        };
+        assert!(is_local(&body, local));
+        assert!(
+            matches!(insts(&body, entry)[0], InstKind::Declare { local: declared } if declared == local)
+        );
+        assert!(body.store.inst_data(local_use).args().eq([local, one]));
+        assert!(body.store.inst_data(ret).args().eq([local]));
+    }
+
+    #[test]
+    fn cross_block_aggregate_temporary() {
+        let db = Db::default();
+        let u256 = make_ty(&db, TypeKind::U256);
+        let array = make_ty(
+            &db,
+            TypeKind::Array(ArrayDef {
+                elem_ty: u256,
+                len: 2,
+            }),
+        );
+        let mut builder = body_builder();
+
+        let header = builder.make_block();
+        let exit = builder.make_block();
+
+        builder.jump(header, SourceInfo::dummy());
+
+        // Each iteration constructs an array in memory of its own, so the
+        // temporary can't be declared once in the entry block.
+        builder.move_to_block(header);
+        let one = builder.make_imm(1.into(), u256);
+        let construct = builder.aggregate_construct(array, vec![one, one], SourceInfo::dummy());
+        let pair = map_to_tmp(&mut builder, construct, array);
+        builder.jump(exit, SourceInfo::dummy());
+
+        builder.move_to_block(exit);
+        let first = builder.make_imm(0.into(), u256);
+        let access = builder.aggregate_access(pair, vec![first], SourceInfo::dummy());
+        map_to_tmp(&mut builder, access, u256);
+        let unit = builder.make_unit(make_ty(&db, TypeKind::Unit));
+        builder.ret(unit, SourceInfo::dummy());
+
+        let mut body = builder.build();
+        let insts_before: Vec<_> = body
+            .order
+            .iter_block()
+            .flat_map(|block| insts(&body, block))
+            .collect();
+        destruct_ssa(&db, &mut body);
+
+        assert_eq!(
+            body.store.inst_result(construct),
+            Some(&AssignableValue::from(pair))
+        );
+        assert!(body.store.inst_data(access).args().eq([pair, first]));
+        let insts_after: Vec<_> = body
+            .order
+            .iter_block()
+            .flat_map(|block| insts(&body, block))
+            .collect();
+        assert_eq!(insts_before, insts_after);
+    }
+}
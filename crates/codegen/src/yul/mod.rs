@@ -3,8 +3,9 @@ use std::borrow::Cow;
 pub mod isel;
 pub mod legalize;
 pub mod runtime;
+pub mod source_map;
 
-mod slot_size;
+pub(crate) mod slot_size;
 
 use yultsur::*;
 
@@ -401,6 +401,14 @@ pub(super) fn make_abi_decode(
         if field_abi_ty.is_static() {
             body.push(statement!{ [ret_value.ident()] := [provider.abi_decode_static(db, header_ptr.expr(), field_ty, abi_loc)] });
         } else {
+            // The head of a dynamic field must point to exactly where the
+            // previous fields' data ends; anything else is a non-canonical
+            // encoding.
+            let head_offset =
+                provider.abi_decode_static(db, header_ptr.expr(), yul_primitive_type(db), abi_loc);
+            body.extend(statements! {
+                (if (iszero((eq([head_offset], [data_offset.expr()])))) { [revert_with_invalid_abi_data(provider, db)] })
+            });
             let identifiers = identifiers! {
                 [ret_value.ident()]
                 [tmp_offset.ident()]
@@ -565,6 +573,13 @@ fn make_abi_decode_primitive_type(
     let ty_size_bits = ty.size_of(db.upcast(), SLOT_SIZE) * 8;
     let validation = if ty_size_bits == 256 {
         statements! {}
+    } else if ty.is_bool(db.upcast()) {
+        // `bool` must be encoded as exactly `0` or `1`.
+        statements! {
+            (if (gt([ret.expr()], 1)) {
+                [revert_with_invalid_abi_data(provider, db)]
+            })
+        }
     } else if ty.is_signed(db.upcast()) {
         let shift_num = literal_expression! { ( ty_size_bits - 1) };
         let tmp1 = YulVariable::new("tmp1");
@@ -47,6 +47,18 @@ pub(super) fn make_avail(func_name: &str) -> RuntimeFunction {
     RuntimeFunction::from_statement(func)
 }
 
+pub(super) fn make_free(func_name: &str) -> RuntimeFunction {
+    let func_name = YulVariable::new(func_name);
+    let free_address_ptr = literal_expression! {(FREE_MEMORY_ADDRESS_STORE)};
+    let func = function_definition! {
+        function [func_name.ident()](ptr) {
+            (mstore([free_address_ptr], ptr))
+        }
+    };
+
+    RuntimeFunction::from_statement(func)
+}
+
 pub(super) fn make_mcopym(func_name: &str) -> RuntimeFunction {
     let func_name = YulVariable::new(func_name);
     let src = YulVariable::new("src");
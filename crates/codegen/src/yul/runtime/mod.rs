@@ -9,7 +9,7 @@ use std::fmt::Write;
 
 use fe_abi::types::AbiType;
 use fe_analyzer::namespace::items::ContractId;
-use fe_mir::ir::{types::ArrayDef, FunctionId, TypeId, TypeKind};
+use fe_mir::ir::{inst::YulIntrinsicOp, types::ArrayDef, FunctionId, TypeId, TypeKind};
 use indexmap::IndexMap;
 use yultsur::*;
 
@@ -26,6 +26,10 @@ pub trait RuntimeProvider {
 
     fn avail(&mut self, db: &dyn CodegenDb) -> yul::Expression;
 
+    /// Resets the free memory pointer to `ptr`, a value obtained from `avail`,
+    /// which releases all memory allocated since.
+    fn free(&mut self, db: &dyn CodegenDb, ptr: yul::Expression) -> yul::Expression;
+
     fn create(
         &mut self,
         db: &dyn CodegenDb,
@@ -269,6 +273,12 @@ impl RuntimeProvider for DefaultRuntimeProvider {
         self.create_then_call(name, arg, |_| data::make_avail(name))
     }
 
+    fn free(&mut self, _db: &dyn CodegenDb, ptr: yul::Expression) -> yul::Expression {
+        let name = "$free";
+        let arg = vec![ptr];
+        self.create_then_call(name, arg, |_| data::make_free(name))
+    }
+
     fn create(
         &mut self,
         db: &dyn CodegenDb,
@@ -441,13 +451,21 @@ impl RuntimeProvider for DefaultRuntimeProvider {
 
     fn ptr_copy(
         &mut self,
-        _db: &dyn CodegenDb,
+        db: &dyn CodegenDb,
         src: yul::Expression,
         dst: yul::Expression,
         size: yul::Expression,
         is_src_storage: bool,
         is_dst_storage: bool,
     ) -> yul::Expression {
+        let is_mcopy_available = db
+            .codegen_options()
+            .evm_version
+            .supports(YulIntrinsicOp::Mcopy);
+        if !is_src_storage && !is_dst_storage && is_mcopy_available {
+            return expression! { mcopy([dst], [src], [size]) };
+        }
+
         let args = vec![src, dst, size];
         match (is_src_storage, is_dst_storage) {
             (true, true) => {
@@ -0,0 +1,140 @@
+//! Tags Yul code with the Fe source it's lowered from.
+//!
+//! yultsur's AST has no comments, so the tags are calls to marker functions
+//! that isel puts in front of the code of each MIR instruction, e.g.
+//! `$fe_src_3_10_25()` for bytes 10 to 25 of file 3. `$fe_src_none()` marks
+//! code without a Fe source. The markers don't exist in Yul, and are removed
+//! from the printed source with [`YulSourceLocations::strip`] before it's
+//! compiled.
+
+use fe_common::{files::SourceFileId, Span};
+use salsa::{InternId, InternKey};
+use yultsur::{yul, *};
+
+const MARKER_PREFIX: &str = "$fe_src_";
+const NO_SOURCE: &str = "none";
+
+/// Returns the marker of the code lowered from `span`, or of code without a
+/// Fe source.
+pub(crate) fn marker(span: Option<Span>) -> yul::Statement {
+    let tag = match span {
+        Some(span) => format!(
+            "{}_{}_{}",
+            span.file_id.as_intern_id().as_u32(),
+            span.start,
+            span.end
+        ),
+        None => NO_SOURCE.to_string(),
+    };
+    let name = identifier! { (format!("{MARKER_PREFIX}{tag}")) };
+    statement! { ([name]()) }
+}
+
+/// The Fe source of the code in a Yul source, by the offset it starts at.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct YulSourceLocations {
+    markers: Vec<(usize, Option<Span>)>,
+}
+
+impl YulSourceLocations {
+    /// Removes the markers from a printed Yul source. Returns the source
+    /// without them, along with the Fe source of the code at each offset of
+    /// it.
+    pub fn strip(yul_src: &str) -> (String, Self) {
+        let mut stripped = String::with_capacity(yul_src.len());
+        let mut markers = vec![];
+
+        let mut rest = yul_src;
+        while let Some(idx) = rest.find(MARKER_PREFIX) {
+            let (before, marker) = rest.split_at(idx);
+            stripped.push_str(before);
+
+            let tag = marker.find("()").and_then(|tag_end| {
+                parse_tag(&marker[MARKER_PREFIX.len()..tag_end]).map(|span| (tag_end, span))
+            });
+            match tag {
+                Some((tag_end, span)) => {
+                    markers.push((stripped.len(), span));
+                    rest = marker[tag_end + "()".len()..].trim_start();
+                }
+                None => {
+                    stripped.push_str(MARKER_PREFIX);
+                    rest = &marker[MARKER_PREFIX.len()..];
+                }
+            }
+        }
+        stripped.push_str(rest);
+
+        (stripped, Self { markers })
+    }
+
+    /// Returns the Fe source of the Yul code at `offset`, which is the one of
+    /// the closest marker before it.
+    pub fn lookup(&self, offset: usize) -> Option<Span> {
+        let idx = self
+            .markers
+            .partition_point(|(marker_offset, _)| *marker_offset <= offset);
+        idx.checked_sub(1).and_then(|idx| self.markers[idx].1)
+    }
+}
+
+/// Parses the tag of a marker, which is `None` if it isn't one and
+/// `Some(None)` for code without a Fe source.
+fn parse_tag(tag: &str) -> Option<Option<Span>> {
+    if tag == NO_SOURCE {
+        return Some(None);
+    }
+
+    let mut numbers = tag.split('_').map(|number| number.parse::<usize>().ok());
+    let file = numbers.next()??;
+    let start = numbers.next()??;
+    let end = numbers.next()??;
+    if numbers.next().is_some() {
+        return None;
+    }
+
+    let file_id = SourceFileId::from_intern_id(InternId::from(u32::try_from(file).ok()?));
+    Some(Some(Span::new(file_id, start, end)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_markers() {
+        let file_id = SourceFileId::from_intern_id(InternId::from(1u32));
+        let span = Span::new(file_id, 10, 25);
+        let store = statement! { (sstore(0, 1)) };
+        let stop = statement! { (stop()) };
+
+        let tagged = yul::Block {
+            statements: vec![
+                marker(Some(span)),
+                store.clone(),
+                marker(None),
+                stop.clone(),
+            ],
+        };
+        let untagged = yul::Block {
+            statements: vec![store, stop],
+        };
+
+        let (stripped, locations) = YulSourceLocations::strip(&tagged.to_string());
+        assert_eq!(stripped, untagged.to_string());
+        assert_eq!(locations.lookup(0), None);
+        assert_eq!(
+            locations.lookup(stripped.find("sstore").unwrap()),
+            Some(span)
+        );
+        assert_eq!(locations.lookup(stripped.find("stop").unwrap()), None);
+    }
+
+    #[test]
+    fn strip_non_markers() {
+        let yul_src = "{ let $fe_src_x := 1 $fe_src_1_2() }";
+        let (stripped, locations) = YulSourceLocations::strip(yul_src);
+        assert_eq!(stripped, yul_src);
+        assert_eq!(locations, YulSourceLocations::default());
+    }
+}
@@ -1,10 +1,12 @@
 use crate::db::SourceDb;
+pub use crate::error_codes::ErrorCode;
 use crate::files::{SourceFileId, Utf8PathBuf};
 use crate::Span;
 pub use codespan_reporting::diagnostic as cs;
-use codespan_reporting::files::Error as CsError;
+use codespan_reporting::files::{Error as CsError, Files};
 use codespan_reporting::term;
 pub use cs::Severity;
+use serde::Serialize;
 use std::ops::Range;
 use std::rc::Rc;
 use term::termcolor::{BufferWriter, ColorChoice};
@@ -12,6 +14,8 @@ use term::termcolor::{BufferWriter, ColorChoice};
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Diagnostic {
     pub severity: Severity,
+    /// The family of the error, for diagnostics that have one.
+    pub code: Option<ErrorCode>,
     pub message: String,
     pub labels: Vec<Label>,
     pub notes: Vec<String>,
@@ -20,7 +24,7 @@ impl Diagnostic {
     pub fn into_cs(self) -> cs::Diagnostic<SourceFileId> {
         cs::Diagnostic {
             severity: self.severity,
-            code: None,
+            code: self.code.map(|code| code.to_string()),
             message: self.message,
             labels: self.labels.into_iter().map(Label::into_cs_label).collect(),
             notes: self.notes,
@@ -29,6 +33,7 @@ impl Diagnostic {
     pub fn error(message: String) -> Self {
         Self {
             severity: Severity::Error,
+            code: None,
             message,
             labels: vec![],
             notes: vec![],
@@ -116,6 +121,90 @@ pub fn diagnostics_string(db: &dyn SourceDb, diagnostics: &[Diagnostic]) -> Stri
     std::str::from_utf8(buffer.as_slice()).unwrap().to_string()
 }
 
+/// Print the given diagnostics to stdout as JSON, one object per line.
+pub fn print_diagnostics_json(db: &dyn SourceDb, diagnostics: &[Diagnostic]) {
+    for diag in diagnostics {
+        println!("{}", diagnostic_json(db, diag));
+    }
+}
+
+/// Format the given diagnostic as a JSON object, for tools that process the
+/// diagnostics rather than show them.
+pub fn diagnostic_json(db: &dyn SourceDb, diagnostic: &Diagnostic) -> String {
+    let files = SourceDbWrapper(db);
+    let labels = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            let file_id = label.span.file_id;
+            let location = |byte_index| {
+                let location = files
+                    .location(file_id, byte_index)
+                    .expect("label is outside of its file");
+                JsonLocation {
+                    line: location.line_number,
+                    column: location.column_number,
+                }
+            };
+            JsonLabel {
+                style: match label.style {
+                    LabelStyle::Primary => "primary",
+                    LabelStyle::Secondary => "secondary",
+                },
+                file: file_id.path(db).to_string(),
+                start: label.span.start,
+                end: label.span.end,
+                start_location: location(label.span.start),
+                end_location: location(label.span.end),
+                message: &label.message,
+            }
+        })
+        .collect();
+
+    let json = JsonDiagnostic {
+        severity: match diagnostic.severity {
+            Severity::Bug => "bug",
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+            Severity::Help => "help",
+        },
+        code: diagnostic.code.map(ErrorCode::code),
+        message: &diagnostic.message,
+        labels,
+        notes: &diagnostic.notes,
+    };
+    serde_json::to_string(&json).expect("failed to serialize diagnostic")
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    severity: &'static str,
+    code: Option<&'static str>,
+    message: &'a str,
+    labels: Vec<JsonLabel<'a>>,
+    notes: &'a [String],
+}
+
+/// A label with the byte range it covers, and the 1-based line and column
+/// that range starts and ends at.
+#[derive(Serialize)]
+struct JsonLabel<'a> {
+    style: &'static str,
+    file: String,
+    start: usize,
+    end: usize,
+    start_location: JsonLocation,
+    end_location: JsonLocation,
+    message: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonLocation {
+    line: usize,
+    column: usize,
+}
+
 struct SourceDbWrapper<'a>(pub &'a dyn SourceDb);
 
 impl<'a> codespan_reporting::files::Files<'_> for SourceDbWrapper<'a> {
@@ -143,3 +232,36 @@ impl<'a> codespan_reporting::files::Files<'_> for SourceDbWrapper<'a> {
             })
     }
 }
+
+#[test]
+fn test_diagnostic_json() {
+    let mut db = crate::db::TestDb::default();
+    let file_id = SourceFileId::new_local(&mut db, "src/main.fe", "fn f() {\n  x\n}\n".into());
+    let diagnostic = Diagnostic {
+        severity: Severity::Error,
+        code: Some(ErrorCode::UnresolvedName),
+        message: "undefined value `x`".into(),
+        labels: vec![Label::primary(Span::new(file_id, 11, 12), "undefined")],
+        notes: vec!["note".into()],
+    };
+
+    let json: serde_json::Value = serde_json::from_str(&diagnostic_json(&db, &diagnostic)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "severity": "error",
+            "code": "E0010",
+            "message": "undefined value `x`",
+            "labels": [{
+                "style": "primary",
+                "file": "src/main.fe",
+                "start": 11,
+                "end": 12,
+                "start_location": { "line": 2, "column": 3 },
+                "end_location": { "line": 2, "column": 4 },
+                "message": "undefined",
+            }],
+            "notes": ["note"],
+        })
+    );
+}
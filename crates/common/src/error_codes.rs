@@ -0,0 +1,124 @@
+//! Stable codes for the families of errors the compiler reports, along with
+//! the explanations `fe explain` prints for them.
+
+use std::fmt;
+
+macro_rules! error_codes {
+    ($($variant:ident = $code:literal: $title:literal,)*) => {
+        /// The family of an error, shown as e.g. `error[E0042]` in front of its
+        /// message. The codes never change meaning, so they can be documented
+        /// and referred to.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ErrorCode {
+            $(
+                #[doc = $title]
+                $variant,
+            )*
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant),*];
+
+            /// The code, e.g. `"E0042"`.
+            pub fn code(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+
+            /// A short description of the family.
+            pub fn title(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $title,)*
+                }
+            }
+
+            /// A longer description of the family in Markdown, with examples
+            /// of code that causes it and how to fix them.
+            pub fn explanation(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => include_str!(concat!("error_codes/", $code, ".md")),)*
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    SyntaxError = "E0001": "syntax error",
+    VersionMismatch = "E0002": "incompatible compiler version",
+    NotYetImplemented = "E0003": "feature not yet implemented",
+    ProjectLayout = "E0004": "invalid project layout",
+    UnresolvedName = "E0010": "unresolved name",
+    DuplicateDefinition = "E0011": "duplicate definition",
+    NameConflict = "E0012": "name conflict",
+    PrivateItem = "E0013": "private item",
+    InvalidImport = "E0014": "invalid import",
+    MismatchedTypes = "E0020": "mismatched types",
+    InvalidType = "E0021": "invalid type",
+    InvalidOperation = "E0022": "invalid operation",
+    InvalidCast = "E0023": "invalid cast",
+    StorageValue = "E0024": "value in the wrong location",
+    InvalidCall = "E0030": "invalid call",
+    InvalidArguments = "E0031": "invalid call arguments",
+    Unsafe = "E0032": "misuse of `unsafe`",
+    InvalidFunction = "E0033": "invalid function definition",
+    MissingReturn = "E0034": "missing return",
+    InvalidImpl = "E0035": "invalid `impl` block",
+    InvalidContract = "E0036": "invalid contract or event definition",
+    SelectorCollision = "E0037": "function selector collision",
+    InvalidExpression = "E0040": "invalid expression or statement",
+    ConstEval = "E0041": "constant evaluation failed",
+    BorrowConflict = "E0042": "borrow conflict",
+    NotMutable = "E0043": "mutation of an immutable value",
+    InvalidPattern = "E0044": "invalid pattern",
+    UnavailableIntrinsic = "E0045": "intrinsic unavailable in the targeted EVM version",
+    YulCompilation = "E0050": "Yul compilation failed",
+    UnusedVariable = "E0060": "unused variable",
+    UnusedImport = "E0061": "unused import",
+    DeadCode = "E0062": "unused function",
+    UnreachableCode = "E0063": "unreachable code",
+    UnreadField = "E0064": "contract field never read",
+    UnknownLint = "E0065": "unknown lint",
+    StateWriteAfterExternalCall = "E0066": "state written after an external call",
+    UncheckedCallResult = "E0067": "unchecked call result",
+    TxOriginAuth = "E0068": "`tx_origin` used for authorization",
+    UncheckedEcRecover = "E0069": "unchecked `ec_recover` result",
+    DivideBeforeMultiply = "E0070": "division before multiplication",
+}
+
+impl ErrorCode {
+    /// Looks up a code, e.g. `"E0042"`. Lowercase codes are accepted too.
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|error_code| error_code.code().eq_ignore_ascii_case(code))
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[test]
+fn test_error_codes() {
+    assert_eq!(ErrorCode::BorrowConflict.to_string(), "E0042");
+    assert_eq!(
+        ErrorCode::from_code("e0042"),
+        Some(ErrorCode::BorrowConflict)
+    );
+    assert_eq!(ErrorCode::from_code("E9999"), None);
+
+    for (idx, code) in ErrorCode::ALL.iter().enumerate() {
+        assert_eq!(ErrorCode::from_code(code.code()), Some(*code));
+        assert!(ErrorCode::ALL[idx + 1..]
+            .iter()
+            .all(|other| other.code() != code.code()));
+        assert!(code
+            .explanation()
+            .starts_with(&format!("# {}", code.code())));
+    }
+}
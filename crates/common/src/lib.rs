@@ -1,5 +1,6 @@
 pub mod db;
 pub mod diagnostics;
+pub mod error_codes;
 pub mod files;
 pub mod numeric;
 pub mod panic;
@@ -14,13 +14,14 @@ const FE_TOML: &str = "fe.toml";
 
 pub enum FileLoader {
     Static(Vec<(&'static str, &'static str)>),
+    Memory(Vec<(String, String)>),
     Fs,
 }
 
 impl FileLoader {
     pub fn canonicalize_path(&self, path: &str) -> Result<SmolStr, String> {
         match self {
-            FileLoader::Static(_) => Ok(SmolStr::new(
+            FileLoader::Static(_) | FileLoader::Memory(_) => Ok(SmolStr::new(
                 Path::new(path).clean().to_str().expect("path clean failed"),
             )),
             FileLoader::Fs => Ok(SmolStr::new(
@@ -36,16 +37,8 @@ impl FileLoader {
 
     pub fn fe_files(&self, path: &str) -> Result<Vec<(String, String)>, String> {
         match self {
-            FileLoader::Static(files) => Ok(files
-                .iter()
-                .filter_map(|(file_path, content)| {
-                    if file_path.starts_with(path) && file_path.ends_with(".fe") {
-                        Some((file_path.to_string(), content.to_string()))
-                    } else {
-                        None
-                    }
-                })
-                .collect()),
+            FileLoader::Static(files) => Ok(in_memory_fe_files(files, path)),
+            FileLoader::Memory(files) => Ok(in_memory_fe_files(files, path)),
             FileLoader::Fs => {
                 let entries = WalkDir::new(path);
                 let mut files = vec![];
@@ -77,6 +70,12 @@ impl FileLoader {
                     None => Err(format!("could not load static file {}", path)),
                 }
             }
+            FileLoader::Memory(files) => {
+                match files.iter().find(|(file_path, _)| file_path == path) {
+                    Some((_, content)) => Ok(content.clone()),
+                    None => Err(format!("could not load file {}", path)),
+                }
+            }
             FileLoader::Fs => {
                 std::fs::read_to_string(path).map_err(|err| format!("Unable to read file.\n{err}"))
             }
@@ -84,6 +83,24 @@ impl FileLoader {
     }
 }
 
+/// Returns the Fe files under `path` among files kept in memory.
+fn in_memory_fe_files(
+    files: &[(impl AsRef<str>, impl AsRef<str>)],
+    path: &str,
+) -> Vec<(String, String)> {
+    files
+        .iter()
+        .filter_map(|(file_path, content)| {
+            let file_path = file_path.as_ref();
+            if file_path.starts_with(path) && file_path.ends_with(".fe") {
+                Some((file_path.to_string(), content.as_ref().to_string()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 pub struct BuildFiles {
     pub root_project_path: SmolStr,
     pub project_files: IndexMap<SmolStr, ProjectFiles>,
@@ -94,6 +111,18 @@ impl BuildFiles {
         self.project_files[&self.root_project_path].mode
     }
 
+    /// The `evm_version` set in the manifest of the root project.
+    pub fn root_project_evm_version(&self) -> Option<&str> {
+        self.project_files[&self.root_project_path]
+            .evm_version
+            .as_deref()
+    }
+
+    /// The `[profile]` section of the manifest of the root project.
+    pub fn root_project_profile(&self) -> &Profile {
+        &self.project_files[&self.root_project_path].profile
+    }
+
     /// Build files are loaded from the file system.
     pub fn load_fs(root_path: &str) -> Result<Self, String> {
         Self::load(&FileLoader::Fs, root_path)
@@ -107,6 +136,12 @@ impl BuildFiles {
         Self::load(&FileLoader::Static(files), root_path)
     }
 
+    /// Build files are loaded from files that were read into memory, e.g.
+    /// from a standard JSON input.
+    pub fn load_memory(files: Vec<(String, String)>, root_path: &str) -> Result<Self, String> {
+        Self::load(&FileLoader::Memory(files), root_path)
+    }
+
     fn load(loader: &FileLoader, root_project_path: &str) -> Result<Self, String> {
         let root_project_path = loader.canonicalize_path(root_project_path)?;
 
@@ -141,6 +176,8 @@ pub struct ProjectFiles {
     pub name: SmolStr,
     pub version: SmolStr,
     pub mode: ProjectMode,
+    pub evm_version: Option<SmolStr>,
+    pub profile: Profile,
     pub dependencies: Vec<Dependency>,
     pub src: Vec<(String, String)>,
 }
@@ -155,6 +192,8 @@ impl ProjectFiles {
         let manifest = Manifest::load(loader, &manifest_path)?;
         let name = manifest.name;
         let version = manifest.version;
+        let evm_version = manifest.evm_version;
+        let profile = manifest.profile;
 
         let mut dependencies = vec![];
         let mut errors = vec![];
@@ -199,6 +238,8 @@ impl ProjectFiles {
             name,
             version,
             mode,
+            evm_version,
+            profile,
             dependencies,
             src,
         })
@@ -379,9 +420,28 @@ impl Dependency {
 struct Manifest {
     pub name: SmolStr,
     pub version: SmolStr,
+    evm_version: Option<SmolStr>,
+    #[serde(default)]
+    profile: Profile,
     dependencies: Option<Table>,
 }
 
+/// The `[profile]` section of a manifest, which sets how the project is
+/// optimized. The command line overrides it.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    pub optimize: Option<bool>,
+    /// `"size"` or `"gas"`.
+    pub optimize_for: Option<SmolStr>,
+    /// The number of runs solc's optimizer assumes.
+    pub runs: Option<u32>,
+    /// The step sequence of solc's Yul optimizer.
+    pub yul_steps: Option<SmolStr>,
+    /// The number of exported functions from which a contract's dispatcher
+    /// does a binary search over the selectors.
+    pub dispatcher_search_threshold: Option<usize>,
+}
+
 impl Manifest {
     pub fn load(loader: &FileLoader, path: &str) -> Result<Self, String> {
         let content = loader
@@ -3,17 +3,27 @@
 use fe_abi::event::AbiEvent;
 use fe_abi::types::{AbiTupleField, AbiType};
 pub use fe_codegen::db::{CodegenDb, Db};
+pub use fe_codegen::options::{Backend, CodegenOptions, EvmVersion};
+pub use fe_yulc::external::solc_on_path;
 
 use fe_analyzer::namespace::items::{ContractId, FunctionId, IngotId, IngotMode, ModuleId};
-use fe_common::diagnostics::Diagnostic;
+use fe_codegen::yul::source_map::YulSourceLocations;
+use fe_common::diagnostics::{Diagnostic, ErrorCode, Label};
 use fe_common::files::FileKind;
+use fe_common::Span;
 use fe_common::{db::Upcast, utils::files::BuildFiles};
+use fe_mir::db::MirDb;
+use fe_mir::interpret::{Env, Interpreter};
+use fe_mir::ir::inst::InstKind;
 use fe_parser::ast::SmolStr;
 use fe_test_runner::ethabi::{Event, EventParam, ParamType};
 use fe_test_runner::TestSink;
-use indexmap::{indexmap, IndexMap};
-use serde_json::Value;
+use fe_yulc::source_map::{self, SourceMapEntry};
+use indexmap::{indexmap, IndexMap, IndexSet};
+use serde_json::{json, Value};
+use std::collections::HashSet;
 use std::fmt::Display;
+use yultsur::yul;
 
 /// The artifacts of a compiled module.
 pub struct CompiledModule {
@@ -25,15 +35,18 @@ pub struct CompiledModule {
 /// The artifacts of a compiled contract.
 pub struct CompiledContract {
     pub json_abi: String,
+    /// The Yul source of the contract as it's printed, i.e. not escaped for
+    /// the JSON input of solc.
     pub yul: String,
     pub origin: ContractId,
-    #[cfg(feature = "solc-backend")]
     pub bytecode: String,
-    #[cfg(feature = "solc-backend")]
     pub runtime_bytecode: String,
+    /// The source maps of the bytecode and the runtime bytecode as JSON, which
+    /// refer to the Fe source. It's empty unless the `source_locations`
+    /// codegen option is set.
+    pub source_map: String,
 }
 
-#[cfg(feature = "solc-backend")]
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CompiledTest {
     pub name: SmolStr,
@@ -41,7 +54,6 @@ pub struct CompiledTest {
     bytecode: String,
 }
 
-#[cfg(feature = "solc-backend")]
 impl CompiledTest {
     pub fn new(name: SmolStr, events: Vec<AbiEvent>, bytecode: String) -> Self {
         Self {
@@ -57,6 +69,48 @@ impl CompiledTest {
     }
 }
 
+/// A test that runs in the MIR interpreter instead of being compiled to
+/// bytecode, which doesn't require solc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterpretedTest {
+    pub name: SmolStr,
+    func: fe_mir::ir::FunctionId,
+}
+
+impl InterpretedTest {
+    pub fn new(db: &Db, test: FunctionId) -> Self {
+        Self {
+            name: test.name(db),
+            func: db.mir_lowered_func_signature(test),
+        }
+    }
+
+    pub fn execute(&self, db: &Db, sink: &mut TestSink) -> bool {
+        let mut interpreter = Interpreter::new(db, Env::default());
+        // The only parameter of a test is its zero sized context.
+        let args = vec![0.into(); self.func.signature(db).params.len()];
+        match interpreter.call(self.func, &args) {
+            Ok(_) => {
+                let logs: Vec<_> = interpreter
+                    .logs()
+                    .iter()
+                    .map(|log| format!("  {log}"))
+                    .collect();
+                if !logs.is_empty() {
+                    sink.insert_logs(&self.name, &logs.join("\n"))
+                }
+
+                sink.inc_success_count();
+                true
+            }
+            Err(trap) => {
+                sink.insert_failure(&self.name, &format!("  {trap}"));
+                false
+            }
+        }
+    }
+}
+
 fn map_abi_events(events: &[AbiEvent]) -> Vec<Event> {
     events.iter().map(map_abi_event).collect()
 }
@@ -101,12 +155,25 @@ fn map_abi_types(fields: &[AbiTupleField]) -> Vec<ParamType> {
     fields.iter().map(|field| map_abi_type(&field.ty)).collect()
 }
 
+/// The ABI selectors of a contract.
+pub struct ContractSelectors {
+    pub name: String,
+    /// `(signature, selector)` of each exported function.
+    pub functions: Vec<(String, String)>,
+    /// `(signature, topic)` of each event.
+    pub events: Vec<(String, String)>,
+}
+
 #[derive(Debug)]
 pub struct CompileError(pub Vec<Diagnostic>);
 
 pub fn check_single_file(db: &mut Db, path: &str, src: &str) -> Vec<Diagnostic> {
     let module = ModuleId::new_standalone(db, path, src);
-    module.diagnostics(db)
+    let mut diags = module.diagnostics(db);
+    if diags.is_empty() {
+        sink_codegen_diagnostics(db, module, &mut diags);
+    }
+    diags
 }
 
 pub fn compile_single_file(
@@ -118,7 +185,10 @@ pub fn compile_single_file(
     optimize: bool,
 ) -> Result<CompiledModule, CompileError> {
     let module = ModuleId::new_standalone(db, path, src);
-    let diags = module.diagnostics(db);
+    let mut diags = module.diagnostics(db);
+    if diags.is_empty() {
+        sink_codegen_diagnostics(db, module, &mut diags);
+    }
 
     if diags.is_empty() {
         compile_module(db, module, with_bytecode, with_runtime_bytecode, optimize)
@@ -127,18 +197,37 @@ pub fn compile_single_file(
     }
 }
 
-#[cfg(feature = "solc-backend")]
 pub fn compile_single_file_tests(
     db: &mut Db,
     path: &str,
     src: &str,
     optimize: bool,
 ) -> Result<(SmolStr, Vec<CompiledTest>), CompileError> {
+    let module = ModuleId::new_standalone(db, path, src);
+    let mut diags = module.diagnostics(db);
+    if diags.is_empty() {
+        sink_codegen_diagnostics(db, module, &mut diags);
+    }
+
+    if diags.is_empty() {
+        Ok((module.name(db), compile_module_tests(db, module, optimize)?))
+    } else {
+        Err(CompileError(diags))
+    }
+}
+
+/// Analyzes a single file and returns its tests, to be run by the MIR
+/// interpreter.
+pub fn interpret_single_file_tests(
+    db: &mut Db,
+    path: &str,
+    src: &str,
+) -> Result<(SmolStr, Vec<InterpretedTest>), CompileError> {
     let module = ModuleId::new_standalone(db, path, src);
     let diags = module.diagnostics(db);
 
     if diags.is_empty() {
-        Ok((module.name(db), compile_module_tests(db, module, optimize)))
+        Ok((module.name(db), module_interpreted_tests(db, module)))
     } else {
         Err(CompileError(diags))
     }
@@ -151,6 +240,54 @@ pub fn check_ingot(db: &mut Db, build_files: &BuildFiles) -> Vec<Diagnostic> {
 
     let mut diags = ingot.diagnostics(db);
     ingot.sink_external_ingot_diagnostics(db, &mut diags);
+    if diags.is_empty() {
+        for module in ingot.all_modules(db).iter() {
+            sink_codegen_diagnostics(db, *module, &mut diags);
+        }
+    }
+    diags
+}
+
+/// Returns the lint warnings of a single file. There are none if the file has
+/// errors.
+pub fn lint_single_file(db: &mut Db, path: &str, src: &str) -> Vec<Diagnostic> {
+    let module = ModuleId::new_standalone(db, path, src);
+    if module.diagnostics(db).is_empty() {
+        lint_module(db, module)
+    } else {
+        vec![]
+    }
+}
+
+/// Returns the lint warnings of the modules of a project, but not of its
+/// dependencies. There are none if the project has errors.
+pub fn lint_ingot(db: &mut Db, build_files: &BuildFiles) -> Vec<Diagnostic> {
+    let ingot = IngotId::from_build_files(db, build_files);
+
+    let mut diags = ingot.diagnostics(db);
+    ingot.sink_external_ingot_diagnostics(db, &mut diags);
+    if diags.is_empty() {
+        ingot
+            .all_modules(db)
+            .iter()
+            .flat_map(|module| lint_module(db, *module))
+            .collect()
+    } else {
+        vec![]
+    }
+}
+
+/// Returns the warnings of the analyzer lints and of the lints that need the
+/// control flow of functions, in source order.
+fn lint_module(db: &Db, module: ModuleId) -> Vec<Diagnostic> {
+    let mut diags = module.lint_diagnostics(db);
+    diags.extend(fe_mir::lints::module_lints(db, module));
+    diags.sort_by_key(|diag| {
+        diag.labels
+            .first()
+            .map(|label| label.span.start)
+            .unwrap_or_default()
+    });
     diags
 }
 
@@ -175,6 +312,10 @@ pub fn compile_ingot(
     let main_module = ingot
         .root_module(db)
         .expect("missing root module, with no diagnostic");
+    sink_codegen_diagnostics(db, main_module, &mut diags);
+    if !diags.is_empty() {
+        return Err(CompileError(diags));
+    }
     compile_module(
         db,
         main_module,
@@ -184,7 +325,6 @@ pub fn compile_ingot(
     )
 }
 
-#[cfg(feature = "solc-backend")]
 pub fn compile_ingot_tests(
     db: &mut Db,
     build_files: &BuildFiles,
@@ -197,20 +337,150 @@ pub fn compile_ingot_tests(
     if !diags.is_empty() {
         return Err(CompileError(diags));
     }
+    for module in ingot.all_modules(db).iter() {
+        sink_codegen_diagnostics(db, *module, &mut diags);
+    }
 
     if diags.is_empty() {
-        Ok(ingot
+        ingot
             .all_modules(db)
             .iter()
-            .fold(vec![], |mut accum, module| {
-                accum.push((module.name(db), compile_module_tests(db, *module, optimize)));
-                accum
-            }))
+            .map(|module| {
+                Ok((
+                    module.name(db),
+                    compile_module_tests(db, *module, optimize)?,
+                ))
+            })
+            .collect()
     } else {
         Err(CompileError(diags))
     }
 }
 
+/// Analyzes an ingot and returns the tests of each of its modules, to be run by
+/// the MIR interpreter.
+pub fn interpret_ingot_tests(
+    db: &mut Db,
+    build_files: &BuildFiles,
+) -> Result<Vec<(SmolStr, Vec<InterpretedTest>)>, CompileError> {
+    let ingot = IngotId::from_build_files(db, build_files);
+
+    let mut diags = ingot.diagnostics(db);
+    ingot.sink_external_ingot_diagnostics(db, &mut diags);
+    if !diags.is_empty() {
+        return Err(CompileError(diags));
+    }
+
+    Ok(ingot
+        .all_modules(db)
+        .iter()
+        .map(|module| (module.name(db), module_interpreted_tests(db, *module)))
+        .collect())
+}
+
+/// Returns the function selectors and event topics of the contracts in a
+/// single file.
+pub fn selectors_single_file(
+    db: &mut Db,
+    path: &str,
+    src: &str,
+) -> Result<Vec<ContractSelectors>, CompileError> {
+    let module = ModuleId::new_standalone(db, path, src);
+    let diags = module.diagnostics(db);
+    if !diags.is_empty() {
+        return Err(CompileError(diags));
+    }
+
+    Ok(module_selectors(db, module))
+}
+
+/// Returns the function selectors and event topics of the contracts in the
+/// main module of a project.
+pub fn selectors_ingot(
+    db: &mut Db,
+    build_files: &BuildFiles,
+) -> Result<Vec<ContractSelectors>, CompileError> {
+    let ingot = IngotId::from_build_files(db, build_files);
+
+    let mut diags = ingot.diagnostics(db);
+    ingot.sink_external_ingot_diagnostics(db, &mut diags);
+    if !diags.is_empty() {
+        return Err(CompileError(diags));
+    }
+    let main_module = ingot
+        .root_module(db)
+        .expect("missing root module, with no diagnostic");
+
+    Ok(module_selectors(db, main_module))
+}
+
+fn module_selectors(db: &Db, module: ModuleId) -> Vec<ContractSelectors> {
+    let events: Vec<_> = db
+        .codegen_abi_module_events(module)
+        .iter()
+        .map(|event| {
+            let sig = event.signature();
+            (sig.signature().to_string(), format!("0x{}", sig.hash_hex()))
+        })
+        .collect();
+
+    module
+        .all_contracts(db.upcast())
+        .into_iter()
+        .map(|contract| {
+            let functions = contract
+                .all_functions(db.upcast())
+                .iter()
+                .filter_map(|func| {
+                    let mir_func = db.mir_lowered_func_signature(*func);
+                    if !mir_func.linkage(db.upcast()).is_exported()
+                        || func.is_constructor(db.upcast())
+                    {
+                        return None;
+                    }
+                    let selector = db.codegen_abi_function(mir_func).selector();
+                    Some((
+                        selector.selector_signature().to_string(),
+                        format!("0x{}", selector.hex()),
+                    ))
+                })
+                .collect();
+
+            ContractSelectors {
+                name: contract.name(db.upcast()).to_string(),
+                functions,
+                events: events.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Collects the errors that are only detected once a module is lowered, such as
+/// function selector collisions and intrinsics that the targeted EVM version
+/// lacks.
+fn sink_codegen_diagnostics(db: &Db, module: ModuleId, diags: &mut Vec<Diagnostic>) {
+    for contract in module.all_contracts(db.upcast()) {
+        diags.extend(
+            db.codegen_contract_selector_collisions(contract)
+                .iter()
+                .cloned(),
+        );
+        diags.extend(
+            db.codegen_contract_unavailable_intrinsics(contract)
+                .iter()
+                .cloned(),
+        );
+    }
+    for test in module.tests(db.upcast()) {
+        let test = db.mir_lowered_func_signature(test);
+        diags.extend(
+            db.codegen_function_unavailable_intrinsics(test)
+                .iter()
+                .cloned(),
+        );
+    }
+}
+
 /// Returns graphviz string.
 // TODO: This is temporary function for debugging.
 pub fn dump_mir_single_file(db: &mut Db, path: &str, src: &str) -> Result<String, CompileError> {
@@ -226,18 +496,35 @@ pub fn dump_mir_single_file(db: &mut Db, path: &str, src: &str) -> Result<String
     Ok(String::from_utf8(text).unwrap())
 }
 
-#[cfg(feature = "solc-backend")]
-fn compile_test(db: &mut Db, test: FunctionId, optimize: bool) -> CompiledTest {
-    let yul_test = fe_codegen::yul::isel::lower_test(db, test)
-        .to_string()
-        .replace('"', "\\\"");
-    let bytecode = compile_to_evm("test", &yul_test, optimize, false).bytecode;
+fn module_interpreted_tests(db: &Db, module_id: ModuleId) -> Vec<InterpretedTest> {
+    module_id
+        .tests(db)
+        .iter()
+        .map(|test| InterpretedTest::new(db, *test))
+        .collect()
+}
+
+fn compile_test(
+    db: &mut Db,
+    test: FunctionId,
+    optimize: bool,
+) -> Result<CompiledTest, CompileError> {
+    let yul_test = fe_codegen::yul::isel::lower_test(db, test);
+    let origin = YulOrigin {
+        span: test.name_span(db),
+        functions: vec![db.mir_lowered_func_signature(test)],
+    };
+    let (bytecode, _) = compile_to_evm(db, "test", &yul_test, &origin, optimize, false)?;
+    let bytecode = bytecode.bytecode;
     let events = db.codegen_abi_module_events(test.module(db));
-    CompiledTest::new(test.name(db), events, bytecode)
+    Ok(CompiledTest::new(test.name(db), events, bytecode))
 }
 
-#[cfg(feature = "solc-backend")]
-fn compile_module_tests(db: &mut Db, module_id: ModuleId, optimize: bool) -> Vec<CompiledTest> {
+fn compile_module_tests(
+    db: &mut Db,
+    module_id: ModuleId,
+    optimize: bool,
+) -> Result<Vec<CompiledTest>, CompileError> {
     module_id
         .tests(db)
         .iter()
@@ -245,7 +532,6 @@ fn compile_module_tests(db: &mut Db, module_id: ModuleId, optimize: bool) -> Vec
         .collect()
 }
 
-#[cfg(feature = "solc-backend")]
 fn compile_module(
     db: &mut Db,
     module_id: ModuleId,
@@ -258,19 +544,31 @@ fn compile_module(
     for contract in module_id.all_contracts(db.upcast()) {
         let name = &contract.data(db.upcast()).name;
         let abi = db.codegen_abi_contract(contract);
-        let yul_contract = compile_to_yul(db, contract);
+        let yul_object = fe_codegen::yul::isel::lower_contract_deployable(db, contract);
 
-        let (bytecode, runtime_bytecode) = if with_bytecode || with_runtime_bytecode {
+        let (bytecode, runtime_bytecode, source_map) = if with_bytecode || with_runtime_bytecode {
             let deployable_name = db.codegen_contract_deployer_symbol_name(contract);
-            let bytecode = compile_to_evm(
+            let origin = YulOrigin {
+                span: contract.name_span(db.upcast()),
+                functions: db.mir_lower_contract_all_functions(contract).to_vec(),
+            };
+            let (bytecode, locations) = compile_to_evm(
+                db,
                 deployable_name.as_str(),
-                &yul_contract,
+                &yul_object,
+                &origin,
                 optimize,
                 with_runtime_bytecode,
-            );
-            (bytecode.bytecode, bytecode.runtime_bytecode)
+            )?;
+            let source_map = if db.codegen_options().source_locations {
+                fe_source_map(db, &locations, &bytecode)
+                    .map_err(|error| CompileError(yul_error_diagnostics(db, "", &origin, error)))?
+            } else {
+                "".to_string()
+            };
+            (bytecode.bytecode, bytecode.runtime_bytecode, source_map)
         } else {
-            ("".to_string(), "".to_string())
+            ("".to_string(), "".to_string(), "".to_string())
         };
 
         contracts.insert(
@@ -278,10 +576,11 @@ fn compile_module(
             // Maybe put the ContractID here so we can trace it back to the source file
             CompiledContract {
                 json_abi: serde_json::to_string_pretty(&abi).unwrap(),
-                yul: yul_contract,
+                yul: print_yul(db, &yul_object).0,
                 origin: contract,
                 bytecode,
                 runtime_bytecode,
+                source_map,
             },
         );
     }
@@ -293,67 +592,304 @@ fn compile_module(
     })
 }
 
-#[cfg(not(feature = "solc-backend"))]
-fn compile_module(
-    db: &mut Db,
-    module_id: ModuleId,
-    _with_bytecode: bool,
-    _with_runtime_bytecode: bool,
-    _optimize: bool,
-) -> Result<CompiledModule, CompileError> {
-    let mut contracts = IndexMap::default();
-    for contract in module_id.all_contracts(db.upcast()) {
-        let name = &contract.data(db.upcast()).name;
-        let abi = db.codegen_abi_contract(contract);
-        let yul_contract = compile_to_yul(db, contract);
-
-        contracts.insert(
-            name.to_string(),
-            CompiledContract {
-                json_abi: serde_json::to_string_pretty(&abi).unwrap(),
-                yul: yul_contract,
-                origin: contract,
-            },
-        );
+/// Prints a Yul object without the source markers codegen tags it with when
+/// the `source_locations` option is set, along with the Fe source of the code
+/// they tagged.
+fn print_yul(db: &Db, yul_object: &yul::Object) -> (String, YulSourceLocations) {
+    let yul_src = yul_object.to_string();
+    if db.codegen_options().source_locations {
+        YulSourceLocations::strip(&yul_src)
+    } else {
+        (yul_src, YulSourceLocations::default())
     }
+}
 
-    Ok(CompiledModule {
-        src_ast: format!("{:#?}", module_id.ast(db)),
-        lowered_ast: format!("{:#?}", module_id.ast(db)),
-        contracts,
-    })
+/// The Fe code a Yul object is lowered from, which the errors of compiling it
+/// to bytecode are reported in.
+struct YulOrigin {
+    /// The contract or test, where errors that can't be traced back to a
+    /// function are reported.
+    span: Span,
+    /// The functions the object is lowered from, not counting the ones they
+    /// call.
+    functions: Vec<fe_mir::ir::FunctionId>,
 }
 
-fn compile_to_yul(db: &mut Db, contract: ContractId) -> String {
-    let yul_contract = fe_codegen::yul::isel::lower_contract_deployable(db, contract);
-    yul_contract.to_string().replace('"', "\\\"")
+/// Returns the version of the solc that compiles Yul with the codegen options
+/// of `db`, or `None` if the native backend compiles it.
+pub fn solc_version(db: &Db) -> Result<Option<String>, String> {
+    let options = db.codegen_options();
+    if options.backend == Backend::Native {
+        return Ok(None);
+    }
+
+    let version = match &options.solc_path {
+        Some(solc) => fe_yulc::external::version(solc),
+        None => fe_yulc::solc_version(),
+    };
+    version
+        .map(|version| Some(fe_yulc::external::display_version(version)))
+        .map_err(|err| err.to_string())
 }
 
-#[cfg(feature = "solc-backend")]
+/// Compiles a Yul object to bytecode with the backend selected in the codegen
+/// options.
 fn compile_to_evm(
+    db: &Db,
     name: &str,
-    yul_object: &str,
+    yul_object: &yul::Object,
+    origin: &YulOrigin,
     optimize: bool,
     verify_runtime_bytecode: bool,
-) -> fe_yulc::ContractBytecode {
-    match fe_yulc::compile_single_contract(name, yul_object, optimize, verify_runtime_bytecode) {
-        Ok(bytecode) => bytecode,
-
-        Err(error) => {
-            for error in serde_json::from_str::<Value>(&error.0)
-                .expect("unable to deserialize json output")["errors"]
-                .as_array()
-                .expect("errors not an array")
+) -> Result<(fe_yulc::ContractBytecode, YulSourceLocations), CompileError> {
+    let options = db.codegen_options();
+    let evm_version = options.evm_version.name();
+    // solc reports locations in the Yul source as it was given, i.e. unescaped.
+    let (yul_src, locations) = print_yul(db, yul_object);
+    let bytecode = if options.backend == Backend::Native {
+        fe_yulc::native::compile_object(yul_object, evm_version, verify_runtime_bytecode)
+    } else {
+        let escaped_src = yul_src.replace('"', "\\\"");
+        let optimizer = fe_yulc::OptimizerSettings {
+            enabled: optimize,
+            runs: options.optimizer_runs,
+            yul_steps: options.yul_optimizer_steps.clone(),
+        };
+        match &options.solc_path {
+            Some(solc) => fe_yulc::external::compile_single_contract(
+                solc,
+                name,
+                &escaped_src,
+                &optimizer,
+                evm_version,
+                verify_runtime_bytecode,
+            ),
+            None => fe_yulc::compile_single_contract(
+                name,
+                &escaped_src,
+                &optimizer,
+                evm_version,
+                verify_runtime_bytecode,
+            ),
+        }
+    };
+
+    bytecode
+        .map(|bytecode| (bytecode, locations))
+        .map_err(|error| CompileError(yul_error_diagnostics(db, &yul_src, origin, error)))
+}
+
+/// Composes solc's source maps, which refer to the Yul source, with the Fe
+/// source of the Yul code. Returns the source maps that refer to the Fe
+/// source as JSON, along with the paths of the files they refer to.
+fn fe_source_map(
+    db: &Db,
+    locations: &YulSourceLocations,
+    bytecode: &fe_yulc::ContractBytecode,
+) -> Result<String, fe_yulc::YulcError> {
+    let mut files = IndexSet::new();
+    let mut compose = |yul_map: &str| -> Result<String, fe_yulc::YulcError> {
+        let entries: Vec<_> = source_map::decode(yul_map)?
+            .into_iter()
+            .map(|entry| {
+                // solc is given a single Yul source, whose index is 0.
+                let span = (entry.source == 0 && entry.start >= 0)
+                    .then(|| locations.lookup(entry.start as usize))
+                    .flatten();
+                match span {
+                    Some(span) => SourceMapEntry {
+                        start: span.start as i64,
+                        length: (span.end - span.start) as i64,
+                        source: files.insert_full(span.file_id).0 as i64,
+                        ..entry
+                    },
+                    None => SourceMapEntry::unknown(entry.jump, entry.modifier_depth),
+                }
+            })
+            .collect();
+        Ok(source_map::encode(&entries))
+    };
+    let source_map = compose(&bytecode.source_map)?;
+    let runtime_source_map = compose(&bytecode.runtime_source_map)?;
+
+    let sources: Vec<_> = files
+        .iter()
+        .map(|file_id| file_id.path(db).to_string())
+        .collect();
+    let json = json!({
+        "sources": sources,
+        "sourceMap": source_map,
+        "deployedSourceMap": runtime_source_map,
+    });
+    Ok(serde_json::to_string_pretty(&json).unwrap())
+}
+
+fn yul_error_diagnostics(
+    db: &Db,
+    yul_src: &str,
+    origin: &YulOrigin,
+    error: fe_yulc::YulcError,
+) -> Vec<Diagnostic> {
+    let errors = match error {
+        fe_yulc::YulcError::Solc(errors) => errors,
+        error => {
+            return vec![Diagnostic {
+                labels: vec![Label::primary(
+                    origin.span,
+                    "the Yul code generated for this can't be compiled",
+                )],
+                code: Some(ErrorCode::YulCompilation),
+                ..Diagnostic::error(format!("Yul compilation failed: {error}"))
+            }]
+        }
+    };
+
+    errors
+        .into_iter()
+        .map(|error| {
+            let func = error
+                .location
+                .as_ref()
+                .and_then(|location| enclosing_yul_function(yul_src, location.start))
+                .and_then(|symbol| function_by_symbol(db, &origin.functions, symbol));
+            let label = match func {
+                Some(func) => Label::primary(
+                    func.analyzer_func(db).name_span(db.upcast()),
+                    "solc rejected the Yul code generated for this function",
+                ),
+                None => {
+                    Label::primary(origin.span, "solc rejected the Yul code generated for this")
+                }
+            };
+
+            Diagnostic {
+                labels: vec![label],
+                notes: vec![error.formatted_message],
+                code: Some(ErrorCode::YulCompilation),
+                ..Diagnostic::error(format!("Yul compilation failed: {}", error.message))
+            }
+        })
+        .collect()
+}
+
+/// Returns the name of the Yul function whose definition contains `offset`.
+///
+/// Codegen doesn't nest function definitions, so the only candidate is the
+/// last one that starts before `offset`. String literals, e.g. revert
+/// messages, are skipped, since they can contain braces or `function`.
+fn enclosing_yul_function(yul_src: &str, offset: usize) -> Option<&str> {
+    const KEYWORD: &str = "function ";
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$' || c == '.';
+
+    // The function whose header has been seen but whose body isn't open yet,
+    // and the function whose body is open, with the depth of the body.
+    let mut header = None;
+    let mut body: Option<(&str, usize)> = None;
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in yul_src.char_indices() {
+        if idx >= offset {
+            return header.or(body.map(|(name, _)| name));
+        }
+
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => {
+                depth += 1;
+                if let Some(name) = header.take() {
+                    body = Some((name, depth));
+                }
+            }
+            '}' => {
+                if body.map_or(false, |(_, body_depth)| body_depth == depth) {
+                    body = None;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ if yul_src[idx..].starts_with(KEYWORD)
+                && !yul_src[..idx].ends_with(is_ident_char) =>
             {
-                eprintln!(
-                    "Error: {}",
-                    error["formattedMessage"]
-                        .as_str()
-                        .expect("error value not a string")
-                        .replace("\\\n", "\n")
-                )
+                let name = yul_src[idx + KEYWORD.len()..].split('(').next()?;
+                header = Some(name.trim());
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the function named `symbol` in Yul among `roots` and the functions
+/// they call.
+fn function_by_symbol(
+    db: &Db,
+    roots: &[fe_mir::ir::FunctionId],
+    symbol: &str,
+) -> Option<fe_mir::ir::FunctionId> {
+    let mut visited = HashSet::new();
+    let mut worklist = roots.to_vec();
+    while let Some(func) = worklist.pop() {
+        if !visited.insert(func) {
+            continue;
+        }
+        if db.codegen_function_symbol_name(func).as_str() == symbol {
+            return Some(func);
+        }
+
+        let body = db.mir_lowered_func_body(func);
+        for block in body.order.iter_block() {
+            for inst in body.order.iter_inst(block) {
+                if let InstKind::Call { func, .. } = &body.store.inst_data(inst).kind {
+                    worklist.push(*func);
+                }
             }
-            panic!("Yul compilation failed with the above errors")
         }
     }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::enclosing_yul_function;
+
+    #[test]
+    fn yul_function_at_offset() {
+        let yul_src = r#"object "Foo" {
+    code {
+        function $$foo($x) -> $ret {
+            revert_with("} function $bar() {")
+            $ret := $x
+        }
+        function $baz() {
+            let $y := "{"
+            pop($y)
+        }
+    }
+}"#;
+        let offset = |pat: &str| yul_src.find(pat).unwrap();
+
+        assert_eq!(
+            enclosing_yul_function(yul_src, offset("$ret :=")),
+            Some("$$foo")
+        );
+        assert_eq!(
+            enclosing_yul_function(yul_src, offset("pop(")),
+            Some("$baz")
+        );
+        assert_eq!(
+            enclosing_yul_function(yul_src, offset("$x) ->")),
+            Some("$$foo")
+        );
+        assert_eq!(enclosing_yul_function(yul_src, offset("code")), None);
+    }
 }
@@ -5,10 +5,14 @@ use fe_common::panic::install_panic_hook;
 use task::Commands;
 
 #[derive(Parser)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, arg_required_else_help = true)]
 struct FelangCli {
     #[clap(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
+    /// Compile a project given as standard JSON on stdin, and write the
+    /// outputs as JSON to stdout.
+    #[clap(long, exclusive = true)]
+    standard_json: bool,
 }
 
 fn main() {
@@ -17,21 +21,29 @@ fn main() {
     let cli = FelangCli::parse();
 
     match cli.command {
-        Commands::Build(arg) => {
+        // Without a subcommand, `--standard-json` is given.
+        None => {
+            task::standard_json();
+        }
+        Some(Commands::Build(arg)) => {
             task::build(arg);
         }
-        Commands::Check(arg) => {
+        Some(Commands::Check(arg)) => {
             task::check(arg);
         }
-        Commands::New(arg) => {
+        Some(Commands::Explain(arg)) => {
+            task::explain(arg);
+        }
+        Some(Commands::New(arg)) => {
             task::create_new_project(arg);
         }
-        #[cfg(feature = "solc-backend")]
-        Commands::Verify(arg) => {
+        Some(Commands::Selectors(arg)) => {
+            task::selectors(arg);
+        }
+        Some(Commands::Verify(arg)) => {
             task::verify(arg);
         }
-        #[cfg(feature = "solc-backend")]
-        Commands::Test(arg) => {
+        Some(Commands::Test(arg)) => {
             task::test(arg);
         }
     }
@@ -1,12 +1,14 @@
 use std::fs;
 use std::io::{Error, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use clap::{ArgEnum, Args};
-use fe_common::diagnostics::print_diagnostics;
 use fe_common::files::SourceFileId;
 use fe_common::utils::files::{get_project_root, BuildFiles, ProjectMode};
-use fe_driver::CompiledModule;
+use fe_driver::{Backend, CodegenDb, CodegenOptions, CompiledModule, EvmVersion};
+
+use super::profile::{set_profile, BuildSettings, OptimizerArgs, BUILD_SETTINGS_FILE_NAME};
+use super::{report_diagnostics, report_warnings, set_backend, set_evm_version, MessageFormat};
 
 const DEFAULT_OUTPUT_DIR_NAME: &str = "output";
 
@@ -17,6 +19,7 @@ enum Emit {
     LoweredAst,
     Bytecode,
     RuntimeBytecode,
+    SourceMap,
     Tokens,
     Yul,
 }
@@ -40,18 +43,38 @@ pub struct BuildArgs {
     mir: bool,
     #[clap(long)]
     overwrite: bool,
+    #[clap(flatten)]
+    optimizer: OptimizerArgs,
+    /// The EVM version to target, overriding `evm_version` in fe.toml.
+    #[clap(long, takes_value(true))]
+    evm_version: Option<EvmVersion>,
+    /// The compiler that assembles Yul into bytecode.
+    #[clap(long, default_value = "solc")]
+    backend: Backend,
+    /// The solc executable to compile Yul with, instead of the linked solc or
+    /// the `solc` on `PATH`.
     #[clap(long, takes_value(true))]
-    optimize: Option<bool>,
+    solc: Option<PathBuf>,
+    /// How to report diagnostics: rendered for people to read, or as JSON for
+    /// tools.
+    #[clap(arg_enum, long, default_value = "human")]
+    message_format: MessageFormat,
+    /// Treat lint warnings as errors.
+    #[clap(long)]
+    deny_warnings: bool,
 }
 
-fn build_single_file(compile_arg: &BuildArgs) -> (String, CompiledModule) {
+fn build_single_file(compile_arg: &BuildArgs) -> (String, CompiledModule, BuildSettings) {
     let emit = &compile_arg.emit;
-    let with_bytecode = emit.contains(&Emit::Bytecode);
+    let with_bytecode = emit.contains(&Emit::Bytecode) || emit.contains(&Emit::SourceMap);
     let with_runtime_bytecode = emit.contains(&Emit::RuntimeBytecode);
     let input_path = &compile_arg.input_path;
-    let optimize = compile_arg.optimize.unwrap_or(true);
 
     let mut db = fe_driver::Db::default();
+    set_evm_version(&mut db, compile_arg.evm_version, None);
+    let optimize = set_profile(&mut db, &compile_arg.optimizer, None);
+    set_backend(&mut db, compile_arg.backend, compile_arg.solc.clone());
+    set_source_locations(&mut db, emit);
     let content = match std::fs::read_to_string(input_path) {
         Err(err) => {
             eprintln!("Failed to load file: `{input_path}`. Error: {err}");
@@ -71,19 +94,26 @@ fn build_single_file(compile_arg: &BuildArgs) -> (String, CompiledModule) {
         Ok(module) => module,
         Err(error) => {
             eprintln!("Unable to compile {input_path}.");
-            print_diagnostics(&db, &error.0);
+            report_diagnostics(&db, &error.0, compile_arg.message_format);
             std::process::exit(1)
         }
     };
-    (content, compiled_module)
+    let warnings = fe_driver::lint_single_file(&mut db, input_path, &content);
+    report_warnings(
+        &db,
+        &warnings,
+        compile_arg.message_format,
+        compile_arg.deny_warnings,
+    );
+    let settings = BuildSettings::new(&db, optimize);
+    (content, compiled_module, settings)
 }
 
-fn build_ingot(compile_arg: &BuildArgs) -> (String, CompiledModule) {
+fn build_ingot(compile_arg: &BuildArgs) -> (String, CompiledModule, BuildSettings) {
     let emit = &compile_arg.emit;
-    let with_bytecode = emit.contains(&Emit::Bytecode);
+    let with_bytecode = emit.contains(&Emit::Bytecode) || emit.contains(&Emit::SourceMap);
     let with_runtime_bytecode = emit.contains(&Emit::RuntimeBytecode);
     let input_path = &compile_arg.input_path;
-    let optimize = compile_arg.optimize.unwrap_or(true);
 
     if !Path::new(input_path).exists() {
         eprintln!("Input directory does not exist: `{input_path}`.");
@@ -105,6 +135,10 @@ fn build_ingot(compile_arg: &BuildArgs) -> (String, CompiledModule) {
     }
 
     let mut db = fe_driver::Db::default();
+    set_evm_version(&mut db, compile_arg.evm_version, Some(&build_files));
+    let optimize = set_profile(&mut db, &compile_arg.optimizer, Some(&build_files));
+    set_backend(&mut db, compile_arg.backend, compile_arg.solc.clone());
+    set_source_locations(&mut db, emit);
     let compiled_module = match fe_driver::compile_ingot(
         &mut db,
         &build_files,
@@ -115,31 +149,52 @@ fn build_ingot(compile_arg: &BuildArgs) -> (String, CompiledModule) {
         Ok(module) => module,
         Err(error) => {
             eprintln!("Unable to compile {input_path}.");
-            print_diagnostics(&db, &error.0);
+            report_diagnostics(&db, &error.0, compile_arg.message_format);
             std::process::exit(1)
         }
     };
 
+    let warnings = fe_driver::lint_ingot(&mut db, &build_files);
+    report_warnings(
+        &db,
+        &warnings,
+        compile_arg.message_format,
+        compile_arg.deny_warnings,
+    );
+    let settings = BuildSettings::new(&db, optimize);
     // no file content for ingots
-    ("".to_string(), compiled_module)
+    ("".to_string(), compiled_module, settings)
 }
 
-pub fn build(compile_arg: BuildArgs) {
-    let emit = &compile_arg.emit;
-
-    let input_path = &compile_arg.input_path;
-
+pub fn build(mut compile_arg: BuildArgs) {
     if compile_arg.mir {
-        return mir_dump(input_path);
+        return mir_dump(&compile_arg.input_path, compile_arg.message_format);
     }
 
-    let _with_bytecode = emit.contains(&Emit::Bytecode);
-    #[cfg(not(feature = "solc-backend"))]
-    if _with_bytecode {
-        eprintln!("Warning: bytecode output requires 'solc-backend' feature. Try `cargo build --release --features solc-backend`. Skipping.");
+    let is_bytecode = |emit: &Emit| {
+        matches!(
+            emit,
+            Emit::Bytecode | Emit::RuntimeBytecode | Emit::SourceMap
+        )
+    };
+    if compile_arg.backend == Backend::Solc
+        && !cfg!(feature = "solc-backend")
+        && compile_arg.solc.is_none()
+        && fe_driver::solc_on_path().is_none()
+        && compile_arg.emit.iter().any(is_bytecode)
+    {
+        eprintln!("Warning: bytecode output requires 'solc-backend' feature or a `solc` executable. Try `cargo build --release --features solc-backend`, `--solc <path>` or `--backend native`. Skipping.");
+        compile_arg.emit.retain(|emit| !is_bytecode(emit));
+    }
+    if compile_arg.backend == Backend::Native && compile_arg.emit.contains(&Emit::SourceMap) {
+        eprintln!("Warning: source maps require the solc backend. Skipping.");
+        compile_arg.emit.retain(|emit| *emit != Emit::SourceMap);
     }
 
-    let (content, compiled_module) = if Path::new(input_path).is_file() {
+    let emit = &compile_arg.emit;
+    let input_path = &compile_arg.input_path;
+
+    let (content, compiled_module, settings) = if Path::new(input_path).is_file() {
         build_single_file(&compile_arg)
     } else {
         build_ingot(&compile_arg)
@@ -147,7 +202,14 @@ pub fn build(compile_arg: BuildArgs) {
 
     let output_dir = &compile_arg.output_dir;
     let overwrite = compile_arg.overwrite;
-    match write_compiled_module(compiled_module, &content, emit, output_dir, overwrite) {
+    match write_compiled_module(
+        compiled_module,
+        &settings,
+        &content,
+        emit,
+        output_dir,
+        overwrite,
+    ) {
         Ok(_) => eprintln!("Compiled {input_path}. Outputs in `{output_dir}`"),
         Err(err) => {
             eprintln!("Failed to write output to directory: `{output_dir}`. Error: {err}");
@@ -156,8 +218,18 @@ pub fn build(compile_arg: BuildArgs) {
     }
 }
 
+/// Has codegen tag the Yul code with its Fe source when source maps are
+/// emitted.
+fn set_source_locations(db: &mut fe_driver::Db, emit: &[Emit]) {
+    db.set_codegen_options(CodegenOptions {
+        source_locations: emit.contains(&Emit::SourceMap),
+        ..db.codegen_options()
+    });
+}
+
 fn write_compiled_module(
     mut module: CompiledModule,
+    settings: &BuildSettings,
     file_content: &str,
     targets: &[Emit],
     output_dir: &str,
@@ -177,6 +249,9 @@ fn write_compiled_module(
 
     fs::create_dir_all(output_dir).map_err(ioerr_to_string)?;
 
+    let settings = serde_json::to_string_pretty(settings).map_err(|err| err.to_string())?;
+    write_output(&output_dir.join(BUILD_SETTINGS_FILE_NAME), &settings)?;
+
     if targets.contains(&Emit::Ast) {
         write_output(&output_dir.join("module.ast"), &module.src_ast)?;
     }
@@ -207,12 +282,10 @@ fn write_compiled_module(
             write_output(&contract_output_dir.join(file_name), &contract.yul)?;
         }
 
-        #[cfg(feature = "solc-backend")]
         if targets.contains(&Emit::Bytecode) {
             let file_name = format!("{}.bin", &name);
             write_output(&contract_output_dir.join(file_name), &contract.bytecode)?;
         }
-        #[cfg(feature = "solc-backend")]
         if targets.contains(&Emit::RuntimeBytecode) {
             let file_name = format!("{}.runtime.bin", &name);
             write_output(
@@ -220,6 +293,11 @@ fn write_compiled_module(
                 &contract.runtime_bytecode,
             )?;
         }
+
+        if targets.contains(&Emit::SourceMap) {
+            let file_name = format!("{}_source_map.json", &name);
+            write_output(&contract_output_dir.join(file_name), &contract.source_map)?;
+        }
     }
 
     Ok(())
@@ -252,7 +330,7 @@ fn verify_nonexistent_or_empty(dir: &Path) -> Result<(), String> {
     }
 }
 
-fn mir_dump(input_path: &str) {
+fn mir_dump(input_path: &str, message_format: MessageFormat) {
     let mut db = fe_driver::Db::default();
     if Path::new(input_path).is_file() {
         let content = match std::fs::read_to_string(input_path) {
@@ -267,7 +345,7 @@ fn mir_dump(input_path: &str) {
             Ok(text) => println!("{text}"),
             Err(err) => {
                 eprintln!("Unable to dump mir `{input_path}");
-                print_diagnostics(&db, &err.0);
+                report_diagnostics(&db, &err.0, message_format);
                 std::process::exit(1)
             }
         }
@@ -2,20 +2,36 @@ use std::path::Path;
 
 use clap::Args;
 use fe_common::{
-    diagnostics::{print_diagnostics, Diagnostic},
-    utils::files::get_project_root,
-    utils::files::BuildFiles,
+    diagnostics::Diagnostic, utils::files::get_project_root, utils::files::BuildFiles,
 };
-use fe_driver::Db;
+use fe_driver::{Db, EvmVersion};
+
+use super::{report_diagnostics, report_warnings, set_evm_version, MessageFormat};
 
 #[derive(Args)]
 #[clap(about = "Analyze the current project and report errors, but don't build artifacts")]
 pub struct CheckArgs {
     #[clap(default_value_t = get_project_root().unwrap_or(".".to_string()))]
     input_path: String,
+    /// The EVM version to target, overriding `evm_version` in fe.toml.
+    #[clap(long, takes_value(true))]
+    evm_version: Option<EvmVersion>,
+    /// How to report diagnostics: rendered for people to read, or as JSON for
+    /// tools.
+    #[clap(arg_enum, long, default_value = "human")]
+    message_format: MessageFormat,
+    /// Treat lint warnings as errors.
+    #[clap(long)]
+    deny_warnings: bool,
 }
 
-fn check_single_file(db: &mut Db, input_path: &str) -> Vec<Diagnostic> {
+/// Returns the errors and the lint warnings of a single file.
+fn check_single_file(
+    db: &mut Db,
+    input_path: &str,
+    evm_version: Option<EvmVersion>,
+) -> (Vec<Diagnostic>, Vec<Diagnostic>) {
+    set_evm_version(db, evm_version, None);
     let content = match std::fs::read_to_string(input_path) {
         Err(err) => {
             eprintln!("Failed to load file: `{}`. Error: {}", &input_path, err);
@@ -24,10 +40,17 @@ fn check_single_file(db: &mut Db, input_path: &str) -> Vec<Diagnostic> {
         Ok(content) => content,
     };
 
-    fe_driver::check_single_file(db, input_path, &content)
+    let errors = fe_driver::check_single_file(db, input_path, &content);
+    let warnings = fe_driver::lint_single_file(db, input_path, &content);
+    (errors, warnings)
 }
 
-fn check_ingot(db: &mut Db, input_path: &str) -> Vec<Diagnostic> {
+/// Returns the errors and the lint warnings of a project.
+fn check_ingot(
+    db: &mut Db,
+    input_path: &str,
+    evm_version: Option<EvmVersion>,
+) -> (Vec<Diagnostic>, Vec<Diagnostic>) {
     let build_files = match BuildFiles::load_fs(input_path) {
         Ok(files) => files,
         Err(err) => {
@@ -36,7 +59,10 @@ fn check_ingot(db: &mut Db, input_path: &str) -> Vec<Diagnostic> {
         }
     };
 
-    fe_driver::check_ingot(db, &build_files)
+    set_evm_version(db, evm_version, Some(&build_files));
+    let errors = fe_driver::check_ingot(db, &build_files);
+    let warnings = fe_driver::lint_ingot(db, &build_files);
+    (errors, warnings)
 }
 
 pub fn check(args: CheckArgs) {
@@ -44,16 +70,17 @@ pub fn check(args: CheckArgs) {
     let input_path = args.input_path;
 
     // check project
-    let diags = if Path::new(&input_path).is_file() {
-        check_single_file(&mut db, &input_path)
+    let (diags, warnings) = if Path::new(&input_path).is_file() {
+        check_single_file(&mut db, &input_path, args.evm_version)
     } else {
-        check_ingot(&mut db, &input_path)
+        check_ingot(&mut db, &input_path, args.evm_version)
     };
 
     if !diags.is_empty() {
-        print_diagnostics(&db, &diags);
+        report_diagnostics(&db, &diags, args.message_format);
         std::process::exit(1);
     }
+    report_warnings(&db, &warnings, args.message_format, args.deny_warnings);
 
     eprintln!("Finished");
 }
@@ -0,0 +1,29 @@
+use clap::Args;
+use fe_common::diagnostics::ErrorCode;
+
+#[derive(Args)]
+#[clap(about = "Explain an error code, or list all of them")]
+pub struct ExplainArgs {
+    /// The code to explain, e.g. `E0042`.
+    code: Option<String>,
+}
+
+pub fn explain(args: ExplainArgs) {
+    let code = match args.code {
+        Some(code) => code,
+        None => {
+            for code in ErrorCode::ALL {
+                println!("{code}: {}", code.title());
+            }
+            return;
+        }
+    };
+
+    match ErrorCode::from_code(&code) {
+        Some(code) => print!("{}", code.explanation()),
+        None => {
+            eprintln!("`{code}` is not an error code. Run `fe explain` to list them.");
+            std::process::exit(1)
+        }
+    }
+}
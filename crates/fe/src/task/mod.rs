@@ -1,26 +1,109 @@
 mod build;
 mod check;
+mod explain;
 mod new;
-#[cfg(feature = "solc-backend")]
+mod profile;
+mod selectors;
+mod standard_json;
 mod test;
 mod verify;
 
 pub use build::{build, BuildArgs};
 pub use check::{check, CheckArgs};
-use clap::Subcommand;
+pub use explain::{explain, ExplainArgs};
+use std::path::PathBuf;
+
+use clap::{ArgEnum, Subcommand};
+use fe_common::diagnostics::{print_diagnostics, print_diagnostics_json, Diagnostic};
+use fe_common::utils::files::BuildFiles;
+use fe_driver::{Backend, CodegenDb, CodegenOptions, EvmVersion};
 pub use new::{create_new_project, NewProjectArgs};
-#[cfg(feature = "solc-backend")]
+pub use selectors::{selectors, SelectorsArgs};
+pub use standard_json::standard_json;
 pub use test::{test, TestArgs};
-#[cfg(feature = "solc-backend")]
 pub use verify::{verify, VerifyArgs};
 
 #[derive(Subcommand)]
 pub enum Commands {
     Build(BuildArgs),
     Check(CheckArgs),
+    Explain(ExplainArgs),
     New(NewProjectArgs),
-    #[cfg(feature = "solc-backend")]
+    Selectors(SelectorsArgs),
     Verify(VerifyArgs),
-    #[cfg(feature = "solc-backend")]
     Test(TestArgs),
 }
+
+/// How diagnostics are reported.
+#[derive(Copy, Clone, PartialEq, Eq, ArgEnum, Debug)]
+pub enum MessageFormat {
+    /// Rendered for people to read, on stderr.
+    Human,
+    /// One JSON object per diagnostic and line, on stdout.
+    Json,
+}
+
+fn report_diagnostics(db: &fe_driver::Db, diagnostics: &[Diagnostic], format: MessageFormat) {
+    match format {
+        MessageFormat::Human => print_diagnostics(db, diagnostics),
+        MessageFormat::Json => print_diagnostics_json(db, diagnostics),
+    }
+}
+
+/// Reports the lint warnings. With `deny_warnings`, any warning is an error
+/// and makes the command fail.
+fn report_warnings(
+    db: &fe_driver::Db,
+    warnings: &[Diagnostic],
+    format: MessageFormat,
+    deny_warnings: bool,
+) {
+    if warnings.is_empty() {
+        return;
+    }
+
+    report_diagnostics(db, warnings, format);
+    if deny_warnings {
+        eprintln!(
+            "Aborting because of {} warning(s) and `--deny-warnings`.",
+            warnings.len()
+        );
+        std::process::exit(1)
+    }
+}
+
+/// Targets the EVM version given on the command line. Without one, a project
+/// uses the `evm_version` of its fe.toml and a single file uses the default.
+fn set_evm_version(
+    db: &mut fe_driver::Db,
+    evm_version: Option<EvmVersion>,
+    build_files: Option<&BuildFiles>,
+) {
+    let manifest_version = build_files.and_then(BuildFiles::root_project_evm_version);
+    let evm_version = match (evm_version, manifest_version) {
+        (Some(evm_version), _) => evm_version,
+        (None, Some(name)) => match name.parse() {
+            Ok(evm_version) => evm_version,
+            Err(err) => {
+                eprintln!("Invalid `evm_version` in fe.toml: {err}");
+                std::process::exit(1)
+            }
+        },
+        (None, None) => EvmVersion::default(),
+    };
+
+    db.set_codegen_options(CodegenOptions {
+        evm_version,
+        ..db.codegen_options()
+    });
+}
+
+/// Selects the compiler that assembles Yul into bytecode, and the solc
+/// executable the solc backend runs, if any.
+fn set_backend(db: &mut fe_driver::Db, backend: Backend, solc_path: Option<PathBuf>) {
+    db.set_codegen_options(CodegenOptions {
+        backend,
+        solc_path,
+        ..db.codegen_options()
+    });
+}
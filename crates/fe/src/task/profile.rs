@@ -0,0 +1,197 @@
+//! The optimizer settings of a build, which come from the command line and the
+//! `[profile]` section of fe.toml.
+
+use std::{fmt, str::FromStr};
+
+use clap::Args;
+use fe_common::utils::files::{BuildFiles, Profile};
+use fe_driver::{CodegenDb, CodegenOptions};
+use serde::{Deserialize, Serialize};
+
+/// The name of the file `fe build` records the build settings in.
+pub const BUILD_SETTINGS_FILE_NAME: &str = "build_settings.json";
+
+#[derive(Args)]
+pub struct OptimizerArgs {
+    /// Whether to optimize, overriding `optimize` in fe.toml.
+    #[clap(long, takes_value(true))]
+    pub(super) optimize: Option<bool>,
+    /// Favor smaller bytecode or cheaper execution, overriding `optimize_for`
+    /// in fe.toml.
+    #[clap(long, takes_value(true))]
+    pub(super) optimize_for: Option<OptimizeFor>,
+    /// The number of runs solc's optimizer assumes, overriding `runs` in
+    /// fe.toml and the preset of `--optimize-for`.
+    #[clap(long, takes_value(true))]
+    pub(super) runs: Option<u32>,
+    /// The step sequence of solc's Yul optimizer, overriding `yul_steps` in
+    /// fe.toml.
+    #[clap(long, takes_value(true))]
+    pub(super) yul_steps: Option<String>,
+    /// The number of exported functions from which a contract's dispatcher
+    /// does a binary search over the selectors instead of a linear `switch`,
+    /// overriding `dispatcher_search_threshold` in fe.toml.
+    #[clap(long, takes_value(true))]
+    pub(super) dispatcher_search_threshold: Option<usize>,
+}
+
+impl OptimizerArgs {
+    /// Arguments that only set whether to optimize.
+    pub fn optimize(optimize: Option<bool>) -> Self {
+        Self {
+            optimize,
+            optimize_for: None,
+            runs: None,
+            yul_steps: None,
+            dispatcher_search_threshold: None,
+        }
+    }
+}
+
+/// A preset of optimizer settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizeFor {
+    Size,
+    Gas,
+}
+
+impl OptimizeFor {
+    /// The optimizer runs the preset assumes, unless they're given.
+    fn runs(self) -> u32 {
+        match self {
+            OptimizeFor::Size => 1,
+            OptimizeFor::Gas => 10_000,
+        }
+    }
+}
+
+impl fmt::Display for OptimizeFor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptimizeFor::Size => f.write_str("size"),
+            OptimizeFor::Gas => f.write_str("gas"),
+        }
+    }
+}
+
+impl FromStr for OptimizeFor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "size" => Ok(OptimizeFor::Size),
+            "gas" => Ok(OptimizeFor::Gas),
+            _ => Err(format!(
+                "unknown optimization goal `{s}`, expected size or gas"
+            )),
+        }
+    }
+}
+
+/// Sets the optimizer settings given on the command line. Without them, a
+/// project uses the `[profile]` of its fe.toml. Returns whether to optimize.
+pub fn set_profile(
+    db: &mut fe_driver::Db,
+    args: &OptimizerArgs,
+    build_files: Option<&BuildFiles>,
+) -> bool {
+    let default_profile = Profile::default();
+    let profile = build_files.map_or(&default_profile, BuildFiles::root_project_profile);
+
+    let optimize_for = match (args.optimize_for, &profile.optimize_for) {
+        (Some(optimize_for), _) => Some(optimize_for),
+        (None, Some(name)) => match name.parse() {
+            Ok(optimize_for) => Some(optimize_for),
+            Err(err) => {
+                eprintln!("Invalid `optimize_for` in fe.toml: {err}");
+                std::process::exit(1)
+            }
+        },
+        (None, None) => None,
+    };
+    let optimize = args.optimize.or(profile.optimize).unwrap_or(true);
+    let runs = args
+        .runs
+        .or(profile.runs)
+        .or_else(|| optimize_for.map(OptimizeFor::runs));
+    let yul_steps = args
+        .yul_steps
+        .clone()
+        .or_else(|| profile.yul_steps.as_ref().map(ToString::to_string));
+    let dispatcher_search_threshold = args
+        .dispatcher_search_threshold
+        .or(profile.dispatcher_search_threshold);
+
+    db.set_codegen_options(CodegenOptions {
+        reclaim_memory: optimize,
+        optimize_mir: optimize,
+        // Inlining makes calls cheaper at the cost of larger bytecode.
+        inline_functions: optimize && optimize_for != Some(OptimizeFor::Size),
+        optimizer_runs: runs,
+        yul_optimizer_steps: yul_steps,
+        dispatcher_search_threshold,
+        ..db.codegen_options()
+    });
+    optimize
+}
+
+/// The settings a project was built with, which `fe build` records in its
+/// output so that `fe verify` can build it the same way.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildSettings {
+    pub optimize: bool,
+    pub inline_functions: bool,
+    pub optimizer_runs: Option<u32>,
+    pub yul_optimizer_steps: Option<String>,
+    pub dispatcher_search_threshold: Option<usize>,
+    pub evm_version: String,
+    pub backend: String,
+    /// The version of the solc that compiled the Yul, unless the native
+    /// backend did or there was no solc to ask.
+    pub solc_version: Option<String>,
+}
+
+impl BuildSettings {
+    /// The settings of a build with the codegen options of `db`.
+    pub fn new(db: &fe_driver::Db, optimize: bool) -> Self {
+        let options = db.codegen_options();
+        Self {
+            optimize,
+            inline_functions: options.inline_functions,
+            optimizer_runs: options.optimizer_runs,
+            yul_optimizer_steps: options.yul_optimizer_steps,
+            dispatcher_search_threshold: options.dispatcher_search_threshold,
+            evm_version: options.evm_version.to_string(),
+            backend: options.backend.to_string(),
+            solc_version: fe_driver::solc_version(db).ok().flatten(),
+        }
+    }
+
+    /// Sets the codegen options of `db` to build with these settings. Fails if
+    /// the solc that would compile the Yul isn't the recorded version, since
+    /// another version can produce different bytecode.
+    pub fn apply(&self, db: &mut fe_driver::Db) -> Result<(), String> {
+        db.set_codegen_options(CodegenOptions {
+            reclaim_memory: self.optimize,
+            optimize_mir: self.optimize,
+            inline_functions: self.inline_functions,
+            optimizer_runs: self.optimizer_runs,
+            yul_optimizer_steps: self.yul_optimizer_steps.clone(),
+            dispatcher_search_threshold: self.dispatcher_search_threshold,
+            evm_version: self.evm_version.parse()?,
+            backend: self.backend.parse()?,
+            ..db.codegen_options()
+        });
+
+        let recorded = match &self.solc_version {
+            Some(recorded) => recorded,
+            None => return Ok(()),
+        };
+        match fe_driver::solc_version(db)? {
+            Some(version) if version != *recorded => Err(format!(
+                "The build settings were recorded with solc {recorded}, but solc {version} would compile the Yul. Try `--solc <path>` with solc {recorded}."
+            )),
+            _ => Ok(()),
+        }
+    }
+}
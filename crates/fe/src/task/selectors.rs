@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use clap::Args;
+use fe_common::diagnostics::print_diagnostics;
+use fe_common::utils::files::{get_project_root, BuildFiles};
+use fe_driver::ContractSelectors;
+
+#[derive(Args)]
+#[clap(about = "List the function selectors and event topics of the current project")]
+pub struct SelectorsArgs {
+    #[clap(default_value_t = get_project_root().unwrap_or(".".to_string()))]
+    input_path: String,
+}
+
+pub fn selectors(args: SelectorsArgs) {
+    let input_path = &args.input_path;
+    let mut db = fe_driver::Db::default();
+
+    let result = if Path::new(input_path).is_file() {
+        let content = match std::fs::read_to_string(input_path) {
+            Err(err) => {
+                eprintln!("Failed to load file: `{input_path}`. Error: {err}");
+                std::process::exit(1)
+            }
+            Ok(content) => content,
+        };
+        fe_driver::selectors_single_file(&mut db, input_path, &content)
+    } else {
+        let build_files = match BuildFiles::load_fs(input_path) {
+            Ok(files) => files,
+            Err(err) => {
+                eprintln!("Failed to load project files.\nError: {err}");
+                std::process::exit(1)
+            }
+        };
+        fe_driver::selectors_ingot(&mut db, &build_files)
+    };
+
+    match result {
+        Ok(contracts) => print_selectors(&contracts),
+        Err(error) => {
+            eprintln!("Unable to analyze {input_path}.");
+            print_diagnostics(&db, &error.0);
+            std::process::exit(1)
+        }
+    }
+}
+
+fn print_selectors(contracts: &[ContractSelectors]) {
+    for contract in contracts {
+        println!("{}", contract.name);
+        for (signature, selector) in &contract.functions {
+            println!("  {selector}  {signature}");
+        }
+        for (signature, topic) in &contract.events {
+            println!("  {topic}  event {signature}");
+        }
+        println!();
+    }
+}
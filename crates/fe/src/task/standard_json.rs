@@ -0,0 +1,488 @@
+//! Compiles a project that's given as JSON on stdin, and writes the outputs
+//! as JSON to stdout. This lets tools integrate the compiler without writing
+//! the project to disk and running `fe build` on it.
+//!
+//! The input has the shape
+//!
+//! ```json
+//! {
+//!   "sources": { "fe.toml": { "content": "..." }, "src/main.fe": { "content": "..." } },
+//!   "dependencies": { "../lib": { "fe.toml": { "content": "..." }, "src/lib.fe": { "content": "..." } } },
+//!   "settings": {
+//!     "optimizer": { "enabled": true, "optimizeFor": "gas", "runs": 200, "yulSteps": "...",
+//!                    "dispatcherSearchThreshold": 8 },
+//!     "evmVersion": "cancun",
+//!     "backend": "solc",
+//!     "outputSelection": ["abi", "bytecode", "deployedBytecode", "yul", "storageLayout", "sourceMap"]
+//!   }
+//! }
+//! ```
+//!
+//! where the sources of the root project are given by their path relative to
+//! it, and the ones of the projects it depends on by the path its fe.toml
+//! gives for them. Settings that are left out default to the `[profile]` of
+//! the root project's fe.toml, like they do for `fe build`.
+
+use std::{collections::BTreeMap, io, path::Path};
+
+use fe_common::diagnostics::{diagnostics_string, Diagnostic, ErrorCode, LabelStyle, Severity};
+use fe_common::utils::files::{BuildFiles, ProjectMode};
+use fe_driver::{Backend, CodegenDb, CodegenOptions, CompiledModule, EvmVersion};
+use path_clean::PathClean;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::profile::{set_profile, OptimizeFor, OptimizerArgs};
+use super::{set_backend, set_evm_version};
+
+/// The path the root project is loaded from.
+const ROOT_PATH: &str = ".";
+
+#[derive(Deserialize)]
+struct Input {
+    sources: BTreeMap<String, Source>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, BTreeMap<String, Source>>,
+    #[serde(default)]
+    settings: Settings,
+}
+
+#[derive(Deserialize)]
+struct Source {
+    content: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Settings {
+    optimizer: Optimizer,
+    evm_version: Option<String>,
+    backend: Option<String>,
+    output_selection: Option<Vec<Output>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+struct Optimizer {
+    enabled: Option<bool>,
+    optimize_for: Option<String>,
+    runs: Option<u32>,
+    yul_steps: Option<String>,
+    dispatcher_search_threshold: Option<usize>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum Output {
+    Abi,
+    Bytecode,
+    DeployedBytecode,
+    Yul,
+    StorageLayout,
+    SourceMap,
+}
+
+/// The outputs when the input doesn't select any, which are the ones `fe
+/// build` emits by default.
+const DEFAULT_OUTPUTS: [Output; 2] = [Output::Abi, Output::Bytecode];
+
+pub fn standard_json() {
+    let output = match io::read_to_string(io::stdin()) {
+        Ok(input) => compile(&input),
+        Err(err) => error_output(format!("Failed to read the standard input: {err}")),
+    };
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+fn compile(input: &str) -> Value {
+    let input: Input = match serde_json::from_str(input) {
+        Ok(input) => input,
+        Err(err) => return error_output(format!("Invalid standard JSON input: {err}")),
+    };
+    let settings = &input.settings;
+    let outputs = settings
+        .output_selection
+        .clone()
+        .unwrap_or_else(|| DEFAULT_OUTPUTS.to_vec());
+
+    let evm_version = match settings
+        .evm_version
+        .as_deref()
+        .map(str::parse::<EvmVersion>)
+    {
+        Some(Err(err)) => return error_output(format!("Invalid `evmVersion`: {err}")),
+        evm_version => evm_version.and_then(Result::ok),
+    };
+    let backend = match settings.backend.as_deref().map(str::parse::<Backend>) {
+        Some(Err(err)) => return error_output(format!("Invalid `backend`: {err}")),
+        backend => backend.and_then(Result::ok).unwrap_or_default(),
+    };
+    let optimizer = &settings.optimizer;
+    let optimize_for = match optimizer
+        .optimize_for
+        .as_deref()
+        .map(str::parse::<OptimizeFor>)
+    {
+        Some(Err(err)) => return error_output(format!("Invalid `optimizeFor`: {err}")),
+        optimize_for => optimize_for.and_then(Result::ok),
+    };
+    let optimizer = OptimizerArgs {
+        optimize: optimizer.enabled,
+        optimize_for,
+        runs: optimizer.runs,
+        yul_steps: optimizer.yul_steps.clone(),
+        dispatcher_search_threshold: optimizer.dispatcher_search_threshold,
+    };
+
+    let build_files = match BuildFiles::load_memory(input_files(input), ROOT_PATH) {
+        Ok(files) => files,
+        Err(err) => return error_output(format!("Failed to load project files.\n{err}")),
+    };
+    if build_files.root_project_mode() == ProjectMode::Lib {
+        return error_output("No build targets in library mode.".into());
+    }
+
+    let mut db = fe_driver::Db::default();
+    set_evm_version(&mut db, evm_version, Some(&build_files));
+    let optimize = set_profile(&mut db, &optimizer, Some(&build_files));
+    set_backend(&mut db, backend, None);
+    db.set_codegen_options(CodegenOptions {
+        source_locations: outputs.contains(&Output::SourceMap),
+        ..db.codegen_options()
+    });
+
+    let with_bytecode = outputs.contains(&Output::Bytecode) || outputs.contains(&Output::SourceMap);
+    let with_runtime_bytecode = outputs.contains(&Output::DeployedBytecode);
+    match fe_driver::compile_ingot(
+        &mut db,
+        &build_files,
+        with_bytecode,
+        with_runtime_bytecode,
+        optimize,
+    ) {
+        Ok(module) => {
+            let contracts = contracts_output(&db, module, &outputs);
+            let warnings: Vec<_> = fe_driver::lint_ingot(&mut db, &build_files)
+                .iter()
+                .map(|diag| diagnostic_output(&db, diag))
+                .collect();
+            json!({
+                "contracts": contracts,
+                "errors": warnings,
+            })
+        }
+        Err(error) => {
+            let errors: Vec<_> = error
+                .0
+                .iter()
+                .map(|diag| diagnostic_output(&db, diag))
+                .collect();
+            json!({ "errors": errors })
+        }
+    }
+}
+
+/// Returns the files of the input by the path they're loaded from, which is
+/// the path `BuildFiles` resolves the dependencies to.
+fn input_files(input: Input) -> Vec<(String, String)> {
+    let project_files = |project_path: &Path, sources: BTreeMap<String, Source>| {
+        sources
+            .into_iter()
+            .map(|(path, source)| {
+                let path = project_path.join(path).to_string_lossy().to_string();
+                (path, source.content)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut files = project_files(Path::new(ROOT_PATH), input.sources);
+    for (dependency_path, sources) in input.dependencies {
+        let dependency_path = Path::new(ROOT_PATH).join(dependency_path).clean();
+        files.extend(project_files(&dependency_path, sources));
+    }
+    files
+}
+
+fn contracts_output(db: &fe_driver::Db, module: CompiledModule, outputs: &[Output]) -> Value {
+    let mut contracts = BTreeMap::<String, serde_json::Map<String, Value>>::new();
+    for (name, contract) in module.contracts {
+        let mut output = json!({});
+        if outputs.contains(&Output::Abi) {
+            output["abi"] = serde_json::from_str(&contract.json_abi).unwrap();
+        }
+        if outputs.contains(&Output::Yul) {
+            output["yul"] = contract.yul.into();
+        }
+        if outputs.contains(&Output::StorageLayout) {
+            let layout: Vec<_> = db
+                .codegen_contract_storage_layout(contract.origin)
+                .iter()
+                .map(|field| {
+                    json!({
+                        "name": field.name.as_str(),
+                        "type": field.ty,
+                        "slot": field.slot,
+                        "offset": field.offset,
+                        "size": field.size,
+                    })
+                })
+                .collect();
+            output["storageLayout"] = layout.into();
+        }
+        if outputs.contains(&Output::Bytecode) {
+            output["evm"]["bytecode"] = json!({ "object": contract.bytecode });
+        }
+        if outputs.contains(&Output::DeployedBytecode) {
+            output["evm"]["deployedBytecode"] = json!({ "object": contract.runtime_bytecode });
+        }
+        if outputs.contains(&Output::SourceMap) && !contract.source_map.is_empty() {
+            output["sourceMap"] = serde_json::from_str(&contract.source_map).unwrap();
+        }
+
+        let path = contract.origin.span(db).file_id.path(db);
+        let path = path.as_str();
+        contracts
+            .entry(path.strip_prefix("./").unwrap_or(path).to_string())
+            .or_default()
+            .insert(name, output);
+    }
+    json!(contracts)
+}
+
+fn diagnostic_output(db: &fe_driver::Db, diag: &Diagnostic) -> Value {
+    let severity = match diag.severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "info",
+    };
+    let mut output = json!({
+        "severity": severity,
+        "errorCode": diag.code.map(ErrorCode::code),
+        "message": diag.message,
+        "formattedMessage": diagnostics_string(db, std::slice::from_ref(diag)),
+    });
+
+    let primary = diag
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary);
+    if let Some(label) = primary {
+        let path = label.span.file_id.path(db);
+        let path = path.as_str();
+        output["sourceLocation"] = json!({
+            "file": path.strip_prefix("./").unwrap_or(path),
+            "start": label.span.start,
+            "end": label.span.end,
+        });
+    }
+    output
+}
+
+/// The output when the input can't be compiled at all.
+fn error_output(message: String) -> Value {
+    json!({
+        "errors": [{
+            "severity": "error",
+            "message": message,
+            "formattedMessage": message,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "name = \"app\"\nversion = \"1.0\"\n";
+
+    const CONTRACT: &str = r#"
+contract Foo {
+    value: u256
+    owner: address
+
+    pub fn get(self) -> u256 {
+        return self.value
+    }
+}
+"#;
+
+    fn compile_main(main: &str, settings: Value) -> Value {
+        let input = json!({
+            "sources": {
+                "fe.toml": { "content": MANIFEST },
+                "src/main.fe": { "content": main },
+            },
+            "settings": settings,
+        });
+        compile(&input.to_string())
+    }
+
+    /// Returns the message of the only error of `output`.
+    fn error_message(output: &Value) -> &str {
+        let errors = output["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1, "{output}");
+        assert_eq!(errors[0]["severity"], "error");
+        errors[0]["message"].as_str().unwrap()
+    }
+
+    #[test]
+    fn invalid_input() {
+        let output = compile("{ \"sources\": 1 }");
+        assert!(error_message(&output).starts_with("Invalid standard JSON input"));
+        assert!(output.get("contracts").is_none());
+    }
+
+    #[test]
+    fn invalid_settings() {
+        for (settings, message) in [
+            (json!({ "evmVersion": "frontier" }), "Invalid `evmVersion`"),
+            (json!({ "backend": "llvm" }), "Invalid `backend`"),
+            (
+                json!({ "optimizer": { "optimizeFor": "speed" } }),
+                "Invalid `optimizeFor`",
+            ),
+        ] {
+            let output = compile_main(CONTRACT, settings);
+            assert!(error_message(&output).starts_with(message), "{output}");
+        }
+    }
+
+    #[test]
+    fn library_mode() {
+        let input = json!({
+            "sources": {
+                "fe.toml": { "content": MANIFEST },
+                "src/lib.fe": { "content": "pub fn foo() {}" },
+            },
+        });
+        let output = compile(&input.to_string());
+        assert_eq!(error_message(&output), "No build targets in library mode.");
+    }
+
+    #[test]
+    fn dependency_paths() {
+        let input: Input = serde_json::from_value(json!({
+            "sources": {
+                "fe.toml": { "content": "" },
+                "src/main.fe": { "content": "" },
+            },
+            "dependencies": {
+                "../lib": { "src/lib.fe": { "content": "" } },
+                "deps/../vendored": { "src/lib.fe": { "content": "" } },
+            },
+        }))
+        .unwrap();
+        let paths: Vec<_> = input_files(input)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "./fe.toml",
+                "./src/main.fe",
+                "../lib/src/lib.fe",
+                "vendored/src/lib.fe",
+            ]
+        );
+    }
+
+    #[test]
+    fn dependencies() {
+        let input = json!({
+            "sources": {
+                "fe.toml": {
+                    "content": format!("{MANIFEST}\n[dependencies]\nlib = \"../lib\"\n"),
+                },
+                "src/main.fe": {
+                    "content": "use lib::double\n\ncontract Foo {\n    pub fn get() -> u256 {\n        return double(21)\n    }\n}\n",
+                },
+            },
+            "dependencies": {
+                "../lib": {
+                    "fe.toml": { "content": "name = \"lib\"\nversion = \"1.0\"\n" },
+                    "src/lib.fe": {
+                        "content": "pub fn double(_ x: u256) -> u256 {\n    return x * 2\n}\n",
+                    },
+                },
+            },
+            "settings": { "outputSelection": ["abi"] },
+        });
+        let output = compile(&input.to_string());
+        assert_eq!(output["errors"], json!([]), "{output}");
+        assert!(output["contracts"]["src/main.fe"]["Foo"]["abi"].is_array());
+    }
+
+    #[test]
+    fn output_selection() {
+        let output = compile_main(CONTRACT, json!({ "outputSelection": ["abi"] }));
+        let foo = &output["contracts"]["src/main.fe"]["Foo"];
+        assert!(foo["abi"].is_array(), "{output}");
+        assert!(foo.get("evm").is_none());
+        assert!(foo.get("yul").is_none());
+        assert!(foo.get("storageLayout").is_none());
+
+        let output = compile_main(
+            CONTRACT,
+            json!({
+                "backend": "native",
+                "outputSelection": ["bytecode", "yul", "storageLayout"],
+            }),
+        );
+        let foo = &output["contracts"]["src/main.fe"]["Foo"];
+        assert!(foo.get("abi").is_none(), "{output}");
+        assert!(!foo["evm"]["bytecode"]["object"]
+            .as_str()
+            .unwrap()
+            .is_empty());
+        assert!(foo["evm"].get("deployedBytecode").is_none());
+
+        let yul = foo["yul"].as_str().unwrap();
+        assert!(yul.contains("object \""), "{yul}");
+        assert!(!yul.contains("\\\""), "{yul}");
+
+        let layout = foo["storageLayout"].as_array().unwrap();
+        let names: Vec<_> = layout.iter().map(|field| &field["name"]).collect();
+        assert_eq!(names, ["value", "owner"]);
+        assert_eq!(layout[0]["slot"], 0);
+        assert_eq!(layout[1]["slot"], 1);
+    }
+
+    #[test]
+    fn errors() {
+        let main = "contract Foo {\n    pub fn get() -> u256 {\n        return true\n    }\n}\n";
+        let output = compile_main(main, json!({}));
+        assert!(output.get("contracts").is_none());
+
+        let errors = output["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1, "{output}");
+        let error = &errors[0];
+        assert_eq!(error["severity"], "error");
+        assert_eq!(error["errorCode"], "E0020");
+        assert!(error["formattedMessage"]
+            .as_str()
+            .unwrap()
+            .contains(error["message"].as_str().unwrap()));
+
+        let location = &error["sourceLocation"];
+        assert_eq!(location["file"], "src/main.fe");
+        let start = location["start"].as_u64().unwrap() as usize;
+        let end = location["end"].as_u64().unwrap() as usize;
+        assert!(main[start..end].contains("true"), "{location}");
+    }
+
+    #[test]
+    fn warnings() {
+        let main = "contract Foo {\n    pub fn get() -> u256 {\n        let unused: u256 = 1\n        return 2\n    }\n}\n";
+        let output = compile_main(main, json!({ "outputSelection": ["abi"] }));
+        assert!(output["contracts"]["src/main.fe"]["Foo"]["abi"].is_array());
+
+        let errors = output["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1, "{output}");
+        let warning = &errors[0];
+        assert_eq!(warning["severity"], "warning");
+        assert_eq!(warning["errorCode"], "E0060");
+        assert_eq!(warning["message"], "unused variable `unused`");
+        assert_eq!(warning["sourceLocation"]["file"], "src/main.fe");
+    }
+}
@@ -1,13 +1,14 @@
-#![cfg(feature = "solc-backend")]
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use clap::Args;
 use colored::Colorize;
-use fe_common::diagnostics::print_diagnostics;
 use fe_common::utils::files::{get_project_root, BuildFiles};
-use fe_driver::CompiledTest;
+use fe_driver::{Backend, CompileError, CompiledTest, EvmVersion, InterpretedTest};
 use fe_test_runner::TestSink;
 
+use super::profile::{set_profile, OptimizerArgs};
+use super::{report_diagnostics, report_warnings, set_backend, set_evm_version, MessageFormat};
+
 #[derive(Args)]
 #[clap(about = "Execute tests in the current project")]
 pub struct TestArgs {
@@ -15,10 +16,59 @@ pub struct TestArgs {
     input_path: String,
     #[clap(long, takes_value(true))]
     filter: Option<String>,
+    #[clap(flatten)]
+    optimizer: OptimizerArgs,
+    /// The EVM version to target, overriding `evm_version` in fe.toml.
     #[clap(long, takes_value(true))]
-    optimize: Option<bool>,
+    evm_version: Option<EvmVersion>,
     #[clap(long)]
     logs: bool,
+    /// Runs the tests in the MIR interpreter instead of compiling them, which
+    /// doesn't need solc. This is also done when there's no solc to compile
+    /// them with and `--backend native` isn't given.
+    #[clap(long)]
+    interpret: bool,
+    /// The compiler that assembles the tests' Yul into bytecode.
+    #[clap(long, default_value = "solc")]
+    backend: Backend,
+    /// The solc executable to compile Yul with, instead of the linked solc or
+    /// the `solc` on `PATH`.
+    #[clap(long, takes_value(true))]
+    solc: Option<PathBuf>,
+    /// How to report diagnostics: rendered for people to read, or as JSON for
+    /// tools.
+    #[clap(arg_enum, long, default_value = "human")]
+    message_format: MessageFormat,
+    /// Treat lint warnings as errors.
+    #[clap(long)]
+    deny_warnings: bool,
+}
+
+impl TestArgs {
+    /// Whether the tests are compiled to bytecode rather than interpreted.
+    fn compile(&self) -> bool {
+        let has_solc = cfg!(feature = "solc-backend")
+            || self.solc.is_some()
+            || fe_driver::solc_on_path().is_some();
+        !self.interpret && (self.backend == Backend::Native || has_solc)
+    }
+}
+
+/// A test that can be executed, either compiled or interpreted.
+trait Test: Clone {
+    fn name(&self) -> &str;
+}
+
+impl Test for CompiledTest {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Test for InterpretedTest {
+    fn name(&self) -> &str {
+        &self.name
+    }
 }
 
 pub fn test(args: TestArgs) {
@@ -37,7 +87,12 @@ pub fn test(args: TestArgs) {
     }
 }
 
-pub fn execute_tests(module_name: &str, tests: &[CompiledTest], sink: &mut TestSink) {
+fn execute_tests<T: Test>(
+    module_name: &str,
+    tests: &[T],
+    sink: &mut TestSink,
+    mut execute: impl FnMut(&T, &mut TestSink) -> bool,
+) {
     if tests.len() == 1 {
         println!("executing 1 test in {module_name}:");
     } else {
@@ -45,8 +100,8 @@ pub fn execute_tests(module_name: &str, tests: &[CompiledTest], sink: &mut TestS
     }
 
     for test in tests {
-        print!("  {} ...", test.name);
-        let test_passed = test.execute(sink);
+        print!("  {} ...", test.name());
+        let test_passed = execute(test, sink);
 
         if test_passed {
             println!(" {}", "passed".green())
@@ -59,10 +114,12 @@ pub fn execute_tests(module_name: &str, tests: &[CompiledTest], sink: &mut TestS
 
 fn test_single_file(args: &TestArgs) -> TestSink {
     let input_path = &args.input_path;
-    let optimize = args.optimize.unwrap_or(true);
     let logs = args.logs;
 
     let mut db = fe_driver::Db::default();
+    set_evm_version(&mut db, args.evm_version, None);
+    let optimize = set_profile(&mut db, &args.optimizer, None);
+    set_backend(&mut db, args.backend, args.solc.clone());
     let content = match std::fs::read_to_string(input_path) {
         Err(err) => {
             eprintln!("Failed to load file: `{input_path}`. Error: {err}");
@@ -71,23 +128,29 @@ fn test_single_file(args: &TestArgs) -> TestSink {
         Ok(content) => content,
     };
 
-    match fe_driver::compile_single_file_tests(&mut db, input_path, &content, optimize) {
-        Ok((name, tests)) => {
-            let mut sink = TestSink::new(logs);
-            execute_tests(&name, &tests, &mut sink);
-            sink
-        }
-        Err(error) => {
-            eprintln!("Unable to compile {input_path}.");
-            print_diagnostics(&db, &error.0);
-            std::process::exit(1)
+    let warnings = fe_driver::lint_single_file(&mut db, input_path, &content);
+    report_warnings(&db, &warnings, args.message_format, args.deny_warnings);
+
+    let mut sink = TestSink::new(logs);
+    if args.compile() {
+        match fe_driver::compile_single_file_tests(&mut db, input_path, &content, optimize) {
+            Ok((name, tests)) => execute_tests(&name, &tests, &mut sink, CompiledTest::execute),
+            Err(error) => exit_with_diagnostics(&db, args, &error),
         }
+        return sink;
     }
+
+    match fe_driver::interpret_single_file_tests(&mut db, input_path, &content) {
+        Ok((name, tests)) => execute_tests(&name, &tests, &mut sink, |test, sink| {
+            test.execute(&db, sink)
+        }),
+        Err(error) => exit_with_diagnostics(&db, args, &error),
+    }
+    sink
 }
 
 fn test_ingot(args: &TestArgs) -> TestSink {
     let input_path = &args.input_path;
-    let optimize = args.optimize.unwrap_or(true);
     let logs = args.logs;
 
     if !Path::new(input_path).exists() {
@@ -104,29 +167,52 @@ fn test_ingot(args: &TestArgs) -> TestSink {
     };
 
     let mut db = fe_driver::Db::default();
+    set_evm_version(&mut db, args.evm_version, Some(&build_files));
+    let optimize = set_profile(&mut db, &args.optimizer, Some(&build_files));
+    set_backend(&mut db, args.backend, args.solc.clone());
+
+    let warnings = fe_driver::lint_ingot(&mut db, &build_files);
+    report_warnings(&db, &warnings, args.message_format, args.deny_warnings);
+
+    let mut sink = TestSink::new(logs);
+    if args.compile() {
+        match fe_driver::compile_ingot_tests(&mut db, &build_files, optimize) {
+            Ok(test_batches) => {
+                for (module_name, tests) in test_batches {
+                    let tests = filter_tests(&tests, &args.filter);
+                    execute_tests(&module_name, &tests, &mut sink, CompiledTest::execute);
+                }
+            }
+            Err(error) => exit_with_diagnostics(&db, args, &error),
+        }
+        return sink;
+    }
 
-    match fe_driver::compile_ingot_tests(&mut db, &build_files, optimize) {
+    match fe_driver::interpret_ingot_tests(&mut db, &build_files) {
         Ok(test_batches) => {
-            let mut sink = TestSink::new(logs);
             for (module_name, tests) in test_batches {
                 let tests = filter_tests(&tests, &args.filter);
-                execute_tests(&module_name, &tests, &mut sink);
+                execute_tests(&module_name, &tests, &mut sink, |test, sink| {
+                    test.execute(&db, sink)
+                });
             }
-            sink
-        }
-        Err(error) => {
-            eprintln!("Unable to compile {input_path}.");
-            print_diagnostics(&db, &error.0);
-            std::process::exit(1)
         }
+        Err(error) => exit_with_diagnostics(&db, args, &error),
     }
+    sink
+}
+
+fn exit_with_diagnostics(db: &fe_driver::Db, args: &TestArgs, error: &CompileError) -> ! {
+    eprintln!("Unable to compile {}.", args.input_path);
+    report_diagnostics(db, &error.0, args.message_format);
+    std::process::exit(1)
 }
 
-fn filter_tests(tests: &[CompiledTest], filter: &Option<String>) -> Vec<CompiledTest> {
+fn filter_tests<T: Test>(tests: &[T], filter: &Option<String>) -> Vec<T> {
     match filter {
         Some(word) if !word.is_empty() => tests
             .iter()
-            .filter(|test| test.name.contains(word))
+            .filter(|test| test.name().contains(word))
             .cloned()
             .collect(),
         Some(_) | None => tests.to_vec(),
@@ -1,13 +1,16 @@
-#![cfg(feature = "solc-backend")]
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use clap::Args;
 use colored::Colorize;
 use fe_common::utils::files::BuildFiles;
-use fe_driver::{CompiledContract, CompiledModule, Db};
+use fe_driver::{Backend, CodegenDb, CompiledContract, CompiledModule, Db};
 use serde::Deserialize;
 use url::Url;
 
+use super::profile::{set_profile, BuildSettings, OptimizerArgs};
+use super::{set_backend, set_evm_version};
+
 #[derive(Args)]
 #[clap(about = "Verify any onchain contract against local available source code.")]
 pub struct VerifyArgs {
@@ -19,6 +22,20 @@ pub struct VerifyArgs {
     verbose: bool,
     #[clap(long, help("Verify against unoptimized bytecode"))]
     unoptimized: bool,
+    /// The compiler that assembles Yul into bytecode, `solc` by default.
+    #[clap(long, takes_value(true), conflicts_with("settings"))]
+    backend: Option<Backend>,
+    /// The solc executable to compile Yul with, instead of the linked solc or
+    /// the `solc` on `PATH`.
+    #[clap(long, takes_value(true))]
+    solc: Option<PathBuf>,
+    #[clap(
+        long,
+        takes_value(true),
+        conflicts_with("unoptimized"),
+        help("Build with the settings `fe build` recorded, e.g. `output/build_settings.json`")
+    )]
+    settings: Option<PathBuf>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -26,7 +43,7 @@ struct Response {
     result: String,
 }
 
-fn build_ingot(db: &mut Db, optimize: bool) -> Result<CompiledModule, String> {
+fn build_ingot(db: &mut Db, args: &VerifyArgs) -> Result<CompiledModule, String> {
     let input_path = ".";
 
     if !Path::new(input_path).exists() {
@@ -38,6 +55,26 @@ fn build_ingot(db: &mut Db, optimize: bool) -> Result<CompiledModule, String> {
         Err(err) => return Err(format!("Failed to load project files.\nError: {err}")),
     };
 
+    set_backend(db, args.backend.unwrap_or_default(), args.solc.clone());
+    let optimize = match &args.settings {
+        Some(path) => {
+            let settings = read_settings(path)?;
+            settings.apply(db)?;
+            settings.optimize
+        }
+        None => {
+            set_evm_version(db, None, Some(&build_files));
+            let optimizer = OptimizerArgs::optimize(args.unoptimized.then_some(false));
+            set_profile(db, &optimizer, Some(&build_files))
+        }
+    };
+    if db.codegen_options().backend == Backend::Solc
+        && !cfg!(feature = "solc-backend")
+        && args.solc.is_none()
+        && fe_driver::solc_on_path().is_none()
+    {
+        return Err("Verifying with the solc backend requires the 'solc-backend' feature or a `solc` executable. Try `cargo build --release --features solc-backend`, `--solc <path>` or `--backend native`.".into());
+    }
     let compiled_module = match fe_driver::compile_ingot(db, &build_files, true, true, optimize) {
         Ok(module) => module,
         Err(_) => return Err(format!("Unable to compile {input_path}.")),
@@ -46,6 +83,13 @@ fn build_ingot(db: &mut Db, optimize: bool) -> Result<CompiledModule, String> {
     Ok(compiled_module)
 }
 
+fn read_settings(path: &Path) -> Result<BuildSettings, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read `{}`: {err}", path.display()))?;
+    serde_json::from_str(&content)
+        .map_err(|err| format!("Invalid build settings in `{}`: {err}", path.display()))
+}
+
 fn validate_args(args: &VerifyArgs) -> Result<(), String> {
     if !args.contract_address.starts_with("0x") {
         return Err(format!(
@@ -103,7 +147,7 @@ fn do_work(args: VerifyArgs) -> Result<(), String> {
     let bytecode = byte_code.strip_prefix("0x").unwrap_or(&byte_code);
 
     let mut db = fe_driver::Db::default();
-    let compiled_module = build_ingot(&mut db, !args.unoptimized)?;
+    let compiled_module = build_ingot(&mut db, &args)?;
     for (_, contract) in compiled_module.contracts {
         if contract.runtime_bytecode == bytecode {
             print_success(contract, &args, bytecode, &db);
@@ -112,6 +156,7 @@ fn do_work(args: VerifyArgs) -> Result<(), String> {
     }
     eprintln!("{}", "No contract found with matching bytecode".bold());
     eprintln!("Note: If the contract was deployed with optimization disabled, try verifying with the `--unoptimized` flag.");
+    eprintln!("Note: If the contract was built with `fe build`, try verifying with `--settings output/build_settings.json`.");
     eprintln!(
         "Note: Make sure to use the same compiler version as the one used to deploy the contract."
     );
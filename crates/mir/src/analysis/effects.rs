@@ -0,0 +1,212 @@
+use std::ops::BitOr;
+
+use fxhash::FxHashMap;
+
+use crate::{
+    db::MirDb,
+    ir::{
+        inst::{CallType, InstKind, YulIntrinsicOp},
+        value::AssignableValue,
+        FunctionBody, FunctionId, InstId, ValueId,
+    },
+};
+
+/// Whether some code may read or write contract storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageEffects {
+    pub reads: bool,
+    pub writes: bool,
+}
+
+impl StorageEffects {
+    pub const NONE: Self = Self {
+        reads: false,
+        writes: false,
+    };
+    pub const READ: Self = Self {
+        reads: true,
+        writes: false,
+    };
+    pub const WRITE: Self = Self {
+        reads: false,
+        writes: true,
+    };
+    pub const ALL: Self = Self {
+        reads: true,
+        writes: true,
+    };
+}
+
+impl BitOr for StorageEffects {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            reads: self.reads || rhs.reads,
+            writes: self.writes || rhs.writes,
+        }
+    }
+}
+
+/// Summarizes the storage effects of functions, including the effects of the
+/// functions they transitively call.
+///
+/// The summary is conservative: a call to another contract or the creation of
+/// a contract may reenter the caller, so it's assumed to read and write any
+/// storage.
+#[derive(Debug, Default)]
+pub struct EffectSummary {
+    funcs: FxHashMap<FunctionId, StorageEffects>,
+}
+
+impl EffectSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the storage effects of calling `func`.
+    pub fn func_effects(&mut self, db: &dyn MirDb, func: FunctionId) -> StorageEffects {
+        if let Some(effects) = self.funcs.get(&func) {
+            return *effects;
+        }
+
+        let mut visited = vec![func];
+        let mut worklist = vec![func];
+        let mut effects = StorageEffects::NONE;
+        while let Some(func) = worklist.pop() {
+            if let Some(known) = self.funcs.get(&func) {
+                effects = effects | *known;
+                continue;
+            }
+
+            let body = func.body(db);
+            for block in body.order.iter_block() {
+                for inst in body.order.iter_inst(block) {
+                    effects = effects | local_effects(db, &body, inst);
+                    if let InstKind::Call {
+                        func: callee,
+                        call_type: CallType::Internal,
+                        ..
+                    } = body.store.inst_data(inst).kind
+                    {
+                        if !visited.contains(&callee) {
+                            visited.push(callee);
+                            worklist.push(callee);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.funcs.insert(func, effects);
+        effects
+    }
+
+    /// Returns the storage effects of the code `inst` runs, i.e. of a callee,
+    /// another contract or an intrinsic. Reading the operands of `inst` and
+    /// writing its result aren't included.
+    pub fn callee_effects(
+        &mut self,
+        db: &dyn MirDb,
+        body: &FunctionBody,
+        inst: InstId,
+    ) -> StorageEffects {
+        match body.store.inst_data(inst).kind {
+            InstKind::Call {
+                func,
+                call_type: CallType::Internal,
+                ..
+            } => self.func_effects(db, func),
+            _ => foreign_effects(body, inst),
+        }
+    }
+}
+
+/// Returns the storage effects of `inst` other than those of an internal
+/// callee.
+fn local_effects(db: &dyn MirDb, body: &FunctionBody, inst: InstId) -> StorageEffects {
+    let mut effects = foreign_effects(body, inst);
+    if reads_storage_operand(db, body, inst) {
+        effects = effects | StorageEffects::READ;
+    }
+    if writes_storage_result(db, body, inst) {
+        effects = effects | StorageEffects::WRITE;
+    }
+    effects
+}
+
+/// Returns the storage effects of another contract or an intrinsic run by
+/// `inst`.
+fn foreign_effects(body: &FunctionBody, inst: InstId) -> StorageEffects {
+    match &body.store.inst_data(inst).kind {
+        InstKind::Call {
+            call_type: CallType::External,
+            ..
+        }
+        | InstKind::Create { .. }
+        | InstKind::Create2 { .. } => StorageEffects::ALL,
+        InstKind::YulIntrinsic { op, .. } => intrinsic_effects(*op),
+        _ => StorageEffects::NONE,
+    }
+}
+
+pub fn intrinsic_effects(op: YulIntrinsicOp) -> StorageEffects {
+    match op {
+        YulIntrinsicOp::Sload | YulIntrinsicOp::Staticcall => StorageEffects::READ,
+        YulIntrinsicOp::Sstore => StorageEffects::WRITE,
+        YulIntrinsicOp::Create
+        | YulIntrinsicOp::Create2
+        | YulIntrinsicOp::Call
+        | YulIntrinsicOp::Callcode
+        | YulIntrinsicOp::Delegatecall
+        | YulIntrinsicOp::Selfdestruct => StorageEffects::ALL,
+        _ => StorageEffects::NONE,
+    }
+}
+
+/// Returns `true` if `inst` reads storage through one of its operands.
+/// Computing a pointer from a storage pointer or copying the pointer itself
+/// doesn't read storage.
+pub fn reads_storage_operand(db: &dyn MirDb, body: &FunctionBody, inst: InstId) -> bool {
+    let inst_data = body.store.inst_data(inst);
+    match &inst_data.kind {
+        InstKind::Declare { .. }
+        | InstKind::AggregateAccess { .. }
+        | InstKind::MapAccess { .. } => false,
+        InstKind::Bind { src } if is_ptr_copy(db, body, inst, *src) => false,
+        _ => inst_data
+            .args()
+            .any(|arg| body.store.value_ty(arg).is_sptr(db)),
+    }
+}
+
+/// Returns `true` if `inst` writes storage through its result.
+pub fn writes_storage_result(db: &dyn MirDb, body: &FunctionBody, inst: InstId) -> bool {
+    let result = match body.store.inst_result(inst) {
+        Some(result) => result,
+        None => return false,
+    };
+    if !result.ty(db, &body.store).is_sptr(db) {
+        return false;
+    }
+
+    match (result, &body.store.inst_data(inst).kind) {
+        (
+            AssignableValue::Value(_),
+            InstKind::AggregateAccess { .. } | InstKind::MapAccess { .. },
+        ) => false,
+        (AssignableValue::Value(_), InstKind::Bind { src }) => !is_ptr_copy(db, body, inst, *src),
+        _ => true,
+    }
+}
+
+/// Returns `true` if binding `src` in `inst` copies a storage pointer rather
+/// than the value it points to.
+fn is_ptr_copy(db: &dyn MirDb, body: &FunctionBody, inst: InstId, src: ValueId) -> bool {
+    match body.store.inst_result(inst) {
+        Some(AssignableValue::Value(dst)) => {
+            body.store.value_ty(*dst).is_sptr(db) && body.store.value_ty(src).is_sptr(db)
+        }
+        _ => false,
+    }
+}
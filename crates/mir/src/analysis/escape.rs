@@ -0,0 +1,323 @@
+use fxhash::FxHashSet;
+use num_bigint::BigInt;
+
+use super::{cfg::ControlFlowGraph, loop_tree::LoopTree};
+
+use crate::{
+    db::MirDb,
+    ir::{
+        inst::{InstKind, YulIntrinsicOp},
+        value::AssignableValue,
+        BasicBlockId, FunctionBody, InstId, Value, ValueId,
+    },
+};
+
+/// The address the allocator keeps the free memory pointer at.
+const FREE_MEMORY_POINTER_ADDRESS: usize = 0x40;
+
+/// Finds the scopes, i.e. loop iterations and the function body itself,
+/// whose memory allocations don't outlive them.
+///
+/// A value *may hold an address* of memory allocated in a scope if it is a
+/// memory pointer defined in the scope, the result of a call or of a memory
+/// reading intrinsic, or if it's computed from such a value. The memory of a
+/// scope escapes if such a value is
+/// 1. used after the scope or returned,
+/// 2. assigned to a variable declared outside of the scope, or stored into an
+///    aggregate that isn't freshly allocated in the scope or into a map,
+/// 3. written with an intrinsic, i.e. to raw memory, storage or transient
+///    storage. Bumping the free memory pointer is an allocation, not an escape.
+///
+/// A call in the scope that takes a pointer to memory that isn't freshly
+/// allocated in the scope is treated as an escape as well, because the callee
+/// may store its own allocations there.
+#[derive(Debug, Default)]
+pub struct EscapeAnalysis {
+    /// Headers of the loops whose allocations can be released at the end of
+    /// each iteration.
+    reclaimable_loops: FxHashSet<BasicBlockId>,
+    /// `true` if the allocations of the function can be released when it
+    /// returns.
+    is_function_reclaimable: bool,
+}
+
+impl EscapeAnalysis {
+    pub fn compute(
+        db: &dyn MirDb,
+        func: &FunctionBody,
+        cfg: &ControlFlowGraph,
+        loop_tree: &LoopTree,
+    ) -> Self {
+        let mut analysis = Self::default();
+        for lp in loop_tree.loops() {
+            let header = loop_tree.loop_header(lp);
+            let region: FxHashSet<_> = loop_tree.iter_blocks_post_order(cfg, lp).collect();
+            if !Scope::new(db, func, region).escapes() {
+                analysis.reclaimable_loops.insert(header);
+            }
+        }
+
+        let body = func.order.iter_block().collect();
+        analysis.is_function_reclaimable = !Scope::new(db, func, body).escapes();
+
+        analysis
+    }
+
+    /// Returns `true` if no memory allocated in an iteration of the loop headed
+    /// by `header` is reachable once the iteration is over.
+    pub fn is_loop_reclaimable(&self, header: BasicBlockId) -> bool {
+        self.reclaimable_loops.contains(&header)
+    }
+
+    /// Returns `true` if no memory allocated by the function is reachable once
+    /// it returns.
+    pub fn is_function_reclaimable(&self) -> bool {
+        self.is_function_reclaimable
+    }
+}
+
+struct Scope<'a> {
+    db: &'a dyn MirDb,
+    func: &'a FunctionBody,
+    blocks: FxHashSet<BasicBlockId>,
+    /// Instructions in the scope, in layout order.
+    insts: Vec<InstId>,
+    /// Values that are defined in the scope.
+    defs: FxHashSet<ValueId>,
+    /// Memory pointers defined in the scope that only ever point to memory
+    /// allocated in the scope.
+    fresh: FxHashSet<ValueId>,
+}
+
+impl<'a> Scope<'a> {
+    fn new(db: &'a dyn MirDb, func: &'a FunctionBody, blocks: FxHashSet<BasicBlockId>) -> Self {
+        let mut insts = vec![];
+        let mut defs = FxHashSet::default();
+        let mut aliased = FxHashSet::default();
+        for block in func.order.iter_block() {
+            if !blocks.contains(&block) {
+                continue;
+            }
+
+            for inst in func.order.iter_inst(block) {
+                insts.push(inst);
+                let kind = &func.store.inst_data(inst).kind;
+                if let InstKind::Declare { local } = kind {
+                    defs.insert(*local);
+                    continue;
+                }
+
+                if let Some(AssignableValue::Value(value)) = func.store.inst_result(inst) {
+                    if matches!(func.store.value_data(*value), Value::Temporary { .. }) {
+                        defs.insert(*value);
+                    }
+                    if !is_fresh_memory(func, kind) {
+                        aliased.insert(*value);
+                    }
+                }
+            }
+        }
+
+        let fresh = defs
+            .iter()
+            .copied()
+            .filter(|value| !aliased.contains(value) && func.store.value_ty(*value).is_mptr(db))
+            .collect();
+
+        Self {
+            db,
+            func,
+            blocks,
+            insts,
+            defs,
+            fresh,
+        }
+    }
+
+    fn escapes(&self) -> bool {
+        // Values that may hold an address allocated in the scope.
+        let mut tainted = self.fresh.clone();
+        // Set once memory allocated in the scope may contain such an address, from
+        // then on loads from it are tainted too.
+        let mut memory_tainted = false;
+
+        loop {
+            let mut changed = false;
+            for &inst in &self.insts {
+                match self.visit(inst, &tainted, memory_tainted) {
+                    Visit::Escape => return true,
+                    Visit::Continue {
+                        taints,
+                        taints_memory,
+                    } => {
+                        if taints_memory && !memory_tainted {
+                            memory_tainted = true;
+                            changed = true;
+                        }
+                        for value in taints {
+                            changed |= tainted.insert(value);
+                        }
+                    }
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        // Tainted values must not be used after the scope.
+        self.func.order.iter_block().any(|block| {
+            !self.blocks.contains(&block)
+                && self.func.order.iter_inst(block).any(|inst| {
+                    self.func
+                        .store
+                        .inst_data(inst)
+                        .args()
+                        .any(|arg| tainted.contains(&arg))
+                })
+        })
+    }
+
+    fn visit(&self, inst: InstId, tainted: &FxHashSet<ValueId>, memory_tainted: bool) -> Visit {
+        let data = self.func.store.inst_data(inst);
+        let is_tainted = |value: &ValueId| tainted.contains(value);
+
+        let mut taints_memory = false;
+        let result_tainted = match &data.kind {
+            InstKind::Declare { .. } => false,
+
+            InstKind::Call { args, .. } => {
+                for arg in args {
+                    let arg_ty = self.func.store.value_ty(*arg);
+                    if !arg_ty.is_mptr(self.db) || arg_ty.deref(self.db).is_zero_sized(self.db) {
+                        continue;
+                    }
+                    if !self.fresh.contains(arg) {
+                        return Visit::Escape;
+                    }
+                    taints_memory = true;
+                }
+                // A `bool` can't hold an address.
+                !self.func.store.inst_result(inst).map_or(false, |result| {
+                    result.ty(self.db, &self.func.store).is_bool(self.db)
+                })
+            }
+
+            InstKind::YulIntrinsic { op, args } => match op {
+                // Writing to an address from the scope is fine, writing the
+                // address itself is not.
+                YulIntrinsicOp::Mstore | YulIntrinsicOp::Mstore8 => {
+                    if is_tainted(&args[1]) && !self.is_free_memory_pointer(args[0]) {
+                        return Visit::Escape;
+                    }
+                    false
+                }
+                YulIntrinsicOp::Sstore | YulIntrinsicOp::Tstore => {
+                    if is_tainted(&args[1]) {
+                        return Visit::Escape;
+                    }
+                    false
+                }
+                YulIntrinsicOp::Mcopy => {
+                    if is_tainted(&args[1]) && memory_tainted {
+                        return Visit::Escape;
+                    }
+                    false
+                }
+                YulIntrinsicOp::Mload | YulIntrinsicOp::Msize => true,
+                _ => args.iter().any(is_tainted),
+            },
+
+            InstKind::Load { src } | InstKind::MemCopy { src } => is_tainted(src) && memory_tainted,
+
+            InstKind::Return { arg } => {
+                if arg.as_ref().map_or(false, is_tainted) {
+                    return Visit::Escape;
+                }
+                false
+            }
+
+            InstKind::AggregateConstruct { args, .. } => {
+                if args.iter().any(is_tainted) {
+                    taints_memory = true;
+                }
+                false
+            }
+
+            InstKind::Keccak256 { .. }
+            | InstKind::MapAccess { .. }
+            | InstKind::Emit { .. }
+            | InstKind::Create { .. }
+            | InstKind::Create2 { .. } => false,
+
+            _ => data.args().any(|arg| is_tainted(&arg)),
+        };
+
+        let mut taints = vec![];
+        match self.func.store.inst_result(inst) {
+            Some(AssignableValue::Value(value)) if result_tainted => {
+                if !self.defs.contains(value) {
+                    return Visit::Escape;
+                }
+                taints.push(*value);
+            }
+            Some(dst @ (AssignableValue::Aggregate { .. } | AssignableValue::Map { .. }))
+                if result_tainted =>
+            {
+                match root_value(dst) {
+                    Some(root) if self.fresh.contains(&root) => taints_memory = true,
+                    _ => return Visit::Escape,
+                }
+            }
+            _ => {}
+        }
+
+        Visit::Continue {
+            taints,
+            taints_memory,
+        }
+    }
+
+    /// Returns `true` if `value` is the address the free memory pointer is
+    /// kept at.
+    fn is_free_memory_pointer(&self, value: ValueId) -> bool {
+        match self.func.store.value_data(value) {
+            Value::Immediate { imm, .. } => *imm == BigInt::from(FREE_MEMORY_POINTER_ADDRESS),
+            _ => false,
+        }
+    }
+}
+
+enum Visit {
+    Escape,
+    Continue {
+        taints: Vec<ValueId>,
+        taints_memory: bool,
+    },
+}
+
+/// Returns `true` if an instruction of `kind` writes its result to memory
+/// that is only reachable through the result.
+fn is_fresh_memory(func: &FunctionBody, kind: &InstKind) -> bool {
+    match kind {
+        InstKind::AggregateConstruct { .. }
+        | InstKind::MemCopy { .. }
+        | InstKind::AbiEncode { .. }
+        | InstKind::Call { .. } => true,
+        InstKind::Bind { src } => {
+            matches!(func.store.value_data(*src), Value::Constant { .. })
+        }
+        _ => false,
+    }
+}
+
+/// Returns the value whose memory an aggregate assignment writes to, or `None`
+/// if it writes to a map.
+fn root_value(value: &AssignableValue) -> Option<ValueId> {
+    match value {
+        AssignableValue::Value(value) => Some(*value),
+        AssignableValue::Aggregate { lhs, .. } => root_value(lhs),
+        AssignableValue::Map { .. } => None,
+    }
+}
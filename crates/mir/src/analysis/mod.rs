@@ -1,9 +1,15 @@
 pub mod cfg;
 pub mod domtree;
+pub mod effects;
+pub mod escape;
 pub mod loop_tree;
 pub mod post_domtree;
+pub mod verify;
 
 pub use cfg::ControlFlowGraph;
 pub use domtree::DomTree;
+pub use effects::EffectSummary;
+pub use escape::EscapeAnalysis;
 pub use loop_tree::LoopTree;
 pub use post_domtree::PostDomTree;
+pub use verify::{verify, VerifyError};
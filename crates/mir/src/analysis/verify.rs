@@ -0,0 +1,866 @@
+use std::fmt;
+
+use fe_common::db::SourceDb;
+use fxhash::FxHashMap;
+use num_traits::ToPrimitive;
+
+use super::{cfg::ControlFlowGraph, domtree::DomTree};
+
+use crate::{
+    db::MirDb,
+    ir::{
+        inst::{BinOp, CastKind, InstKind, UnOp},
+        value::AssignableValue,
+        BasicBlockId, FunctionBody, InstId, SourceInfo, TypeId, TypeKind, Value, ValueId,
+    },
+};
+
+/// An inconsistency found in a function body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyError {
+    pub message: String,
+    /// The source of the instruction or block the error was found in.
+    pub source: SourceInfo,
+}
+
+impl VerifyError {
+    /// Returns the message followed by the location of its source, if any.
+    pub fn display(&self, db: &dyn MirDb) -> String {
+        if self.source.is_dummy() {
+            return self.message.clone();
+        }
+
+        let span = self.source.span;
+        let db: &dyn SourceDb = db.upcast();
+        let line = span.file_id.line_index(db, span.start);
+        let column = span
+            .file_id
+            .line_range(db, line)
+            .map_or(0, |range| span.start - range.start);
+        format!(
+            "{} at {}:{}:{}",
+            self.message,
+            span.file_id.path(db),
+            line + 1,
+            column + 1
+        )
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "{}", self.message)
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Checks that `body` is well formed, i.e.
+/// 1. every block ends in a terminator, which is its only terminator, and its
+///    phis come first,
+/// 2. branches only jump to blocks in the body,
+/// 3. a temporary is defined by an instruction that dominates each of its uses,
+///    and a phi argument is defined at the end of its predecessor,
+/// 4. the operands of instructions have the types the instructions take, e.g.
+///    the arguments of a call have the types of the callee's parameters, a
+///    returned value has the return type and aggregate accesses index fields
+///    that exist.
+///
+/// Locals are read and written through memory and aren't checked. Neither are
+/// uses in unreachable blocks, which have no dominators.
+pub fn verify(db: &dyn MirDb, body: &FunctionBody) -> Result<(), VerifyError> {
+    verify_layout(body)?;
+
+    let cfg = ControlFlowGraph::compute(body);
+    let domtree = DomTree::compute(&cfg);
+    Verifier::new(db, body, &cfg, &domtree).verify()
+}
+
+/// Verifies `body` and panics with the first error, naming the `stage` of the
+/// pipeline it's found after. This is only meant to be called in debug builds.
+pub fn assert_valid(db: &dyn MirDb, body: &FunctionBody, stage: &str) {
+    if let Err(err) = verify(db, body) {
+        panic!(
+            "invalid MIR of `{}` {stage}: {}",
+            body.fid.debug_name(db),
+            err.display(db)
+        );
+    }
+}
+
+fn verify_layout(body: &FunctionBody) -> Result<(), VerifyError> {
+    for block in body.order.iter_block() {
+        let last_inst = match body.order.last_inst(block) {
+            Some(inst) => inst,
+            None => {
+                return Err(VerifyError {
+                    message: format!("block {} is empty", block.index()),
+                    source: body.source.clone(),
+                })
+            }
+        };
+
+        let mut is_phi_allowed = true;
+        for inst in body.order.iter_inst(block) {
+            let inst_data = body.store.inst_data(inst);
+            let error = |message: String| VerifyError {
+                message,
+                source: inst_data.source.clone(),
+            };
+
+            let is_phi = matches!(inst_data.kind, InstKind::Phi { .. });
+            if is_phi && !is_phi_allowed {
+                return Err(error(format!(
+                    "phi after a non-phi instruction in block {}",
+                    block.index()
+                )));
+            }
+            is_phi_allowed &= is_phi;
+
+            if inst != last_inst && body.store.is_terminator(inst) {
+                return Err(error(format!(
+                    "terminator in the middle of block {}",
+                    block.index()
+                )));
+            }
+        }
+
+        if !body.store.is_terminator(last_inst) {
+            return Err(VerifyError {
+                message: format!("block {} doesn't end in a terminator", block.index()),
+                source: body.store.inst_data(last_inst).source.clone(),
+            });
+        }
+        for dest in body.store.branch_info(last_inst).block_iter() {
+            if !body.order.is_block_inserted(dest) {
+                return Err(VerifyError {
+                    message: format!(
+                        "block {} jumps to block {}, which isn't in the body",
+                        block.index(),
+                        dest.index()
+                    ),
+                    source: body.store.inst_data(last_inst).source.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct Verifier<'a> {
+    db: &'a dyn MirDb,
+    body: &'a FunctionBody,
+    cfg: &'a ControlFlowGraph,
+    domtree: &'a DomTree,
+    /// The position of each instruction in its block.
+    positions: FxHashMap<InstId, usize>,
+}
+
+impl<'a> Verifier<'a> {
+    fn new(
+        db: &'a dyn MirDb,
+        body: &'a FunctionBody,
+        cfg: &'a ControlFlowGraph,
+        domtree: &'a DomTree,
+    ) -> Self {
+        let positions = body
+            .order
+            .iter_block()
+            .flat_map(|block| body.order.iter_inst(block).enumerate())
+            .map(|(pos, inst)| (inst, pos))
+            .collect();
+        Self {
+            db,
+            body,
+            cfg,
+            domtree,
+            positions,
+        }
+    }
+
+    fn verify(&self) -> Result<(), VerifyError> {
+        for block in self.body.order.iter_block() {
+            for inst in self.body.order.iter_inst(block) {
+                if self.domtree.is_reachable(block) {
+                    self.verify_uses(block, inst)?;
+                }
+                self.verify_types(inst)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn verify_uses(&self, block: BasicBlockId, inst: InstId) -> Result<(), VerifyError> {
+        let inst_data = self.body.store.inst_data(inst);
+        if let InstKind::Phi { args, blocks } = &inst_data.kind {
+            if args.len() != blocks.len() {
+                return Err(self.error(inst, "phi has a different number of values and blocks"));
+            }
+            let preds = self.cfg.preds(block);
+            for (&arg, &pred) in args.iter().zip(blocks) {
+                if !preds.contains(&pred) {
+                    return Err(self.error(
+                        inst,
+                        format!(
+                            "phi refers to block {}, which isn't a predecessor",
+                            pred.index()
+                        ),
+                    ));
+                }
+                if self.domtree.is_reachable(pred) {
+                    self.verify_def(inst, arg, Use::BlockEnd(pred))?;
+                }
+            }
+            for &pred in preds {
+                if self.domtree.is_reachable(pred) && !blocks.contains(&pred) {
+                    return Err(
+                        self.error(inst, format!("phi has no value for block {}", pred.index()))
+                    );
+                }
+            }
+            return Ok(());
+        }
+
+        let mut values: Vec<_> = inst_data.args().collect();
+        if let Some(result) = self.body.store.inst_result(inst) {
+            collect_assignable_uses(result, true, &mut values);
+        }
+        for value in values {
+            self.verify_def(inst, value, Use::Inst(block, inst))?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `value` is defined at `use_`, which is in `inst`.
+    fn verify_def(&self, inst: InstId, value: ValueId, use_: Use) -> Result<(), VerifyError> {
+        let def = match self.body.store.value_data(value) {
+            Value::Temporary { inst, .. } => *inst,
+            _ => return Ok(()),
+        };
+
+        let undefined = || {
+            self.error(
+                inst,
+                format!("use of undefined temporary {}", value.index()),
+            )
+        };
+        if !self.body.order.is_inst_inserted(def)
+            || self.body.store.inst_result(def) != Some(&AssignableValue::Value(value))
+        {
+            return Err(undefined());
+        }
+
+        let def_block = self.body.order.inst_block(def);
+        let is_defined = match use_ {
+            Use::Inst(block, user) if block == def_block => {
+                self.positions[&def] < self.positions[&user]
+            }
+            Use::Inst(block, _) | Use::BlockEnd(block) => {
+                self.domtree.is_reachable(def_block) && self.domtree.dominates(def_block, block)
+            }
+        };
+        if is_defined {
+            Ok(())
+        } else {
+            Err(self.error(
+                inst,
+                format!(
+                    "temporary {} isn't defined on every path to its use",
+                    value.index()
+                ),
+            ))
+        }
+    }
+
+    fn verify_types(&self, inst: InstId) -> Result<(), VerifyError> {
+        let store = &self.body.store;
+        match &store.inst_data(inst).kind {
+            // The right hand side of shifts and exponents may be of any
+            // unsigned type.
+            InstKind::Binary { op, lhs, rhs }
+                if !matches!(op, BinOp::Shl | BinOp::Shr | BinOp::Pow) =>
+            {
+                let (lhs_ty, rhs_ty) = (store.value_ty(*lhs), store.value_ty(*rhs));
+                if !is_same_ty(self.db, lhs_ty, rhs_ty)
+                    && lhs_ty.is_primitive(self.db)
+                    && rhs_ty.is_primitive(self.db)
+                {
+                    return Err(self.error(
+                        inst,
+                        format!(
+                            "operands of `{op}` have different types `{}` and `{}`",
+                            lhs_ty.as_string(self.db),
+                            rhs_ty.as_string(self.db)
+                        ),
+                    ));
+                }
+            }
+
+            InstKind::Unary { op, value } => {
+                let ty = store.value_ty(*value);
+                if *op == UnOp::Not && !ty.is_bool(self.db) {
+                    return Err(self.error(inst, "operand of `not` isn't a bool"));
+                }
+                if *op != UnOp::Not && !ty.is_integral(self.db) {
+                    return Err(self.error(inst, format!("operand of `{op}` isn't an integer")));
+                }
+                self.verify_result_ty(inst, ty)?;
+            }
+
+            InstKind::Cast { kind, value, to } => {
+                let ty = store.value_ty(*value);
+                let is_primitive = |ty: TypeId| ty.is_primitive(self.db) || ty.is_contract(self.db);
+                match kind {
+                    CastKind::Primitive if !is_primitive(ty) || !is_primitive(*to) => {
+                        return Err(self.error(
+                            inst,
+                            format!(
+                                "primitive cast from `{}` to `{}`",
+                                ty.as_string(self.db),
+                                to.as_string(self.db)
+                            ),
+                        ));
+                    }
+                    CastKind::Untag if !ty.deref(self.db).is_enum(self.db) => {
+                        return Err(self.error(
+                            inst,
+                            format!(
+                                "untag cast of `{}`, which isn't an enum",
+                                ty.as_string(self.db)
+                            ),
+                        ));
+                    }
+                    _ => {}
+                }
+                self.verify_result_ty(inst, *to)?;
+            }
+
+            InstKind::Load { src } => {
+                let ty = store.value_ty(*src);
+                if !ty.is_ptr(self.db) {
+                    return Err(self.error(
+                        inst,
+                        format!(
+                            "load from `{}`, which isn't a pointer",
+                            ty.as_string(self.db)
+                        ),
+                    ));
+                }
+                self.verify_result_ty(inst, ty.deref(self.db))?;
+            }
+
+            // Only aggregates and strings are copied, primitives are loaded.
+            InstKind::MemCopy { src }
+                if store.value_ty(*src).deref(self.db).is_primitive(self.db) =>
+            {
+                return Err(self.error(
+                    inst,
+                    format!(
+                        "mem copy of `{}`, which is primitive",
+                        store.value_ty(*src).as_string(self.db)
+                    ),
+                ));
+            }
+
+            InstKind::AggregateAccess { value, indices } => {
+                self.verify_aggregate_access(inst, *value, indices)?;
+            }
+
+            InstKind::MapAccess { key, value } => {
+                let ty = store.value_ty(*value);
+                let key_ty = match ty.deref(self.db).data(self.db).kind {
+                    TypeKind::Map(def) => def.key_ty,
+                    _ => {
+                        return Err(self.error(
+                            inst,
+                            format!(
+                                "map access into `{}`, which isn't a map",
+                                ty.as_string(self.db)
+                            ),
+                        ))
+                    }
+                };
+                self.verify_operand_ty(inst, "key of the map access", *key, key_ty)?;
+            }
+
+            InstKind::Call { func, args, .. } => {
+                let sig = func.signature(self.db);
+                let params = &sig.params;
+                if args.len() != params.len() {
+                    return Err(self.error(
+                        inst,
+                        format!(
+                            "call to `{}` has {} arguments, but it takes {}",
+                            func.debug_name(self.db),
+                            args.len(),
+                            params.len()
+                        ),
+                    ));
+                }
+                for (arg, param) in args.iter().zip(params) {
+                    self.verify_operand_ty(
+                        inst,
+                        &format!("argument `{}`", param.name),
+                        *arg,
+                        param.ty,
+                    )?;
+                }
+            }
+
+            InstKind::Return { arg: Some(arg) } => {
+                if let Some(return_ty) = self.body.fid.return_type(self.db) {
+                    self.verify_operand_ty(inst, "return value", *arg, return_ty)?;
+                }
+            }
+
+            InstKind::Branch { cond, .. } if !store.value_ty(*cond).is_primitive(self.db) => {
+                return Err(self.error(inst, "branch condition isn't a primitive value"));
+            }
+
+            InstKind::Phi { args, .. } => {
+                let ty = match store.inst_result(inst) {
+                    Some(result) => result.ty(self.db, store),
+                    None => return Err(self.error(inst, "phi has no result")),
+                };
+                if args
+                    .iter()
+                    .any(|arg| !is_same_ty(self.db, store.value_ty(*arg), ty))
+                {
+                    return Err(self.error(
+                        inst,
+                        format!(
+                            "phi has a value that isn't of type `{}`",
+                            ty.as_string(self.db)
+                        ),
+                    ));
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Checks that each index of an aggregate access is an integer, and that
+    /// the fields of tuples, structs and enums are indexed by immediates within
+    /// their number of fields.
+    fn verify_aggregate_access(
+        &self,
+        inst: InstId,
+        value: ValueId,
+        indices: &[ValueId],
+    ) -> Result<(), VerifyError> {
+        let store = &self.body.store;
+        let mut ty = store.value_ty(value);
+        for &index in indices {
+            let aggregate_ty = ty.deref(self.db);
+            if !aggregate_ty.is_aggregate(self.db) {
+                return Err(self.error(
+                    inst,
+                    format!(
+                        "aggregate access into `{}`, which isn't an aggregate",
+                        ty.as_string(self.db)
+                    ),
+                ));
+            }
+            if !store.value_ty(index).is_integral(self.db) {
+                return Err(self.error(inst, "index of an aggregate access isn't an integer"));
+            }
+
+            if !aggregate_ty.is_array(self.db) {
+                let field_num = aggregate_ty.aggregate_field_num(self.db);
+                let is_field = match store.value_data(index) {
+                    Value::Immediate { imm, .. } => {
+                        imm.to_usize().map_or(false, |idx| idx < field_num)
+                    }
+                    _ => false,
+                };
+                if !is_field {
+                    return Err(self.error(
+                        inst,
+                        format!(
+                            "field index into `{}` isn't an immediate below {field_num}",
+                            aggregate_ty.as_string(self.db)
+                        ),
+                    ));
+                }
+            }
+            ty = ty.projection_ty(self.db, store.value_data(index));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `value`, which `inst` uses as `what`, is of type `ty`. Like
+    /// the operands of binary operations, only primitive types are compared,
+    /// as aggregates are passed by pointer.
+    fn verify_operand_ty(
+        &self,
+        inst: InstId,
+        what: &str,
+        value: ValueId,
+        ty: TypeId,
+    ) -> Result<(), VerifyError> {
+        let value_ty = self.body.store.value_ty(value);
+        if !is_same_ty(self.db, value_ty, ty)
+            && value_ty.is_primitive(self.db)
+            && ty.is_primitive(self.db)
+        {
+            return Err(self.error(
+                inst,
+                format!(
+                    "{what} is of type `{}`, not `{}`",
+                    value_ty.as_string(self.db),
+                    ty.as_string(self.db)
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the result of `inst`, if it's a temporary or a local, is of
+    /// type `ty`. Only primitive types are compared.
+    fn verify_result_ty(&self, inst: InstId, ty: TypeId) -> Result<(), VerifyError> {
+        match self.body.store.inst_result(inst) {
+            Some(AssignableValue::Value(result)) => {
+                self.verify_operand_ty(inst, "result", *result, ty)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn error(&self, inst: InstId, message: impl Into<String>) -> VerifyError {
+        VerifyError {
+            message: message.into(),
+            source: self.body.store.inst_data(inst).source.clone(),
+        }
+    }
+}
+
+/// Returns `true` if `a` and `b` are the same type. Types lowered from
+/// different analyzer types, e.g. `u256` and `mut u256`, have different ids.
+fn is_same_ty(db: &dyn MirDb, a: TypeId, b: TypeId) -> bool {
+    a == b || a.data(db).kind == b.data(db).kind
+}
+
+#[derive(Clone, Copy)]
+enum Use {
+    /// A use by an instruction in a block.
+    Inst(BasicBlockId, InstId),
+    /// A use at the end of a block, by a phi of its successor.
+    BlockEnd(BasicBlockId),
+}
+
+/// Collects the values read by an assignment to `value`.
+fn collect_assignable_uses(value: &AssignableValue, is_dest: bool, uses: &mut Vec<ValueId>) {
+    match value {
+        AssignableValue::Value(value) => {
+            if !is_dest {
+                uses.push(*value)
+            }
+        }
+        AssignableValue::Aggregate { lhs, idx } => {
+            uses.push(*idx);
+            collect_assignable_uses(lhs, false, uses);
+        }
+        AssignableValue::Map { lhs, key } => {
+            uses.push(*key);
+            collect_assignable_uses(lhs, false, uses);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use fe_analyzer::namespace::items::{Item, ModuleId};
+
+    use crate::{
+        db::NewDb,
+        ir::{
+            body_builder::BodyBuilder,
+            inst::{CallType, Inst},
+            types::{MapDef, TupleDef},
+            FunctionId, Type, TypeId, TypeKind,
+        },
+    };
+
+    /// Lowers the signature of `fn test(x: u8) -> u256`, which the bodies of
+    /// the tests are built for.
+    fn test_func(db: &mut NewDb) -> FunctionId {
+        let module =
+            ModuleId::new_standalone(db, "test.fe", "fn test(x: u8) -> u256 {\n    return 0\n}\n");
+        let func = module
+            .all_items(&*db)
+            .iter()
+            .find_map(|item| match item {
+                Item::Function(func) => Some(*func),
+                _ => None,
+            })
+            .unwrap();
+        db.mir_lowered_func_signature(func)
+    }
+
+    fn body_builder(db: &mut NewDb) -> BodyBuilder {
+        BodyBuilder::new(test_func(db), SourceInfo::dummy())
+    }
+
+    /// Returns the error of a body that runs `inst` and returns.
+    fn verify_inst(
+        db: &mut NewDb,
+        inst: impl FnOnce(&mut BodyBuilder, &NewDb) -> InstId,
+    ) -> String {
+        let u256 = make_ty(db, TypeKind::U256);
+        let mut builder = body_builder(db);
+        inst(&mut builder, db);
+        let zero = builder.make_imm(0.into(), u256);
+        builder.ret(zero, SourceInfo::dummy());
+
+        let body = builder.build();
+        verify(db, &body).unwrap_err().message
+    }
+
+    fn make_ty(db: &NewDb, kind: TypeKind) -> TypeId {
+        db.mir_intern_type(Type::new(kind, None).into())
+    }
+
+    #[test]
+    fn use_not_dominated_by_def() {
+        let mut db = NewDb::default();
+        let u256 = make_ty(&db, TypeKind::U256);
+        let bool_ty = make_ty(&db, TypeKind::Bool);
+
+        let mut builder = body_builder(&mut db);
+        let then_block = builder.make_block();
+        let else_block = builder.make_block();
+        let merge_block = builder.make_block();
+
+        let cond = builder.make_imm_from_bool(true, bool_ty);
+        builder.branch(cond, then_block, else_block, SourceInfo::dummy());
+
+        builder.move_to_block(then_block);
+        let one = builder.make_imm(1.into(), u256);
+        let bind = builder.bind(one, SourceInfo::dummy());
+        let v0 = builder.make_value(Value::Temporary {
+            inst: bind,
+            ty: u256,
+        });
+        builder.map_result(bind, v0.into());
+        builder.jump(merge_block, SourceInfo::dummy());
+
+        builder.move_to_block(else_block);
+        builder.jump(merge_block, SourceInfo::dummy());
+
+        builder.move_to_block(merge_block);
+        let add = builder.add(v0, one, SourceInfo::dummy());
+        let v1 = builder.make_value(Value::Temporary {
+            inst: add,
+            ty: u256,
+        });
+        builder.map_result(add, v1.into());
+        builder.ret(v1, SourceInfo::dummy());
+
+        let body = builder.build();
+        let err = verify(&db, &body).unwrap_err();
+        assert!(err.message.contains("isn't defined on every path"));
+    }
+
+    #[test]
+    fn unterminated_block() {
+        let mut db = NewDb::default();
+        let u256 = make_ty(&db, TypeKind::U256);
+
+        let mut builder = body_builder(&mut db);
+        let one = builder.make_imm(1.into(), u256);
+        builder.bind(one, SourceInfo::dummy());
+
+        let body = builder.build();
+        let err = verify(&db, &body).unwrap_err();
+        assert!(err.message.contains("doesn't end in a terminator"));
+    }
+
+    #[test]
+    fn mismatched_operand_types() {
+        let mut db = NewDb::default();
+        let u256 = make_ty(&db, TypeKind::U256);
+        let u8 = make_ty(&db, TypeKind::U8);
+
+        let mut builder = body_builder(&mut db);
+        let lhs = builder.make_imm(1.into(), u256);
+        let rhs = builder.make_imm(1.into(), u8);
+        let add = builder.add(lhs, rhs, SourceInfo::dummy());
+        let value = builder.make_value(Value::Temporary {
+            inst: add,
+            ty: u256,
+        });
+        builder.map_result(add, value.into());
+        builder.ret(value, SourceInfo::dummy());
+
+        let body = builder.build();
+        let err = verify(&db, &body).unwrap_err();
+        assert!(err.message.contains("different types"));
+    }
+
+    #[test]
+    fn phi_without_value_for_predecessor() {
+        let mut db = NewDb::default();
+        let u256 = make_ty(&db, TypeKind::U256);
+
+        let mut builder = body_builder(&mut db);
+        let entry = builder.current_block();
+        let next = builder.make_block();
+        let other = builder.make_block();
+        builder.jump(next, SourceInfo::dummy());
+
+        builder.move_to_block(other);
+        builder.jump(next, SourceInfo::dummy());
+
+        builder.move_to_block(next);
+        let one = builder.make_imm(1.into(), u256);
+        builder.ret(one, SourceInfo::dummy());
+        let mut body = builder.build();
+
+        let phi = body.store.store_inst(Inst::new(
+            InstKind::Phi {
+                args: vec![one],
+                blocks: vec![entry],
+            },
+            SourceInfo::dummy(),
+        ));
+        let value = body.store.store_value(Value::Temporary {
+            inst: phi,
+            ty: u256,
+        });
+        body.store.map_result(phi, value.into());
+        body.order.prepend_inst(phi, next);
+        assert!(verify(&db, &body).is_ok());
+
+        body.replace_phi_pred(next, entry, other);
+        let err = verify(&db, &body).unwrap_err();
+        assert!(err.message.contains("phi has no value"));
+    }
+
+    #[test]
+    fn unary_operand_types() {
+        let mut db = NewDb::default();
+        let err = verify_inst(&mut db, |builder, db| {
+            let bool_ty = make_ty(db, TypeKind::Bool);
+            let value = builder.make_imm_from_bool(true, bool_ty);
+            builder.neg(value, SourceInfo::dummy())
+        });
+        assert!(err.contains("operand of `-` isn't an integer"), "{err}");
+    }
+
+    #[test]
+    fn cast_result_type() {
+        let mut db = NewDb::default();
+        let err = verify_inst(&mut db, |builder, db| {
+            let u8 = make_ty(db, TypeKind::U8);
+            let u256 = make_ty(db, TypeKind::U256);
+            let value = builder.make_imm(1.into(), u8);
+            let cast = builder.primitive_cast(value, u256, SourceInfo::dummy());
+            let result = builder.make_value(Value::Temporary { inst: cast, ty: u8 });
+            builder.map_result(cast, result.into());
+            cast
+        });
+        assert!(err.contains("result is of type `u8`, not `u256`"), "{err}");
+    }
+
+    #[test]
+    fn load_and_mem_copy_sources() {
+        let mut db = NewDb::default();
+        let err = verify_inst(&mut db, |builder, db| {
+            let u256 = make_ty(db, TypeKind::U256);
+            let value = builder.make_imm(1.into(), u256);
+            builder.load(value, SourceInfo::dummy())
+        });
+        assert!(err.contains("isn't a pointer"), "{err}");
+
+        let err = verify_inst(&mut db, |builder, db| {
+            let u256 = make_ty(db, TypeKind::U256);
+            let value = builder.make_imm(1.into(), u256.make_mptr(db));
+            builder.mem_copy(value, SourceInfo::dummy())
+        });
+        assert!(err.contains("which is primitive"), "{err}");
+    }
+
+    #[test]
+    fn call_arguments() {
+        let mut db = NewDb::default();
+        let err = verify_inst(&mut db, |builder, db| {
+            let u256 = make_ty(db, TypeKind::U256);
+            let arg = builder.make_imm(1.into(), u256);
+            let func = builder.func_id();
+            builder.call(func, vec![arg], CallType::Internal, SourceInfo::dummy())
+        });
+        assert!(
+            err.contains("argument `x` is of type `u256`, not `u8`"),
+            "{err}"
+        );
+
+        let err = verify_inst(&mut db, |builder, _| {
+            let func = builder.func_id();
+            builder.call(func, vec![], CallType::Internal, SourceInfo::dummy())
+        });
+        assert!(err.contains("has 0 arguments, but it takes 1"), "{err}");
+    }
+
+    #[test]
+    fn return_type() {
+        let mut db = NewDb::default();
+        let u8 = make_ty(&db, TypeKind::U8);
+
+        let mut builder = body_builder(&mut db);
+        let value = builder.make_imm(1.into(), u8);
+        builder.ret(value, SourceInfo::dummy());
+
+        let body = builder.build();
+        let err = verify(&db, &body).unwrap_err();
+        assert!(err
+            .message
+            .contains("return value is of type `u8`, not `u256`"));
+    }
+
+    #[test]
+    fn aggregate_and_map_access_indices() {
+        let mut db = NewDb::default();
+        let err = verify_inst(&mut db, |builder, db| {
+            let u256 = make_ty(db, TypeKind::U256);
+            let tuple = make_ty(
+                db,
+                TypeKind::Tuple(TupleDef {
+                    items: vec![u256, u256],
+                }),
+            );
+            let value = builder.make_imm(0.into(), tuple.make_mptr(db));
+            let index = builder.make_imm(2.into(), u256);
+            builder.aggregate_access(value, vec![index], SourceInfo::dummy())
+        });
+        assert!(err.contains("isn't an immediate below 2"), "{err}");
+
+        let err = verify_inst(&mut db, |builder, db| {
+            let u256 = make_ty(db, TypeKind::U256);
+            let address = make_ty(db, TypeKind::Address);
+            let map = make_ty(
+                db,
+                TypeKind::Map(MapDef {
+                    key_ty: address,
+                    value_ty: u256,
+                }),
+            );
+            let value = builder.make_imm(0.into(), map.make_sptr(db));
+            let key = builder.make_imm(1.into(), u256);
+            builder.map_access(value, key, SourceInfo::dummy())
+        });
+        assert!(
+            err.contains("key of the map access is of type `u256`, not `address`"),
+            "{err}"
+        );
+    }
+}
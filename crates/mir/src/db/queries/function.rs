@@ -8,6 +8,7 @@ use fe_analyzer::namespace::types as analyzer_types;
 use smol_str::SmolStr;
 
 use crate::{
+    analysis::verify,
     db::MirDb,
     ir::{self, function::Linkage, FunctionSignature, TypeId},
     lower::function::{lower_func_body, lower_func_signature, lower_monomorphized_func_signature},
@@ -44,7 +45,11 @@ pub fn mir_lowered_pseudo_monomorphized_func_signature(
 }
 
 pub fn mir_lowered_func_body(db: &dyn MirDb, func: ir::FunctionId) -> Rc<ir::FunctionBody> {
-    lower_func_body(db, func)
+    let body = lower_func_body(db, func);
+    if cfg!(debug_assertions) {
+        verify::assert_valid(db, &body, "after lowering");
+    }
+    body
 }
 
 impl ir::FunctionId {
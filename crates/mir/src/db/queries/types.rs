@@ -198,6 +198,10 @@ impl TypeId {
         )
     }
 
+    pub fn is_bool(self, db: &dyn MirDb) -> bool {
+        matches!(&self.data(db).kind, TypeKind::Bool)
+    }
+
     pub fn is_address(self, db: &dyn MirDb) -> bool {
         matches!(&self.data(db).kind, TypeKind::Address)
     }
@@ -0,0 +1,206 @@
+use fe_common::utils::keccak;
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::{
+    ir::inst::YulIntrinsicOp,
+    word::{bool_word, to_signed, to_word, word_max},
+};
+
+use super::{
+    memory::{from_bytes, to_bytes, WORD_SIZE},
+    Exit, Interpreter, Log, Trap,
+};
+
+impl Interpreter<'_> {
+    /// Executes the EVM instruction `op` and returns its result, if any.
+    pub(super) fn intrinsic(
+        &mut self,
+        op: YulIntrinsicOp,
+        args: &[BigInt],
+    ) -> Result<Option<BigInt>, Exit> {
+        use YulIntrinsicOp as Op;
+
+        let arg = |idx: usize| args[idx].clone();
+        let signed_arg = |idx: usize| to_signed(&args[idx]);
+        let result = match op {
+            Op::Stop | Op::Return => return Err(Exit::Halt),
+            Op::Revert => {
+                let size = self.memory_offset(&arg(1))?;
+                let data = self.memory_read(&arg(0), size)?;
+                return Err(Trap::RawRevert(data).into());
+            }
+            Op::Invalid => return Err(Trap::Invalid.into()),
+
+            Op::Add => to_word(arg(0) + arg(1)),
+            Op::Sub => to_word(arg(0) - arg(1)),
+            Op::Mul => to_word(arg(0) * arg(1)),
+            Op::Div | Op::Sdiv | Op::Mod | Op::Smod if args[1].is_zero() => BigInt::zero(),
+            Op::Div => arg(0) / arg(1),
+            Op::Sdiv => to_word(signed_arg(0) / signed_arg(1)),
+            Op::Mod => arg(0) % arg(1),
+            Op::Smod => to_word(signed_arg(0) % signed_arg(1)),
+            Op::Exp => arg(0).modpow(&arg(1), &(BigInt::from(1) << 256)),
+            Op::Addmod | Op::Mulmod if args[2].is_zero() => BigInt::zero(),
+            Op::Addmod => (arg(0) + arg(1)) % arg(2),
+            Op::Mulmod => (arg(0) * arg(1)) % arg(2),
+            Op::Signextend => match args[0].to_usize().filter(|byte| *byte < 31) {
+                Some(byte) => {
+                    let bits = (byte + 1) * 8;
+                    let mask = (BigInt::from(1) << bits) - 1;
+                    let low = arg(1) & &mask;
+                    if low.bit(bits as u64 - 1) {
+                        low | (word_max() ^ mask)
+                    } else {
+                        low
+                    }
+                }
+                None => arg(1),
+            },
+
+            Op::Not => word_max() - arg(0),
+            Op::Lt => bool_word(args[0] < args[1]),
+            Op::Gt => bool_word(args[0] > args[1]),
+            Op::Slt => bool_word(signed_arg(0) < signed_arg(1)),
+            Op::Sgt => bool_word(signed_arg(0) > signed_arg(1)),
+            Op::Eq => bool_word(args[0] == args[1]),
+            Op::Iszero => bool_word(args[0].is_zero()),
+            Op::And => arg(0) & arg(1),
+            Op::Or => arg(0) | arg(1),
+            Op::Xor => arg(0) ^ arg(1),
+            Op::Byte => match args[0].to_usize().filter(|idx| *idx < WORD_SIZE) {
+                Some(idx) => to_bytes(&args[1])[idx].into(),
+                None => BigInt::zero(),
+            },
+            Op::Shl => match args[0].to_usize().filter(|shift| *shift < 256) {
+                Some(shift) => to_word(arg(1) << shift),
+                None => BigInt::zero(),
+            },
+            Op::Shr => match args[0].to_usize().filter(|shift| *shift < 256) {
+                Some(shift) => arg(1) >> shift,
+                None => BigInt::zero(),
+            },
+            Op::Sar => match args[0].to_usize().filter(|shift| *shift < 256) {
+                Some(shift) => to_word(signed_arg(1) >> shift),
+                None if args[1].bit(255) => word_max(),
+                None => BigInt::zero(),
+            },
+
+            Op::Keccak256 => {
+                let size = self.memory_offset(&arg(1))?;
+                let data = self.memory_read(&arg(0), size)?;
+                from_bytes(&keccak::full_as_bytes(&data))
+            }
+            Op::Pc => BigInt::zero(),
+            Op::Pop => return Ok(None),
+
+            Op::Mload => self.memory_load_word(&arg(0))?,
+            Op::Mstore => {
+                self.memory_write(&arg(0), &to_bytes(&args[1]))?;
+                return Ok(None);
+            }
+            Op::Mstore8 => {
+                self.memory_write(&arg(0), &[to_bytes(&args[1])[WORD_SIZE - 1]])?;
+                return Ok(None);
+            }
+            Op::Mcopy => {
+                let size = self.memory_offset(&arg(2))?;
+                let data = self.memory_read(&arg(1), size)?;
+                self.memory_write(&arg(0), &data)?;
+                return Ok(None);
+            }
+            Op::Msize => self.memory.size().into(),
+
+            Op::Sload => self.storage.load(&args[0]),
+            Op::Sstore => {
+                self.storage.store(arg(0), arg(1));
+                return Ok(None);
+            }
+            Op::Tload => self.transient_storage.load(&args[0]),
+            Op::Tstore => {
+                self.transient_storage.store(arg(0), arg(1));
+                return Ok(None);
+            }
+
+            Op::Calldataload => {
+                let data = self.calldata(&args[0], WORD_SIZE);
+                from_bytes(&data)
+            }
+            Op::Calldatasize => self.env.calldata.len().into(),
+            Op::Calldatacopy => {
+                let size = self.memory_offset(&arg(2))?;
+                let data = self.calldata(&args[1], size);
+                self.memory_write(&arg(0), &data)?;
+                return Ok(None);
+            }
+
+            // The interpreted code isn't deployed, so there is no code to
+            // inspect.
+            Op::Codesize | Op::Extcodesize | Op::Extcodehash | Op::Blockhash => BigInt::zero(),
+            Op::Codecopy | Op::Extcodecopy => {
+                let (dst, size) = if op == Op::Codecopy {
+                    (arg(0), arg(2))
+                } else {
+                    (arg(1), arg(3))
+                };
+                let size = self.memory_offset(&size)?;
+                self.memory_write(&dst, &vec![0; size])?;
+                return Ok(None);
+            }
+
+            // No other contract is ever called, so there is no return data.
+            Op::Returndatasize => BigInt::zero(),
+            Op::Returndatacopy => {
+                if args[1].is_zero() && args[2].is_zero() {
+                    return Ok(None);
+                }
+                return Err(Trap::Invalid.into());
+            }
+
+            Op::Log0 | Op::Log1 | Op::Log2 | Op::Log3 | Op::Log4 => {
+                let size = self.memory_offset(&arg(1))?;
+                let data = self.memory_read(&arg(0), size)?;
+                let topics = args[2..].to_vec();
+                self.logs.push(Log::Raw { topics, data });
+                return Ok(None);
+            }
+
+            Op::Gas => self.env.gas_limit.clone(),
+            Op::Address => self.env.address.clone(),
+            Op::Balance if args[0] == self.env.address => self.env.balance.clone(),
+            Op::Balance => BigInt::zero(),
+            Op::Selfbalance => self.env.balance.clone(),
+            Op::Caller => self.env.caller.clone(),
+            Op::Callvalue => self.env.call_value.clone(),
+            Op::Origin => self.env.origin.clone(),
+            Op::Gasprice => self.env.gas_price.clone(),
+            Op::Gaslimit => self.env.gas_limit.clone(),
+            Op::Chainid => self.env.chain_id.clone(),
+            Op::Basefee => self.env.base_fee.clone(),
+            Op::Coinbase => self.env.coinbase.clone(),
+            Op::Timestamp => self.env.timestamp.clone(),
+            Op::Number => self.env.block_number.clone(),
+            Op::Prevrandao => self.env.prevrandao.clone(),
+
+            Op::Create
+            | Op::Create2
+            | Op::Call
+            | Op::Callcode
+            | Op::Delegatecall
+            | Op::Staticcall
+            | Op::Selfdestruct => return Err(Trap::Unsupported(format!("`{op}`")).into()),
+        };
+
+        Ok(Some(result))
+    }
+
+    /// Returns `size` bytes of the calldata from `offset`, padded with zeros.
+    fn calldata(&self, offset: &BigInt, size: usize) -> Vec<u8> {
+        let calldata = &self.env.calldata;
+        let start = offset.to_usize().unwrap_or(usize::MAX).min(calldata.len());
+        let end = start.saturating_add(size).min(calldata.len());
+        let mut data = calldata[start..end].to_vec();
+        data.resize(size, 0);
+        data
+    }
+}
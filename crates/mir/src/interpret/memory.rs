@@ -0,0 +1,120 @@
+use fxhash::FxHashMap;
+use num_bigint::{BigInt, Sign};
+use num_traits::{ToPrimitive, Zero};
+
+/// The size of a word and a storage slot in bytes.
+pub(super) const WORD_SIZE: usize = 32;
+
+/// Zero initialized, byte addressed memory that grows in words as it's
+/// accessed, like the memory of the EVM.
+#[derive(Debug, Default, Clone)]
+pub(super) struct Memory {
+    bytes: Vec<u8>,
+}
+
+impl Memory {
+    pub(super) fn read(&mut self, offset: usize, size: usize) -> Vec<u8> {
+        if size == 0 {
+            return vec![];
+        }
+        self.expand(offset + size);
+        self.bytes[offset..offset + size].to_vec()
+    }
+
+    pub(super) fn write(&mut self, offset: usize, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        self.expand(offset + data.len());
+        self.bytes[offset..offset + data.len()].copy_from_slice(data);
+    }
+
+    pub(super) fn load_word(&mut self, offset: usize) -> BigInt {
+        from_bytes(&self.read(offset, WORD_SIZE))
+    }
+
+    pub(super) fn store_word(&mut self, offset: usize, word: &BigInt) {
+        self.write(offset, &to_bytes(word))
+    }
+
+    /// Returns the size of the accessed memory, which is always a multiple of
+    /// the word size.
+    pub(super) fn size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn expand(&mut self, end: usize) {
+        if end > self.bytes.len() {
+            let len = (end + WORD_SIZE - 1) / WORD_SIZE * WORD_SIZE;
+            self.bytes.resize(len, 0);
+        }
+    }
+}
+
+/// Word addressed storage, whose unset slots are zero.
+///
+/// Storage pointers are byte addresses, i.e. the byte at `ptr` is the byte
+/// `ptr % 32` of the slot `ptr / 32`, counted from the most significant one.
+#[derive(Debug, Default, Clone)]
+pub(super) struct Storage {
+    slots: FxHashMap<BigInt, BigInt>,
+}
+
+impl Storage {
+    pub(super) fn load(&self, slot: &BigInt) -> BigInt {
+        self.slots.get(slot).cloned().unwrap_or_default()
+    }
+
+    pub(super) fn store(&mut self, slot: BigInt, word: BigInt) {
+        if word.is_zero() {
+            self.slots.remove(&slot);
+        } else {
+            self.slots.insert(slot, word);
+        }
+    }
+
+    pub(super) fn read(&self, ptr: &BigInt, size: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(size);
+        let mut ptr = ptr.clone();
+        while data.len() < size {
+            let (slot, offset) = slot_and_offset(&ptr);
+            let len = (WORD_SIZE - offset).min(size - data.len());
+            data.extend_from_slice(&to_bytes(&self.load(&slot))[offset..offset + len]);
+            ptr += len;
+        }
+        data
+    }
+
+    pub(super) fn write(&mut self, ptr: &BigInt, mut data: &[u8]) {
+        let mut ptr = ptr.clone();
+        while !data.is_empty() {
+            let (slot, offset) = slot_and_offset(&ptr);
+            let len = (WORD_SIZE - offset).min(data.len());
+            let mut bytes = to_bytes(&self.load(&slot));
+            bytes[offset..offset + len].copy_from_slice(&data[..len]);
+            self.store(slot, from_bytes(&bytes));
+            data = &data[len..];
+            ptr += len;
+        }
+    }
+}
+
+fn slot_and_offset(ptr: &BigInt) -> (BigInt, usize) {
+    let slot = ptr / WORD_SIZE;
+    let offset = (ptr % WORD_SIZE).to_usize().unwrap();
+    (slot, offset)
+}
+
+/// Returns the big endian bytes of a word.
+pub(super) fn to_bytes(word: &BigInt) -> [u8; WORD_SIZE] {
+    let (_, bytes) = word.to_bytes_be();
+    let mut word_bytes = [0; WORD_SIZE];
+    let len = bytes.len().min(WORD_SIZE);
+    word_bytes[WORD_SIZE - len..].copy_from_slice(&bytes[bytes.len() - len..]);
+    word_bytes
+}
+
+/// Interprets big endian bytes as an unsigned integer.
+pub(super) fn from_bytes(bytes: &[u8]) -> BigInt {
+    BigInt::from_bytes_be(Sign::Plus, bytes)
+}
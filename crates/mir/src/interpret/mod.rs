@@ -0,0 +1,1076 @@
+//! An interpreter of MIR function bodies.
+//!
+//! The interpreter runs a function the way the code generated for it runs on
+//! the EVM: values are 256 bit words, aggregates live in memory or storage and
+//! are laid out as codegen lays them out, and checked arithmetic reverts with
+//! the same panic codes. This makes it usable to run tests without compiling
+//! them to EVM bytecode, and as an oracle to check optimizations and codegen
+//! against.
+//!
+//! Functions run in a mock [`Env`], which is what `Context` and the EVM
+//! intrinsics observe. Calling other contracts and creating contracts is not
+//! supported.
+
+use std::{fmt, rc::Rc};
+
+use fe_common::utils::keccak;
+use fxhash::FxHashMap;
+use num_bigint::{BigInt, Sign};
+use num_traits::{ToPrimitive, Zero};
+use smol_str::SmolStr;
+
+use crate::{
+    db::MirDb,
+    ir::{
+        constant::ConstantValue,
+        inst::{BinOp, CallType, CastKind, InstKind, UnOp},
+        types::TupleDef,
+        value::AssignableValue,
+        BasicBlockId, FunctionBody, FunctionId, InstId, Type, TypeId, TypeKind, Value, ValueId,
+    },
+    word::{bool_word, checked_result, clean_word, to_signed, to_word, word_max},
+};
+
+use memory::{from_bytes, to_bytes, Memory, Storage, WORD_SIZE};
+
+mod intrinsic;
+mod memory;
+
+/// The number of instructions [`Interpreter`] executes before giving up, by
+/// default.
+pub const DEFAULT_STEP_LIMIT: usize = 10_000_000;
+
+/// The deepest internal calls can be nested.
+const MAX_CALL_DEPTH: usize = 256;
+
+/// The largest memory offset the interpreter accesses, which would cost more
+/// gas than a block has on the EVM.
+const MEMORY_LIMIT: usize = 1 << 25;
+
+/// Memory below this address is the scratch space used to hash map keys and
+/// the free memory pointer.
+const FREE_MEMORY_START: usize = 0x60;
+const FREE_MEMORY_POINTER: usize = 0x40;
+
+const PANIC_OVERFLOW: u8 = 0x11;
+const PANIC_ZERO_DIVISION: u8 = 0x12;
+const PANIC_OUT_OF_BOUNDS: u8 = 0x32;
+
+/// The environment a function is interpreted in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Env {
+    /// The address of the running contract.
+    pub address: BigInt,
+    /// The balance of the running contract.
+    pub balance: BigInt,
+    pub caller: BigInt,
+    pub origin: BigInt,
+    pub call_value: BigInt,
+    pub calldata: Vec<u8>,
+    pub gas_price: BigInt,
+    pub gas_limit: BigInt,
+    pub chain_id: BigInt,
+    pub base_fee: BigInt,
+    pub coinbase: BigInt,
+    pub timestamp: BigInt,
+    pub block_number: BigInt,
+    pub prevrandao: BigInt,
+}
+
+impl Default for Env {
+    /// Returns the environment tests run in on the EVM, where they are
+    /// deployed at `0x42` and called with empty calldata.
+    fn default() -> Self {
+        Self {
+            address: 0x42.into(),
+            balance: 0.into(),
+            caller: 0.into(),
+            origin: 0.into(),
+            call_value: 0.into(),
+            calldata: vec![],
+            gas_price: 0.into(),
+            gas_limit: u64::MAX.into(),
+            chain_id: 1.into(),
+            base_fee: 0.into(),
+            coinbase: 0.into(),
+            timestamp: 1.into(),
+            block_number: 0.into(),
+            prevrandao: 0.into(),
+        }
+    }
+}
+
+/// The reason a function didn't return.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Trap {
+    /// Reverted with `Panic(code)`, e.g. `0x11` on an arithmetic overflow.
+    Panic(BigInt),
+
+    /// Reverted with a string, e.g. by a failed `assert` with a message.
+    Error(String),
+
+    /// Reverted with a struct of the given name, or with no data.
+    Revert(Option<SmolStr>),
+
+    /// Reverted with the given data by the `__revert` intrinsic.
+    RawRevert(Vec<u8>),
+
+    /// Halted exceptionally, e.g. by the `__invalid` intrinsic.
+    Invalid,
+
+    /// Reached an operation the interpreter can't perform, such as a call to
+    /// another contract.
+    Unsupported(String),
+
+    StepLimitExceeded,
+    StackOverflow,
+    MemoryLimitExceeded,
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Panic(code) => write!(w, "reverted with panic code {code:#x}"),
+            Self::Error(message) => write!(w, "reverted with error {message:?}"),
+            Self::Revert(Some(name)) => write!(w, "reverted with `{name}`"),
+            Self::Revert(None) => write!(w, "reverted"),
+            Self::RawRevert(data) => {
+                write!(w, "reverted with the following output: {}", hex(data))
+            }
+            Self::Invalid => write!(w, "halted exceptionally"),
+            Self::Unsupported(op) => write!(w, "{op} is not supported by the interpreter"),
+            Self::StepLimitExceeded => write!(w, "exceeded the step limit"),
+            Self::StackOverflow => write!(w, "exceeded the call depth limit"),
+            Self::MemoryLimitExceeded => write!(w, "exceeded the memory limit"),
+        }
+    }
+}
+
+impl std::error::Error for Trap {}
+
+/// A log written while interpreting a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Log {
+    /// An event emitted with `ctx.emit`, and the bytes of the event struct.
+    Event { name: SmolStr, data: Vec<u8> },
+
+    /// A log written by one of the `__log` intrinsics.
+    Raw { topics: Vec<BigInt>, data: Vec<u8> },
+}
+
+impl fmt::Display for Log {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Event { name, data } => write!(w, "{name} emitted with data {}", hex(data)),
+            Self::Raw { topics, data } => {
+                let topics: Vec<_> = topics.iter().map(|topic| format!("{topic:#x}")).collect();
+                write!(
+                    w,
+                    "log with topics [{}] and data {}",
+                    topics.join(", "),
+                    hex(data)
+                )
+            }
+        }
+    }
+}
+
+/// Interprets functions in a mock environment. Memory, storage and logs are
+/// kept between calls.
+pub struct Interpreter<'a> {
+    db: &'a dyn MirDb,
+    env: Env,
+    memory: Memory,
+    storage: Storage,
+    transient_storage: Storage,
+    logs: Vec<Log>,
+    step_limit: usize,
+    steps: usize,
+    depth: usize,
+}
+
+/// How the execution of a function ended early.
+enum Exit {
+    Trap(Trap),
+    /// The whole execution halted successfully, e.g. by the `__stop` intrinsic.
+    Halt,
+}
+
+impl From<Trap> for Exit {
+    fn from(trap: Trap) -> Self {
+        Self::Trap(trap)
+    }
+}
+
+/// Where the execution continues after an instruction.
+enum Flow {
+    Next,
+    Jump(BasicBlockId),
+    Return(Option<BigInt>),
+}
+
+/// The values of a running function.
+struct Frame {
+    body: Rc<FunctionBody>,
+    values: FxHashMap<ValueId, BigInt>,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(db: &'a dyn MirDb, env: Env) -> Self {
+        Self {
+            db,
+            env,
+            memory: Memory::default(),
+            storage: Storage::default(),
+            transient_storage: Storage::default(),
+            logs: vec![],
+            step_limit: DEFAULT_STEP_LIMIT,
+            steps: 0,
+            depth: 0,
+        }
+    }
+
+    /// Sets the number of instructions executed, over all calls, after which
+    /// the interpreter traps.
+    pub fn set_step_limit(&mut self, step_limit: usize) {
+        self.step_limit = step_limit;
+    }
+
+    /// Calls `func` with a word for each of its parameters, and returns the
+    /// word it returns, if any.
+    ///
+    /// The words of aggregate arguments are pointers to memory, and zero sized
+    /// and contract arguments are ignored. A function that halts the execution
+    /// successfully, e.g. with `__stop`, returns `None`.
+    pub fn call(&mut self, func: FunctionId, args: &[BigInt]) -> Result<Option<BigInt>, Trap> {
+        match self.run(func, args.to_vec()) {
+            Ok(value) => Ok(value),
+            Err(Exit::Halt) => Ok(None),
+            Err(Exit::Trap(trap)) => Err(trap),
+        }
+    }
+
+    pub fn logs(&self) -> &[Log] {
+        &self.logs
+    }
+
+    /// Returns the word stored in the storage slot `slot`.
+    pub fn storage_slot(&self, slot: &BigInt) -> BigInt {
+        self.storage.load(slot)
+    }
+
+    fn run(&mut self, func: FunctionId, args: Vec<BigInt>) -> Result<Option<BigInt>, Exit> {
+        if self.depth == MAX_CALL_DEPTH {
+            return Err(Trap::StackOverflow.into());
+        }
+
+        let body = self.db.mir_lowered_func_body(func);
+        let mut frame = Frame {
+            body: body.clone(),
+            values: FxHashMap::default(),
+        };
+        for (arg, word) in body.store.func_args().zip(args) {
+            frame.values.insert(arg, word);
+        }
+
+        self.depth += 1;
+        let result = self.run_body(&mut frame);
+        self.depth -= 1;
+        result
+    }
+
+    fn run_body(&mut self, frame: &mut Frame) -> Result<Option<BigInt>, Exit> {
+        let body = frame.body.clone();
+        let mut block = body.order.entry();
+        loop {
+            let mut dest = None;
+            for inst in body.order.iter_inst(block) {
+                self.steps += 1;
+                if self.steps > self.step_limit {
+                    return Err(Trap::StepLimitExceeded.into());
+                }
+
+                match self.exec_inst(frame, inst)? {
+                    Flow::Next => {}
+                    Flow::Jump(block) => {
+                        dest = Some(block);
+                        break;
+                    }
+                    Flow::Return(value) => return Ok(value),
+                }
+            }
+
+            let dest = dest.expect("block without a terminator");
+            self.enter_block(frame, dest, block)?;
+            block = dest;
+        }
+    }
+
+    /// Assigns the phis of `block`, which is entered from `pred`.
+    fn enter_block(
+        &mut self,
+        frame: &mut Frame,
+        block: BasicBlockId,
+        pred: BasicBlockId,
+    ) -> Result<(), Exit> {
+        let body = frame.body.clone();
+        let mut words = vec![];
+        for phi in body.phis(block) {
+            if let InstKind::Phi { args, blocks } = &body.store.inst_data(phi).kind {
+                let idx = blocks
+                    .iter()
+                    .position(|block| *block == pred)
+                    .expect("phi without a value for the predecessor");
+                words.push((phi, self.word(frame, args[idx])?));
+            }
+        }
+
+        for (phi, word) in words {
+            if let Some(AssignableValue::Value(value)) = body.store.inst_result(phi) {
+                frame.values.insert(*value, word);
+            }
+        }
+        Ok(())
+    }
+
+    fn exec_inst(&mut self, frame: &mut Frame, inst: InstId) -> Result<Flow, Exit> {
+        let db = self.db;
+        let body = frame.body.clone();
+        // Phis are assigned when their block is entered.
+        if matches!(
+            body.store.inst_data(inst).kind,
+            InstKind::Phi { .. } | InstKind::Nop
+        ) {
+            return Ok(Flow::Next);
+        }
+
+        if let Some(AssignableValue::Value(value)) = body.store.inst_result(inst) {
+            if matches!(body.store.value_data(*value), Value::Temporary { .. })
+                || !frame.values.contains_key(value)
+            {
+                self.declare(frame, *value)?;
+            }
+        }
+
+        match &body.store.inst_data(inst).kind {
+            InstKind::Declare { local } => self.declare(frame, *local)?,
+
+            InstKind::Unary { op, value } => {
+                let result_ty = self.result_ty(frame, inst).deref(db);
+                let word = self.word(frame, *value)?;
+                let result = match op {
+                    UnOp::Not => bool_word(word.is_zero()),
+                    UnOp::Inv => word_max() - word,
+                    // Codegen doesn't check the negation of an immediate.
+                    UnOp::Neg if body.store.value_data(*value).is_imm() => to_word(-word),
+                    UnOp::Neg => {
+                        let ty = self.value_ty(frame, *value);
+                        self.checked(-self.int(&word, ty), ty)?
+                    }
+                };
+                self.assign(frame, inst, result, result_ty)?;
+            }
+
+            InstKind::Binary { op, lhs, rhs } => {
+                let result_ty = self.result_ty(frame, inst).deref(db);
+                let result = self.binary(frame, *op, *lhs, *rhs, result_ty)?;
+                self.assign(frame, inst, result, result_ty)?;
+            }
+
+            InstKind::Cast { kind, value, to } => {
+                let from_ty = self.value_ty(frame, *value);
+                let word = self.word(frame, *value)?;
+                match kind {
+                    CastKind::Primitive => {
+                        let result = clean(db, word, from_ty);
+                        self.assign(frame, inst, result, *to)?;
+                    }
+                    CastKind::Untag => {
+                        let offset = from_ty.deref(db).enum_data_offset(db, WORD_SIZE);
+                        let to = if to.is_aggregate(db) && !to.is_zero_sized(db) {
+                            make_ptr(db, *to, from_ty.is_sptr(db))
+                        } else {
+                            *to
+                        };
+                        self.assign(frame, inst, to_word(word + offset), to)?;
+                    }
+                }
+            }
+
+            InstKind::AggregateConstruct { ty, args } => {
+                if !ty.deref(db).is_zero_sized(db) {
+                    let place = body.store.inst_result(inst).unwrap();
+                    let ptr = self.place_ptr(frame, place)?;
+                    let ptr_ty = self.place_ty(frame, place);
+                    let mut arg_words = vec![];
+                    let mut arg_tys = vec![];
+                    for arg in args {
+                        let arg_ty = self.value_ty(frame, *arg);
+                        if !arg_ty.deref(db).is_zero_sized(db) {
+                            arg_words.push(self.word(frame, *arg)?);
+                            arg_tys.push(arg_ty);
+                        }
+                    }
+                    self.aggregate_init(ptr, ptr_ty, &arg_words, &arg_tys)?;
+                }
+            }
+
+            InstKind::Bind { src } => {
+                let string = match body.store.value_data(*src) {
+                    Value::Constant { constant, .. } => match &constant.data(db).value {
+                        ConstantValue::Str(s) => Some(s.clone()),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
+                // A string literal is copied to the result rather than
+                // constructed in memory first.
+                if let Some(s) = string {
+                    let place = body.store.inst_result(inst).unwrap();
+                    let ptr = self.place_ptr(frame, place)?;
+                    let is_sptr = self.place_ty(frame, place).is_sptr(db);
+                    self.string_copy(ptr, &s, is_sptr)?;
+                } else {
+                    let src_ty = self.value_ty(frame, *src);
+                    let word = self.word(frame, *src)?;
+                    self.assign(frame, inst, word, src_ty)?;
+                }
+            }
+
+            InstKind::MemCopy { src } => {
+                let place = body.store.inst_result(inst).unwrap();
+                let dst = self.place_ptr(frame, place)?;
+                let dst_ty = self.place_ty(frame, place);
+                let src_ty = self.value_ty(frame, *src);
+                let src = self.word(frame, *src)?;
+                let size = src_ty.deref(db).size_of(db, WORD_SIZE);
+                self.ptr_copy(&src, &dst, size, src_ty.is_sptr(db), dst_ty.is_sptr(db))?;
+            }
+
+            InstKind::Load { src } => {
+                let src_ty = self.value_ty(frame, *src);
+                let word = self.word(frame, *src)?;
+                self.assign(frame, inst, word, src_ty)?;
+            }
+
+            InstKind::AggregateAccess { value, indices } => {
+                let mut ptr = self.word(frame, *value)?;
+                let mut ty = self.value_ty(frame, *value);
+                for idx in indices {
+                    ptr = self.elem_ptr(frame, ptr, *idx, ty.deref(db))?;
+                    ty = ty.projection_ty(db, body.store.value_data(*idx));
+                }
+                self.assign(frame, inst, ptr, ty)?;
+            }
+
+            InstKind::MapAccess { value, key } => {
+                let map_ptr = self.word(frame, *value)?;
+                let value_ty = match &self.value_ty(frame, *value).deref(db).data(db).kind {
+                    TypeKind::Map(def) => def.value_ty,
+                    _ => unreachable!(),
+                };
+                let ptr = self.map_value_ptr(frame, map_ptr, *key)?;
+                self.assign(frame, inst, ptr, value_ty.make_sptr(db))?;
+            }
+
+            InstKind::Call {
+                func,
+                args,
+                call_type,
+            } => {
+                if *call_type == CallType::External {
+                    return Err(Trap::Unsupported("calling another contract".into()).into());
+                }
+
+                let mut arg_words = vec![];
+                for arg in args {
+                    arg_words.push(self.word(frame, *arg)?);
+                }
+                let result = self.run(*func, arg_words)?;
+                if let Some(result_ty) = func.signature(db).return_type {
+                    if !result_ty.is_contract(db) && !result_ty.deref(db).is_zero_sized(db) {
+                        let result_ty = legalized_ty(db, result_ty);
+                        self.assign(frame, inst, result.unwrap_or_default(), result_ty)?;
+                    }
+                }
+            }
+
+            InstKind::Revert { arg: None } => return Err(Trap::Revert(None).into()),
+
+            InstKind::Revert { arg: Some(arg) } => {
+                let arg_ty = self.value_ty(frame, *arg);
+                let trap = match &arg_ty.deref(db).data(db).kind {
+                    TypeKind::Struct(def) => Trap::Revert(Some(def.name.clone())),
+                    TypeKind::String(_) => {
+                        let ptr = self.word(frame, *arg)?;
+                        let len = self.memory_load_word(&ptr)?;
+                        let len = self.memory_offset(&len)?;
+                        let data = self.memory_read(&(ptr + WORD_SIZE), len)?;
+                        Trap::Error(String::from_utf8_lossy(&data).into_owned())
+                    }
+                    _ => Trap::Panic(self.word(frame, *arg)?),
+                };
+                return Err(trap.into());
+            }
+
+            InstKind::Emit { arg } => {
+                let event_ty = self.value_ty(frame, *arg);
+                let name = match &event_ty.deref(db).data(db).kind {
+                    TypeKind::Struct(def) => def.name.clone(),
+                    _ => SmolStr::default(),
+                };
+                let ptr = self.word(frame, *arg)?;
+                let size = event_ty.deref(db).size_of(db, WORD_SIZE);
+                let data = if event_ty.is_sptr(db) {
+                    self.storage.read(&ptr, size)
+                } else if event_ty.is_mptr(db) {
+                    self.memory_read(&ptr, size)?
+                } else {
+                    vec![]
+                };
+                self.logs.push(Log::Event { name, data });
+            }
+
+            InstKind::Return { arg } => {
+                let value = match arg {
+                    Some(arg) if !self.value_ty(frame, *arg).deref(db).is_zero_sized(db) => {
+                        Some(self.word(frame, *arg)?)
+                    }
+                    _ => None,
+                };
+                return Ok(Flow::Return(value));
+            }
+
+            InstKind::Keccak256 { arg } => {
+                let ptr = self.word(frame, *arg)?;
+                let size = self.value_ty(frame, *arg).deref(db).size_of(db, WORD_SIZE);
+                let data = self.memory_read(&ptr, size)?;
+                let hash = from_bytes(&keccak::full_as_bytes(&data));
+                let u256_ty = u256_ty(db);
+                self.assign(frame, inst, hash, u256_ty)?;
+            }
+
+            InstKind::AbiEncode { .. } => {
+                return Err(Trap::Unsupported("ABI encoding".into()).into())
+            }
+
+            InstKind::Create { .. } | InstKind::Create2 { .. } => {
+                return Err(Trap::Unsupported("creating a contract".into()).into())
+            }
+
+            InstKind::YulIntrinsic { op, args } => {
+                let mut arg_words = vec![];
+                for arg in args {
+                    arg_words.push(self.word(frame, *arg)?);
+                }
+                if let Some(result) = self.intrinsic(*op, &arg_words)? {
+                    let u256_ty = u256_ty(db);
+                    self.assign(frame, inst, result, u256_ty)?;
+                }
+            }
+
+            InstKind::Jump { dest } => return Ok(Flow::Jump(*dest)),
+
+            InstKind::Branch { cond, then, else_ } => {
+                let dest = if self.word(frame, *cond)?.is_zero() {
+                    *else_
+                } else {
+                    *then
+                };
+                return Ok(Flow::Jump(dest));
+            }
+
+            InstKind::Switch {
+                disc,
+                table,
+                default,
+            } => {
+                let disc = self.word(frame, *disc)?;
+                for (value, dest) in table.iter() {
+                    if self.word(frame, value)? == disc {
+                        return Ok(Flow::Jump(dest));
+                    }
+                }
+                let dest = default.expect("switch without a matching arm");
+                return Ok(Flow::Jump(dest));
+            }
+
+            InstKind::Phi { .. } | InstKind::Nop => unreachable!(),
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn binary(
+        &mut self,
+        frame: &Frame,
+        op: BinOp,
+        lhs: ValueId,
+        rhs: ValueId,
+        result_ty: TypeId,
+    ) -> Result<BigInt, Exit> {
+        let db = self.db;
+        let is_lhs_signed = self.value_ty(frame, lhs).is_signed(db);
+        let lhs = self.word(frame, lhs)?;
+        let rhs = self.word(frame, rhs)?;
+
+        let checked_lhs = self.int(&lhs, result_ty);
+        let checked_rhs = self.int(&rhs, result_ty);
+        let result = match op {
+            BinOp::Add => self.checked(checked_lhs + checked_rhs, result_ty)?,
+            BinOp::Sub => self.checked(checked_lhs - checked_rhs, result_ty)?,
+            BinOp::Mul => self.checked(checked_lhs * checked_rhs, result_ty)?,
+            BinOp::Div | BinOp::Mod if checked_rhs.is_zero() => {
+                return Err(Trap::Panic(PANIC_ZERO_DIVISION.into()).into())
+            }
+            BinOp::Div => self.checked(checked_lhs / checked_rhs, result_ty)?,
+            BinOp::Mod => self.checked(checked_lhs % checked_rhs, result_ty)?,
+            BinOp::Pow => {
+                // The exponent is unsigned. Anything larger than 256 overflows
+                // unless the base is `0`, `1` or `-1`.
+                let power = match rhs.to_u32().filter(|exp| *exp <= 256) {
+                    Some(exp) => checked_lhs.pow(exp),
+                    None if checked_lhs.is_zero() || checked_lhs == BigInt::from(1) => checked_lhs,
+                    None if checked_lhs == BigInt::from(-1) => {
+                        if rhs.bit(0) {
+                            checked_lhs
+                        } else {
+                            1.into()
+                        }
+                    }
+                    None => return Err(Trap::Panic(PANIC_OVERFLOW.into()).into()),
+                };
+                self.checked(power, result_ty)?
+            }
+            BinOp::Shl => match rhs.to_usize().filter(|shift| *shift < 256) {
+                Some(shift) => to_word(lhs << shift),
+                None => 0.into(),
+            },
+            BinOp::Shr => {
+                let lhs = if result_ty.is_signed(db) {
+                    to_signed(&lhs)
+                } else {
+                    lhs
+                };
+                match rhs.to_usize().filter(|shift| *shift < 256) {
+                    Some(shift) => to_word(lhs >> shift),
+                    None if lhs.sign() == Sign::Minus => word_max(),
+                    None => 0.into(),
+                }
+            }
+            BinOp::BitOr | BinOp::LogicalOr => lhs | rhs,
+            BinOp::BitXor => lhs ^ rhs,
+            BinOp::BitAnd | BinOp::LogicalAnd => lhs & rhs,
+            BinOp::Eq => bool_word(lhs == rhs),
+            BinOp::Ne => bool_word(lhs != rhs),
+            _ => {
+                let (lhs, rhs) = if is_lhs_signed {
+                    (to_signed(&lhs), to_signed(&rhs))
+                } else {
+                    (lhs, rhs)
+                };
+                bool_word(match op {
+                    BinOp::Ge => lhs >= rhs,
+                    BinOp::Gt => lhs > rhs,
+                    BinOp::Le => lhs <= rhs,
+                    BinOp::Lt => lhs < rhs,
+                    _ => unreachable!(),
+                })
+            }
+        };
+
+        Ok(result)
+    }
+
+    /// Returns the integer `word` represents as a value of `ty`.
+    fn int(&self, word: &BigInt, ty: TypeId) -> BigInt {
+        if ty.is_signed(self.db) {
+            to_signed(word)
+        } else {
+            word.clone()
+        }
+    }
+
+    /// Returns the word of `value`, or traps with an overflow if it doesn't fit
+    /// in `ty`.
+    fn checked(&self, value: BigInt, ty: TypeId) -> Result<BigInt, Exit> {
+        checked_result(self.db, value, ty).ok_or_else(|| Trap::Panic(PANIC_OVERFLOW.into()).into())
+    }
+
+    /// Assigns `rhs`, whose type is `rhs_ty`, to the result of `inst` the way
+    /// codegen does: pointers to the same kind of location are copied, values
+    /// are loaded from and stored to pointers, and aggregates are copied
+    /// between memory and storage.
+    fn assign(
+        &mut self,
+        frame: &mut Frame,
+        inst: InstId,
+        rhs: BigInt,
+        rhs_ty: TypeId,
+    ) -> Result<(), Exit> {
+        let db = self.db;
+        let body = frame.body.clone();
+        let place = match body.store.inst_result(inst) {
+            Some(place) => place,
+            None => return Ok(()),
+        };
+        let lhs_ty = self.place_ty(frame, place);
+        if lhs_ty.deref(db).is_zero_sized(db) {
+            return Ok(());
+        }
+
+        match place {
+            AssignableValue::Value(value) => {
+                let word = match (lhs_ty.is_ptr(db), rhs_ty.is_ptr(db)) {
+                    (true, true) if lhs_ty.is_mptr(db) == rhs_ty.is_mptr(db) => rhs,
+                    (true, true) => {
+                        let lhs = self.word(frame, *value)?;
+                        let size = rhs_ty.deref(db).size_of(db, WORD_SIZE);
+                        self.ptr_copy(&rhs, &lhs, size, rhs_ty.is_sptr(db), lhs_ty.is_sptr(db))?;
+                        return Ok(());
+                    }
+                    (true, false) => {
+                        let lhs = self.word(frame, *value)?;
+                        return self.ptr_store(&lhs, &rhs, lhs_ty);
+                    }
+                    (false, true) => clean(db, self.ptr_load(&rhs, rhs_ty)?, lhs_ty),
+                    (false, false) => clean(db, rhs, lhs_ty),
+                };
+                frame.values.insert(*value, word);
+            }
+
+            AssignableValue::Aggregate { .. } | AssignableValue::Map { .. } => {
+                let lhs = self.place_ptr(frame, place)?;
+                if rhs_ty.is_ptr(db) {
+                    let size = rhs_ty.deref(db).size_of(db, WORD_SIZE);
+                    self.ptr_copy(&rhs, &lhs, size, rhs_ty.is_sptr(db), lhs_ty.is_sptr(db))?;
+                } else {
+                    self.ptr_store(&lhs, &rhs, lhs_ty)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Declares `value`, allocating memory for it if it is a pointer to memory.
+    fn declare(&mut self, frame: &mut Frame, value: ValueId) -> Result<(), Exit> {
+        let db = self.db;
+        let ty = self.value_ty(frame, value);
+        let word = if ty.is_mptr(db) {
+            self.alloc(ty.deref(db).size_of(db, WORD_SIZE))?
+        } else {
+            BigInt::zero()
+        };
+        frame.values.insert(value, word);
+        Ok(())
+    }
+
+    fn word(&mut self, frame: &Frame, value: ValueId) -> Result<BigInt, Exit> {
+        let word = match frame.body.store.value_data(value) {
+            Value::Local(_) | Value::Temporary { .. } => {
+                frame.values.get(&value).cloned().unwrap_or_default()
+            }
+            Value::Immediate { imm, .. } => to_word(imm.clone()),
+            Value::Constant { constant, .. } => match &constant.data(self.db).value {
+                ConstantValue::Immediate(imm) => to_word(imm.clone()),
+                ConstantValue::Bool(b) => bool_word(*b),
+                ConstantValue::Str(s) => {
+                    let ptr = self.alloc(WORD_SIZE + s.len())?;
+                    self.string_copy(ptr.clone(), s, false)?;
+                    ptr
+                }
+            },
+            Value::Unit { .. } => BigInt::zero(),
+        };
+        Ok(word)
+    }
+
+    /// Returns the type `value` has once legalized for codegen.
+    fn value_ty(&self, frame: &Frame, value: ValueId) -> TypeId {
+        legalized_ty(self.db, frame.body.store.value_ty(value))
+    }
+
+    fn result_ty(&self, frame: &Frame, inst: InstId) -> TypeId {
+        let place = frame.body.store.inst_result(inst).unwrap();
+        self.place_ty(frame, place)
+    }
+
+    fn place_ty(&self, frame: &Frame, place: &AssignableValue) -> TypeId {
+        let db = self.db;
+        match place {
+            AssignableValue::Value(value) => self.value_ty(frame, *value),
+            AssignableValue::Aggregate { lhs, idx } => self
+                .place_ty(frame, lhs)
+                .projection_ty(db, frame.body.store.value_data(*idx)),
+            AssignableValue::Map { lhs, .. } => {
+                match &self.place_ty(frame, lhs).deref(db).data(db).kind {
+                    TypeKind::Map(def) => def.value_ty.make_sptr(db),
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    fn place_ptr(&mut self, frame: &Frame, place: &AssignableValue) -> Result<BigInt, Exit> {
+        match place {
+            AssignableValue::Value(value) => self.word(frame, *value),
+            AssignableValue::Aggregate { lhs, idx } => {
+                let base = self.place_ptr(frame, lhs)?;
+                let base_ty = self.place_ty(frame, lhs).deref(self.db);
+                self.elem_ptr(frame, base, *idx, base_ty)
+            }
+            AssignableValue::Map { lhs, key } => {
+                let map_ptr = self.place_ptr(frame, lhs)?;
+                self.map_value_ptr(frame, map_ptr, *key)
+            }
+        }
+    }
+
+    /// Returns the pointer to the element `idx` of the aggregate at `base`.
+    fn elem_ptr(
+        &mut self,
+        frame: &Frame,
+        base: BigInt,
+        idx: ValueId,
+        base_ty: TypeId,
+    ) -> Result<BigInt, Exit> {
+        let db = self.db;
+        let offset = match &base_ty.data(db).kind {
+            TypeKind::Array(def) => {
+                let idx_word = self.word(frame, idx)?;
+                // Codegen only checks the bounds of non-immediate indices.
+                if !frame.body.store.value_data(idx).is_imm() && idx_word >= BigInt::from(def.len) {
+                    return Err(Trap::Panic(PANIC_OUT_OF_BOUNDS.into()).into());
+                }
+                idx_word * base_ty.array_elem_size(db, WORD_SIZE)
+            }
+            _ => match frame.body.store.value_data(idx) {
+                Value::Immediate { imm, .. } => base_ty
+                    .aggregate_elem_offset(db, imm.clone(), WORD_SIZE)
+                    .into(),
+                _ => panic!("only array type can use dynamic value indexing"),
+            },
+        };
+        Ok(to_word(base + offset))
+    }
+
+    /// Returns the storage pointer of the value of `key` in the map at
+    /// `map_ptr`, i.e. the hash of the key and the map pointer with its lowest
+    /// byte cleared.
+    fn map_value_ptr(
+        &mut self,
+        frame: &Frame,
+        map_ptr: BigInt,
+        key: ValueId,
+    ) -> Result<BigInt, Exit> {
+        let db = self.db;
+        let key_ty = self.value_ty(frame, key);
+        let key = if key_ty.deref(db).is_zero_sized(db) {
+            from_bytes(&keccak::full_as_bytes(&[]))
+        } else if key_ty.is_ptr(db) {
+            let ptr = self.word(frame, key)?;
+            let data = self.memory_read(&ptr, key_ty.deref(db).size_of(db, WORD_SIZE))?;
+            from_bytes(&keccak::full_as_bytes(&data))
+        } else {
+            self.word(frame, key)?
+        };
+
+        self.memory.store_word(0, &key);
+        self.memory.store_word(WORD_SIZE, &map_ptr);
+        let hash = from_bytes(&keccak::full_as_bytes(&self.memory.read(0, 2 * WORD_SIZE)));
+        Ok(hash & (word_max() ^ BigInt::from(0xff)))
+    }
+
+    /// Initializes the aggregate at `ptr` with the words of its fields, or the
+    /// discriminant and the data of an enum.
+    fn aggregate_init(
+        &mut self,
+        ptr: BigInt,
+        ptr_ty: TypeId,
+        args: &[BigInt],
+        arg_tys: &[TypeId],
+    ) -> Result<(), Exit> {
+        let db = self.db;
+        let is_sptr = ptr_ty.is_sptr(db);
+        let inner_ty = ptr_ty.deref(db);
+
+        if inner_ty.is_enum(db) {
+            let disc_ptr_ty = make_ptr(db, arg_tys[0], is_sptr);
+            self.ptr_store(&ptr, &args[0], disc_ptr_ty)?;
+            if args.len() == 1 {
+                return Ok(());
+            }
+
+            let data_ty = Type::new(
+                TypeKind::Tuple(TupleDef {
+                    items: arg_tys[1..].iter().map(|ty| ty.deref(db)).collect(),
+                }),
+                None,
+            );
+            let data_ptr_ty = make_ptr(db, db.mir_intern_type(data_ty.into()), is_sptr);
+            let data_ptr = ptr + inner_ty.enum_data_offset(db, WORD_SIZE);
+            return self.aggregate_init(data_ptr, data_ptr_ty, &args[1..], &arg_tys[1..]);
+        }
+
+        for (idx, (arg, arg_ty)) in args.iter().zip(arg_tys).enumerate() {
+            let field_ty = inner_ty.projection_ty_imm(db, idx).deref(db);
+            let field_ptr = &ptr + inner_ty.aggregate_elem_offset(db, idx, WORD_SIZE);
+            if field_ty.is_aggregate(db) || field_ty.is_string(db) {
+                let size = field_ty.size_of(db, WORD_SIZE);
+                self.ptr_copy(arg, &field_ptr, size, arg_ty.is_sptr(db), is_sptr)?;
+            } else {
+                self.ptr_store(&field_ptr, arg, make_ptr(db, field_ty, is_sptr))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the length of `s` followed by its bytes to `ptr`.
+    fn string_copy(&mut self, ptr: BigInt, s: &str, is_sptr: bool) -> Result<(), Exit> {
+        let len = BigInt::from(s.len());
+        let data_ptr = &ptr + WORD_SIZE;
+        if is_sptr {
+            self.storage.write(&ptr, &to_bytes(&len));
+            self.storage.write(&data_ptr, s.as_bytes());
+        } else {
+            self.memory_write(&ptr, &to_bytes(&len))?;
+            self.memory_write(&data_ptr, s.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Loads the primitive `ptr_ty` points to.
+    fn ptr_load(&mut self, ptr: &BigInt, ptr_ty: TypeId) -> Result<BigInt, Exit> {
+        let db = self.db;
+        let size = ptr_ty.deref(db).size_of(db, WORD_SIZE);
+        let data = if ptr_ty.is_sptr(db) {
+            self.storage.read(ptr, size)
+        } else {
+            self.memory_read(ptr, size)?
+        };
+        Ok(from_bytes(&data))
+    }
+
+    /// Stores the low bytes of `value` to the primitive `ptr_ty` points to.
+    fn ptr_store(&mut self, ptr: &BigInt, value: &BigInt, ptr_ty: TypeId) -> Result<(), Exit> {
+        let db = self.db;
+        let size = ptr_ty.deref(db).size_of(db, WORD_SIZE);
+        let bytes = to_bytes(value);
+        let data = &bytes[WORD_SIZE - size..];
+        if ptr_ty.is_sptr(db) {
+            self.storage.write(ptr, data);
+            Ok(())
+        } else {
+            self.memory_write(ptr, data)
+        }
+    }
+
+    fn ptr_copy(
+        &mut self,
+        src: &BigInt,
+        dst: &BigInt,
+        size: usize,
+        is_src_storage: bool,
+        is_dst_storage: bool,
+    ) -> Result<(), Exit> {
+        let data = if is_src_storage {
+            // Copies within storage copy whole slots.
+            let size = if is_dst_storage {
+                (size + WORD_SIZE - 1) / WORD_SIZE * WORD_SIZE
+            } else {
+                size
+            };
+            self.storage.read(src, size)
+        } else {
+            self.memory_read(src, size)?
+        };
+
+        if is_dst_storage {
+            self.storage.write(dst, &data);
+            Ok(())
+        } else {
+            self.memory_write(dst, &data)
+        }
+    }
+
+    /// Allocates `size` bytes of memory by bumping the free memory pointer.
+    fn alloc(&mut self, size: usize) -> Result<BigInt, Exit> {
+        let ptr = self.memory.load_word(FREE_MEMORY_POINTER);
+        let ptr = if ptr.is_zero() {
+            FREE_MEMORY_START.into()
+        } else {
+            ptr
+        };
+        let end = to_word(&ptr + size);
+        self.memory_offset(&end)?;
+        self.memory.store_word(FREE_MEMORY_POINTER, &end);
+        Ok(ptr)
+    }
+
+    fn memory_read(&mut self, ptr: &BigInt, size: usize) -> Result<Vec<u8>, Exit> {
+        if size == 0 {
+            return Ok(vec![]);
+        }
+        let offset = self.memory_offset(ptr)?;
+        self.memory_offset(&(ptr + size))?;
+        Ok(self.memory.read(offset, size))
+    }
+
+    fn memory_write(&mut self, ptr: &BigInt, data: &[u8]) -> Result<(), Exit> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let offset = self.memory_offset(ptr)?;
+        self.memory_offset(&(ptr + data.len()))?;
+        self.memory.write(offset, data);
+        Ok(())
+    }
+
+    fn memory_load_word(&mut self, ptr: &BigInt) -> Result<BigInt, Exit> {
+        Ok(from_bytes(&self.memory_read(ptr, WORD_SIZE)?))
+    }
+
+    /// Converts a memory offset to `usize`, trapping if it's out of reach.
+    fn memory_offset(&self, offset: &BigInt) -> Result<usize, Exit> {
+        offset
+            .to_usize()
+            .filter(|offset| *offset <= MEMORY_LIMIT)
+            .ok_or_else(|| Trap::MemoryLimitExceeded.into())
+    }
+}
+
+/// Returns the type a value of type `ty` has once legalized for codegen, where
+/// aggregates are pointers to memory and contracts are pointers to storage.
+fn legalized_ty(db: &dyn MirDb, ty: TypeId) -> TypeId {
+    if ty.is_contract(db) {
+        ty.make_sptr(db)
+    } else if (ty.is_aggregate(db) || ty.is_string(db)) && !ty.is_zero_sized(db) {
+        ty.make_mptr(db)
+    } else {
+        ty
+    }
+}
+
+fn make_ptr(db: &dyn MirDb, ty: TypeId, is_sptr: bool) -> TypeId {
+    if is_sptr {
+        ty.make_sptr(db)
+    } else {
+        ty.make_mptr(db)
+    }
+}
+
+fn u256_ty(db: &dyn MirDb) -> TypeId {
+    db.mir_intern_type(Type::new(TypeKind::U256, None).into())
+}
+
+/// Cleans `word` up to `ty` if it's a primitive.
+fn clean(db: &dyn MirDb, word: BigInt, ty: TypeId) -> BigInt {
+    clean_word(db, &word, ty).unwrap_or(word)
+}
+
+fn hex(data: &[u8]) -> String {
+    let digits: String = data.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{digits}")
+}
@@ -118,6 +118,16 @@ impl BodyBuilder {
         self.body.store.store_value(Value::Local(local))
     }
 
+    pub fn unary(&mut self, op: UnOp, value: ValueId, source: SourceInfo) -> InstId {
+        let inst = Inst::unary(op, value, source);
+        self.insert_inst(inst)
+    }
+
+    pub fn binary(&mut self, op: BinOp, lhs: ValueId, rhs: ValueId, source: SourceInfo) -> InstId {
+        let inst = Inst::binary(op, lhs, rhs, source);
+        self.insert_inst(inst)
+    }
+
     impl_unary_inst!(not, UnOp::Not);
     impl_unary_inst!(neg, UnOp::Neg);
     impl_unary_inst!(inv, UnOp::Inv);
@@ -184,6 +194,17 @@ impl BodyBuilder {
         self.insert_inst(inst)
     }
 
+    pub fn phi(
+        &mut self,
+        args: Vec<ValueId>,
+        blocks: Vec<BasicBlockId>,
+        source: SourceInfo,
+    ) -> InstId {
+        let kind = InstKind::Phi { args, blocks };
+        let inst = Inst::new(kind, source);
+        self.insert_inst(inst)
+    }
+
     pub fn mem_copy(&mut self, src: ValueId, source: SourceInfo) -> InstId {
         let kind = InstKind::MemCopy { src };
         let inst = Inst::new(kind, source);
@@ -83,6 +83,41 @@ impl FunctionBody {
             source,
         }
     }
+
+    /// Returns the phis at the beginning of `block`.
+    pub fn phis(&self, block: BasicBlockId) -> impl Iterator<Item = InstId> + '_ {
+        self.order
+            .iter_inst(block)
+            .take_while(|inst| matches!(self.store.inst_data(*inst).kind, InstKind::Phi { .. }))
+    }
+
+    /// Makes the phis of `block` take the arguments they took from `from` from
+    /// `to` instead.
+    pub fn replace_phi_pred(&mut self, block: BasicBlockId, from: BasicBlockId, to: BasicBlockId) {
+        let phis: Vec<_> = self.phis(block).collect();
+        for phi in phis {
+            if let InstKind::Phi { blocks, .. } = &mut self.store.inst_data_mut(phi).kind {
+                for pred in blocks {
+                    if *pred == from {
+                        *pred = to;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes the arguments the phis of `block` take from `pred`.
+    pub fn remove_phi_pred(&mut self, block: BasicBlockId, pred: BasicBlockId) {
+        let phis: Vec<_> = self.phis(block).collect();
+        for phi in phis {
+            if let InstKind::Phi { args, blocks } = &mut self.store.inst_data_mut(phi).kind {
+                while let Some(idx) = blocks.iter().position(|block| *block == pred) {
+                    args.remove(idx);
+                    blocks.remove(idx);
+                }
+            }
+        }
+    }
 }
 
 /// A collection of basic block, instructions and values appear in a function
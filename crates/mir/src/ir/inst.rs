@@ -50,6 +50,14 @@ pub enum InstKind {
         src: ValueId,
     },
 
+    /// Selects `args[i]` if control came from `blocks[i]`. Phis are placed at
+    /// the beginning of a block, and have an argument for each predecessor of
+    /// their block.
+    Phi {
+        args: Vec<ValueId>,
+        blocks: Vec<BasicBlockId>,
+    },
+
     MemCopy {
         src: ValueId,
     },
@@ -258,9 +266,10 @@ impl Inst {
                 ValueIter::one(*value).chain(ValueIter::Slice(indices.iter()))
             }
 
-            AggregateConstruct { args, .. } | Call { args, .. } | YulIntrinsic { args, .. } => {
-                ValueIter::Slice(args.iter())
-            }
+            AggregateConstruct { args, .. }
+            | Call { args, .. }
+            | YulIntrinsic { args, .. }
+            | Phi { args, .. } => ValueIter::Slice(args.iter()),
         }
     }
 
@@ -302,9 +311,10 @@ impl Inst {
                 ValueIterMut::one(value).chain(ValueIterMut::Slice(indices.iter_mut()))
             }
 
-            AggregateConstruct { args, .. } | Call { args, .. } | YulIntrinsic { args, .. } => {
-                ValueIterMut::Slice(args.iter_mut())
-            }
+            AggregateConstruct { args, .. }
+            | Call { args, .. }
+            | YulIntrinsic { args, .. }
+            | Phi { args, .. } => ValueIterMut::Slice(args.iter_mut()),
         }
     }
 }
@@ -437,8 +447,11 @@ pub enum YulIntrinsicOp {
     Mload,
     Mstore,
     Mstore8,
+    Mcopy,
     Sload,
     Sstore,
+    Tload,
+    Tstore,
     Msize,
     Gas,
     Address,
@@ -526,8 +539,11 @@ impl fmt::Display for YulIntrinsicOp {
             Self::Mload => "__mload",
             Self::Mstore => "__mstore",
             Self::Mstore8 => "__mstore8",
+            Self::Mcopy => "__mcopy",
             Self::Sload => "__sload",
             Self::Sstore => "__sstore",
+            Self::Tload => "__tload",
+            Self::Tstore => "__tstore",
             Self::Msize => "__msize",
             Self::Gas => "__gas",
             Self::Address => "__address",
@@ -612,8 +628,11 @@ impl From<fe_analyzer::builtins::Intrinsic> for YulIntrinsicOp {
             Intrinsic::__mload => Self::Mload,
             Intrinsic::__mstore => Self::Mstore,
             Intrinsic::__mstore8 => Self::Mstore8,
+            Intrinsic::__mcopy => Self::Mcopy,
             Intrinsic::__sload => Self::Sload,
             Intrinsic::__sstore => Self::Sstore,
+            Intrinsic::__tload => Self::Tload,
+            Intrinsic::__tstore => Self::Tstore,
             Intrinsic::__msize => Self::Msize,
             Intrinsic::__gas => Self::Gas,
             Intrinsic::__address => Self::Address,
@@ -1,7 +1,12 @@
 pub mod analysis;
 pub mod db;
 pub mod graphviz;
+pub mod interpret;
 pub mod ir;
+pub mod lints;
+pub mod optimize;
 pub mod pretty_print;
+pub mod text;
 
 mod lower;
+mod word;
@@ -0,0 +1,224 @@
+//! The lints that need the control flow of a function. The other lints are in
+//! `fe_analyzer::lints`, which also defines how they are turned off.
+
+use std::ops::BitOr;
+
+use fe_analyzer::lints::{is_allowed_in_function, Lint};
+use fe_analyzer::namespace::items::ModuleId;
+use fe_common::diagnostics::{Diagnostic, Label};
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{
+    analysis::{effects::writes_storage_result, ControlFlowGraph},
+    db::MirDb,
+    ir::{
+        inst::{CallType, InstKind, YulIntrinsicOp},
+        BasicBlockId, FunctionBody, FunctionId, InstId,
+    },
+};
+
+/// Returns the warnings of the control flow lints for the contracts of the
+/// module.
+pub fn module_lints(db: &dyn MirDb, module: ModuleId) -> Vec<Diagnostic> {
+    let mut summaries = CallSummaries::default();
+    let mut diagnostics = vec![];
+    for contract in db.module_contracts(module).iter() {
+        for func in db.mir_lower_contract_all_functions(*contract).iter() {
+            let analyzer_func = func.analyzer_func(db);
+            if !is_allowed_in_function(
+                db.upcast(),
+                analyzer_func,
+                Lint::StateWriteAfterExternalCall,
+            ) {
+                state_writes_after_external_calls(db, *func, &mut summaries, &mut diagnostics);
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Warns about each storage write that can run after a call which may reenter
+/// the contract, such as a call to another contract or `ctx.send_value`.
+fn state_writes_after_external_calls(
+    db: &dyn MirDb,
+    func: FunctionId,
+    summaries: &mut CallSummaries,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let body = func.body(db);
+    let cfg = ControlFlowGraph::compute(&body);
+
+    let mut reported = FxHashSet::default();
+    for block in body.order.iter_block() {
+        for call in body.order.iter_inst(block) {
+            if !summaries.inst(db, &body, call).calls_out {
+                continue;
+            }
+
+            for write in reachable_insts(&body, &cfg, block, call) {
+                let write_source = &body.store.inst_data(write).source;
+                let call_source = &body.store.inst_data(call).source;
+                if write_source.is_dummy()
+                    || call_source.is_dummy()
+                    || !summaries.inst(db, &body, write).writes
+                    || !reported.insert(write)
+                {
+                    continue;
+                }
+
+                let mut diagnostic = Lint::StateWriteAfterExternalCall.warning(
+                    "contract state is written after an external call".into(),
+                    vec![
+                        Label::primary(write_source.span, "this writes to storage"),
+                        Label::secondary(
+                            call_source.span,
+                            "after this call, which may reenter the contract",
+                        ),
+                    ],
+                );
+                diagnostic.notes.insert(
+                    0,
+                    "Note: the account that is called may call back into this contract \
+                     while its state is outdated. Update the state before the call."
+                        .into(),
+                );
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+}
+
+/// Returns the instructions that can run after `inst` in `block`.
+fn reachable_insts(
+    body: &FunctionBody,
+    cfg: &ControlFlowGraph,
+    block: BasicBlockId,
+    inst: InstId,
+) -> Vec<InstId> {
+    let mut insts: Vec<_> = body
+        .order
+        .iter_inst(block)
+        .skip_while(|other| *other != inst)
+        .skip(1)
+        .collect();
+
+    let mut visited = FxHashSet::default();
+    let mut worklist = cfg.succs(block).to_vec();
+    while let Some(block) = worklist.pop() {
+        if visited.insert(block) {
+            insts.extend(body.order.iter_inst(block));
+            worklist.extend(cfg.succs(block));
+        }
+    }
+    insts
+}
+
+/// What the code an instruction runs may do, including the code of the
+/// functions it transitively calls.
+#[derive(Debug, Clone, Copy, Default)]
+struct CallSummary {
+    /// Whether it calls another account or creates a contract.
+    calls_out: bool,
+    /// Whether it writes to storage.
+    writes: bool,
+}
+
+impl BitOr for CallSummary {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            calls_out: self.calls_out || rhs.calls_out,
+            writes: self.writes || rhs.writes,
+        }
+    }
+}
+
+/// The summaries of the functions that have been visited.
+#[derive(Debug, Default)]
+struct CallSummaries {
+    funcs: FxHashMap<FunctionId, CallSummary>,
+}
+
+impl CallSummaries {
+    /// Returns what `inst` and the code it runs may do.
+    fn inst(&mut self, db: &dyn MirDb, body: &FunctionBody, inst: InstId) -> CallSummary {
+        let local = local_summary(db, body, inst);
+        match body.store.inst_data(inst).kind {
+            InstKind::Call {
+                func,
+                call_type: CallType::Internal,
+                ..
+            } => local | self.func(db, func),
+            _ => local,
+        }
+    }
+
+    /// Returns what calling `func` may do.
+    fn func(&mut self, db: &dyn MirDb, func: FunctionId) -> CallSummary {
+        if let Some(summary) = self.funcs.get(&func) {
+            return *summary;
+        }
+
+        let mut visited = vec![func];
+        let mut worklist = vec![func];
+        let mut summary = CallSummary::default();
+        while let Some(func) = worklist.pop() {
+            if let Some(known) = self.funcs.get(&func) {
+                summary = summary | *known;
+                continue;
+            }
+
+            let body = func.body(db);
+            for block in body.order.iter_block() {
+                for inst in body.order.iter_inst(block) {
+                    summary = summary | local_summary(db, &body, inst);
+                    if let InstKind::Call {
+                        func: callee,
+                        call_type: CallType::Internal,
+                        ..
+                    } = body.store.inst_data(inst).kind
+                    {
+                        if !visited.contains(&callee) {
+                            visited.push(callee);
+                            worklist.push(callee);
+                        }
+                    }
+                }
+            }
+        }
+
+        self.funcs.insert(func, summary);
+        summary
+    }
+}
+
+/// Returns what `inst` may do other than what an internal callee does.
+fn local_summary(db: &dyn MirDb, body: &FunctionBody, inst: InstId) -> CallSummary {
+    let calls_out = match &body.store.inst_data(inst).kind {
+        InstKind::Call {
+            call_type: CallType::External,
+            ..
+        }
+        | InstKind::Create { .. }
+        | InstKind::Create2 { .. } => true,
+        InstKind::YulIntrinsic { op, .. } => matches!(
+            op,
+            YulIntrinsicOp::Create
+                | YulIntrinsicOp::Create2
+                | YulIntrinsicOp::Call
+                | YulIntrinsicOp::Callcode
+                | YulIntrinsicOp::Delegatecall
+        ),
+        _ => false,
+    };
+    let writes = writes_storage_result(db, body, inst)
+        || matches!(
+            body.store.inst_data(inst).kind,
+            InstKind::YulIntrinsic {
+                op: YulIntrinsicOp::Sstore,
+                ..
+            }
+        );
+    CallSummary { calls_out, writes }
+}
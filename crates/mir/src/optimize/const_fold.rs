@@ -0,0 +1,280 @@
+use num_bigint::BigInt;
+
+use crate::{
+    db::MirDb,
+    ir::{
+        constant::ConstantValue,
+        inst::{BinOp, CastKind, InstKind, UnOp},
+        value::AssignableValue,
+        FunctionBody, InstId, TypeId, Value, ValueId,
+    },
+    word::{bool_word, checked_result, clean_word, to_signed, to_word, word_max},
+};
+
+use super::Pass;
+
+/// Evaluates `Unary`, `Binary` and primitive `Cast` instructions whose operands
+/// are immediates or constants, and replaces them with a `Bind` of the result.
+///
+/// Folding mirrors what codegen emits: values are 256 bit words and the result
+/// is cleaned up to its type on assignment. Checked arithmetic is only folded
+/// if it doesn't overflow, so that the runtime check still reverts otherwise.
+/// Shifts are left alone.
+pub struct ConstFold;
+
+impl Pass for ConstFold {
+    fn name(&self) -> &'static str {
+        "const-fold"
+    }
+
+    fn run(&mut self, db: &dyn MirDb, body: &mut FunctionBody) -> bool {
+        let insts: Vec<_> = body
+            .order
+            .iter_block()
+            .flat_map(|block| body.order.iter_inst(block))
+            .collect();
+
+        let mut changed = false;
+        for inst in insts {
+            if let Some((word, ty)) = fold_inst(db, body, inst) {
+                let imm = body.store.store_value(Value::Immediate { imm: word, ty });
+                body.store.inst_data_mut(inst).kind = InstKind::Bind { src: imm };
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+/// Returns the folded result of `inst` and its type.
+fn fold_inst(db: &dyn MirDb, body: &FunctionBody, inst: InstId) -> Option<(BigInt, TypeId)> {
+    let result_ty = match body.store.inst_result(inst) {
+        Some(AssignableValue::Value(value)) => body.store.value_ty(*value),
+        _ => return None,
+    };
+    if !result_ty.is_primitive(db) || result_ty.is_unit(db) {
+        return None;
+    }
+
+    let word = match &body.store.inst_data(inst).kind {
+        InstKind::Unary { op, value } => fold_unary(db, body, *op, *value, result_ty)?,
+        InstKind::Binary { op, lhs, rhs } => fold_binary(db, body, *op, *lhs, *rhs, result_ty)?,
+        InstKind::Cast {
+            kind: CastKind::Primitive,
+            value,
+            ..
+        } => {
+            let from_ty = body.store.value_ty(*value);
+            if !from_ty.is_primitive(db) {
+                return None;
+            }
+            clean_word(db, &word_of(db, body, *value)?, from_ty)?
+        }
+        _ => return None,
+    };
+
+    Some((clean_word(db, &word, result_ty)?, result_ty))
+}
+
+fn fold_unary(
+    db: &dyn MirDb,
+    body: &FunctionBody,
+    op: UnOp,
+    value: ValueId,
+    result_ty: TypeId,
+) -> Option<BigInt> {
+    let word = word_of(db, body, value)?;
+    match op {
+        UnOp::Not => Some(bool_word(word == BigInt::from(0))),
+        UnOp::Inv => Some(word_max() - word),
+        // Codegen doesn't check the negation of an immediate.
+        UnOp::Neg if body.store.value_data(value).is_imm() => Some(to_word(-word)),
+        UnOp::Neg => {
+            let value = checked_operand(db, body, value)?;
+            checked_result(db, -value, result_ty)
+        }
+    }
+}
+
+fn fold_binary(
+    db: &dyn MirDb,
+    body: &FunctionBody,
+    op: BinOp,
+    lhs: ValueId,
+    rhs: ValueId,
+    result_ty: TypeId,
+) -> Option<BigInt> {
+    let is_lhs_signed = body.store.value_ty(lhs).is_signed(db);
+    let checked = || {
+        let lhs = checked_operand(db, body, lhs)?;
+        let rhs = checked_operand(db, body, rhs)?;
+        let zero = BigInt::from(0);
+        let result = match op {
+            BinOp::Add => lhs + rhs,
+            BinOp::Sub => lhs - rhs,
+            BinOp::Mul => lhs * rhs,
+            BinOp::Div if rhs != zero => lhs / rhs,
+            BinOp::Mod if rhs != zero => lhs % rhs,
+            BinOp::Pow if rhs >= zero => {
+                // Bounds the size of the result, anything larger overflows
+                // unless the base is `0`, `1` or `-1`.
+                let exp = u32::try_from(&rhs).ok().filter(|exp| *exp <= 256)?;
+                lhs.pow(exp)
+            }
+            _ => return None,
+        };
+        checked_result(db, result, result_ty)
+    };
+
+    match op {
+        BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod | BinOp::Pow => checked(),
+        BinOp::Shl | BinOp::Shr => None,
+        _ => {
+            let lhs = word_of(db, body, lhs)?;
+            let rhs = word_of(db, body, rhs)?;
+            let (lhs_ord, rhs_ord) = if is_lhs_signed {
+                (to_signed(&lhs), to_signed(&rhs))
+            } else {
+                (lhs.clone(), rhs.clone())
+            };
+
+            Some(match op {
+                BinOp::BitOr | BinOp::LogicalOr => lhs | rhs,
+                BinOp::BitXor => lhs ^ rhs,
+                BinOp::BitAnd | BinOp::LogicalAnd => lhs & rhs,
+                BinOp::Eq => bool_word(lhs == rhs),
+                BinOp::Ne => bool_word(lhs != rhs),
+                BinOp::Ge => bool_word(lhs_ord >= rhs_ord),
+                BinOp::Gt => bool_word(lhs_ord > rhs_ord),
+                BinOp::Le => bool_word(lhs_ord <= rhs_ord),
+                BinOp::Lt => bool_word(lhs_ord < rhs_ord),
+                _ => unreachable!(),
+            })
+        }
+    }
+}
+
+/// Returns the 256 bit word codegen emits for `value`, if it's an immediate or
+/// a numeric constant.
+fn word_of(db: &dyn MirDb, body: &FunctionBody, value: ValueId) -> Option<BigInt> {
+    match body.store.value_data(value) {
+        Value::Immediate { imm, .. } => Some(to_word(imm.clone())),
+        Value::Constant { constant, .. } => match &constant.data(db).value {
+            ConstantValue::Immediate(imm) => Some(to_word(imm.clone())),
+            ConstantValue::Bool(b) => Some(bool_word(*b)),
+            ConstantValue::Str(_) => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the integer `value` represents, if it is a valid value of its
+/// integral type.
+fn checked_operand(db: &dyn MirDb, body: &FunctionBody, value: ValueId) -> Option<BigInt> {
+    let ty = body.store.value_ty(value);
+    if !ty.is_integral(db) {
+        return None;
+    }
+
+    let word = word_of(db, body, value)?;
+    if clean_word(db, &word, ty)? != word {
+        return None;
+    }
+
+    Some(if ty.is_signed(db) {
+        to_signed(&word)
+    } else {
+        word
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{
+        db::{MirDb, NewDb},
+        ir::{body_builder::BodyBuilder, FunctionId, Inst, SourceInfo, Type, TypeKind},
+    };
+
+    fn make_ty(db: &NewDb, kind: TypeKind) -> TypeId {
+        db.mir_intern_type(Rc::new(Type::new(kind, None)))
+    }
+
+    fn fold_binary_imm(
+        db: &NewDb,
+        op: BinOp,
+        (lhs, rhs): (i64, i64),
+        ty: TypeId,
+        result_ty: TypeId,
+    ) -> Option<BigInt> {
+        let mut builder = BodyBuilder::new(FunctionId(0), SourceInfo::dummy());
+        let lhs = builder.make_imm(to_word(lhs.into()), ty);
+        let rhs = builder.make_imm(to_word(rhs.into()), ty);
+        let inst = builder
+            .body
+            .store
+            .store_inst(Inst::binary(op, lhs, rhs, SourceInfo::dummy()));
+        let result = builder.make_value(Value::Temporary {
+            inst,
+            ty: result_ty,
+        });
+        builder.map_result(inst, result.into());
+
+        fold_inst(db, &builder.body, inst).map(|(word, _)| word)
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let db = NewDb::default();
+        let u8_ty = make_ty(&db, TypeKind::U8);
+        let i8_ty = make_ty(&db, TypeKind::I8);
+
+        let fold = |op, operands, ty| fold_binary_imm(&db, op, operands, ty, ty);
+        assert_eq!(fold(BinOp::Add, (200, 55), u8_ty), Some(255.into()));
+        assert_eq!(fold(BinOp::Add, (200, 56), u8_ty), None);
+        assert_eq!(fold(BinOp::Sub, (0, 1), u8_ty), None);
+        assert_eq!(fold(BinOp::Div, (1, 0), u8_ty), None);
+        assert_eq!(fold(BinOp::Pow, (2, 7), u8_ty), Some(128.into()));
+        assert_eq!(fold(BinOp::Pow, (2, 8), u8_ty), None);
+
+        assert_eq!(
+            fold(BinOp::Sub, (-100, 28), i8_ty),
+            Some(to_word((-128).into()))
+        );
+        assert_eq!(fold(BinOp::Sub, (-100, 29), i8_ty), None);
+        assert_eq!(fold(BinOp::Div, (-128, -1), i8_ty), None);
+        assert_eq!(fold(BinOp::Div, (-7, 2), i8_ty), Some(to_word((-3).into())));
+        assert_eq!(fold(BinOp::Mod, (-7, 2), i8_ty), Some(to_word((-1).into())));
+    }
+
+    #[test]
+    fn comparison() {
+        let db = NewDb::default();
+        let i8_ty = make_ty(&db, TypeKind::I8);
+        let u8_ty = make_ty(&db, TypeKind::U8);
+        let bool_ty = make_ty(&db, TypeKind::Bool);
+
+        let fold = |op, operands, ty| fold_binary_imm(&db, op, operands, ty, bool_ty);
+        assert_eq!(fold(BinOp::Lt, (-1, 1), i8_ty), Some(1.into()));
+        assert_eq!(fold(BinOp::Ge, (-1, 1), i8_ty), Some(0.into()));
+        assert_eq!(fold(BinOp::Eq, (3, 3), u8_ty), Some(1.into()));
+        assert_eq!(fold(BinOp::Shl, (1, 1), u8_ty), None);
+    }
+
+    #[test]
+    fn clean() {
+        let db = NewDb::default();
+        let i8_ty = make_ty(&db, TypeKind::I8);
+        let u8_ty = make_ty(&db, TypeKind::U8);
+
+        assert_eq!(clean_word(&db, &300.into(), u8_ty), Some(44.into()));
+        assert_eq!(
+            clean_word(&db, &128.into(), i8_ty),
+            Some(to_word((-128).into()))
+        );
+        assert_eq!(clean_word(&db, &127.into(), i8_ty), Some(127.into()));
+    }
+}
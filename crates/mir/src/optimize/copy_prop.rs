@@ -0,0 +1,164 @@
+use fxhash::FxHashMap;
+
+use crate::{
+    db::MirDb,
+    ir::{
+        inst::{InstKind, UnOp},
+        value::AssignableValue,
+        FunctionBody, Value, ValueId,
+    },
+    word::clean_word,
+};
+
+use super::{def_counts, Pass};
+
+/// Replaces the uses of a value that is only assigned once, by a `Bind`, with
+/// the bound value.
+///
+/// Only immediates and temporaries, which are never reassigned, are
+/// propagated. An immediate is not propagated into an array index or the
+/// operand of a negation, since codegen omits the runtime checks of those when
+/// the operand is an immediate.
+pub struct CopyProp;
+
+impl Pass for CopyProp {
+    fn name(&self) -> &'static str {
+        "copy-prop"
+    }
+
+    fn run(&mut self, db: &dyn MirDb, body: &mut FunctionBody) -> bool {
+        let copies = collect_copies(db, body);
+        if copies.is_empty() {
+            return false;
+        }
+
+        let resolve = |value: ValueId| {
+            let mut value = value;
+            // A copy never binds itself, but guard against cycles anyway.
+            for _ in 0..copies.len() {
+                match copies.get(&value) {
+                    Some(src) => value = *src,
+                    None => break,
+                }
+            }
+            value
+        };
+
+        let mut changed = false;
+        let insts: Vec<_> = body
+            .order
+            .iter_block()
+            .flat_map(|block| body.order.iter_inst(block))
+            .collect();
+        for inst in insts {
+            let mut inst_data = body.store.inst_data(inst).clone();
+            let mut rewrite = |value: &mut ValueId, allow_imm: bool| {
+                let new = resolve(*value);
+                if new != *value && (allow_imm || !body.store.value_data(new).is_imm()) {
+                    *value = new;
+                    changed = true;
+                }
+            };
+
+            match inst_data.kind {
+                InstKind::Declare { .. } => {}
+                InstKind::Unary {
+                    op: UnOp::Neg,
+                    ref mut value,
+                } => rewrite(value, false),
+                InstKind::AggregateAccess {
+                    ref mut value,
+                    ref mut indices,
+                } => {
+                    rewrite(value, true);
+                    for idx in indices {
+                        rewrite(idx, false);
+                    }
+                }
+                _ => {
+                    for arg in inst_data.args_mut() {
+                        rewrite(arg, true);
+                    }
+                }
+            }
+
+            let result = body.store.inst_result(inst).cloned().map(|mut result| {
+                rewrite_assignable(&mut result, true, &mut rewrite);
+                result
+            });
+
+            body.store.replace_inst(inst, inst_data);
+            if let Some(result) = result {
+                body.store.map_result(inst, result);
+            }
+        }
+
+        changed
+    }
+}
+
+/// Returns the values that can be replaced, mapped to their replacements.
+fn collect_copies(db: &dyn MirDb, body: &FunctionBody) -> FxHashMap<ValueId, ValueId> {
+    let defs = def_counts(body);
+    let is_single_def = |value: ValueId| defs.get(&value).copied() == Some(1);
+
+    let mut copies = FxHashMap::default();
+    for block in body.order.iter_block() {
+        for inst in body.order.iter_inst(block) {
+            let src = match body.store.inst_data(inst).kind {
+                InstKind::Bind { src } => src,
+                _ => continue,
+            };
+            let dst = match body.store.inst_result(inst) {
+                Some(AssignableValue::Value(dst)) => *dst,
+                _ => continue,
+            };
+
+            let is_dst_replaceable = match body.store.value_data(dst) {
+                Value::Temporary { .. } => true,
+                Value::Local(local) => !local.is_arg,
+                _ => false,
+            };
+            let is_src_propagatable = match body.store.value_data(src) {
+                Value::Temporary { .. } => is_single_def(src),
+                // Codegen uses an immediate as is, so it must already be a
+                // valid value of its type.
+                Value::Immediate { imm, ty } => clean_word(db, imm, *ty).as_ref() == Some(imm),
+                _ => false,
+            };
+
+            if src != dst
+                && is_dst_replaceable
+                && is_src_propagatable
+                && is_single_def(dst)
+                && body.store.value_ty(src) == body.store.value_ty(dst)
+            {
+                copies.insert(dst, src);
+            }
+        }
+    }
+
+    copies
+}
+
+fn rewrite_assignable(
+    value: &mut AssignableValue,
+    is_dest: bool,
+    rewrite: &mut impl FnMut(&mut ValueId, bool),
+) {
+    match value {
+        AssignableValue::Value(value) => {
+            if !is_dest {
+                rewrite(value, true)
+            }
+        }
+        AssignableValue::Aggregate { lhs, idx } => {
+            rewrite(idx, false);
+            rewrite_assignable(lhs, false, rewrite);
+        }
+        AssignableValue::Map { lhs, key } => {
+            rewrite(key, true);
+            rewrite_assignable(lhs, false, rewrite);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use fxhash::FxHashSet;
+
+use crate::{
+    analysis::ControlFlowGraph,
+    db::MirDb,
+    ir::{
+        inst::{BinOp, InstKind, UnOp},
+        value::AssignableValue,
+        FunctionBody, InstId,
+    },
+};
+
+use super::{def_counts, use_counts, Pass};
+
+/// Removes unreachable blocks, instructions without side effects whose result
+/// is never used, `Declare`s of locals that are never used and `Nop`s.
+pub struct DeadCodeElim;
+
+impl Pass for DeadCodeElim {
+    fn name(&self) -> &'static str {
+        "dce"
+    }
+
+    fn run(&mut self, _db: &dyn MirDb, body: &mut FunctionBody) -> bool {
+        let mut changed = remove_unreachable_blocks(body);
+
+        loop {
+            let uses = use_counts(body);
+            let defs = def_counts(body);
+            let is_unused = |value| !uses.contains_key(&value);
+
+            let dead_insts: Vec<_> = body
+                .order
+                .iter_block()
+                .flat_map(|block| body.order.iter_inst(block))
+                .filter(|inst| match &body.store.inst_data(*inst).kind {
+                    InstKind::Nop => true,
+                    InstKind::Declare { local } => is_unused(*local) && !defs.contains_key(local),
+                    _ => match body.store.inst_result(*inst) {
+                        Some(AssignableValue::Value(value)) => {
+                            is_unused(*value) && is_pure(body, *inst)
+                        }
+                        _ => false,
+                    },
+                })
+                .collect();
+
+            if dead_insts.is_empty() {
+                break;
+            }
+
+            for inst in dead_insts {
+                body.order.remove_inst(inst);
+                body.store.remove_inst_result(inst);
+            }
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+fn remove_unreachable_blocks(body: &mut FunctionBody) -> bool {
+    let cfg = ControlFlowGraph::compute(body);
+    let reachable: FxHashSet<_> = cfg.post_order().collect();
+    let unreachable: Vec<_> = body
+        .order
+        .iter_block()
+        .filter(|block| !reachable.contains(block))
+        .collect();
+
+    for &block in &unreachable {
+        for succ in cfg.succs(block) {
+            body.remove_phi_pred(*succ, block);
+        }
+    }
+    for &block in &unreachable {
+        body.order.remove_block(block);
+    }
+
+    !unreachable.is_empty()
+}
+
+/// Returns `true` if `inst` neither has side effects nor can revert.
+fn is_pure(body: &FunctionBody, inst: InstId) -> bool {
+    match &body.store.inst_data(inst).kind {
+        InstKind::Bind { .. }
+        | InstKind::Phi { .. }
+        | InstKind::Cast { .. }
+        | InstKind::Load { .. } => true,
+        InstKind::Unary { op, .. } => matches!(op, UnOp::Not | UnOp::Inv),
+        // Arithmetic is checked.
+        InstKind::Binary { op, .. } => !matches!(
+            op,
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod | BinOp::Pow
+        ),
+        _ => false,
+    }
+}
@@ -0,0 +1,387 @@
+use std::rc::Rc;
+
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{
+    analysis::ControlFlowGraph,
+    db::MirDb,
+    ir::{
+        inst::{CallType, InstKind, SwitchTable},
+        value::{AssignableValue, Local},
+        BasicBlock, BasicBlockId, FunctionBody, FunctionId, Inst, InstId, Value, ValueId,
+    },
+};
+
+use super::def_counts;
+
+/// Replaces calls to small internal functions with the body of the callee.
+///
+/// A callee is inlined if its cost, the number of instructions that aren't
+/// merely moving values around, is at most [`Inliner::THRESHOLD`], or if it's
+/// marked `#inline`. A callee marked `#noinline` is never inlined, nor is a
+/// callee that is recursive or calls back into the caller.
+///
+/// Only a callee with a single reachable `return` is inlined, so that the
+/// result of the call is defined in one place.
+#[derive(Default)]
+pub struct Inliner {
+    /// Maps a function to the functions it transitively calls.
+    callees: FxHashMap<FunctionId, Rc<FxHashSet<FunctionId>>>,
+}
+
+impl Inliner {
+    /// The maximum cost of a callee that isn't marked `#inline` to be inlined.
+    pub const THRESHOLD: usize = 8;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn should_inline(&mut self, db: &dyn MirDb, caller: FunctionId, callee: FunctionId) -> bool {
+        let analyzer_func = callee.analyzer_func(db);
+        if analyzer_func.is_noinline(db.upcast()) || callee == caller {
+            return false;
+        }
+
+        let reachable = self.transitive_callees(db, callee);
+        if reachable.contains(&callee) || reachable.contains(&caller) {
+            return false;
+        }
+
+        analyzer_func.is_inline(db.upcast()) || cost(&callee.body(db)) <= Self::THRESHOLD
+    }
+
+    fn transitive_callees(
+        &mut self,
+        db: &dyn MirDb,
+        func: FunctionId,
+    ) -> Rc<FxHashSet<FunctionId>> {
+        if let Some(callees) = self.callees.get(&func) {
+            return callees.clone();
+        }
+
+        let mut callees = FxHashSet::default();
+        let mut worklist = direct_callees(&func.body(db));
+        while let Some(callee) = worklist.pop() {
+            if callees.insert(callee) {
+                worklist.extend(direct_callees(&callee.body(db)));
+            }
+        }
+
+        let callees = Rc::new(callees);
+        self.callees.insert(func, callees.clone());
+        callees
+    }
+}
+
+impl super::Pass for Inliner {
+    fn name(&self) -> &'static str {
+        "inline"
+    }
+
+    fn run(&mut self, db: &dyn MirDb, body: &mut FunctionBody) -> bool {
+        let calls: Vec<_> = body
+            .order
+            .iter_block()
+            .flat_map(|block| body.order.iter_inst(block))
+            .filter_map(|inst| match body.store.inst_data(inst).kind {
+                InstKind::Call {
+                    func,
+                    call_type: CallType::Internal,
+                    ..
+                } => Some((inst, func)),
+                _ => None,
+            })
+            .collect();
+
+        let mut changed = false;
+        for (call, callee) in calls {
+            if self.should_inline(db, body.fid, callee) {
+                changed |= inline_call(db, body, call, &callee.body(db));
+            }
+        }
+
+        changed
+    }
+}
+
+/// Returns the cost of inlining `body`.
+fn cost(body: &FunctionBody) -> usize {
+    let cfg = ControlFlowGraph::compute(body);
+    cfg.post_order()
+        .flat_map(|block| body.order.iter_inst(block))
+        .filter(|inst| {
+            !matches!(
+                body.store.inst_data(*inst).kind,
+                InstKind::Declare { .. }
+                    | InstKind::Bind { .. }
+                    | InstKind::Phi { .. }
+                    | InstKind::Jump { .. }
+                    | InstKind::Return { .. }
+                    | InstKind::Nop
+            )
+        })
+        .count()
+}
+
+fn direct_callees(body: &FunctionBody) -> Vec<FunctionId> {
+    body.order
+        .iter_block()
+        .flat_map(|block| body.order.iter_inst(block))
+        .filter_map(|inst| match body.store.inst_data(inst).kind {
+            InstKind::Call {
+                func,
+                call_type: CallType::Internal,
+                ..
+            } => Some(func),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Inlines `callee` at `call` in `body`. Returns `false` and leaves `body`
+/// untouched if the call can't be inlined.
+fn inline_call(
+    db: &dyn MirDb,
+    body: &mut FunctionBody,
+    call: InstId,
+    callee: &FunctionBody,
+) -> bool {
+    let args = match &body.store.inst_data(call).kind {
+        InstKind::Call { args, .. } => args.clone(),
+        _ => unreachable!(),
+    };
+    let params: Vec<_> = callee.store.func_args().collect();
+    if args.len() != params.len()
+        || args
+            .iter()
+            .zip(&params)
+            .any(|(arg, param)| body.store.value_ty(*arg) != callee.store.value_ty(*param))
+    {
+        return false;
+    }
+
+    let cfg = ControlFlowGraph::compute(callee);
+    let reachable: FxHashSet<_> = cfg.post_order().collect();
+    let blocks: Vec<_> = callee
+        .order
+        .iter_block()
+        .filter(|block| reachable.contains(block))
+        .collect();
+    let mut returns = blocks.iter().filter_map(|block| {
+        let terminator = callee.order.terminator(&callee.store, *block)?;
+        match callee.store.inst_data(terminator).kind {
+            InstKind::Return { arg } => Some((terminator, arg)),
+            _ => None,
+        }
+    });
+    let (ret, ret_value) = match (returns.next(), returns.next()) {
+        (Some(ret), None) => ret,
+        _ => return false,
+    };
+
+    // The result of the call is bound to the returned value, unless it's
+    // zero-sized and removed in legalization anyway.
+    let result = body.store.inst_result(call).cloned();
+    let bind_result = match (&result, ret_value) {
+        (None, _) => false,
+        (Some(result), _) if result.ty(db, &body.store).deref(db).is_zero_sized(db) => false,
+        (Some(result), Some(ret_value))
+            if result.ty(db, &body.store) == callee.store.value_ty(ret_value) =>
+        {
+            true
+        }
+        _ => return false,
+    };
+
+    // A parameter is replaced with its argument, unless the callee assigns to
+    // it or the argument is an immediate, in which case the argument is bound
+    // to a new local. An immediate would make codegen omit runtime checks.
+    let defs = def_counts(callee);
+    let mut param_binds = vec![];
+    for (&arg, &param) in args.iter().zip(&params) {
+        if defs.contains_key(&param) || body.store.value_data(arg).is_imm() {
+            if !callee.store.value_ty(param).is_primitive(db) {
+                return false;
+            }
+            param_binds.push((param, arg));
+        }
+    }
+
+    let source = body.store.inst_data(call).source.clone();
+    let call_block = body.order.inst_block(call);
+
+    // Split the block at the call. The call itself is turned into the binding
+    // of its result later.
+    let cont = body.store.store_block(BasicBlock {});
+    body.order.insert_block_after_block(cont, call_block);
+    while let Some(inst) = body.order.next_inst(call) {
+        body.order.remove_inst(inst);
+        body.order.append_inst(inst, cont);
+    }
+    body.order.remove_inst(call);
+    let terminator = body.order.terminator(&body.store, cont).unwrap();
+    let succs: Vec<_> = body.store.branch_info(terminator).block_iter().collect();
+    for succ in succs {
+        body.replace_phi_pred(succ, call_block, cont);
+    }
+
+    let mut cloner = BodyCloner::new(callee);
+    for (param, arg) in args.iter().zip(&params) {
+        cloner.values.insert(*param, *arg);
+    }
+    for (param, arg) in param_binds {
+        let name = callee.store.local_name(param).unwrap_or_default();
+        let local = Local::tmp_local(name.into(), callee.store.value_ty(param));
+        let local = body.store.store_value(Value::Local(local));
+        cloner.values.insert(param, local);
+
+        let bind = body
+            .store
+            .store_inst(Inst::new(InstKind::Bind { src: arg }, source.clone()));
+        body.store.map_result(bind, local.into());
+        body.order.append_inst(bind, call_block);
+    }
+
+    for &block in &blocks {
+        let new_block = body.store.store_block(BasicBlock {});
+        body.order.insert_block_before_block(new_block, cont);
+        cloner.blocks.insert(block, new_block);
+    }
+    for &block in &blocks {
+        for inst in callee.order.iter_inst(block) {
+            cloner
+                .insts
+                .insert(inst, body.store.store_inst(Inst::nop()));
+        }
+    }
+
+    let jump = body.store.store_inst(Inst::new(
+        InstKind::Jump {
+            dest: cloner.blocks[&callee.order.entry()],
+        },
+        source.clone(),
+    ));
+    body.order.append_inst(jump, call_block);
+
+    for &block in &blocks {
+        let new_block = cloner.blocks[&block];
+        for inst in callee.order.iter_inst(block) {
+            if inst == ret {
+                break;
+            }
+            let new_inst = cloner.clone_inst(body, inst);
+            body.order.append_inst(new_inst, new_block);
+        }
+    }
+
+    let ret_block = cloner.blocks[&callee.order.inst_block(ret)];
+    if bind_result {
+        let src = cloner.map_value(body, ret_value.unwrap());
+        body.store.inst_data_mut(call).kind = InstKind::Bind { src };
+        body.order.append_inst(call, ret_block);
+    } else {
+        body.store.remove_inst_result(call);
+    }
+    let jump = body
+        .store
+        .store_inst(Inst::new(InstKind::Jump { dest: cont }, source));
+    body.order.append_inst(jump, ret_block);
+
+    true
+}
+
+/// Copies the instructions and values of a callee into a caller.
+struct BodyCloner<'a> {
+    callee: &'a FunctionBody,
+    blocks: FxHashMap<BasicBlockId, BasicBlockId>,
+    insts: FxHashMap<InstId, InstId>,
+    values: FxHashMap<ValueId, ValueId>,
+}
+
+impl<'a> BodyCloner<'a> {
+    fn new(callee: &'a FunctionBody) -> Self {
+        Self {
+            callee,
+            blocks: FxHashMap::default(),
+            insts: FxHashMap::default(),
+            values: FxHashMap::default(),
+        }
+    }
+
+    /// Copies `inst` to the instruction that was allocated for it in `body`.
+    fn clone_inst(&mut self, body: &mut FunctionBody, inst: InstId) -> InstId {
+        let mut inst_data = self.callee.store.inst_data(inst).clone();
+        for arg in inst_data.args_mut() {
+            *arg = self.map_value(body, *arg);
+        }
+
+        match &mut inst_data.kind {
+            InstKind::Jump { dest } => *dest = self.blocks[dest],
+            InstKind::Phi { blocks, .. } => {
+                for block in blocks {
+                    *block = self.blocks[block];
+                }
+            }
+            InstKind::Branch { then, else_, .. } => {
+                *then = self.blocks[then];
+                *else_ = self.blocks[else_];
+            }
+            InstKind::Switch { table, default, .. } => {
+                let mut new_table = SwitchTable::default();
+                for (value, dest) in table.iter() {
+                    new_table.add_arm(value, self.blocks[&dest]);
+                }
+                *table = new_table;
+                if let Some(default) = default {
+                    *default = self.blocks[default];
+                }
+            }
+            _ => {}
+        }
+
+        let new_inst = self.insts[&inst];
+        body.store.replace_inst(new_inst, inst_data);
+        if let Some(result) = self.callee.store.inst_result(inst) {
+            let result = self.map_assignable(body, result);
+            body.store.map_result(new_inst, result);
+        }
+
+        new_inst
+    }
+
+    fn map_assignable(
+        &mut self,
+        body: &mut FunctionBody,
+        value: &AssignableValue,
+    ) -> AssignableValue {
+        match value {
+            AssignableValue::Value(value) => self.map_value(body, *value).into(),
+            AssignableValue::Aggregate { lhs, idx } => AssignableValue::Aggregate {
+                lhs: self.map_assignable(body, lhs).into(),
+                idx: self.map_value(body, *idx),
+            },
+            AssignableValue::Map { lhs, key } => AssignableValue::Map {
+                lhs: self.map_assignable(body, lhs).into(),
+                key: self.map_value(body, *key),
+            },
+        }
+    }
+
+    fn map_value(&mut self, body: &mut FunctionBody, value: ValueId) -> ValueId {
+        if let Some(new_value) = self.values.get(&value) {
+            return *new_value;
+        }
+
+        let new_value = match self.callee.store.value_data(value).clone() {
+            Value::Temporary { inst, ty } => Value::Temporary {
+                inst: self.insts[&inst],
+                ty,
+            },
+            value => value,
+        };
+        let new_value = body.store.store_value(new_value);
+        self.values.insert(value, new_value);
+        new_value
+    }
+}
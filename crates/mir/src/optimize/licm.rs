@@ -0,0 +1,375 @@
+use fxhash::FxHashSet;
+
+use crate::{
+    analysis::{loop_tree::LoopId, ControlFlowGraph, DomTree, LoopTree},
+    db::MirDb,
+    ir::{
+        inst::{BinOp, InstKind, UnOp},
+        value::AssignableValue,
+        BasicBlock, BasicBlockId, FunctionBody, Inst, InstId, Value, ValueId,
+    },
+};
+
+use super::Pass;
+
+/// Hoists loop-invariant computations out of loops, e.g. the storage slot of
+/// `self.balances[owner]`, which costs a `keccak256` per access, is computed
+/// once before a loop that doesn't reassign `owner`.
+///
+/// An instruction is hoisted into the preheader of its loop if it has no side
+/// effects, its result is a temporary and its arguments aren't assigned in the
+/// loop. Instructions that can't revert are hoisted from anywhere in the loop.
+/// Checked arithmetic is only hoisted from the loop header if nothing before it
+/// in the header can end the execution, since the header runs whenever the
+/// preheader does. Loads aren't hoisted, because the loop may write the
+/// location they read.
+///
+/// A preheader is inserted if the header doesn't have a single predecessor
+/// outside the loop that jumps to it unconditionally.
+pub struct Licm;
+
+impl Pass for Licm {
+    fn name(&self) -> &'static str {
+        "licm"
+    }
+
+    fn run(&mut self, db: &dyn MirDb, body: &mut FunctionBody) -> bool {
+        let mut changed = false;
+
+        // Inserting a preheader changes the CFG, so the analyses are computed
+        // again after each loop that something is hoisted from. Inner loops
+        // come first, so that what's hoisted from them can be hoisted from
+        // their parents as well.
+        'restart: loop {
+            let cfg = ControlFlowGraph::compute(body);
+            let domtree = DomTree::compute(&cfg);
+            let loop_tree = LoopTree::compute(&cfg, &domtree);
+
+            let loops: Vec<_> = loop_tree.loops().collect();
+            for &lp in loops.iter().rev() {
+                let invariants = loop_invariants(db, body, &domtree, &loop_tree, lp);
+                if invariants.is_empty() {
+                    continue;
+                }
+
+                let preheader = match preheader(body, &cfg, &loop_tree, lp) {
+                    Some(preheader) => preheader,
+                    None => continue,
+                };
+                let terminator = body.order.terminator(&body.store, preheader).unwrap();
+                for inst in invariants {
+                    body.order.remove_inst(inst);
+                    body.order.insert_inst_before_inst(inst, terminator);
+                }
+
+                changed = true;
+                continue 'restart;
+            }
+
+            return changed;
+        }
+    }
+}
+
+/// Returns the instructions of `lp` that can be hoisted, in the order they're
+/// executed.
+fn loop_invariants(
+    db: &dyn MirDb,
+    body: &FunctionBody,
+    domtree: &DomTree,
+    loop_tree: &LoopTree,
+    lp: LoopId,
+) -> Vec<InstId> {
+    let header = loop_tree.loop_header(lp);
+    let blocks: Vec<_> = domtree
+        .rpo()
+        .iter()
+        .copied()
+        .filter(|block| loop_tree.is_block_in_loop(*block, lp))
+        .collect();
+
+    // The values whose value may change in an iteration.
+    let mut variants = FxHashSet::default();
+    for &block in &blocks {
+        for inst in body.order.iter_inst(block) {
+            if let Some(AssignableValue::Value(value)) = body.store.inst_result(inst) {
+                variants.insert(*value);
+            }
+        }
+    }
+
+    let mut invariants = vec![];
+    for &block in &blocks {
+        // Whether every instruction so far in the header either is hoisted or
+        // can't end the execution.
+        let mut is_header_prefix = block == header;
+
+        for inst in body.order.iter_inst(block) {
+            let inst_data = body.store.inst_data(inst);
+            let result = match body.store.inst_result(inst) {
+                Some(AssignableValue::Value(value))
+                    if matches!(body.store.value_data(*value), Value::Temporary { .. }) =>
+                {
+                    Some(*value)
+                }
+                _ => None,
+            };
+
+            let is_hoistable = result.is_some()
+                && inst_data.args().all(|arg| !variants.contains(&arg))
+                && match hoistability(db, body, inst) {
+                    Hoistability::Anywhere => true,
+                    Hoistability::FromHeader => is_header_prefix,
+                    Hoistability::Never => false,
+                };
+
+            if is_hoistable {
+                variants.remove(&result.unwrap());
+                invariants.push(inst);
+            } else if may_end_execution(db, body, inst) {
+                is_header_prefix = false;
+            }
+        }
+    }
+
+    invariants
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Hoistability {
+    /// The instruction has no side effects and can't revert.
+    Anywhere,
+    /// The instruction has no side effects, but may revert.
+    FromHeader,
+    Never,
+}
+
+fn hoistability(db: &dyn MirDb, body: &FunctionBody, inst: InstId) -> Hoistability {
+    match &body.store.inst_data(inst).kind {
+        InstKind::Bind { .. } | InstKind::Cast { .. } => Hoistability::Anywhere,
+
+        InstKind::Unary { op, .. } => match op {
+            UnOp::Not | UnOp::Inv => Hoistability::Anywhere,
+            UnOp::Neg => Hoistability::FromHeader,
+        },
+
+        InstKind::Binary { op, .. } => match op {
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod | BinOp::Pow => {
+                Hoistability::FromHeader
+            }
+            _ => Hoistability::Anywhere,
+        },
+
+        // A map key that isn't primitive is hashed from memory, which the loop
+        // may write.
+        InstKind::MapAccess { key, .. } if body.store.value_ty(*key).is_primitive(db) => {
+            Hoistability::Anywhere
+        }
+
+        // Indexing an array with a value that isn't an immediate is checked.
+        InstKind::AggregateAccess { indices, .. }
+            if indices
+                .iter()
+                .all(|idx| body.store.value_data(*idx).is_imm()) =>
+        {
+            Hoistability::Anywhere
+        }
+        InstKind::AggregateAccess { .. } => Hoistability::FromHeader,
+
+        _ => Hoistability::Never,
+    }
+}
+
+fn may_end_execution(db: &dyn MirDb, body: &FunctionBody, inst: InstId) -> bool {
+    !matches!(
+        body.store.inst_data(inst).kind,
+        InstKind::Nop | InstKind::Declare { .. } | InstKind::Phi { .. } | InstKind::Load { .. }
+    ) && hoistability(db, body, inst) != Hoistability::Anywhere
+}
+
+/// Returns the preheader of `lp`, inserting one if needed. Returns `None` if the
+/// header of `lp` has no predecessor outside the loop.
+fn preheader(
+    body: &mut FunctionBody,
+    cfg: &ControlFlowGraph,
+    loop_tree: &LoopTree,
+    lp: LoopId,
+) -> Option<BasicBlockId> {
+    let header = loop_tree.loop_header(lp);
+    let outside_preds: Vec<_> = cfg
+        .preds(header)
+        .iter()
+        .copied()
+        .filter(|pred| !loop_tree.is_block_in_loop(*pred, lp))
+        .collect();
+
+    match outside_preds.as_slice() {
+        [] => return None,
+        [pred] if cfg.succs(*pred).len() == 1 => return Some(*pred),
+        _ => {}
+    }
+
+    let source = body
+        .store
+        .inst_data(body.order.first_inst(header)?)
+        .source
+        .clone();
+    let preheader = body.store.store_block(BasicBlock {});
+    body.order.insert_block_before_block(preheader, header);
+    let jump = body
+        .store
+        .store_inst(Inst::new(InstKind::Jump { dest: header }, source.clone()));
+    body.order.append_inst(jump, preheader);
+
+    for &pred in &outside_preds {
+        let terminator = body.order.terminator(&body.store, pred).unwrap();
+        body.store
+            .rewrite_branch_dest(terminator, header, preheader);
+    }
+
+    // The arguments the header's phis take from outside the loop are merged in
+    // the preheader.
+    let phis: Vec<_> = body.phis(header).collect();
+    for phi in phis {
+        let (mut args, mut blocks) = match &body.store.inst_data(phi).kind {
+            InstKind::Phi { args, blocks } => (args.clone(), blocks.clone()),
+            _ => unreachable!(),
+        };
+
+        let mut outside_args = vec![];
+        let mut outside_blocks = vec![];
+        while let Some(idx) = blocks.iter().position(|b| outside_preds.contains(b)) {
+            outside_args.push(args.remove(idx));
+            outside_blocks.push(blocks.remove(idx));
+        }
+
+        let arg = if outside_args.len() == 1 {
+            outside_args[0]
+        } else {
+            let ty = body.store.value_ty(outside_args[0]);
+            let inst = body.store.store_inst(Inst::new(
+                InstKind::Phi {
+                    args: outside_args,
+                    blocks: outside_blocks,
+                },
+                source.clone(),
+            ));
+            let value = body.store.store_value(Value::Temporary { inst, ty });
+            body.store.map_result(inst, value.into());
+            body.order.prepend_inst(inst, preheader);
+            value
+        };
+        args.push(arg);
+        blocks.push(preheader);
+        body.store.inst_data_mut(phi).kind = InstKind::Phi { args, blocks };
+    }
+
+    Some(preheader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        db::{MirDb, NewDb},
+        ir::{
+            body_builder::BodyBuilder, value::Local, FunctionId, SourceInfo, Type, TypeId, TypeKind,
+        },
+    };
+
+    fn make_ty(db: &NewDb, kind: TypeKind) -> TypeId {
+        db.mir_intern_type(Type::new(kind, None).into())
+    }
+
+    fn make_tmp(builder: &mut BodyBuilder, inst: InstId, ty: TypeId) -> ValueId {
+        let value = builder.make_value(Value::Temporary { inst, ty });
+        builder.map_result(inst, value.into());
+        value
+    }
+
+    /// Builds
+    /// ```text
+    /// entry: branch cond bb1 bb2
+    /// bb1: jump header
+    /// bb2: jump header
+    /// header: i = phi [0: bb1, 1: bb2, next: header]
+    ///         x = a & b
+    ///         y = i & x
+    ///         next = a + b
+    ///         branch y header exit
+    /// exit: return x
+    /// ```
+    fn build_loop(db: &NewDb) -> (FunctionBody, [InstId; 3], BasicBlockId) {
+        let u256 = make_ty(db, TypeKind::U256);
+        let bool_ty = make_ty(db, TypeKind::Bool);
+        let mut builder = BodyBuilder::new(FunctionId(0), SourceInfo::dummy());
+
+        let bb1 = builder.make_block();
+        let bb2 = builder.make_block();
+        let header = builder.make_block();
+        let exit = builder.make_block();
+
+        let arg = |builder: &mut BodyBuilder, name: &str, ty| {
+            builder.store_func_arg(Local::arg_local(name.into(), ty, SourceInfo::dummy()))
+        };
+        let cond = arg(&mut builder, "cond", bool_ty);
+        let a = arg(&mut builder, "a", u256);
+        let b = arg(&mut builder, "b", u256);
+        builder.branch(cond, bb1, bb2, SourceInfo::dummy());
+        for block in [bb1, bb2] {
+            builder.move_to_block(block);
+            builder.jump(header, SourceInfo::dummy());
+        }
+
+        builder.move_to_block(header);
+        let zero = builder.make_imm(0.into(), u256);
+        let one = builder.make_imm(1.into(), u256);
+        let phi = builder.phi(vec![zero, one], vec![bb1, bb2], SourceInfo::dummy());
+        let i = make_tmp(&mut builder, phi, u256);
+        let x_inst = builder.bit_and(a, b, SourceInfo::dummy());
+        let x = make_tmp(&mut builder, x_inst, u256);
+        let y_inst = builder.bit_and(i, x, SourceInfo::dummy());
+        let y = make_tmp(&mut builder, y_inst, bool_ty);
+        let next_inst = builder.add(a, b, SourceInfo::dummy());
+        let next = make_tmp(&mut builder, next_inst, u256);
+        builder.branch(y, header, exit, SourceInfo::dummy());
+
+        builder.move_to_block(exit);
+        builder.ret(x, SourceInfo::dummy());
+
+        let mut body = builder.build();
+        if let InstKind::Phi { args, blocks } = &mut body.store.inst_data_mut(phi).kind {
+            args.push(next);
+            blocks.push(header);
+        }
+        (body, [x_inst, y_inst, next_inst], header)
+    }
+
+    #[test]
+    fn hoist_into_new_preheader() {
+        let db = NewDb::default();
+        let (mut func, [x_inst, y_inst, next_inst], header) = build_loop(&db);
+        assert!(Licm.run(&db, &mut func));
+
+        let preheader = func.order.prev_block(header).unwrap();
+        assert_eq!(func.order.inst_block(x_inst), preheader);
+        assert_eq!(func.order.inst_block(next_inst), preheader);
+        assert_eq!(func.order.inst_block(y_inst), header);
+
+        // The phi merges the values from outside the loop in the preheader.
+        let outer_phi = func.phis(preheader).next().unwrap();
+        assert!(matches!(
+            &func.store.inst_data(outer_phi).kind,
+            InstKind::Phi { args, .. } if args.len() == 2
+        ));
+        let phi = func.phis(header).next().unwrap();
+        let blocks = match &func.store.inst_data(phi).kind {
+            InstKind::Phi { blocks, .. } => blocks.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(blocks, vec![header, preheader]);
+
+        assert!(!Licm.run(&db, &mut func));
+    }
+}
@@ -0,0 +1,637 @@
+use fxhash::{FxHashMap, FxHashSet};
+
+use crate::{
+    analysis::{ControlFlowGraph, DomTree},
+    db::MirDb,
+    ir::{
+        inst::InstKind, value::AssignableValue, BasicBlockId, FunctionBody, Inst, InstId, Value,
+        ValueId,
+    },
+};
+
+use super::Pass;
+
+/// Promotes locals of primitive types to SSA values.
+///
+/// Every assignment to a local defines a new value, and phis are inserted at
+/// the dominance frontiers of the assignments where the local is live. A
+/// `Declare` defines the local as zero, which is what codegen initializes it
+/// to. Function arguments aren't promoted.
+///
+/// Phis whose arguments are all the same value are removed as well, since
+/// simplifying the CFG can leave them behind.
+pub struct Mem2Reg;
+
+impl Pass for Mem2Reg {
+    fn name(&self) -> &'static str {
+        "mem2reg"
+    }
+
+    fn run(&mut self, db: &dyn MirDb, body: &mut FunctionBody) -> bool {
+        let cfg = ControlFlowGraph::compute(body);
+        let locals = promotable_locals(db, body, &cfg);
+        let promoted = !locals.is_empty();
+        if promoted {
+            SsaBuilder::new(&cfg, locals).build(body);
+        }
+
+        remove_trivial_phis(body) || promoted
+    }
+}
+
+/// Returns the locals that can be promoted, i.e. the locals of primitive types
+/// other than unit that aren't arguments and aren't used in unreachable blocks.
+fn promotable_locals(
+    db: &dyn MirDb,
+    body: &FunctionBody,
+    cfg: &ControlFlowGraph,
+) -> FxHashSet<ValueId> {
+    let reachable: FxHashSet<_> = cfg.post_order().collect();
+    let mut locals = FxHashSet::default();
+    let mut unreachable_uses = FxHashSet::default();
+    for block in body.order.iter_block() {
+        for inst in body.order.iter_inst(block) {
+            let mut values: Vec<_> = body.store.inst_data(inst).args().collect();
+            if let Some(result) = body.store.inst_result(inst) {
+                collect_assignable_values(result, &mut values);
+            }
+
+            for value in values {
+                let is_candidate = match body.store.value_data(value) {
+                    Value::Local(local) => {
+                        !local.is_arg && local.ty.is_primitive(db) && !local.ty.is_unit(db)
+                    }
+                    _ => false,
+                };
+                if !is_candidate {
+                    continue;
+                }
+                if reachable.contains(&block) {
+                    locals.insert(value);
+                } else {
+                    unreachable_uses.insert(value);
+                }
+            }
+        }
+    }
+
+    locals.retain(|local| !unreachable_uses.contains(local));
+    locals
+}
+
+fn collect_assignable_values(value: &AssignableValue, values: &mut Vec<ValueId>) {
+    match value {
+        AssignableValue::Value(value) => values.push(*value),
+        AssignableValue::Aggregate { lhs, idx } => {
+            values.push(*idx);
+            collect_assignable_values(lhs, values);
+        }
+        AssignableValue::Map { lhs, key } => {
+            values.push(*key);
+            collect_assignable_values(lhs, values);
+        }
+    }
+}
+
+struct SsaBuilder<'a> {
+    cfg: &'a ControlFlowGraph,
+    domtree: DomTree,
+    locals: FxHashSet<ValueId>,
+    /// Maps a phi that was inserted to the local it merges.
+    phis: FxHashMap<InstId, ValueId>,
+    /// The current value of each local during renaming.
+    stacks: FxHashMap<ValueId, Vec<ValueId>>,
+}
+
+impl<'a> SsaBuilder<'a> {
+    fn new(cfg: &'a ControlFlowGraph, locals: FxHashSet<ValueId>) -> Self {
+        Self {
+            cfg,
+            domtree: DomTree::compute(cfg),
+            locals,
+            phis: FxHashMap::default(),
+            stacks: FxHashMap::default(),
+        }
+    }
+
+    fn build(mut self, body: &mut FunctionBody) {
+        self.insert_phis(body);
+        self.rename(body);
+    }
+
+    fn insert_phis(&mut self, body: &mut FunctionBody) {
+        let df = self.domtree.compute_df(self.cfg);
+        let live_ins = self.live_ins(body);
+
+        let mut def_blocks: FxHashMap<ValueId, Vec<BasicBlockId>> = FxHashMap::default();
+        for &block in self.domtree.rpo() {
+            for inst in body.order.iter_inst(block) {
+                if let Some(local) = self.defined_local(body, inst) {
+                    let blocks = def_blocks.entry(local).or_default();
+                    if blocks.last() != Some(&block) {
+                        blocks.push(block);
+                    }
+                }
+            }
+        }
+
+        // Visit the locals in a fixed order, so that the output doesn't depend
+        // on hashing.
+        let mut locals: Vec<_> = self.locals.iter().copied().collect();
+        locals.sort_by_key(|local| local.index());
+        for local in locals {
+            let mut worklist = def_blocks.remove(&local).unwrap_or_default();
+            let mut visited: FxHashSet<_> = FxHashSet::default();
+            while let Some(block) = worklist.pop() {
+                for frontier in df.frontiers(block).into_iter().flatten() {
+                    if !visited.insert(frontier) {
+                        continue;
+                    }
+                    if !live_ins[&frontier].contains(&local) {
+                        continue;
+                    }
+
+                    let ty = body.store.value_ty(local);
+                    let phi = body.store.store_inst(Inst::new(
+                        InstKind::Phi {
+                            args: vec![],
+                            blocks: vec![],
+                        },
+                        body.source.clone(),
+                    ));
+                    let result = body.store.store_value(Value::Temporary { inst: phi, ty });
+                    body.store.map_result(phi, result.into());
+                    body.order.prepend_inst(phi, frontier);
+                    self.phis.insert(phi, local);
+                    worklist.push(frontier);
+                }
+            }
+        }
+    }
+
+    /// Returns the promoted locals that are live at the beginning of each
+    /// reachable block.
+    fn live_ins(&self, body: &FunctionBody) -> FxHashMap<BasicBlockId, FxHashSet<ValueId>> {
+        let mut uses: FxHashMap<BasicBlockId, FxHashSet<ValueId>> = FxHashMap::default();
+        let mut defs: FxHashMap<BasicBlockId, FxHashSet<ValueId>> = FxHashMap::default();
+        for block in self.cfg.post_order() {
+            let block_uses = uses.entry(block).or_default();
+            let block_defs = defs.entry(block).or_default();
+            for inst in body.order.iter_inst(block) {
+                for value in self.used_values(body, inst) {
+                    if self.locals.contains(&value) && !block_defs.contains(&value) {
+                        block_uses.insert(value);
+                    }
+                }
+                if let Some(local) = self.defined_local(body, inst) {
+                    block_defs.insert(local);
+                }
+            }
+        }
+
+        let mut live_ins = uses.clone();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in self.cfg.post_order() {
+                let mut live: FxHashSet<_> = self
+                    .cfg
+                    .succs(block)
+                    .iter()
+                    .flat_map(|succ| live_ins[succ].iter().copied())
+                    .filter(|local| !defs[&block].contains(local))
+                    .collect();
+                live.extend(uses[&block].iter().copied());
+                if live.len() != live_ins[&block].len() {
+                    live_ins.insert(block, live);
+                    changed = true;
+                }
+            }
+        }
+
+        live_ins
+    }
+
+    fn rename(&mut self, body: &mut FunctionBody) {
+        let mut children: FxHashMap<BasicBlockId, Vec<BasicBlockId>> = FxHashMap::default();
+        for &block in self.domtree.rpo().iter().skip(1) {
+            if let Some(idom) = self.domtree.idom(block) {
+                children.entry(idom).or_default().push(block);
+            }
+        }
+
+        enum Visit {
+            Enter(BasicBlockId),
+            Exit(Vec<ValueId>),
+        }
+
+        let mut worklist = vec![Visit::Enter(self.cfg.entry())];
+        while let Some(visit) = worklist.pop() {
+            match visit {
+                Visit::Enter(block) => {
+                    let defined = self.rename_block(body, block);
+                    worklist.push(Visit::Exit(defined));
+                    if let Some(children) = children.get(&block) {
+                        worklist.extend(children.iter().rev().map(|child| Visit::Enter(*child)));
+                    }
+                }
+                Visit::Exit(defined) => {
+                    for local in defined {
+                        self.stacks.get_mut(&local).unwrap().pop();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renames the uses and definitions of locals in `block`, and fills in the
+    /// arguments of the phis in its successors. Returns the locals that were
+    /// defined, once for each definition.
+    fn rename_block(&mut self, body: &mut FunctionBody, block: BasicBlockId) -> Vec<ValueId> {
+        let mut defined = vec![];
+        let insts: Vec<_> = body.order.iter_inst(block).collect();
+        for inst in insts {
+            if let Some(&local) = self.phis.get(&inst) {
+                let result = body.store.inst_result(inst).unwrap().value_id().unwrap();
+                self.push(local, result);
+                defined.push(local);
+                continue;
+            }
+
+            let mut inst_data = body.store.inst_data(inst).clone();
+            if let InstKind::Declare { local } = inst_data.kind {
+                if self.locals.contains(&local) {
+                    // The local is defined as zero, which is removed if unused.
+                    let ty = body.store.value_ty(local);
+                    let zero = body
+                        .store
+                        .store_value(Value::Immediate { imm: 0.into(), ty });
+                    let value = body.store.store_value(Value::Temporary { inst, ty });
+                    body.store.inst_data_mut(inst).kind = InstKind::Bind { src: zero };
+                    body.store.map_result(inst, value.into());
+                    self.push(local, value);
+                    defined.push(local);
+                }
+                continue;
+            }
+
+            if !matches!(inst_data.kind, InstKind::Phi { .. }) {
+                for arg in inst_data.args_mut() {
+                    *arg = self.current(body, *arg);
+                }
+                body.store.replace_inst(inst, inst_data);
+            }
+
+            if let Some(mut result) = body.store.inst_result(inst).cloned() {
+                let defined_local = match result {
+                    AssignableValue::Value(value) if self.locals.contains(&value) => Some(value),
+                    _ => {
+                        self.rename_assignable_uses(body, &mut result);
+                        None
+                    }
+                };
+
+                if let Some(local) = defined_local {
+                    let ty = body.store.value_ty(local);
+                    let value = body.store.store_value(Value::Temporary { inst, ty });
+                    result = value.into();
+                    self.push(local, value);
+                    defined.push(local);
+                }
+                body.store.map_result(inst, result);
+            }
+        }
+
+        let mut succs = self.cfg.succs(block).to_vec();
+        succs.sort_unstable_by_key(|succ| succ.index());
+        succs.dedup();
+        for succ in succs {
+            let phis: Vec<_> = body.phis(succ).collect();
+            for phi in phis {
+                let local = match self.phis.get(&phi) {
+                    Some(local) => *local,
+                    None => continue,
+                };
+                let value = self.current(body, local);
+                if let InstKind::Phi { args, blocks } = &mut body.store.inst_data_mut(phi).kind {
+                    args.push(value);
+                    blocks.push(block);
+                }
+            }
+        }
+
+        defined
+    }
+
+    fn rename_assignable_uses(&mut self, body: &mut FunctionBody, value: &mut AssignableValue) {
+        match value {
+            AssignableValue::Value(_) => {}
+            AssignableValue::Aggregate { lhs, idx } => {
+                *idx = self.current(body, *idx);
+                self.rename_assignable_uses(body, lhs);
+            }
+            AssignableValue::Map { lhs, key } => {
+                *key = self.current(body, *key);
+                self.rename_assignable_uses(body, lhs);
+            }
+        }
+    }
+
+    fn push(&mut self, local: ValueId, value: ValueId) {
+        self.stacks.entry(local).or_default().push(value);
+    }
+
+    /// Returns the current value of `value` if it's a promoted local, and
+    /// `value` itself otherwise. A local that isn't assigned yet is zero.
+    fn current(&self, body: &mut FunctionBody, value: ValueId) -> ValueId {
+        if !self.locals.contains(&value) {
+            return value;
+        }
+
+        match self.stacks.get(&value).and_then(|stack| stack.last()) {
+            Some(current) => *current,
+            None => {
+                let ty = body.store.value_ty(value);
+                body.store
+                    .store_value(Value::Immediate { imm: 0.into(), ty })
+            }
+        }
+    }
+
+    /// Returns the promoted local that `inst` declares or assigns to.
+    fn defined_local(&self, body: &FunctionBody, inst: InstId) -> Option<ValueId> {
+        let local = match (
+            &body.store.inst_data(inst).kind,
+            body.store.inst_result(inst),
+        ) {
+            (InstKind::Declare { local }, _) => *local,
+            (_, Some(AssignableValue::Value(value))) => *value,
+            _ => return None,
+        };
+        self.locals.contains(&local).then_some(local)
+    }
+
+    /// Returns the values `inst` reads.
+    fn used_values(&self, body: &FunctionBody, inst: InstId) -> Vec<ValueId> {
+        let inst_data = body.store.inst_data(inst);
+        if matches!(inst_data.kind, InstKind::Declare { .. }) {
+            return vec![];
+        }
+
+        let mut values: Vec<_> = inst_data.args().collect();
+        if let Some(result) = body.store.inst_result(inst) {
+            super::collect_assignable_uses(result, true, &mut values);
+        }
+        values
+    }
+}
+
+/// Removes the phis whose arguments are all the same value, apart from the
+/// phi itself. Returns `true` if a phi was removed.
+fn remove_trivial_phis(body: &mut FunctionBody) -> bool {
+    let mut changed = false;
+    loop {
+        let mut replacements = FxHashMap::default();
+        let blocks: Vec<_> = body.order.iter_block().collect();
+        for block in blocks {
+            let phis: Vec<_> = body.phis(block).collect();
+            for phi in phis {
+                let result = body.store.inst_result(phi).unwrap().value_id().unwrap();
+                let args = match &body.store.inst_data(phi).kind {
+                    InstKind::Phi { args, .. } => args,
+                    _ => unreachable!(),
+                };
+                let mut unique = args.iter().copied().filter(|arg| *arg != result);
+                let src = match unique.next() {
+                    Some(src) if unique.all(|arg| arg == src) => src,
+                    _ => continue,
+                };
+
+                // An immediate is bound rather than propagated, since codegen
+                // omits runtime checks of some immediate operands.
+                if body.store.value_data(src).is_imm() {
+                    body.store.inst_data_mut(phi).kind = InstKind::Bind { src };
+                    body.order.remove_inst(phi);
+                    match body.phis(block).last() {
+                        Some(last_phi) => body.order.insert_inst_after(phi, last_phi),
+                        None => body.order.prepend_inst(phi, block),
+                    }
+                } else {
+                    body.order.remove_inst(phi);
+                    body.store.remove_inst_result(phi);
+                    replacements.insert(result, src);
+                }
+                changed = true;
+            }
+        }
+
+        if replacements.is_empty() {
+            return changed;
+        }
+        replace_uses(body, &replacements);
+    }
+}
+
+fn replace_uses(body: &mut FunctionBody, replacements: &FxHashMap<ValueId, ValueId>) {
+    let resolve = |mut value: ValueId| {
+        while let Some(new) = replacements.get(&value) {
+            value = *new;
+        }
+        value
+    };
+
+    let insts: Vec<_> = body
+        .order
+        .iter_block()
+        .flat_map(|block| body.order.iter_inst(block))
+        .collect();
+    for inst in insts {
+        let mut inst_data = body.store.inst_data(inst).clone();
+        for arg in inst_data.args_mut() {
+            *arg = resolve(*arg);
+        }
+        body.store.replace_inst(inst, inst_data);
+
+        if let Some(mut result) = body.store.inst_result(inst).cloned() {
+            replace_assignable_uses(&mut result, &resolve);
+            body.store.map_result(inst, result);
+        }
+    }
+}
+
+fn replace_assignable_uses(value: &mut AssignableValue, resolve: &impl Fn(ValueId) -> ValueId) {
+    match value {
+        AssignableValue::Value(_) => {}
+        AssignableValue::Aggregate { lhs, idx } => {
+            *idx = resolve(*idx);
+            replace_assignable_uses(lhs, resolve);
+        }
+        AssignableValue::Map { lhs, key } => {
+            *key = resolve(*key);
+            replace_assignable_uses(lhs, resolve);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        db::{MirDb, NewDb},
+        ir::{
+            body_builder::BodyBuilder, value::Local, FunctionId, SourceInfo, Type, TypeId, TypeKind,
+        },
+    };
+
+    fn body_builder() -> BodyBuilder {
+        BodyBuilder::new(FunctionId(0), SourceInfo::dummy())
+    }
+
+    fn make_ty(db: &NewDb, kind: TypeKind) -> TypeId {
+        db.mir_intern_type(Type::new(kind, None).into())
+    }
+
+    /// Builds `let x; if cond { x = 1 } else { x = 2 }; return x`, leaving the
+    /// use of `x` out if `use_x` is `false`.
+    fn build_diamond(db: &NewDb, use_x: bool) -> (FunctionBody, ValueId, BasicBlockId) {
+        let u256 = make_ty(db, TypeKind::U256);
+        let bool_ty = make_ty(db, TypeKind::Bool);
+        let mut builder = body_builder();
+
+        let then_block = builder.make_block();
+        let else_block = builder.make_block();
+        let merge_block = builder.make_block();
+
+        let cond = builder.store_func_arg(Local::arg_local(
+            "cond".into(),
+            bool_ty,
+            SourceInfo::dummy(),
+        ));
+        let x = builder.declare(Local::user_local("x".into(), u256, SourceInfo::dummy()));
+        builder.branch(cond, then_block, else_block, SourceInfo::dummy());
+
+        for (block, imm) in [(then_block, 1), (else_block, 2)] {
+            builder.move_to_block(block);
+            let imm = builder.make_imm(imm.into(), u256);
+            let bind = builder.bind(imm, SourceInfo::dummy());
+            builder.map_result(bind, x.into());
+            builder.jump(merge_block, SourceInfo::dummy());
+        }
+
+        builder.move_to_block(merge_block);
+        let ret = if use_x {
+            x
+        } else {
+            builder.make_imm(0.into(), u256)
+        };
+        builder.ret(ret, SourceInfo::dummy());
+
+        (builder.build(), x, merge_block)
+    }
+
+    fn mentions(body: &FunctionBody, value: ValueId) -> bool {
+        body.order
+            .iter_block()
+            .flat_map(|block| body.order.iter_inst(block))
+            .any(|inst| {
+                body.store.inst_data(inst).args().any(|arg| arg == value)
+                    || body.store.inst_result(inst).and_then(|r| r.value_id()) == Some(value)
+            })
+    }
+
+    #[test]
+    fn insert_phi_at_merge() {
+        let db = NewDb::default();
+        let (mut func, x, merge_block) = build_diamond(&db, true);
+        assert!(Mem2Reg.run(&db, &mut func));
+        assert!(!mentions(&func, x));
+
+        let phis: Vec<_> = func.phis(merge_block).collect();
+        assert_eq!(phis.len(), 1);
+        let phi_result = func.store.inst_result(phis[0]).unwrap().value_id();
+        let ret = func.order.terminator(&func.store, merge_block).unwrap();
+        assert!(matches!(
+            func.store.inst_data(ret).kind,
+            InstKind::Return { arg } if arg == phi_result
+        ));
+
+        let args = match &func.store.inst_data(phis[0]).kind {
+            InstKind::Phi { args, blocks } => {
+                assert_eq!(blocks.len(), 2);
+                args.clone()
+            }
+            _ => unreachable!(),
+        };
+        for arg in args {
+            let def = match func.store.value_data(arg) {
+                Value::Temporary { inst, .. } => *inst,
+                _ => panic!("a phi argument must be the value of an assignment"),
+            };
+            assert!(matches!(
+                func.store.inst_data(def).kind,
+                InstKind::Bind { src } if func.store.value_data(src).is_imm()
+            ));
+        }
+    }
+
+    #[test]
+    fn no_phi_for_dead_local() {
+        let db = NewDb::default();
+        let (mut func, x, merge_block) = build_diamond(&db, false);
+        assert!(Mem2Reg.run(&db, &mut func));
+        assert!(!mentions(&func, x));
+        assert_eq!(func.phis(merge_block).count(), 0);
+    }
+
+    #[test]
+    fn remove_trivial_phi() {
+        let db = NewDb::default();
+        let u256 = make_ty(&db, TypeKind::U256);
+        let mut builder = body_builder();
+
+        let then_block = builder.make_block();
+        let else_block = builder.make_block();
+        let merge_block = builder.make_block();
+
+        let arg = builder.store_func_arg(Local::arg_local("a".into(), u256, SourceInfo::dummy()));
+        let a = builder.add(arg, arg, SourceInfo::dummy());
+        let a_value = builder.make_value(Value::Temporary { inst: a, ty: u256 });
+        builder.map_result(a, a_value.into());
+        let bool_ty = make_ty(&db, TypeKind::Bool);
+        let cond = builder.store_func_arg(Local::arg_local(
+            "cond".into(),
+            bool_ty,
+            SourceInfo::dummy(),
+        ));
+        builder.branch(cond, then_block, else_block, SourceInfo::dummy());
+
+        builder.move_to_block(then_block);
+        builder.jump(merge_block, SourceInfo::dummy());
+        builder.move_to_block(else_block);
+        builder.jump(merge_block, SourceInfo::dummy());
+
+        builder.move_to_block(merge_block);
+        let phi = builder.phi(
+            vec![a_value, a_value],
+            vec![then_block, else_block],
+            SourceInfo::dummy(),
+        );
+        let phi_value = builder.make_value(Value::Temporary {
+            inst: phi,
+            ty: u256,
+        });
+        builder.map_result(phi, phi_value.into());
+        builder.ret(phi_value, SourceInfo::dummy());
+
+        let mut func = builder.build();
+        assert!(Mem2Reg.run(&db, &mut func));
+        assert_eq!(func.phis(merge_block).count(), 0);
+
+        let ret = func.order.terminator(&func.store, merge_block).unwrap();
+        assert!(matches!(
+            func.store.inst_data(ret).kind,
+            InstKind::Return { arg: Some(arg) } if arg == a_value
+        ));
+    }
+}
@@ -0,0 +1,158 @@
+//! This module provides optimization passes that transform a [`FunctionBody`]
+//! in-place, and a [`PassManager`] to run them.
+
+mod const_fold;
+mod copy_prop;
+mod dce;
+mod inline;
+mod licm;
+mod mem2reg;
+mod simplify_cfg;
+mod storage;
+
+pub use const_fold::ConstFold;
+pub use copy_prop::CopyProp;
+pub use dce::DeadCodeElim;
+pub use inline::Inliner;
+pub use licm::Licm;
+pub use mem2reg::Mem2Reg;
+pub use simplify_cfg::SimplifyCfg;
+pub use storage::StorageForward;
+
+use fxhash::FxHashMap;
+
+use crate::{
+    analysis::verify,
+    db::MirDb,
+    ir::{inst::InstKind, value::AssignableValue, FunctionBody, ValueId},
+};
+
+/// A transformation of a function body.
+pub trait Pass {
+    fn name(&self) -> &'static str;
+
+    /// Runs the pass on `body`. Returns `true` if `body` changed.
+    fn run(&mut self, db: &dyn MirDb, body: &mut FunctionBody) -> bool;
+}
+
+/// Runs a pipeline of passes over a function body until none of them changes
+/// it anymore, since a pass often exposes more work for the others.
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn Pass>>,
+}
+
+impl PassManager {
+    /// The maximum number of times the whole pipeline is run on a body.
+    const MAX_ITERATIONS: usize = 8;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a pass manager with the passes that are run with `--optimize`.
+    pub fn with_default_passes() -> Self {
+        let mut pm = Self::new();
+        pm.add_pass(Inliner::new())
+            .add_pass(StorageForward::new())
+            .add_simplification_passes();
+        pm
+    }
+
+    /// Adds the passes that simplify a body without looking at other
+    /// functions.
+    pub fn add_simplification_passes(&mut self) -> &mut Self {
+        self.add_pass(Mem2Reg)
+            .add_pass(CopyProp)
+            .add_pass(ConstFold)
+            .add_pass(Licm)
+            .add_pass(SimplifyCfg)
+            .add_pass(DeadCodeElim)
+    }
+
+    pub fn add_pass(&mut self, pass: impl Pass + 'static) -> &mut Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// In debug builds, `body` is verified after each pass that changes it.
+    pub fn run(&mut self, db: &dyn MirDb, body: &mut FunctionBody) {
+        for _ in 0..Self::MAX_ITERATIONS {
+            let mut changed = false;
+            for pass in &mut self.passes {
+                let pass_changed = pass.run(db, body);
+                if pass_changed && cfg!(debug_assertions) {
+                    verify::assert_valid(db, body, &format!("after `{}`", pass.name()));
+                }
+                changed |= pass_changed;
+            }
+
+            if !changed {
+                break;
+            }
+        }
+    }
+}
+
+/// Runs the default optimization pipeline on `body`.
+pub fn optimize_func_body(db: &dyn MirDb, body: &mut FunctionBody) {
+    PassManager::with_default_passes().run(db, body)
+}
+
+/// Returns the number of instructions that read each value. A `Declare` doesn't
+/// read its local, and an instruction doesn't read the value it assigns to.
+fn use_counts(body: &FunctionBody) -> FxHashMap<ValueId, usize> {
+    let mut uses = FxHashMap::default();
+    for block in body.order.iter_block() {
+        for inst in body.order.iter_inst(block) {
+            let inst_data = body.store.inst_data(inst);
+            if matches!(inst_data.kind, InstKind::Declare { .. }) {
+                continue;
+            }
+
+            let mut values: Vec<_> = inst_data.args().collect();
+            if let Some(result) = body.store.inst_result(inst) {
+                collect_assignable_uses(result, true, &mut values);
+            }
+            for value in values {
+                *uses.entry(value).or_default() += 1;
+            }
+        }
+    }
+
+    uses
+}
+
+/// Returns the number of instructions that assign to each value.
+fn def_counts(body: &FunctionBody) -> FxHashMap<ValueId, usize> {
+    let mut defs = FxHashMap::default();
+    for block in body.order.iter_block() {
+        for inst in body.order.iter_inst(block) {
+            if let Some(AssignableValue::Value(value)) = body.store.inst_result(inst) {
+                *defs.entry(*value).or_default() += 1;
+            }
+        }
+    }
+
+    defs
+}
+
+/// Collects the values read by an assignment to `value`. Assigning to an
+/// aggregate element or a map entry reads the pointer it's written through.
+fn collect_assignable_uses(value: &AssignableValue, is_dest: bool, uses: &mut Vec<ValueId>) {
+    match value {
+        AssignableValue::Value(value) => {
+            if !is_dest {
+                uses.push(*value)
+            }
+        }
+        AssignableValue::Aggregate { lhs, idx } => {
+            uses.push(*idx);
+            collect_assignable_uses(lhs, false, uses);
+        }
+        AssignableValue::Map { lhs, key } => {
+            uses.push(*key);
+            collect_assignable_uses(lhs, false, uses);
+        }
+    }
+}
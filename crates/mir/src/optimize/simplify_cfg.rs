@@ -0,0 +1,157 @@
+use num_traits::Zero;
+
+use crate::{
+    analysis::ControlFlowGraph,
+    db::MirDb,
+    ir::{
+        inst::{BranchInfo, InstKind},
+        BasicBlockId, FunctionBody, Value,
+    },
+};
+
+use super::Pass;
+
+/// Turns branches on an immediate or to the same block into jumps, and merges
+/// a block into its only predecessor if that predecessor jumps to it.
+pub struct SimplifyCfg;
+
+impl Pass for SimplifyCfg {
+    fn name(&self) -> &'static str {
+        "simplify-cfg"
+    }
+
+    fn run(&mut self, _db: &dyn MirDb, body: &mut FunctionBody) -> bool {
+        let mut changed = fold_branches(body);
+        while let Some((pred, succ)) = find_mergeable_blocks(body) {
+            merge_blocks(body, pred, succ);
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+fn fold_branches(body: &mut FunctionBody) -> bool {
+    let mut changed = false;
+    let blocks: Vec<_> = body.order.iter_block().collect();
+    for block in blocks {
+        let terminator = match body.order.terminator(&body.store, block) {
+            Some(terminator) => terminator,
+            None => continue,
+        };
+
+        let (dest, removed) = match body.store.branch_info(terminator) {
+            BranchInfo::Branch(_, then, else_) if then == else_ => (then, None),
+            BranchInfo::Branch(cond, then, else_) => match body.store.value_data(cond) {
+                Value::Immediate { imm, .. } if imm.is_zero() => (else_, Some(then)),
+                Value::Immediate { .. } => (then, Some(else_)),
+                _ => continue,
+            },
+            _ => continue,
+        };
+
+        body.store.inst_data_mut(terminator).kind = InstKind::Jump { dest };
+        if let Some(removed) = removed {
+            body.remove_phi_pred(removed, block);
+        }
+        changed = true;
+    }
+
+    changed
+}
+
+/// Returns a block that unconditionally jumps to a block, which is not the
+/// entry block and has no other predecessor.
+fn find_mergeable_blocks(body: &FunctionBody) -> Option<(BasicBlockId, BasicBlockId)> {
+    let cfg = ControlFlowGraph::compute(body);
+    let entry = body.order.entry();
+    body.order.iter_block().find_map(|block| {
+        let terminator = body.order.terminator(&body.store, block)?;
+        match body.store.branch_info(terminator) {
+            BranchInfo::Jump(dest)
+                if dest != block
+                    && dest != entry
+                    && matches!(cfg.preds(dest), [pred] if *pred == block) =>
+            {
+                Some((block, dest))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Moves the instructions of `succ` to the end of `pred`, replacing the jump
+/// that connects them, and removes `succ`. The phis of `succ` only have one
+/// argument, and become a `Bind` of it.
+fn merge_blocks(body: &mut FunctionBody, pred: BasicBlockId, succ: BasicBlockId) {
+    let jump = body.order.terminator(&body.store, pred).unwrap();
+    body.order.remove_inst(jump);
+
+    let phis: Vec<_> = body.phis(succ).collect();
+    for phi in phis {
+        let inst_data = body.store.inst_data_mut(phi);
+        if let InstKind::Phi { args, .. } = &inst_data.kind {
+            inst_data.kind = InstKind::Bind { src: args[0] };
+        }
+    }
+    let terminator = body.order.terminator(&body.store, succ).unwrap();
+    let succ_succs: Vec<_> = body.store.branch_info(terminator).block_iter().collect();
+    for block in succ_succs {
+        body.replace_phi_pred(block, succ, pred);
+    }
+
+    while let Some(inst) = body.order.first_inst(succ) {
+        body.order.remove_inst(inst);
+        body.order.append_inst(inst, pred);
+    }
+    body.order.remove_block(succ);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        db::NewDb,
+        ir::{body_builder::BodyBuilder, FunctionId, SourceInfo, TypeId},
+    };
+
+    #[test]
+    fn fold_constant_branch() {
+        let mut builder = BodyBuilder::new(FunctionId(0), SourceInfo::dummy());
+
+        let then_block = builder.make_block();
+        let else_block = builder.make_block();
+        let merge_block = builder.make_block();
+
+        let dummy_ty = TypeId(0);
+        let v0 = builder.make_imm_from_bool(true, dummy_ty);
+        builder.branch(v0, then_block, else_block, SourceInfo::dummy());
+
+        builder.move_to_block(then_block);
+        builder.jump(merge_block, SourceInfo::dummy());
+
+        builder.move_to_block(else_block);
+        builder.jump(merge_block, SourceInfo::dummy());
+
+        builder.move_to_block(merge_block);
+        let dummy_value = builder.make_unit(dummy_ty);
+        builder.ret(dummy_value, SourceInfo::dummy());
+
+        let mut func = builder.build();
+        let db = NewDb::default();
+        assert!(SimplifyCfg.run(&db, &mut func));
+
+        // `then_block` is merged into the entry block. `merge_block` still has
+        // `else_block` as a predecessor until it's removed as unreachable.
+        let entry = func.order.entry();
+        let blocks: Vec<_> = func.order.iter_block().collect();
+        assert_eq!(blocks, vec![entry, else_block, merge_block]);
+
+        let terminator = func.order.terminator(&func.store, entry).unwrap();
+        assert!(matches!(
+            func.store.branch_info(terminator),
+            BranchInfo::Jump(dest) if dest == merge_block
+        ));
+    }
+}
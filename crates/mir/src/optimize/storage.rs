@@ -0,0 +1,399 @@
+use fxhash::FxHashMap;
+
+use crate::{
+    analysis::{
+        effects::{reads_storage_operand, EffectSummary},
+        ControlFlowGraph, DomTree, LoopTree,
+    },
+    db::MirDb,
+    ir::{
+        inst::InstKind, value::AssignableValue, BasicBlockId, FunctionBody, Inst, InstId, Value,
+        ValueId,
+    },
+};
+
+use super::Pass;
+
+/// Forwards storage values that are already known and removes stores that are
+/// overwritten before they're read, e.g. `self.balances[a] += x` reads the
+/// entry once and `self.x = 1; self.x = 2` writes it once.
+///
+/// A load of a storage location is replaced with the value that was last
+/// loaded from or stored to it, unless the location may have been written in
+/// between, by a store that may alias it or by a call that may write storage
+/// according to [`EffectSummary`]. What's known at the end of a block flows
+/// into a successor only if the block is its sole predecessor in the same
+/// loop, so that a forwarded value is always in scope where it's used.
+///
+/// A store is removed if a later store in the same block writes the same
+/// location and nothing in between may read it.
+#[derive(Default)]
+pub struct StorageForward {
+    effects: EffectSummary,
+}
+
+impl StorageForward {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn visit_inst(
+        &mut self,
+        db: &dyn MirDb,
+        body: &FunctionBody,
+        inst: InstId,
+        state: &mut State,
+        accesses: &mut FxHashMap<InstId, Access>,
+        forwards: &mut Vec<(InstId, Known)>,
+    ) {
+        let kind = &body.store.inst_data(inst).kind;
+        let mut access = Access::default();
+
+        // The location a load reads, if its value isn't known yet.
+        let mut loaded = None;
+        if let InstKind::Load { src } = kind {
+            if body.store.value_ty(*src).is_sptr(db) {
+                let place = state.resolve_ptr(*src);
+                match state.lookup(&place) {
+                    Some(known) => forwards.push((inst, known)),
+                    None => loaded = Some((place.clone(), body.store.value_ty(*src).deref(db))),
+                }
+                access.read = Some(place);
+            }
+        }
+        if let InstKind::MemCopy { src } = kind {
+            if body.store.value_ty(*src).is_sptr(db) {
+                access.read = Some(state.resolve_ptr(*src));
+            }
+        }
+
+        if self.effects.callee_effects(db, body, inst).writes {
+            state.places.clear();
+        }
+
+        match body.store.inst_result(inst) {
+            Some(AssignableValue::Value(value)) => {
+                let value = *value;
+                state.kill_value(value);
+                let value_ty = body.store.value_ty(value);
+                if !value_ty.is_sptr(db) {
+                    match loaded {
+                        Some((place, ty)) if ty == value_ty => {
+                            state.places.push((place, Known::Value(value)))
+                        }
+                        _ => {}
+                    }
+                } else {
+                    match kind {
+                        InstKind::AggregateAccess {
+                            value: ptr,
+                            indices,
+                        } => {
+                            let mut place = state.resolve_ptr(*ptr);
+                            place
+                                .projections
+                                .extend(indices.iter().copied().map(Projection::Index));
+                            state.ptrs.insert(value, place);
+                        }
+                        InstKind::MapAccess { value: ptr, key } => {
+                            let mut place = state.resolve_ptr(*ptr);
+                            place.projections.push(Projection::Key(*key));
+                            state.ptrs.insert(value, place);
+                        }
+                        InstKind::Bind { src } if body.store.value_ty(*src).is_sptr(db) => {}
+                        // Anything else may store through the pointer.
+                        _ => state.places.clear(),
+                    }
+                }
+            }
+
+            Some(result) => {
+                let result_ty = result.ty(db, &body.store);
+                if result_ty.is_sptr(db) {
+                    let place = state.resolve_assignable(result);
+                    state.kill_may_alias(body, &place);
+
+                    let elem_ty = result_ty.deref(db);
+                    let known = match kind {
+                        InstKind::Bind { src } if body.store.value_ty(*src) == elem_ty => {
+                            Some(Known::Value(*src))
+                        }
+                        InstKind::Bind { .. } => None,
+                        InstKind::Load { .. }
+                        | InstKind::Unary { .. }
+                        | InstKind::Binary { .. }
+                        | InstKind::Cast { .. }
+                        | InstKind::Keccak256 { .. } => Some(Known::Store(inst)),
+                        _ => None,
+                    };
+                    if elem_ty.is_primitive(db) {
+                        if let Some(known) = known {
+                            state.places.push((place.clone(), known));
+                        }
+                        access.write = Some(place);
+                    }
+                }
+            }
+
+            None => {}
+        }
+
+        accesses.insert(inst, access);
+    }
+
+    /// Returns the stores in `block` that are overwritten before they're read.
+    fn dead_stores(
+        &mut self,
+        db: &dyn MirDb,
+        body: &FunctionBody,
+        block: BasicBlockId,
+        accesses: &FxHashMap<InstId, Access>,
+    ) -> Vec<InstId> {
+        let insts: Vec<_> = body.order.iter_inst(block).collect();
+        let mut overwritten: Vec<Place> = vec![];
+        let mut dead = vec![];
+        for &inst in insts.iter().rev() {
+            let access = &accesses[&inst];
+            let kind = &body.store.inst_data(inst).kind;
+
+            if let Some(place) = &access.write {
+                if overwritten.iter().any(|p| p.must_alias(place)) {
+                    dead.push(inst);
+                } else {
+                    overwritten.push(place.clone());
+                }
+            }
+
+            if let Some(AssignableValue::Value(value)) = body.store.inst_result(inst) {
+                overwritten.retain(|place| !place.mentions(*value));
+            }
+
+            let may_read = match kind {
+                // A callee or an intrinsic may also end the execution, which
+                // keeps all stores.
+                InstKind::Call { .. } => true,
+                InstKind::YulIntrinsic { op, .. } => {
+                    op.is_terminator() || self.effects.callee_effects(db, body, inst).reads
+                }
+                _ => self.effects.callee_effects(db, body, inst).reads,
+            };
+            if may_read {
+                overwritten.clear();
+            } else if let Some(place) = &access.read {
+                overwritten.retain(|p| !p.may_alias(body, place));
+            } else if reads_storage_operand(db, body, inst) {
+                overwritten.clear();
+            }
+        }
+
+        dead
+    }
+}
+
+impl Pass for StorageForward {
+    fn name(&self) -> &'static str {
+        "storage-forward"
+    }
+
+    fn run(&mut self, db: &dyn MirDb, body: &mut FunctionBody) -> bool {
+        let cfg = ControlFlowGraph::compute(body);
+        let domtree = DomTree::compute(&cfg);
+        let loop_tree = LoopTree::compute(&cfg, &domtree);
+
+        let mut exit_states: FxHashMap<BasicBlockId, State> = FxHashMap::default();
+        let mut accesses = FxHashMap::default();
+        let mut forwards = vec![];
+        let mut dead_stores = vec![];
+        for &block in domtree.rpo() {
+            let mut state = match cfg.preds(block) {
+                [pred] if loop_tree.loop_of_block(*pred) == loop_tree.loop_of_block(block) => {
+                    exit_states.get(pred).cloned().unwrap_or_default()
+                }
+                _ => State::default(),
+            };
+
+            for inst in body.order.iter_inst(block) {
+                self.visit_inst(db, body, inst, &mut state, &mut accesses, &mut forwards);
+            }
+            dead_stores.extend(self.dead_stores(db, body, block, &accesses));
+            exit_states.insert(block, state);
+        }
+
+        if forwards.is_empty() && dead_stores.is_empty() {
+            return false;
+        }
+
+        // The result of a store whose value is forwarded is bound to a
+        // temporary first, and stored from there unless the store is dead.
+        let mut stored_values = FxHashMap::default();
+        for store in dead_stores {
+            let value = bind_result_to_tmp(db, body, store);
+            stored_values.insert(store, value);
+        }
+        for (load, known) in forwards {
+            let src = match known {
+                Known::Value(value) => value,
+                Known::Store(store) => *stored_values.entry(store).or_insert_with(|| {
+                    let place = body.store.inst_result(store).cloned().unwrap();
+                    let value = bind_result_to_tmp(db, body, store);
+                    let source = body.store.inst_data(store).source.clone();
+                    let bind = body
+                        .store
+                        .store_inst(Inst::new(InstKind::Bind { src: value }, source));
+                    body.store.map_result(bind, place);
+                    body.order.insert_inst_after(bind, store);
+                    value
+                }),
+            };
+            body.store.inst_data_mut(load).kind = InstKind::Bind { src };
+        }
+
+        true
+    }
+}
+
+/// Replaces the place `store` writes to with a new temporary, and returns it.
+fn bind_result_to_tmp(db: &dyn MirDb, body: &mut FunctionBody, store: InstId) -> ValueId {
+    let ty = body
+        .store
+        .inst_result(store)
+        .unwrap()
+        .ty(db, &body.store)
+        .deref(db);
+    let value = body.store.store_value(Value::Temporary { inst: store, ty });
+    body.store.map_result(store, value.into());
+    value
+}
+
+/// A storage location: a storage pointer and the projections that are applied
+/// to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Place {
+    base: ValueId,
+    projections: Vec<Projection>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Projection {
+    Index(ValueId),
+    Key(ValueId),
+}
+
+impl Place {
+    fn new(base: ValueId) -> Self {
+        Self {
+            base,
+            projections: vec![],
+        }
+    }
+
+    fn mentions(&self, value: ValueId) -> bool {
+        self.base == value
+            || self.projections.iter().any(|proj| match proj {
+                Projection::Index(v) | Projection::Key(v) => *v == value,
+            })
+    }
+
+    fn must_alias(&self, other: &Place) -> bool {
+        self == other
+    }
+
+    /// Returns `false` only if the places are provably disjoint, i.e. they
+    /// differ in an immediate field index, array index or map key.
+    fn may_alias(&self, body: &FunctionBody, other: &Place) -> bool {
+        if self.base != other.base {
+            return true;
+        }
+
+        !self
+            .projections
+            .iter()
+            .zip(&other.projections)
+            .any(|(lhs, rhs)| match (lhs, rhs) {
+                (Projection::Index(lhs), Projection::Index(rhs))
+                | (Projection::Key(lhs), Projection::Key(rhs)) => {
+                    match (body.store.value_data(*lhs), body.store.value_data(*rhs)) {
+                        (
+                            Value::Immediate {
+                                imm: lhs_imm,
+                                ty: lhs_ty,
+                            },
+                            Value::Immediate {
+                                imm: rhs_imm,
+                                ty: rhs_ty,
+                            },
+                        ) => lhs_ty == rhs_ty && lhs_imm != rhs_imm,
+                        _ => false,
+                    }
+                }
+                _ => false,
+            })
+    }
+}
+
+/// The value a storage location is known to hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Known {
+    Value(ValueId),
+    /// The value computed by a store to the location.
+    Store(InstId),
+}
+
+#[derive(Debug, Clone, Default)]
+struct State {
+    places: Vec<(Place, Known)>,
+    /// Storage pointers computed by projections, mapped to the places they
+    /// point to.
+    ptrs: FxHashMap<ValueId, Place>,
+}
+
+impl State {
+    fn resolve_ptr(&self, ptr: ValueId) -> Place {
+        self.ptrs
+            .get(&ptr)
+            .cloned()
+            .unwrap_or_else(|| Place::new(ptr))
+    }
+
+    fn resolve_assignable(&self, value: &AssignableValue) -> Place {
+        match value {
+            AssignableValue::Value(value) => self.resolve_ptr(*value),
+            AssignableValue::Aggregate { lhs, idx } => {
+                let mut place = self.resolve_assignable(lhs);
+                place.projections.push(Projection::Index(*idx));
+                place
+            }
+            AssignableValue::Map { lhs, key } => {
+                let mut place = self.resolve_assignable(lhs);
+                place.projections.push(Projection::Key(*key));
+                place
+            }
+        }
+    }
+
+    fn lookup(&self, place: &Place) -> Option<Known> {
+        self.places
+            .iter()
+            .find(|(p, _)| p.must_alias(place))
+            .map(|(_, known)| *known)
+    }
+
+    /// Forgets what depends on the previous value of a reassigned value.
+    fn kill_value(&mut self, value: ValueId) {
+        self.places
+            .retain(|(place, known)| !place.mentions(value) && *known != Known::Value(value));
+        self.ptrs
+            .retain(|ptr, place| *ptr != value && !place.mentions(value));
+    }
+
+    fn kill_may_alias(&mut self, body: &FunctionBody, place: &Place) {
+        self.places.retain(|(p, _)| !p.may_alias(body, place));
+    }
+}
+
+#[derive(Debug, Default)]
+struct Access {
+    read: Option<Place>,
+    /// The primitive location written by a store.
+    write: Option<Place>,
+}
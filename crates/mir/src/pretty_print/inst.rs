@@ -2,7 +2,12 @@ use std::fmt::{self, Write};
 
 use crate::{
     db::MirDb,
-    ir::{function::BodyDataStore, inst::InstKind, InstId},
+    ir::{
+        function::BodyDataStore,
+        inst::{CastKind, InstKind},
+        value::AssignableValue,
+        BasicBlockId, InstId, ValueId,
+    },
 };
 
 use super::PrettyPrint;
@@ -14,193 +19,262 @@ impl PrettyPrint for InstId {
         store: &BodyDataStore,
         w: &mut W,
     ) -> fmt::Result {
-        if let Some(result) = store.inst_result(*self) {
-            result.pretty_print(db, store, w)?;
-            write!(w, ": ")?;
+        print_inst(db, store, *self, &mut PrettyOperands { db, store }, w)
+    }
+}
 
-            let result_ty = result.ty(db, store);
-            result_ty.pretty_print(db, store, w)?;
-            write!(w, " = ")?;
-        }
+/// Prints the values and blocks that instructions refer to. The pretty printer
+/// and the textual format of [`crate::text`] name them differently, but print
+/// instructions the same way, with [`print_inst`].
+pub(crate) trait OperandPrinter {
+    fn print_value<W: Write>(&mut self, value: ValueId, w: &mut W) -> fmt::Result;
 
-        match &store.inst_data(*self).kind {
-            InstKind::Declare { local } => {
-                write!(w, "let ")?;
-                local.pretty_print(db, store, w)?;
-                write!(w, ": ")?;
-                store.value_ty(*local).pretty_print(db, store, w)
-            }
+    fn print_block<W: Write>(&mut self, block: BasicBlockId, w: &mut W) -> fmt::Result;
 
-            InstKind::Unary { op, value } => {
-                write!(w, "{op}")?;
-                value.pretty_print(db, store, w)
-            }
-
-            InstKind::Binary { op, lhs, rhs } => {
-                lhs.pretty_print(db, store, w)?;
-                write!(w, " {op} ")?;
-                rhs.pretty_print(db, store, w)
+    fn print_values<W: Write>(&mut self, values: &[ValueId], w: &mut W) -> fmt::Result {
+        for (i, value) in values.iter().enumerate() {
+            if i != 0 {
+                write!(w, ", ")?;
             }
+            self.print_value(*value, w)?;
+        }
+        Ok(())
+    }
 
-            InstKind::Cast { value, to, .. } => {
-                value.pretty_print(db, store, w)?;
-                write!(w, " as ")?;
-                to.pretty_print(db, store, w)
+    fn print_assignable<W: Write>(&mut self, value: &AssignableValue, w: &mut W) -> fmt::Result {
+        match value {
+            AssignableValue::Value(value) => self.print_value(*value, w),
+            AssignableValue::Aggregate { lhs, idx } => {
+                self.print_assignable(lhs, w)?;
+                write!(w, ".<")?;
+                self.print_value(*idx, w)?;
+                write!(w, ">")
             }
-
-            InstKind::AggregateConstruct { ty, args } => {
-                ty.pretty_print(db, store, w)?;
+            AssignableValue::Map { lhs, key } => {
+                self.print_assignable(lhs, w)?;
                 write!(w, "{{")?;
-                if args.is_empty() {
-                    return write!(w, "}}");
-                }
-
-                let arg_len = args.len();
-                for (arg_idx, arg) in args.iter().enumerate().take(arg_len - 1) {
-                    write!(w, "<{arg_idx}>: ")?;
-                    arg.pretty_print(db, store, w)?;
-                    write!(w, ", ")?;
-                }
-                let arg = args[arg_len - 1];
-                write!(w, "<{}>: ", arg_len - 1)?;
-                arg.pretty_print(db, store, w)?;
+                self.print_value(*key, w)?;
                 write!(w, "}}")
             }
+        }
+    }
+}
 
-            InstKind::Bind { src } => {
-                write!(w, "bind ")?;
-                src.pretty_print(db, store, w)
-            }
+pub(super) struct PrettyOperands<'a> {
+    pub(super) db: &'a dyn MirDb,
+    pub(super) store: &'a BodyDataStore,
+}
 
-            InstKind::MemCopy { src } => {
-                write!(w, "memcopy ")?;
-                src.pretty_print(db, store, w)
-            }
+impl OperandPrinter for PrettyOperands<'_> {
+    fn print_value<W: Write>(&mut self, value: ValueId, w: &mut W) -> fmt::Result {
+        value.pretty_print(self.db, self.store, w)
+    }
 
-            InstKind::Load { src } => {
-                write!(w, "load ")?;
-                src.pretty_print(db, store, w)
-            }
+    fn print_block<W: Write>(&mut self, block: BasicBlockId, w: &mut W) -> fmt::Result {
+        write!(w, "BB{}", block.index())
+    }
+}
 
-            InstKind::AggregateAccess { value, indices } => {
-                value.pretty_print(db, store, w)?;
-                for index in indices {
-                    write!(w, ".<")?;
-                    index.pretty_print(db, store, w)?;
-                    write!(w, ">")?
-                }
-                Ok(())
-            }
+/// Prints `inst`, preceded by its result and the type of the result.
+pub(crate) fn print_inst<P: OperandPrinter, W: Write>(
+    db: &dyn MirDb,
+    store: &BodyDataStore,
+    inst: InstId,
+    operands: &mut P,
+    w: &mut W,
+) -> fmt::Result {
+    if let Some(result) = store.inst_result(inst) {
+        operands.print_assignable(result, w)?;
+        write!(w, ": ")?;
+        result.ty(db, store).print(db, w)?;
+        write!(w, " = ")?;
+    }
 
-            InstKind::MapAccess { value, key } => {
-                value.pretty_print(db, store, w)?;
-                write!(w, "{{")?;
-                key.pretty_print(db, store, w)?;
-                write!(w, "}}")
-            }
+    match &store.inst_data(inst).kind {
+        InstKind::Declare { local } => {
+            write!(w, "let ")?;
+            operands.print_value(*local, w)?;
+            write!(w, ": ")?;
+            store.value_ty(*local).print(db, w)
+        }
 
-            InstKind::Call {
-                func,
-                args,
-                call_type,
-            } => {
-                let name = func.debug_name(db);
-                write!(w, "{name}@{call_type}(")?;
-                args.as_slice().pretty_print(db, store, w)?;
-                write!(w, ")")
-            }
+        InstKind::Unary { op, value } => {
+            write!(w, "{op} ")?;
+            operands.print_value(*value, w)
+        }
 
-            InstKind::Jump { dest } => {
-                write!(w, "jump BB{}", dest.index())
-            }
+        InstKind::Binary { op, lhs, rhs } => {
+            operands.print_value(*lhs, w)?;
+            write!(w, " {op} ")?;
+            operands.print_value(*rhs, w)
+        }
 
-            InstKind::Branch { cond, then, else_ } => {
-                write!(w, "branch ")?;
-                cond.pretty_print(db, store, w)?;
-                write!(w, " then: BB{} else: BB{}", then.index(), else_.index())
+        InstKind::Cast { kind, value, to } => {
+            if *kind == CastKind::Untag {
+                write!(w, "untag ")?;
             }
+            operands.print_value(*value, w)?;
+            write!(w, " as ")?;
+            to.print(db, w)
+        }
 
-            InstKind::Switch {
-                disc,
-                table,
-                default,
-            } => {
-                write!(w, "switch ")?;
-                disc.pretty_print(db, store, w)?;
-                for (value, block) in table.iter() {
-                    write!(w, " ")?;
-                    value.pretty_print(db, store, w)?;
-                    write!(w, ": BB{}", block.index())?;
-                }
-
-                if let Some(default) = default {
-                    write!(w, " default: BB{}", default.index())
-                } else {
-                    Ok(())
+        InstKind::AggregateConstruct { ty, args } => {
+            ty.print(db, w)?;
+            write!(w, "{{")?;
+            for (idx, arg) in args.iter().enumerate() {
+                if idx != 0 {
+                    write!(w, ", ")?;
                 }
+                write!(w, "<{idx}>: ")?;
+                operands.print_value(*arg, w)?;
             }
+            write!(w, "}}")
+        }
 
-            InstKind::Revert { arg } => {
-                write!(w, "revert ")?;
-                if let Some(arg) = arg {
-                    arg.pretty_print(db, store, w)?;
-                }
-                Ok(())
-            }
+        InstKind::Bind { src } => {
+            write!(w, "bind ")?;
+            operands.print_value(*src, w)
+        }
 
-            InstKind::Emit { arg } => {
-                write!(w, "emit ")?;
-                arg.pretty_print(db, store, w)
-            }
+        InstKind::MemCopy { src } => {
+            write!(w, "memcopy ")?;
+            operands.print_value(*src, w)
+        }
 
-            InstKind::Return { arg } => {
-                if let Some(arg) = arg {
-                    write!(w, "return ")?;
-                    arg.pretty_print(db, store, w)
-                } else {
-                    write!(w, "return")
-                }
-            }
+        InstKind::Load { src } => {
+            write!(w, "load ")?;
+            operands.print_value(*src, w)
+        }
 
-            InstKind::Keccak256 { arg } => {
-                write!(w, "keccak256 ")?;
-                arg.pretty_print(db, store, w)
+        InstKind::AggregateAccess { value, indices } => {
+            operands.print_value(*value, w)?;
+            for index in indices {
+                write!(w, ".<")?;
+                operands.print_value(*index, w)?;
+                write!(w, ">")?;
             }
+            Ok(())
+        }
 
-            InstKind::AbiEncode { arg } => {
-                write!(w, "abi_encode ")?;
-                arg.pretty_print(db, store, w)
-            }
+        InstKind::MapAccess { value, key } => {
+            operands.print_value(*value, w)?;
+            write!(w, "{{")?;
+            operands.print_value(*key, w)?;
+            write!(w, "}}")
+        }
+
+        InstKind::Call {
+            func,
+            args,
+            call_type,
+        } => {
+            write!(w, "{}@{call_type}(", func.debug_name(db))?;
+            operands.print_values(args, w)?;
+            write!(w, ")")
+        }
+
+        InstKind::Jump { dest } => {
+            write!(w, "jump ")?;
+            operands.print_block(*dest, w)
+        }
 
-            InstKind::Nop => {
-                write!(w, "nop")
+        InstKind::Branch { cond, then, else_ } => {
+            write!(w, "branch ")?;
+            operands.print_value(*cond, w)?;
+            write!(w, " then: ")?;
+            operands.print_block(*then, w)?;
+            write!(w, " else: ")?;
+            operands.print_block(*else_, w)
+        }
+
+        InstKind::Switch {
+            disc,
+            table,
+            default,
+        } => {
+            write!(w, "switch ")?;
+            operands.print_value(*disc, w)?;
+            for (value, block) in table.iter() {
+                write!(w, " ")?;
+                operands.print_value(value, w)?;
+                write!(w, ": ")?;
+                operands.print_block(block, w)?;
+            }
+            if let Some(default) = default {
+                write!(w, " default: ")?;
+                operands.print_block(*default, w)?;
             }
+            Ok(())
+        }
 
-            InstKind::Create { value, contract } => {
-                write!(w, "create ")?;
-                let contract_name = contract.name(db.upcast());
-                write!(w, "{contract_name} ")?;
-                value.pretty_print(db, store, w)
+        InstKind::Revert { arg } => {
+            write!(w, "revert")?;
+            if let Some(arg) = arg {
+                write!(w, " ")?;
+                operands.print_value(*arg, w)?;
             }
+            Ok(())
+        }
+
+        InstKind::Emit { arg } => {
+            write!(w, "emit ")?;
+            operands.print_value(*arg, w)
+        }
 
-            InstKind::Create2 {
-                value,
-                salt,
-                contract,
-            } => {
-                write!(w, "create2 ")?;
-                let contract_name = contract.name(db.upcast());
-                write!(w, "{contract_name} ")?;
-                value.pretty_print(db, store, w)?;
+        InstKind::Return { arg } => {
+            write!(w, "return")?;
+            if let Some(arg) = arg {
                 write!(w, " ")?;
-                salt.pretty_print(db, store, w)
+                operands.print_value(*arg, w)?;
             }
+            Ok(())
+        }
+
+        InstKind::Keccak256 { arg } => {
+            write!(w, "keccak256 ")?;
+            operands.print_value(*arg, w)
+        }
+
+        InstKind::AbiEncode { arg } => {
+            write!(w, "abi_encode ")?;
+            operands.print_value(*arg, w)
+        }
 
-            InstKind::YulIntrinsic { op, args } => {
-                write!(w, "{op}(")?;
-                args.as_slice().pretty_print(db, store, w)?;
-                write!(w, ")")
+        InstKind::Nop => write!(w, "nop"),
+
+        InstKind::Phi { args, blocks } => {
+            write!(w, "phi [")?;
+            for (i, (arg, block)) in args.iter().zip(blocks).enumerate() {
+                if i != 0 {
+                    write!(w, ", ")?;
+                }
+                operands.print_value(*arg, w)?;
+                write!(w, ": ")?;
+                operands.print_block(*block, w)?;
             }
+            write!(w, "]")
+        }
+
+        InstKind::Create { value, contract } => {
+            write!(w, "create {} ", contract.name(db.upcast()))?;
+            operands.print_value(*value, w)
+        }
+
+        InstKind::Create2 {
+            value,
+            salt,
+            contract,
+        } => {
+            write!(w, "create2 {} ", contract.name(db.upcast()))?;
+            operands.print_value(*value, w)?;
+            write!(w, " ")?;
+            operands.print_value(*salt, w)
+        }
+
+        InstKind::YulIntrinsic { op, args } => {
+            write!(w, "{op}(")?;
+            operands.print_values(args, w)?;
+            write!(w, ")")
         }
     }
 }
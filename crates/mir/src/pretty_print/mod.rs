@@ -6,6 +6,8 @@ mod inst;
 mod types;
 mod value;
 
+pub(crate) use inst::{print_inst, OperandPrinter};
+
 pub trait PrettyPrint {
     fn pretty_print<W: fmt::Write>(
         &self,
@@ -7,7 +7,7 @@ use crate::{
     },
 };
 
-use super::PrettyPrint;
+use super::{inst::PrettyOperands, OperandPrinter, PrettyPrint};
 
 impl PrettyPrint for ValueId {
     fn pretty_print<W: Write>(
@@ -61,21 +61,6 @@ impl PrettyPrint for AssignableValue {
         store: &BodyDataStore,
         w: &mut W,
     ) -> fmt::Result {
-        match self {
-            Self::Value(value) => value.pretty_print(db, store, w),
-            Self::Aggregate { lhs, idx } => {
-                lhs.pretty_print(db, store, w)?;
-                write!(w, ".<")?;
-                idx.pretty_print(db, store, w)?;
-                write!(w, ">")
-            }
-
-            Self::Map { lhs, key } => {
-                lhs.pretty_print(db, store, w)?;
-                write!(w, "{{")?;
-                key.pretty_print(db, store, w)?;
-                write!(w, "}}")
-            }
-        }
+        PrettyOperands { db, store }.print_assignable(self, w)
     }
 }
@@ -0,0 +1,32 @@
+//! A textual format of [`FunctionBody`](crate::ir::FunctionBody) that can be
+//! parsed back, so that a pass can be tested on a body written by hand.
+//!
+//! ```text
+//! fn(_0: u256, _1: bool) {
+//! BB0:
+//!     branch _1 then: BB1 else: BB2
+//! BB1:
+//!     _2: u256 = _0 + 1_u256
+//!     jump BB2
+//! BB2:
+//!     _3: u256 = phi [_0: BB0, _2: BB1]
+//!     return _3
+//! }
+//! ```
+//!
+//! The arguments are listed in the header. Values are named `_N` and blocks
+//! `BBN`, and the printer numbers them in the order they appear. The first
+//! block is the entry block. A value that's assigned but isn't an argument or
+//! declared with `let` is a temporary of the instruction that assigns it.
+//! Immediates are suffixed with their type, e.g. `-1_i8`, except for booleans,
+//! which are `true` and `false`. Comments start with `//`.
+//!
+//! Instructions are written like in [`crate::pretty_print`]. Calls, contract
+//! creation and constants refer to items of a module, and structs, enums and
+//! contracts are named types, so they're printed but can't be parsed.
+
+mod parse;
+mod print;
+
+pub use parse::{parse_func_body, ParseError};
+pub use print::print_func_body;
@@ -0,0 +1,982 @@
+use std::{fmt, rc::Rc, str::FromStr};
+
+use fe_analyzer::builtins::Intrinsic;
+use fxhash::{FxHashMap, FxHashSet};
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use smol_str::SmolStr;
+
+use crate::{
+    db::MirDb,
+    ir::{
+        body_builder::BodyBuilder,
+        inst::{BinOp, SwitchTable, UnOp, YulIntrinsicOp},
+        types::{ArrayDef, MapDef, TupleDef},
+        value::{AssignableValue, Local},
+        BasicBlock, BasicBlockId, FunctionBody, FunctionId, InstId, SourceInfo, Type, TypeId,
+        TypeKind, Value, ValueId,
+    },
+};
+
+/// An error in textual MIR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        write!(w, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a function body in the textual MIR format.
+pub fn parse_func_body(
+    db: &dyn MirDb,
+    fid: FunctionId,
+    src: &str,
+) -> Result<FunctionBody, ParseError> {
+    let tokens = lex(src)?;
+    Parser::new(db, fid, tokens).parse()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(SmolStr),
+    Int(BigInt),
+    Punct(&'static str),
+    Newline,
+    Eof,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, w: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Ident(ident) => write!(w, "`{ident}`"),
+            Self::Int(int) => write!(w, "`{int}`"),
+            Self::Punct(punct) => write!(w, "`{punct}`"),
+            Self::Newline => write!(w, "end of line"),
+            Self::Eof => write!(w, "end of input"),
+        }
+    }
+}
+
+/// Punctuations, longer ones first.
+const PUNCTS: &[&str] = &[
+    "**", "<<", ">>", "==", "!=", ">=", "<=", "+", "-", "*", "/", "%", "|", "^", "&", "<", ">",
+    "(", ")", "[", "]", "{", "}", ",", ":", ";", "=", ".", "@", "~",
+];
+
+fn lex(src: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = vec![];
+    for (idx, line) in src.lines().enumerate() {
+        let line_no = idx + 1;
+        let mut rest = match line.find("//") {
+            Some(comment) => &line[..comment],
+            None => line,
+        };
+
+        loop {
+            rest = rest.trim_start();
+            let c = match rest.chars().next() {
+                Some(c) => c,
+                None => break,
+            };
+
+            let is_digit_at = |pos: usize| rest[pos..].starts_with(|c: char| c.is_ascii_digit());
+            let (token, len) = if is_digit_at(0) || (c == '-' && is_digit_at(1)) {
+                let len = rest[1..]
+                    .find(|c: char| !c.is_ascii_digit())
+                    .map_or(rest.len(), |len| len + 1);
+                (Token::Int(rest[..len].parse().unwrap()), len)
+            } else if c.is_alphabetic() || c == '_' || c == '$' {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                    .unwrap_or(rest.len());
+                (Token::Ident(rest[..len].into()), len)
+            } else if let Some(punct) = PUNCTS.iter().find(|punct| rest.starts_with(**punct)) {
+                (Token::Punct(*punct), punct.len())
+            } else {
+                return Err(ParseError {
+                    line: line_no,
+                    message: format!("unexpected character `{c}`"),
+                });
+            };
+
+            tokens.push((token, line_no));
+            rest = &rest[len..];
+        }
+        tokens.push((Token::Newline, line_no));
+    }
+
+    let last_line = tokens.last().map_or(1, |(_, line)| *line);
+    tokens.push((Token::Eof, last_line));
+    Ok(tokens)
+}
+
+/// The value an instruction assigns to.
+enum Target {
+    /// A temporary or a local, which isn't known until its name is looked up.
+    Named(SmolStr),
+    Place(AssignableValue),
+}
+
+struct Parser<'a> {
+    db: &'a dyn MirDb,
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    builder: BodyBuilder,
+
+    values: FxHashMap<SmolStr, ValueId>,
+    /// Arguments and declared locals.
+    locals: FxHashSet<ValueId>,
+    /// Values that are used but not defined yet, and the line they're first
+    /// used on. They're stored as placeholders, which are replaced when they're
+    /// defined.
+    undefined: FxHashMap<ValueId, (SmolStr, usize)>,
+
+    blocks: FxHashMap<SmolStr, BasicBlockId>,
+    /// Blocks that are referred to but not labeled yet, and the line they're
+    /// first referred to on.
+    unlabeled: FxHashMap<BasicBlockId, (SmolStr, usize)>,
+    has_label: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn new(db: &'a dyn MirDb, fid: FunctionId, tokens: Vec<(Token, usize)>) -> Self {
+        Self {
+            db,
+            tokens,
+            pos: 0,
+            builder: BodyBuilder::new(fid, SourceInfo::dummy()),
+            values: FxHashMap::default(),
+            locals: FxHashSet::default(),
+            undefined: FxHashMap::default(),
+            blocks: FxHashMap::default(),
+            unlabeled: FxHashMap::default(),
+            has_label: false,
+        }
+    }
+
+    fn parse(mut self) -> Result<FunctionBody, ParseError> {
+        self.skip_newlines();
+        self.expect_keyword("fn")?;
+        self.expect("(")?;
+        if !self.eat(")") {
+            loop {
+                let name = self.value_name()?;
+                self.expect(":")?;
+                let ty = self.ty()?;
+                let local = Local::arg_local(name.clone(), ty, SourceInfo::dummy());
+                let arg = self.builder.store_func_arg(local);
+                self.define_local(name, arg)?;
+
+                if self.eat(")") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        self.expect("{")?;
+        self.expect_newline()?;
+
+        loop {
+            self.skip_newlines();
+            if self.eat("}") {
+                break;
+            }
+
+            if let Some(label) = self.peek_block_name() {
+                self.bump();
+                self.expect(":")?;
+                self.place_block(label)?;
+            } else if !self.has_label {
+                return Err(self.error("expected a block label"));
+            } else {
+                self.inst()?;
+            }
+            self.expect_newline()?;
+        }
+        self.skip_newlines();
+        if self.peek() != &Token::Eof {
+            return Err(self.unexpected("end of input"));
+        }
+
+        if let Some((name, line)) = self.undefined.values().min_by_key(|(_, line)| *line) {
+            return Err(ParseError {
+                line: *line,
+                message: format!("`{name}` is never defined"),
+            });
+        }
+        if let Some((name, line)) = self.unlabeled.values().min_by_key(|(_, line)| *line) {
+            return Err(ParseError {
+                line: *line,
+                message: format!("`{name}` is never labeled"),
+            });
+        }
+
+        Ok(self.builder.build())
+    }
+
+    fn inst(&mut self) -> Result<(), ParseError> {
+        if self.eat_keyword("let") {
+            let name = self.value_name()?;
+            self.expect(":")?;
+            let ty = self.ty()?;
+            let local =
+                self.builder
+                    .declare(Local::user_local(name.clone(), ty, SourceInfo::dummy()));
+            return self.define_local(name, local);
+        }
+
+        if self.peek_value_name().is_none() {
+            self.rhs()?;
+            return Ok(());
+        }
+
+        let line = self.line();
+        let target = self.target()?;
+        self.expect(":")?;
+        let ty = self.ty()?;
+        self.expect("=")?;
+        let inst = self.rhs()?;
+
+        let result = match target {
+            Target::Place(place) => place,
+            Target::Named(name) => {
+                let value = match self.values.get(&name).copied() {
+                    Some(value) if self.locals.contains(&value) => {
+                        if self.builder.value_ty(value) != ty {
+                            return Err(ParseError {
+                                line,
+                                message: format!(
+                                    "`{name}` isn't of type `{}`",
+                                    ty.as_string(self.db)
+                                ),
+                            });
+                        }
+                        value
+                    }
+                    Some(value) => {
+                        if self.undefined.remove(&value).is_none() {
+                            return Err(ParseError {
+                                line,
+                                message: format!("`{name}` is assigned twice"),
+                            });
+                        }
+                        self.builder
+                            .body
+                            .store
+                            .replace_value(value, Value::Temporary { inst, ty });
+                        value
+                    }
+                    None => {
+                        let value = self.builder.make_value(Value::Temporary { inst, ty });
+                        self.values.insert(name, value);
+                        value
+                    }
+                };
+                value.into()
+            }
+        };
+        self.builder.map_result(inst, result);
+        Ok(())
+    }
+
+    /// Parses the right-hand side of an instruction and inserts the
+    /// instruction.
+    fn rhs(&mut self) -> Result<InstId, ParseError> {
+        let source = SourceInfo::dummy();
+
+        if let Token::Ident(ident) = self.peek().clone() {
+            let inst = match ident.as_str() {
+                "bind" | "memcopy" | "load" | "keccak256" | "abi_encode" | "emit" | "not" => {
+                    self.bump();
+                    let value = self.value()?;
+                    match ident.as_str() {
+                        "bind" => self.builder.bind(value, source),
+                        "memcopy" => self.builder.mem_copy(value, source),
+                        "load" => self.builder.load(value, source),
+                        "keccak256" => self.builder.keccak256(value, source),
+                        "abi_encode" => self.builder.abi_encode(value, source),
+                        "emit" => self.builder.emit(value, source),
+                        _ => self.builder.unary(UnOp::Not, value, source),
+                    }
+                }
+
+                "untag" => {
+                    self.bump();
+                    let value = self.value()?;
+                    self.expect_keyword("as")?;
+                    let ty = self.ty()?;
+                    self.builder.untag_cast(value, ty, source)
+                }
+
+                "jump" => {
+                    self.bump();
+                    let dest = self.block()?;
+                    self.builder.jump(dest, source)
+                }
+
+                "branch" => {
+                    self.bump();
+                    let cond = self.value()?;
+                    self.expect_keyword("then")?;
+                    self.expect(":")?;
+                    let then = self.block()?;
+                    self.expect_keyword("else")?;
+                    self.expect(":")?;
+                    let else_ = self.block()?;
+                    self.builder.branch(cond, then, else_, source)
+                }
+
+                "switch" => {
+                    self.bump();
+                    let disc = self.value()?;
+                    let mut table = SwitchTable::default();
+                    let mut default = None;
+                    while self.peek() != &Token::Newline {
+                        if self.eat_keyword("default") {
+                            self.expect(":")?;
+                            default = Some(self.block()?);
+                            break;
+                        }
+                        let value = self.value()?;
+                        self.expect(":")?;
+                        let block = self.block()?;
+                        table.add_arm(value, block);
+                    }
+                    self.builder.switch(disc, table, default, source)
+                }
+
+                "revert" => {
+                    self.bump();
+                    let arg = if self.peek() == &Token::Newline {
+                        None
+                    } else {
+                        Some(self.value()?)
+                    };
+                    self.builder.revert(arg, source)
+                }
+
+                "return" => {
+                    self.bump();
+                    let arg = self.value()?;
+                    self.builder.ret(arg, source)
+                }
+
+                "nop" => {
+                    self.bump();
+                    self.builder.nop(source)
+                }
+
+                "phi" => {
+                    self.bump();
+                    self.expect("[")?;
+                    let mut args = vec![];
+                    let mut blocks = vec![];
+                    if !self.eat("]") {
+                        loop {
+                            args.push(self.value()?);
+                            self.expect(":")?;
+                            blocks.push(self.block()?);
+                            if self.eat("]") {
+                                break;
+                            }
+                            self.expect(",")?;
+                        }
+                    }
+                    self.builder.phi(args, blocks, source)
+                }
+
+                "create" | "create2" => {
+                    return Err(self.error("contract creation can't be parsed"));
+                }
+
+                _ if ident.starts_with("__") => {
+                    let op = match Intrinsic::from_str(&ident) {
+                        Ok(intrinsic) => YulIntrinsicOp::from(intrinsic),
+                        Err(_) => {
+                            return Err(self.error(format!("unknown intrinsic `{ident}`")));
+                        }
+                    };
+                    self.bump();
+                    self.expect("(")?;
+                    let args = self.values_until(")")?;
+                    self.builder.yul_intrinsic(op, args, source)
+                }
+
+                _ if self.peek_nth(1) == &Token::Punct("@") => {
+                    return Err(self.error("calls can't be parsed"));
+                }
+
+                _ => return self.value_rhs(),
+            };
+            return Ok(inst);
+        }
+
+        match self.peek() {
+            Token::Punct("-") => {
+                self.bump();
+                let value = self.value()?;
+                Ok(self.builder.unary(UnOp::Neg, value, source))
+            }
+            Token::Punct("~") => {
+                self.bump();
+                let value = self.value()?;
+                Ok(self.builder.unary(UnOp::Inv, value, source))
+            }
+            Token::Punct("(") | Token::Punct("[") if self.peek_nth(1) != &Token::Punct(")") => {
+                let ty = self.ty()?;
+                self.expect("{")?;
+                let mut args = vec![];
+                if !self.eat("}") {
+                    loop {
+                        self.expect("<")?;
+                        match self.bump() {
+                            Token::Int(idx) if idx == BigInt::from(args.len()) => {}
+                            _ => {
+                                return Err(self.error(format!("expected `{}`", args.len())));
+                            }
+                        }
+                        self.expect(">")?;
+                        self.expect(":")?;
+                        args.push(self.value()?);
+                        if self.eat("}") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                Ok(self.builder.aggregate_construct(ty, args, source))
+            }
+            _ => self.value_rhs(),
+        }
+    }
+
+    /// Parses a right-hand side that starts with a value, i.e. a binary
+    /// operation, a cast or a projection.
+    fn value_rhs(&mut self) -> Result<InstId, ParseError> {
+        let source = SourceInfo::dummy();
+        let value = self.value()?;
+
+        if let Some(op) = self.peek_bin_op() {
+            self.bump();
+            let rhs = self.value()?;
+            return Ok(self.builder.binary(op, value, rhs, source));
+        }
+
+        if self.eat_keyword("as") {
+            let ty = self.ty()?;
+            return Ok(self.builder.primitive_cast(value, ty, source));
+        }
+
+        if self.peek() == &Token::Punct(".") {
+            let mut indices = vec![];
+            while self.eat(".") {
+                self.expect("<")?;
+                indices.push(self.value()?);
+                self.expect(">")?;
+            }
+            return Ok(self.builder.aggregate_access(value, indices, source));
+        }
+
+        if self.eat("{") {
+            let key = self.value()?;
+            self.expect("}")?;
+            return Ok(self.builder.map_access(value, key, source));
+        }
+
+        Err(self.unexpected("an instruction"))
+    }
+
+    fn target(&mut self) -> Result<Target, ParseError> {
+        let name = self.value_name()?;
+        if !matches!(self.peek(), Token::Punct(".") | Token::Punct("{")) {
+            return Ok(Target::Named(name));
+        }
+
+        let mut place = AssignableValue::Value(self.named_value(name));
+        loop {
+            if self.eat(".") {
+                self.expect("<")?;
+                let idx = self.value()?;
+                self.expect(">")?;
+                place = AssignableValue::Aggregate {
+                    lhs: place.into(),
+                    idx,
+                };
+            } else if self.eat("{") {
+                let key = self.value()?;
+                self.expect("}")?;
+                place = AssignableValue::Map {
+                    lhs: place.into(),
+                    key,
+                };
+            } else {
+                return Ok(Target::Place(place));
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<ValueId, ParseError> {
+        if let Some(name) = self.peek_value_name() {
+            self.bump();
+            return Ok(self.named_value(name));
+        }
+
+        match self.bump() {
+            Token::Ident(ident) if ident == "true" || ident == "false" => {
+                let ty = self.intern_ty(TypeKind::Bool);
+                Ok(self.builder.make_imm_from_bool(ident == "true", ty))
+            }
+
+            Token::Int(imm) => {
+                let suffix = match self.peek() {
+                    Token::Ident(suffix) if suffix.starts_with('_') => suffix[1..].to_string(),
+                    _ => return Err(self.error("expected the type of the immediate")),
+                };
+                let ty = match primitive_ty(&suffix) {
+                    Some(kind) => self.intern_ty(kind),
+                    None => return Err(self.error(format!("`{suffix}` isn't a primitive type"))),
+                };
+                self.bump();
+                Ok(self.builder.make_imm(imm, ty))
+            }
+
+            Token::Punct("(") => {
+                self.expect(")")?;
+                let ty = self.intern_ty(TypeKind::Unit);
+                Ok(self.builder.make_unit(ty))
+            }
+
+            Token::Ident(ident) if ident == "const" => {
+                self.pos -= 1;
+                Err(self.error("constants can't be parsed"))
+            }
+
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("a value"))
+            }
+        }
+    }
+
+    fn values_until(&mut self, close: &str) -> Result<Vec<ValueId>, ParseError> {
+        let mut values = vec![];
+        if self.eat(close) {
+            return Ok(values);
+        }
+        loop {
+            values.push(self.value()?);
+            if self.eat(close) {
+                return Ok(values);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    /// Returns the value named `name`, or a placeholder if it isn't defined
+    /// yet.
+    fn named_value(&mut self, name: SmolStr) -> ValueId {
+        if let Some(value) = self.values.get(&name) {
+            return *value;
+        }
+
+        let ty = self.intern_ty(TypeKind::Unit);
+        let value = self
+            .builder
+            .make_value(Value::Local(Local::tmp_local(name.clone(), ty)));
+        self.undefined.insert(value, (name.clone(), self.line()));
+        self.values.insert(name, value);
+        value
+    }
+
+    fn define_local(&mut self, name: SmolStr, local: ValueId) -> Result<(), ParseError> {
+        if let Some(value) = self.values.insert(name.clone(), local) {
+            let message = if self.undefined.contains_key(&value) {
+                format!("`{name}` is used before it's declared")
+            } else {
+                format!("`{name}` is declared twice")
+            };
+            return Err(self.error(message));
+        }
+        self.locals.insert(local);
+        Ok(())
+    }
+
+    fn block(&mut self) -> Result<BasicBlockId, ParseError> {
+        let name = match self.peek_block_name() {
+            Some(name) => name,
+            None => return Err(self.unexpected("a block")),
+        };
+        self.bump();
+
+        if let Some(block) = self.blocks.get(&name) {
+            return Ok(*block);
+        }
+        let block = self.builder.body.store.store_block(BasicBlock {});
+        self.blocks.insert(name.clone(), block);
+        self.unlabeled.insert(block, (name, self.line()));
+        Ok(block)
+    }
+
+    /// Starts the block labeled `name`. The first label is the entry block.
+    fn place_block(&mut self, name: SmolStr) -> Result<(), ParseError> {
+        let block = if self.has_label {
+            match self.blocks.get(&name).copied() {
+                Some(block) if self.unlabeled.remove(&block).is_some() => {
+                    self.builder.body.order.append_block(block);
+                    block
+                }
+                Some(_) => return Err(self.error(format!("`{name}` is labeled twice"))),
+                None => {
+                    let block = self.builder.make_block();
+                    self.blocks.insert(name, block);
+                    block
+                }
+            }
+        } else {
+            let entry = self.builder.body.order.entry();
+            self.blocks.insert(name, entry);
+            self.has_label = true;
+            entry
+        };
+
+        self.builder.move_to_block(block);
+        Ok(())
+    }
+
+    fn ty(&mut self) -> Result<TypeId, ParseError> {
+        let kind = match self.bump() {
+            Token::Ident(ident) => match ident.as_str() {
+                "Str" => {
+                    self.expect("<")?;
+                    let len = self.usize()?;
+                    self.expect_close_angle()?;
+                    TypeKind::String(len)
+                }
+                "Map" => {
+                    self.expect("<")?;
+                    let key_ty = self.ty()?;
+                    self.expect(",")?;
+                    let value_ty = self.ty()?;
+                    self.expect_close_angle()?;
+                    TypeKind::Map(MapDef { key_ty, value_ty })
+                }
+                _ => match primitive_ty(&ident) {
+                    Some(kind) => kind,
+                    None => {
+                        self.pos -= 1;
+                        return Err(self.error(format!("named type `{ident}` can't be parsed")));
+                    }
+                },
+            },
+
+            Token::Punct("(") => {
+                let mut items = vec![];
+                if !self.eat(")") {
+                    loop {
+                        items.push(self.ty()?);
+                        if self.eat(")") {
+                            break;
+                        }
+                        self.expect(",")?;
+                    }
+                }
+                if items.is_empty() {
+                    TypeKind::Unit
+                } else {
+                    TypeKind::Tuple(TupleDef { items })
+                }
+            }
+
+            Token::Punct("[") => {
+                let elem_ty = self.ty()?;
+                self.expect(";")?;
+                let len = self.usize()?;
+                self.expect("]")?;
+                TypeKind::Array(ArrayDef { elem_ty, len })
+            }
+
+            Token::Punct("*") => {
+                self.expect("@")?;
+                match self.bump() {
+                    Token::Ident(loc) if loc == "m" => TypeKind::MPtr(self.ty()?),
+                    Token::Ident(loc) if loc == "s" => TypeKind::SPtr(self.ty()?),
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.unexpected("`m` or `s`"));
+                    }
+                }
+            }
+
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected("a type"));
+            }
+        };
+
+        Ok(self.intern_ty(kind))
+    }
+
+    fn intern_ty(&self, kind: TypeKind) -> TypeId {
+        self.db.mir_intern_type(Rc::new(Type::new(kind, None)))
+    }
+
+    fn usize(&mut self) -> Result<usize, ParseError> {
+        match self.bump() {
+            Token::Int(int) => match int.to_usize() {
+                Some(int) => Ok(int),
+                None => {
+                    self.pos -= 1;
+                    Err(self.error("expected a length"))
+                }
+            },
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected("a length"))
+            }
+        }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn peek_nth(&self, n: usize) -> &Token {
+        let pos = (self.pos + n).min(self.tokens.len() - 1);
+        &self.tokens[pos].0
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn bump(&mut self) -> Token {
+        let token = self.peek().clone();
+        if token != Token::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Token::Punct(p) if *p == punct) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), ParseError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{punct}`")))
+        }
+    }
+
+    /// Expects a `>`, splitting a `>>` that closes nested types.
+    fn expect_close_angle(&mut self) -> Result<(), ParseError> {
+        if self.peek() == &Token::Punct(">>") {
+            self.tokens[self.pos].0 = Token::Punct(">");
+            Ok(())
+        } else {
+            self.expect(">")
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Token::Ident(ident) if ident == keyword) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("`{keyword}`")))
+        }
+    }
+
+    fn expect_newline(&mut self) -> Result<(), ParseError> {
+        match self.peek() {
+            Token::Newline => {
+                self.bump();
+                Ok(())
+            }
+            Token::Eof => Ok(()),
+            _ => Err(self.unexpected("end of line")),
+        }
+    }
+
+    fn skip_newlines(&mut self) {
+        while self.peek() == &Token::Newline {
+            self.bump();
+        }
+    }
+
+    fn value_name(&mut self) -> Result<SmolStr, ParseError> {
+        match self.peek_value_name() {
+            Some(name) => {
+                self.bump();
+                Ok(name)
+            }
+            None => Err(self.unexpected("a value name")),
+        }
+    }
+
+    /// Returns the next token if it's a value name, e.g. `_0`.
+    fn peek_value_name(&self) -> Option<SmolStr> {
+        match self.peek() {
+            Token::Ident(ident) if is_numbered(ident, "_") => Some(ident.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the next token if it's a block name, e.g. `BB0`.
+    fn peek_block_name(&self) -> Option<SmolStr> {
+        match self.peek() {
+            Token::Ident(ident) if is_numbered(ident, "BB") => Some(ident.clone()),
+            _ => None,
+        }
+    }
+
+    fn peek_bin_op(&self) -> Option<BinOp> {
+        let op = match self.peek() {
+            Token::Punct(punct) => match *punct {
+                "+" => BinOp::Add,
+                "-" => BinOp::Sub,
+                "*" => BinOp::Mul,
+                "/" => BinOp::Div,
+                "%" => BinOp::Mod,
+                "**" => BinOp::Pow,
+                "<<" => BinOp::Shl,
+                ">>" => BinOp::Shr,
+                "|" => BinOp::BitOr,
+                "^" => BinOp::BitXor,
+                "&" => BinOp::BitAnd,
+                "==" => BinOp::Eq,
+                "!=" => BinOp::Ne,
+                ">=" => BinOp::Ge,
+                ">" => BinOp::Gt,
+                "<=" => BinOp::Le,
+                "<" => BinOp::Lt,
+                _ => return None,
+            },
+            Token::Ident(ident) if ident == "and" => BinOp::LogicalAnd,
+            Token::Ident(ident) if ident == "or" => BinOp::LogicalOr,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line(),
+            message: message.into(),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        self.error(format!("expected {expected}, found {}", self.peek()))
+    }
+}
+
+/// Returns `true` if `ident` is `prefix` followed by a number.
+fn is_numbered(ident: &str, prefix: &str) -> bool {
+    match ident.strip_prefix(prefix) {
+        Some(num) => !num.is_empty() && num.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+fn primitive_ty(name: &str) -> Option<TypeKind> {
+    let kind = match name {
+        "i8" => TypeKind::I8,
+        "i16" => TypeKind::I16,
+        "i32" => TypeKind::I32,
+        "i64" => TypeKind::I64,
+        "i128" => TypeKind::I128,
+        "i256" => TypeKind::I256,
+        "u8" => TypeKind::U8,
+        "u16" => TypeKind::U16,
+        "u32" => TypeKind::U32,
+        "u64" => TypeKind::U64,
+        "u128" => TypeKind::U128,
+        "u256" => TypeKind::U256,
+        "bool" => TypeKind::Bool,
+        "address" => TypeKind::Address,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{db::NewDb, text::print_func_body};
+
+    fn parse(db: &NewDb, src: &str) -> Result<FunctionBody, ParseError> {
+        parse_func_body(db, FunctionId(0), src)
+    }
+
+    #[test]
+    fn round_trip() {
+        let db = NewDb::default();
+        let src = "fn(_0: u256, _1: bool) {
+BB0:
+    let _2: u8
+    branch _1 then: BB1 else: BB2
+BB1:
+    _3: u256 = _0 + 1_u256
+    _2: u8 = _3 as u8
+    jump BB2
+BB2:
+    _4: u256 = phi [_0: BB0, _3: BB1]
+    _5: bool = not _1
+    return _4
+}
+";
+        let body = parse(&db, src).unwrap();
+        assert_eq!(print_func_body(&db, &body), src);
+    }
+
+    #[test]
+    fn undefined_value() {
+        let db = NewDb::default();
+        let src = "fn() {
+BB0:
+    return _0
+}
+";
+        let err = parse(&db, src).unwrap_err();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.message, "`_0` is never defined");
+    }
+
+    #[test]
+    fn temporary_assigned_twice() {
+        let db = NewDb::default();
+        let src = "fn() {
+BB0:
+    _0: u256 = bind 1_u256
+    _0: u256 = bind 2_u256
+    return _0
+}
+";
+        let err = parse(&db, src).unwrap_err();
+        assert_eq!(err.line, 4);
+        assert_eq!(err.message, "`_0` is assigned twice");
+    }
+}
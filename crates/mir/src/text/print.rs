@@ -0,0 +1,99 @@
+use std::fmt::{self, Write};
+
+use fxhash::FxHashMap;
+
+use crate::{
+    db::MirDb,
+    ir::{BasicBlockId, FunctionBody, TypeId, Value, ValueId},
+    pretty_print::{print_inst, OperandPrinter, PrettyPrint},
+};
+
+/// Prints `body` in the textual MIR format.
+pub fn print_func_body(db: &dyn MirDb, body: &FunctionBody) -> String {
+    let mut printer = Printer::new(db, body);
+    let mut s = String::new();
+    printer.print_body(&mut s).unwrap();
+    s
+}
+
+struct Printer<'a> {
+    db: &'a dyn MirDb,
+    body: &'a FunctionBody,
+    values: FxHashMap<ValueId, usize>,
+    blocks: FxHashMap<BasicBlockId, usize>,
+}
+
+impl<'a> Printer<'a> {
+    fn new(db: &'a dyn MirDb, body: &'a FunctionBody) -> Self {
+        let blocks = body
+            .order
+            .iter_block()
+            .enumerate()
+            .map(|(idx, block)| (block, idx))
+            .collect();
+        Self {
+            db,
+            body,
+            values: FxHashMap::default(),
+            blocks,
+        }
+    }
+
+    fn print_body<W: Write>(&mut self, w: &mut W) -> fmt::Result {
+        let body = self.body;
+        write!(w, "fn(")?;
+        for (i, arg) in body.store.func_args().enumerate() {
+            if i != 0 {
+                write!(w, ", ")?;
+            }
+            self.print_value(arg, w)?;
+            write!(w, ": ")?;
+            self.print_ty(body.store.value_ty(arg), w)?;
+        }
+        writeln!(w, ") {{")?;
+
+        for block in body.order.iter_block() {
+            self.print_block(block, w)?;
+            writeln!(w, ":")?;
+            for inst in body.order.iter_inst(block) {
+                write!(w, "    ")?;
+                print_inst(self.db, &body.store, inst, self, w)?;
+                writeln!(w)?;
+            }
+        }
+        writeln!(w, "}}")
+    }
+
+    fn print_ty<W: Write>(&self, ty: TypeId, w: &mut W) -> fmt::Result {
+        ty.print(self.db, w)
+    }
+}
+
+impl OperandPrinter for Printer<'_> {
+    /// Prints a value. Temporaries and locals are numbered in the order they
+    /// first appear.
+    fn print_value<W: Write>(&mut self, value: ValueId, w: &mut W) -> fmt::Result {
+        match self.body.store.value_data(value) {
+            Value::Temporary { .. } | Value::Local(_) => {
+                let next = self.values.len();
+                let idx = *self.values.entry(value).or_insert(next);
+                write!(w, "_{idx}")
+            }
+            Value::Immediate { imm, ty } => {
+                if ty.is_bool(self.db) {
+                    write!(w, "{}", *imm != 0.into())
+                } else {
+                    write!(w, "{imm}_")?;
+                    self.print_ty(*ty, w)
+                }
+            }
+            Value::Constant { .. } | Value::Unit { .. } => {
+                value.pretty_print(self.db, &self.body.store, w)
+            }
+        }
+    }
+
+    fn print_block<W: Write>(&mut self, block: BasicBlockId, w: &mut W) -> fmt::Result {
+        write!(w, "BB{}", self.blocks[&block])
+    }
+}
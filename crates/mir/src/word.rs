@@ -0,0 +1,77 @@
+//! Helpers for the 256 bit words values are represented as at runtime.
+
+use num_bigint::{BigInt, Sign};
+
+use crate::{db::MirDb, ir::TypeId};
+
+/// The slot size used to compute the size of primitive types, which doesn't
+/// depend on it.
+const SLOT_SIZE: usize = 32;
+
+/// Returns the word of `value` if it fits in `ty`.
+pub(crate) fn checked_result(db: &dyn MirDb, value: BigInt, ty: TypeId) -> Option<BigInt> {
+    if !ty.is_integral(db) {
+        return None;
+    }
+
+    let bits = ty.size_of(db, SLOT_SIZE) * 8;
+    let (min, max) = if ty.is_signed(db) {
+        (
+            -(BigInt::from(1) << (bits - 1)),
+            BigInt::from(1) << (bits - 1),
+        )
+    } else {
+        (BigInt::from(0), BigInt::from(1) << bits)
+    };
+
+    (min <= value && value < max).then(|| to_word(value))
+}
+
+/// Returns `word` cleaned up to `ty` the way codegen does, i.e. sign extended
+/// for signed types and masked otherwise.
+pub(crate) fn clean_word(db: &dyn MirDb, word: &BigInt, ty: TypeId) -> Option<BigInt> {
+    if !ty.is_primitive(db) || ty.is_unit(db) {
+        return None;
+    }
+
+    let bits = ty.size_of(db, SLOT_SIZE) * 8;
+    let word = to_word(word.clone());
+    if bits >= 256 {
+        return Some(word);
+    }
+
+    let low = word & ((BigInt::from(1) << bits) - 1);
+    if ty.is_signed(db) && low.bit(bits as u64 - 1) {
+        Some(low | (word_max() ^ ((BigInt::from(1) << bits) - 1)))
+    } else {
+        Some(low)
+    }
+}
+
+/// Wraps `value` into a 256 bit word.
+pub(crate) fn to_word(value: BigInt) -> BigInt {
+    let modulus = BigInt::from(1) << 256;
+    let word = value % &modulus;
+    if word.sign() == Sign::Minus {
+        word + modulus
+    } else {
+        word
+    }
+}
+
+/// Interprets a word as a two's complement signed integer.
+pub(crate) fn to_signed(word: &BigInt) -> BigInt {
+    if word.bit(255) {
+        word - (BigInt::from(1) << 256)
+    } else {
+        word.clone()
+    }
+}
+
+pub(crate) fn word_max() -> BigInt {
+    (BigInt::from(1) << 256) - 1
+}
+
+pub(crate) fn bool_word(b: bool) -> BigInt {
+    BigInt::from(b as u8)
+}
@@ -0,0 +1,215 @@
+use fe_analyzer::namespace::items::ModuleId;
+use fe_mir::{
+    db::{MirDb, NewDb},
+    interpret::{Env, Interpreter, Trap},
+    ir::FunctionId,
+};
+use num_bigint::{BigInt, Sign};
+
+const FIXTURE: &str = "interpret/interpret.fe";
+
+fn lower_fixture(db: &mut NewDb) -> ModuleId {
+    let module = ModuleId::new_standalone(db, "interpret.fe", test_files::fixture(FIXTURE));
+    if !module.diagnostics(db).is_empty() {
+        panic!("analysis of {FIXTURE} failed")
+    }
+    module
+}
+
+fn func(db: &NewDb, module: ModuleId, name: &str) -> FunctionId {
+    db.mir_lower_module_all_functions(module)
+        .iter()
+        .copied()
+        .find(|func| func.debug_name(db) == name)
+        .unwrap_or_else(|| panic!("no function named `{name}`"))
+}
+
+/// Returns the two's complement word of `value`.
+fn word(value: i64) -> BigInt {
+    let word = BigInt::from(value);
+    if word.sign() == Sign::Minus {
+        word + (BigInt::from(1) << 256)
+    } else {
+        word
+    }
+}
+
+fn words(args: &[i64]) -> Vec<BigInt> {
+    args.iter().map(|arg| word(*arg)).collect()
+}
+
+fn call(db: &NewDb, module: ModuleId, name: &str, args: &[i64]) -> Result<Option<BigInt>, Trap> {
+    Interpreter::new(db, Env::default()).call(func(db, module, name), &words(args))
+}
+
+#[test]
+fn interpret_tests() {
+    let mut db = NewDb::default();
+    let module = lower_fixture(&mut db);
+
+    for test in module.tests(&db) {
+        let name = test.name(&db);
+        let func = db.mir_lowered_func_signature(test);
+        let args = vec![BigInt::from(0); func.signature(&db).params.len()];
+        let result = Interpreter::new(&db, Env::default()).call(func, &args);
+        if name == "test_fails" {
+            assert_eq!(result, Err(Trap::Panic(1.into())));
+        } else if let Err(trap) = result {
+            panic!("`{name}` {trap}")
+        }
+    }
+}
+
+#[test]
+fn interpret_return_values() {
+    let mut db = NewDb::default();
+    let module = lower_fixture(&mut db);
+
+    assert_eq!(call(&db, module, "fib", &[20]), Ok(Some(6765.into())));
+    assert_eq!(
+        call(&db, module, "add_u8", &[200, 55]),
+        Ok(Some(255.into()))
+    );
+    assert_eq!(call(&db, module, "get", &[1]), Ok(Some(2.into())));
+    assert_eq!(call(&db, module, "check", &[3]), Ok(None));
+
+    // Signed values are passed and returned as two's complement words.
+    assert_eq!(call(&db, module, "sub_i8", &[0, 1]), Ok(Some(word(-1))));
+}
+
+#[test]
+fn interpret_traps() {
+    let mut db = NewDb::default();
+    let module = lower_fixture(&mut db);
+
+    assert_eq!(
+        call(&db, module, "add_u8", &[200, 56]),
+        Err(Trap::Panic(0x11.into()))
+    );
+    assert_eq!(
+        call(&db, module, "sub_i8", &[-128, 1]),
+        Err(Trap::Panic(0x11.into()))
+    );
+    assert_eq!(
+        call(&db, module, "div", &[1, 0]),
+        Err(Trap::Panic(0x12.into()))
+    );
+    assert_eq!(
+        call(&db, module, "get", &[3]),
+        Err(Trap::Panic(0x32.into()))
+    );
+    assert_eq!(
+        call(&db, module, "check", &[10]),
+        Err(Trap::Error("too large".into()))
+    );
+
+    let mut interpreter = Interpreter::new(&db, Env::default());
+    interpreter.set_step_limit(1000);
+    assert_eq!(
+        interpreter.call(func(&db, module, "spin"), &[]),
+        Err(Trap::StepLimitExceeded)
+    );
+}
+
+#[test]
+fn interpret_storage() {
+    let mut db = NewDb::default();
+    let module = lower_fixture(&mut db);
+    let deposit = func(&db, module, "Bank::deposit");
+    let balance_of = func(&db, module, "Bank::balance_of");
+    let withdraw = func(&db, module, "Bank::withdraw");
+
+    // The contract itself is passed as a zero word, like the storage pointer
+    // it is lowered to.
+    let mut interpreter = Interpreter::new(&db, Env::default());
+    assert_eq!(
+        interpreter.call(deposit, &words(&[0, 1, 100])),
+        Ok(Some(1.into()))
+    );
+    assert_eq!(
+        interpreter.call(deposit, &words(&[0, 2, 50])),
+        Ok(Some(2.into()))
+    );
+    assert_eq!(interpreter.call(withdraw, &words(&[0, 1, 30])), Ok(None));
+    assert_eq!(
+        interpreter.call(balance_of, &words(&[0, 1])),
+        Ok(Some(70.into()))
+    );
+    assert_eq!(
+        interpreter.call(balance_of, &words(&[0, 2])),
+        Ok(Some(50.into()))
+    );
+    assert_eq!(interpreter.storage_slot(&0.into()), 2.into());
+    assert_eq!(
+        interpreter.call(withdraw, &words(&[0, 2, 51])),
+        Err(Trap::Revert(Some("Overdraft".into())))
+    );
+}
+
+/// Calls the functions of the `Functions` contract both interpreted and
+/// compiled, which must return the same values and revert with the same data.
+#[cfg(feature = "solc-backend")]
+#[test]
+fn interpret_matches_compiled() {
+    use fe_compiler_test_utils::{
+        deploy_contract, encode_error_reason, encode_revert, int_token, uint_token,
+        uint_token_from_dec_str, validate_return, validate_revert, with_executor,
+    };
+
+    let mut db = NewDb::default();
+    let module = lower_fixture(&mut db);
+
+    // The name of each function, whether its arguments are signed, and the
+    // arguments.
+    let calls: &[(&str, bool, &[i64])] = &[
+        ("add", false, &[200, 55]),
+        ("add", false, &[200, 56]),
+        ("sub", true, &[0, 1]),
+        ("sub", true, &[-128, 1]),
+        ("quotient", false, &[7, 2]),
+        ("quotient", false, &[1, 0]),
+        ("element", false, &[1]),
+        ("element", false, &[3]),
+        ("fibonacci", false, &[20]),
+        ("checked", false, &[3]),
+        ("checked", false, &[10]),
+    ];
+
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, FIXTURE, "Functions", &[]);
+        for (name, signed, args) in calls {
+            let tokens: Vec<_> = args
+                .iter()
+                .map(|arg| {
+                    if *signed {
+                        int_token(*arg)
+                    } else {
+                        uint_token(*arg as u64)
+                    }
+                })
+                .collect();
+            let capture = harness.capture_call(&mut executor, name, &tokens);
+
+            match call(&db, module, &format!("Functions::{name}"), args) {
+                Ok(value) => {
+                    let mut data = vec![];
+                    if let Some(value) = value {
+                        let (_, bytes) = value.to_bytes_be();
+                        data.resize(32 - bytes.len(), 0);
+                        data.extend(bytes);
+                    }
+                    validate_return(capture, &data)
+                }
+                Err(Trap::Panic(code)) => validate_revert(
+                    capture,
+                    &encode_revert(
+                        "Panic(uint256)",
+                        &[uint_token_from_dec_str(&code.to_string())],
+                    ),
+                ),
+                Err(Trap::Error(reason)) => validate_revert(capture, &encode_error_reason(&reason)),
+                Err(trap) => panic!("`{name}{args:?}` {trap}"),
+            }
+        }
+    });
+}
@@ -0,0 +1,30 @@
+use fe_analyzer::namespace::items::ModuleId;
+use fe_common::diagnostics::diagnostics_string;
+use fe_mir::{db::NewDb, lints::module_lints};
+
+#[test]
+fn state_write_after_external_call() {
+    let mut db = NewDb::default();
+    let module = ModuleId::new_standalone(
+        &mut db,
+        "security.fe",
+        test_files::fixture("lints/security.fe"),
+    );
+    assert!(module.diagnostics(&db).is_empty());
+
+    let warnings = diagnostics_string(&db, &module_lints(&db, module));
+    // `withdraw` writes after `send_value` and `pay` calls a function that
+    // writes after calling `Token`. `withdraw_safely` writes first and
+    // `pay_allowed` turns the lint off.
+    assert_eq!(
+        warnings
+            .matches("contract state is written after an external call")
+            .count(),
+        2,
+        "{warnings}"
+    );
+    assert!(warnings.contains("self.balances[ctx.msg_sender()] = 0"));
+    assert!(warnings.contains("self.clear(ctx)"));
+    assert!(warnings.contains("warning[E0066]: contract state is written after an external call"));
+    assert!(warnings.contains("`#allow(state_write_after_external_call)` in front of the item"));
+}
@@ -6,6 +6,7 @@ use fe_common::{db::Upcast, db::UpcastMut, files::Utf8Path};
 use fe_mir::{
     analysis::{ControlFlowGraph, DomTree, LoopTree, PostDomTree},
     db::{MirDb, NewDb},
+    optimize::optimize_func_body,
 };
 
 macro_rules! test_lowering {
@@ -25,6 +26,13 @@ macro_rules! test_lowering {
             for func in db.mir_lower_module_all_functions(module).iter() {
                 let body = func.body(&db);
                 ControlFlowGraph::compute(&body);
+
+                let mut body = body.as_ref().clone();
+                optimize_func_body(&db, &mut body);
+                let cfg = ControlFlowGraph::compute(&body);
+                let domtree = DomTree::compute(&cfg);
+                LoopTree::compute(&cfg, &domtree);
+                PostDomTree::compute(&body);
             }
         }
     };
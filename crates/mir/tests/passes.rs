@@ -0,0 +1,122 @@
+use fe_analyzer::namespace::items::ModuleId;
+use fe_mir::{
+    db::{MirDb, NewDb},
+    ir::FunctionId,
+    optimize::{
+        ConstFold, CopyProp, DeadCodeElim, Inliner, Licm, Mem2Reg, Pass, SimplifyCfg,
+        StorageForward,
+    },
+    text::{parse_func_body, print_func_body},
+};
+
+/// The expected output of a pass is written as comments after a `// CHECK:`
+/// line at the end of the fixture, and the parser skips it as a comment.
+fn expected_output(src: &str) -> String {
+    let (_, check) = src
+        .split_once("// CHECK:\n")
+        .expect("fixture has no `// CHECK:` line");
+    check
+        .lines()
+        .map(|line| {
+            line.strip_prefix("// ")
+                .or_else(|| line.strip_prefix("//"))
+                .unwrap()
+        })
+        .fold(String::new(), |mut out, line| {
+            out.push_str(line);
+            out.push('\n');
+            out
+        })
+}
+
+macro_rules! test_pass {
+    ($name:ident, $pass:expr, $path:expr) => {
+        #[test]
+        fn $name() {
+            let db = NewDb::default();
+            let src = test_files::fixture($path);
+
+            let mut body = parse_func_body(&db, FunctionId(0), src)
+                .unwrap_or_else(|err| panic!("failed to parse {}: {err}", $path));
+            let mut pass = $pass;
+            pass.run(&db, &mut body);
+
+            let expected = expected_output(src);
+            assert_eq!(print_func_body(&db, &body), expected);
+
+            // The expected output must be in the canonical format as well.
+            let reparsed = parse_func_body(&db, FunctionId(0), &expected)
+                .unwrap_or_else(|err| panic!("failed to parse the output of {}: {err}", $path));
+            assert_eq!(print_func_body(&db, &reparsed), expected);
+        }
+    };
+}
+
+test_pass! { const_fold, ConstFold, "mir_passes/const_fold.mir"}
+test_pass! { copy_prop, CopyProp, "mir_passes/copy_prop.mir"}
+test_pass! { dce, DeadCodeElim, "mir_passes/dce.mir"}
+test_pass! { simplify_cfg, SimplifyCfg, "mir_passes/simplify_cfg.mir"}
+test_pass! { mem2reg, Mem2Reg, "mir_passes/mem2reg.mir"}
+test_pass! { licm, Licm, "mir_passes/licm.mir"}
+test_pass! { storage_forward, StorageForward::new(), "mir_passes/storage_forward.mir"}
+
+/// Checks `output` against the `// CHECK: <text>` and `// CHECK-NOT: <text>`
+/// lines of a fixture. The `CHECK` texts must appear in `output` in order, and
+/// a `CHECK-NOT` text must not appear between the `CHECK` texts around it.
+fn check_directives(output: &str, src: &str) {
+    let mut pos = 0;
+    let mut forbidden: Vec<&str> = vec![];
+    let mut check_forbidden = |forbidden: &mut Vec<&str>, region: &str| {
+        for text in forbidden.drain(..) {
+            assert!(
+                !region.contains(text),
+                "found `{text}` where it's not expected in:\n{output}"
+            );
+        }
+    };
+
+    for line in src.lines() {
+        if let Some(text) = line.strip_prefix("// CHECK-NOT: ") {
+            forbidden.push(text);
+        } else if let Some(text) = line.strip_prefix("// CHECK: ") {
+            let found = output[pos..]
+                .find(text)
+                .unwrap_or_else(|| panic!("`{text}` not found in:\n{output}"));
+            check_forbidden(&mut forbidden, &output[pos..pos + found]);
+            pos += found + text.len();
+        }
+    }
+    check_forbidden(&mut forbidden, &output[pos..]);
+}
+
+/// Runs a pass that needs the other functions of a module, such as the
+/// inliner, on the function `caller` of a Fe fixture.
+macro_rules! test_module_pass {
+    ($name:ident, $pass:expr, $path:expr) => {
+        #[test]
+        fn $name() {
+            let mut db = NewDb::default();
+            let src = test_files::fixture($path);
+            let module = ModuleId::new_standalone(&mut db, $path, src);
+            assert!(
+                module.diagnostics(&db).is_empty(),
+                "failed to analyze {}",
+                $path
+            );
+
+            let func = db
+                .mir_lower_module_all_functions(module)
+                .iter()
+                .copied()
+                .find(|func| func.debug_name(&db) == "caller")
+                .unwrap_or_else(|| panic!("{} has no function `caller`", $path));
+            let mut body = func.body(&db).as_ref().clone();
+            let mut pass = $pass;
+            pass.run(&db, &mut body);
+
+            check_directives(&print_func_body(&db, &body), src);
+        }
+    };
+}
+
+test_module_pass! { inline, Inliner::new(), "mir_passes/inline.fe"}
@@ -25,10 +25,18 @@ pub enum ModuleStmt {
     Trait(Node<Trait>),
     Impl(Node<Impl>),
     Function(Node<Function>),
-    Attribute(Node<SmolStr>),
+    Attribute(Node<Attribute>),
     ParseError(Span),
 }
 
+/// An attribute, e.g. `#indexed` or `#allow(unused_variables, dead_code)`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Attribute {
+    pub name: Node<SmolStr>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<Node<SmolStr>>,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Pragma {
     pub version_requirement: Node<SmolStr>,
@@ -193,7 +201,7 @@ impl Spanned for GenericParameter {
 pub struct Field {
     pub is_pub: bool,
     pub is_const: bool,
-    pub attributes: Vec<Node<SmolStr>>,
+    pub attributes: Vec<Node<Attribute>>,
     pub name: Node<SmolStr>,
     pub typ: Node<TypeDesc>,
     pub value: Option<Node<Expr>>,
@@ -251,6 +259,10 @@ pub struct FunctionSignature {
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Function {
+    /// Attributes of a function defined in a struct, contract, enum or impl
+    /// block. Attributes of a module level function are `ModuleStmt::Attribute`s.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<Node<Attribute>>,
     pub sig: Node<FunctionSignature>,
     pub body: Vec<Node<FuncStmt>>,
 }
@@ -631,6 +643,16 @@ impl fmt::Display for ModuleStmt {
     }
 }
 
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name.kind)?;
+        if !self.args.is_empty() {
+            write!(f, "({})", node_comma_joined(&self.args))?;
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Display for Pragma {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "pragma {}", self.version_requirement.kind)
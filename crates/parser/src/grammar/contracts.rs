@@ -1,5 +1,5 @@
 use super::functions::parse_fn_def;
-use super::types::{parse_field, parse_opt_qualifier};
+use super::types::{parse_attributes, parse_field, parse_opt_qualifier, reject_attributes};
 
 use crate::ast::{Contract, ContractStmt};
 use crate::node::{Node, Span};
@@ -33,6 +33,7 @@ pub fn parse_contract_def(
 
     loop {
         par.eat_newlines();
+        let attributes = parse_attributes(par)?;
         let mut pub_qual = parse_opt_qualifier(par, TokenKind::Pub);
         let const_qual = parse_opt_qualifier(par, TokenKind::Const);
         if pub_qual.is_none() && const_qual.is_some() && par.peek() == Some(TokenKind::Pub) {
@@ -45,7 +46,7 @@ pub fn parse_contract_def(
 
         match par.peek_or_err()? {
             TokenKind::Name => {
-                let field = parse_field(par, vec![], pub_qual, const_qual)?;
+                let field = parse_field(par, attributes, pub_qual, const_qual)?;
                 if !defs.is_empty() {
                     par.error(
                         field.span,
@@ -61,9 +62,12 @@ pub fn parse_contract_def(
                         "`const` qualifier can't be used with function definitions",
                     );
                 }
-                defs.push(ContractStmt::Function(parse_fn_def(par, pub_qual)?));
+                defs.push(ContractStmt::Function(parse_fn_def(
+                    par, attributes, pub_qual,
+                )?));
             }
             TokenKind::BraceClose => {
+                reject_attributes(par, &attributes);
                 span += par.next()?.span;
                 break;
             }
@@ -2,8 +2,8 @@ use super::expressions::parse_expr;
 use super::types::parse_type_desc;
 
 use crate::ast::{
-    BinOperator, Expr, FuncStmt, Function, FunctionArg, FunctionSignature, GenericParameter,
-    LiteralPattern, MatchArm, Path, Pattern, TypeDesc, VarDeclTarget,
+    Attribute, BinOperator, Expr, FuncStmt, Function, FunctionArg, FunctionSignature,
+    GenericParameter, LiteralPattern, MatchArm, Path, Pattern, TypeDesc, VarDeclTarget,
 };
 use crate::node::{Node, Span};
 use crate::{Label, ParseFailed, ParseResult, Parser, TokenKind};
@@ -101,9 +101,14 @@ pub fn parse_fn_sig(
     ))
 }
 
-/// Parse a function definition. The optional `pub` qualifier must be parsed by
-/// the caller, and passed in. Next token must be `unsafe` or `fn`.
-pub fn parse_fn_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<Node<Function>> {
+/// Parse a function definition. The attributes and optional `pub` qualifier
+/// must be parsed by the caller, and passed in. Next token must be `unsafe` or
+/// `fn`.
+pub fn parse_fn_def(
+    par: &mut Parser,
+    attributes: Vec<Node<Attribute>>,
+    pub_qual: Option<Span>,
+) -> ParseResult<Node<Function>> {
     let sig = parse_fn_sig(par, pub_qual)?;
 
     // TODO: allow multi-line return type? `fn f()\n ->\n u8`
@@ -113,6 +118,7 @@ pub fn parse_fn_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<Nod
 
     Ok(Node::new(
         Function {
+            attributes,
             sig: sig.clone(),
             body,
         },
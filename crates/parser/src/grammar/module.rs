@@ -1,8 +1,8 @@
 use super::expressions::parse_expr;
 use super::functions::parse_fn_def;
 use super::types::{
-    parse_impl_def, parse_path_tail, parse_struct_def, parse_trait_def, parse_type_alias,
-    parse_type_desc,
+    parse_attribute, parse_impl_def, parse_path_tail, parse_struct_def, parse_trait_def,
+    parse_type_alias, parse_type_desc,
 };
 use super::{contracts::parse_contract_def, types::parse_enum_def};
 use crate::ast::{ConstantDecl, Module, ModuleStmt, Pragma, Use, UseTree};
@@ -52,7 +52,7 @@ pub fn parse_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
             let pub_span = par.next()?.span;
             match par.peek_or_err()? {
                 TokenKind::Fn | TokenKind::Unsafe => {
-                    ModuleStmt::Function(parse_fn_def(par, Some(pub_span))?)
+                    ModuleStmt::Function(parse_fn_def(par, vec![], Some(pub_span))?)
                 }
                 TokenKind::Struct => ModuleStmt::Struct(parse_struct_def(par, Some(pub_span))?),
                 TokenKind::Enum => ModuleStmt::Enum(parse_enum_def(par, Some(pub_span))?),
@@ -73,12 +73,10 @@ pub fn parse_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
                 }
             }
         }
-        TokenKind::Fn | TokenKind::Unsafe => ModuleStmt::Function(parse_fn_def(par, None)?),
+        TokenKind::Fn | TokenKind::Unsafe => ModuleStmt::Function(parse_fn_def(par, vec![], None)?),
         TokenKind::Hash => {
             let attr = par.expect(TokenKind::Hash, "expected `#`")?;
-            let attr_name = par.expect_with_notes(TokenKind::Name, "failed to parse attribute definition", |_|
-                vec!["Note: an attribute name must start with a letter or underscore, and contain letters, numbers, or underscores".into()])?;
-            ModuleStmt::Attribute(Node::new(attr_name.text.into(), attr.span + attr_name.span))
+            ModuleStmt::Attribute(parse_attribute(par, attr)?)
         }
         _ => {
             let tok = par.next()?;
@@ -1,5 +1,6 @@
 use crate::ast::{
-    self, Enum, Field, GenericArg, Impl, Path, Trait, TypeAlias, TypeDesc, Variant, VariantKind,
+    self, Attribute, Enum, Field, GenericArg, Impl, Path, Trait, TypeAlias, TypeDesc, Variant,
+    VariantKind,
 };
 use crate::grammar::expressions::parse_expr;
 use crate::grammar::functions::{parse_fn_def, parse_fn_sig};
@@ -31,16 +32,7 @@ pub fn parse_struct_def(
     loop {
         par.eat_newlines();
 
-        let attributes = if let Some(attr) = par.optional(TokenKind::Hash) {
-            let attr_name = par.expect_with_notes(TokenKind::Name, "failed to parse attribute definition", |_|
-                vec!["Note: an attribute name must start with a letter or underscore, and contain letters, numbers, or underscores".into()])?;
-            // This hints to a future where we would support multiple attributes per field. For now we don't need it.
-            vec![Node::new(attr_name.text.into(), attr.span + attr_name.span)]
-        } else {
-            vec![]
-        };
-
-        par.eat_newlines();
+        let attributes = parse_attributes(par)?;
 
         let pub_qual = par.optional(TokenKind::Pub).map(|tok| tok.span);
         match par.peek_or_err()? {
@@ -55,9 +47,10 @@ pub fn parse_struct_def(
                 fields.push(field);
             }
             TokenKind::Fn | TokenKind::Unsafe => {
-                functions.push(parse_fn_def(par, pub_qual)?);
+                functions.push(parse_fn_def(par, attributes, pub_qual)?);
             }
             TokenKind::BraceClose if pub_qual.is_none() => {
+                reject_attributes(par, &attributes);
                 span += par.next()?.span;
                 break;
             }
@@ -98,8 +91,10 @@ pub fn parse_enum_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<N
     par.enter_block(span, "enum definition")?;
     loop {
         par.eat_newlines();
+        let attributes = parse_attributes(par)?;
         match par.peek_or_err()? {
             TokenKind::Name => {
+                reject_attributes(par, &attributes);
                 let variant = parse_variant(par)?;
                 if !functions.is_empty() {
                     par.error(
@@ -111,14 +106,14 @@ pub fn parse_enum_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<N
             }
 
             TokenKind::Fn | TokenKind::Unsafe => {
-                functions.push(parse_fn_def(par, None)?);
+                functions.push(parse_fn_def(par, attributes, None)?);
             }
 
             TokenKind::Pub => {
                 let pub_qual = Some(par.next().unwrap().span);
                 match par.peek() {
                     Some(TokenKind::Fn | TokenKind::Unsafe) => {
-                        functions.push(parse_fn_def(par, pub_qual)?);
+                        functions.push(parse_fn_def(par, attributes, pub_qual)?);
                     }
 
                     _ => {
@@ -131,6 +126,7 @@ pub fn parse_enum_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<N
             }
 
             TokenKind::BraceClose => {
+                reject_attributes(par, &attributes);
                 span += par.next()?.span;
                 break;
             }
@@ -235,11 +231,13 @@ pub fn parse_impl_def(par: &mut Parser) -> ParseResult<Node<Impl>> {
 
     loop {
         par.eat_newlines();
+        let attributes = parse_attributes(par)?;
         match par.peek_or_err()? {
             TokenKind::Fn => {
-                functions.push(parse_fn_def(par, None)?);
+                functions.push(parse_fn_def(par, attributes, None)?);
             }
             TokenKind::BraceClose => {
+                reject_attributes(par, &attributes);
                 par.next()?;
                 break;
             }
@@ -290,7 +288,7 @@ pub fn parse_type_alias(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult
 /// `const` qualifiers must be parsed by the caller, and passed in.
 pub fn parse_field(
     par: &mut Parser,
-    attributes: Vec<Node<SmolStr>>,
+    attributes: Vec<Node<Attribute>>,
     pub_qual: Option<Span>,
     const_qual: Option<Span>,
 ) -> ParseResult<Node<Field>> {
@@ -405,6 +403,73 @@ pub fn parse_opt_qualifier(par: &mut Parser, tk: TokenKind) -> Option<Span> {
     }
 }
 
+/// Parse an attribute after its `#` (eg. `indexed` or
+/// `allow(unused_variables, dead_code)`).
+pub fn parse_attribute(par: &mut Parser, hash: Token) -> ParseResult<Node<Attribute>> {
+    let name = par.expect_with_notes(TokenKind::Name, "failed to parse attribute definition", |_|
+        vec!["Note: an attribute name must start with a letter or underscore, and contain letters, numbers, or underscores".into()])?;
+    let mut span = hash.span + name.span;
+
+    let mut args = vec![];
+    if par.peek() == Some(TokenKind::ParenOpen) {
+        par.next()?;
+        loop {
+            let arg = par.expect(TokenKind::Name, "failed to parse attribute arguments")?;
+            args.push(arg.into());
+            let tok = par.next()?;
+            match tok.kind {
+                TokenKind::Comma => {}
+                TokenKind::ParenClose => {
+                    span += tok.span;
+                    break;
+                }
+                _ => {
+                    par.unexpected_token_error(
+                        &tok,
+                        "failed to parse attribute arguments",
+                        vec!["Note: expected `,` or `)`".into()],
+                    );
+                    return Err(ParseFailed);
+                }
+            }
+        }
+    }
+
+    Ok(Node::new(
+        Attribute {
+            name: name.into(),
+            args,
+        },
+        span,
+    ))
+}
+
+/// Parse the attributes (eg. `#indexed`) of a field or function, each of which
+/// may be followed by newlines.
+pub fn parse_attributes(par: &mut Parser) -> ParseResult<Vec<Node<Attribute>>> {
+    let mut attributes = vec![];
+    while let Some(hash) = par.optional(TokenKind::Hash) {
+        attributes.push(parse_attribute(par, hash)?);
+        par.eat_newlines();
+    }
+    Ok(attributes)
+}
+
+/// Report attributes that precede something other than a field or function,
+/// eg. an enum variant or the closing brace of a block.
+pub fn reject_attributes(par: &mut Parser, attributes: &[Node<Attribute>]) {
+    for attr in attributes {
+        par.fancy_error(
+            "misplaced attribute",
+            vec![Label::primary(
+                attr.span,
+                "not followed by a field or function",
+            )],
+            vec!["Note: attributes can only be applied to fields and functions".into()],
+        );
+    }
+}
+
 /// Parse an angle-bracket-wrapped list of generic arguments (eg. the tail end
 /// of `Map<address, u256>`).
 /// # Panics
@@ -1,5 +1,5 @@
 pub use fe_common::diagnostics::Label;
-use fe_common::diagnostics::{Diagnostic, Severity};
+use fe_common::diagnostics::{Diagnostic, ErrorCode, Severity};
 use fe_common::files::SourceFileId;
 
 use crate::lexer::{Lexer, Token, TokenKind};
@@ -310,6 +310,7 @@ impl<'a> Parser<'a> {
     pub fn error<S: Into<String>>(&mut self, span: Span, message: S) {
         self.diagnostics.push(Diagnostic {
             severity: Severity::Error,
+            code: Some(ErrorCode::SyntaxError),
             message: message.into(),
             labels: vec![Label::primary(span, "")],
             notes: vec![],
@@ -326,6 +327,7 @@ impl<'a> Parser<'a> {
     ) {
         self.diagnostics.push(Diagnostic {
             severity: Severity::Error,
+            code: Some(ErrorCode::SyntaxError),
             message: message.into(),
             labels,
             notes,
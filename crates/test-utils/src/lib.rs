@@ -41,6 +41,14 @@ impl GasReporter {
         let description = format!("{function}({input:?})");
         self.add_record(&description, gas_used)
     }
+
+    pub fn total_gas_used(&self) -> u64 {
+        self.records
+            .borrow()
+            .iter()
+            .map(|record| record.gas_used)
+            .sum()
+    }
 }
 
 impl Display for GasReporter {
@@ -267,6 +275,16 @@ pub fn with_executor(test: &dyn Fn(Executor)) {
     with_executor_backend(backend, test)
 }
 
+/// Codegen options for contracts that run on the executor. The executor follows
+/// the London rules, so later opcodes must not be emitted; `prevrandao` is
+/// fine as it reuses the `difficulty` opcode.
+pub fn executor_codegen_options() -> driver::CodegenOptions {
+    driver::CodegenOptions {
+        evm_version: driver::EvmVersion::Paris,
+        ..Default::default()
+    }
+}
+
 #[allow(dead_code)]
 pub fn with_executor_backend(backend: Backend, test: &dyn Fn(Executor)) {
     let config = evm::Config::london();
@@ -335,7 +353,28 @@ pub fn deploy_contract(
     contract_name: &str,
     init_params: &[ethabi::Token],
 ) -> ContractHarness {
+    deploy_contract_with_codegen_options(
+        executor,
+        fixture,
+        contract_name,
+        init_params,
+        executor_codegen_options(),
+    )
+}
+
+#[allow(dead_code)]
+#[cfg(feature = "solc-backend")]
+pub fn deploy_contract_with_codegen_options(
+    executor: &mut Executor,
+    fixture: &str,
+    contract_name: &str,
+    init_params: &[ethabi::Token],
+    codegen_options: driver::CodegenOptions,
+) -> ContractHarness {
+    use driver::CodegenDb;
+
     let mut db = driver::Db::default();
+    db.set_codegen_options(codegen_options);
     let compiled_module = match driver::compile_single_file(
         &mut db,
         fixture,
@@ -364,6 +403,30 @@ pub fn deploy_contract(
     )
 }
 
+/// Runs `calls`, which returns the gas it used, against contracts compiled
+/// with the `baseline` options and then with the options `optimize` turns them
+/// into, and asserts that the optimization saves gas.
+#[allow(dead_code)]
+#[cfg(feature = "solc-backend")]
+pub fn assert_saves_gas(
+    description: &str,
+    baseline: driver::CodegenOptions,
+    optimize: impl Fn(&mut driver::CodegenOptions),
+    calls: &dyn Fn(&mut Executor, driver::CodegenOptions) -> u64,
+) {
+    let mut optimized = baseline.clone();
+    optimize(&mut optimized);
+
+    with_executor(&|mut executor| {
+        let before = calls(&mut executor, baseline.clone());
+        let after = calls(&mut executor, optimized.clone());
+        assert!(
+            after < before,
+            "{description} doesn't save gas: {before} used without it, {after} with it"
+        );
+    });
+}
+
 #[allow(dead_code)]
 #[cfg(feature = "solc-backend")]
 pub fn deploy_contract_from_ingot(
@@ -372,11 +435,13 @@ pub fn deploy_contract_from_ingot(
     contract_name: &str,
     init_params: &[ethabi::Token],
 ) -> ContractHarness {
+    use driver::CodegenDb;
     use fe_common::utils::files::BuildFiles;
 
     let files = test_files::fixture_dir_files("ingots");
     let build_files = BuildFiles::load_static(files, path).expect("failed to load build files");
     let mut db = driver::Db::default();
+    db.set_codegen_options(executor_codegen_options());
     let compiled_module = match driver::compile_ingot(&mut db, &build_files, true, false, true) {
         Ok(module) => module,
         Err(error) => {
@@ -587,7 +652,10 @@ pub fn compile_solidity_contract(
 
 #[allow(dead_code)]
 pub fn load_contract(address: H160, fixture: &str, contract_name: &str) -> ContractHarness {
+    use driver::CodegenDb;
+
     let mut db = driver::Db::default();
+    db.set_codegen_options(executor_codegen_options());
     let compiled_module = driver::compile_single_file(
         &mut db,
         fixture,
@@ -719,8 +787,14 @@ impl ExecutionOutput {
 #[cfg(feature = "solc-backend")]
 fn execute_runtime_functions(executor: &mut Executor, runtime: &Runtime) -> (ExitReason, Vec<u8>) {
     let yul_code = runtime.to_yul().to_string().replace('"', "\\\"");
-    let contract_bytecode = fe_yulc::compile_single_contract("Contract", &yul_code, false, false)
-        .expect("failed to compile Yul");
+    let contract_bytecode = fe_yulc::compile_single_contract(
+        "Contract",
+        &yul_code,
+        &fe_yulc::OptimizerSettings::default(),
+        executor_codegen_options().evm_version.name(),
+        false,
+    )
+    .expect("failed to compile Yul");
     let bytecode = hex::decode(contract_bytecode.bytecode).expect("failed to decode bytecode");
 
     if let evm::Capture::Exit((reason, _, output)) = executor.create(
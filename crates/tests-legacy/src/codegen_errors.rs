@@ -0,0 +1,69 @@
+//! Errors that are only detected once a module is lowered to Yul.
+
+use fe_common::diagnostics::diagnostics_string;
+use fe_driver::{Backend, CodegenDb, CodegenOptions, EvmVersion};
+
+fn error_string(path: &str) -> String {
+    error_string_with_options(path, CodegenOptions::default())
+}
+
+fn error_string_with_options(path: &str, options: CodegenOptions) -> String {
+    let mut db = fe_driver::Db::default();
+    db.set_codegen_options(options);
+    let diags = fe_driver::check_single_file(&mut db, path, test_files::fixture(path));
+    if diags.is_empty() {
+        panic!("expected checking `{path}` to fail with an error")
+    }
+    diagnostics_string(&db, &diags)
+}
+
+#[test]
+fn selector_collision() {
+    let errors = error_string("codegen_errors/selector_collision.fe");
+    assert!(errors.contains("error[E0037]: function selector collision in contract `Foo`"));
+    assert!(errors.contains("`get_55783()` has the selector `0x7df0752c`"));
+    assert!(errors.contains("`get_23316()` has the same selector"));
+}
+
+#[test]
+fn unavailable_intrinsic() {
+    let path = "codegen_errors/unavailable_intrinsic.fe";
+    let errors = error_string_with_options(
+        path,
+        CodegenOptions {
+            evm_version: EvmVersion::Berlin,
+            ..Default::default()
+        },
+    );
+    assert!(
+        errors.contains("error[E0045]: `__tstore` is not available in the `berlin` EVM version")
+    );
+    assert!(errors.contains("`__tstore` requires the `cancun` EVM version or later"));
+    assert!(errors.contains("`__prevrandao` requires the `paris` EVM version or later"));
+    assert!(errors.contains("`__basefee` requires the `london` EVM version or later"));
+
+    // Every intrinsic is available in the latest version.
+    let mut db = fe_driver::Db::default();
+    db.set_codegen_options(CodegenOptions {
+        evm_version: EvmVersion::Cancun,
+        ..Default::default()
+    });
+    assert!(fe_driver::check_single_file(&mut db, path, test_files::fixture(path)).is_empty());
+}
+
+#[test]
+fn yul_compile_failure() {
+    let path = "codegen_errors/stack_too_deep.fe";
+    let mut db = fe_driver::Db::default();
+    db.set_codegen_options(CodegenOptions {
+        backend: Backend::Native,
+        ..Default::default()
+    });
+    let src = test_files::fixture(path);
+    let errors = match fe_driver::compile_single_file(&mut db, path, src, true, false, false) {
+        Ok(_) => panic!("expected compiling `{path}` to fail with an error"),
+        Err(error) => diagnostics_string(&db, &error.0),
+    };
+    assert!(errors.contains("error[E0050]: Yul compilation failed: stack too deep"));
+    assert!(errors.contains("the Yul code generated for this can't be compiled"));
+}
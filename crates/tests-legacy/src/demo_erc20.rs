@@ -182,3 +182,50 @@ fn erc20_token() {
         assert_harness_gas_report!(harness);
     });
 }
+
+/// Deploys the token with `options` and returns the gas used by a few calls.
+fn erc20_calls_gas(executor: &mut Executor, options: fe_driver::CodegenOptions) -> u64 {
+    let harness = deploy_contract_with_codegen_options(
+        executor,
+        "demos/erc20_token.fe",
+        "ERC20",
+        &[string_token("Fe Coin"), string_token("fe")],
+        options,
+    );
+
+    let bob = address_token("2000000000000000000000000000000000000002");
+    let amount = uint_token_from_dec_str("1000000000000000");
+    harness.test_function(
+        executor,
+        "transfer",
+        &[bob.clone(), amount.clone()],
+        Some(&bool_token(true)),
+    );
+    harness.test_function(
+        executor,
+        "approve",
+        &[bob.clone(), amount.clone()],
+        Some(&bool_token(true)),
+    );
+    harness.test_function(executor, "balanceOf", &[bob.clone()], Some(&amount));
+    harness.test_function(
+        executor,
+        "allowance",
+        &[address_token(DEFAULT_CALLER), bob],
+        Some(&amount),
+    );
+
+    harness.gas_reporter.total_gas_used()
+}
+
+#[test]
+fn erc20_token_inlining_gas() {
+    let mut baseline = executor_codegen_options();
+    baseline.optimize_mir = true;
+    assert_saves_gas(
+        "erc20 inlining",
+        baseline,
+        |options| options.inline_functions = true,
+        &erc20_calls_gas,
+    );
+}
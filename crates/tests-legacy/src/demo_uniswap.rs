@@ -289,3 +289,120 @@ fn uniswap_contracts() {
         assert_harness_gas_report!(factory_harness);
     });
 }
+
+/// Deploys the tokens and the factory with `options`, and returns the gas used
+/// by the pair to add liquidity, swap and check its reserves.
+fn uniswap_pair_gas(executor: &mut Executor, options: fe_driver::CodegenOptions) -> u64 {
+    let alice = address_token(DEFAULT_CALLER);
+    let bob = address_token("42");
+
+    let token0_harness = deploy_contract_with_codegen_options(
+        executor,
+        "demos/erc20_token.fe",
+        "ERC20",
+        &[string_token("Fe Coin"), string_token("fe")],
+        options.clone(),
+    );
+    let mut token1_harness = deploy_contract_with_codegen_options(
+        executor,
+        "demos/erc20_token.fe",
+        "ERC20",
+        &[string_token("Maker"), string_token("mkr")],
+        options.clone(),
+    );
+    token1_harness.test_function(
+        executor,
+        "transfer",
+        &[
+            bob.clone(),
+            uint_token_from_dec_str("500000000000000000000000"),
+        ],
+        Some(&bool_token(true)),
+    );
+
+    let factory_harness = deploy_contract_with_codegen_options(
+        executor,
+        "demos/uniswap.fe",
+        "UniswapV2Factory",
+        &[address_token("0")],
+        options,
+    );
+    let pair_address = factory_harness
+        .call_function(
+            executor,
+            "create_pair",
+            &[
+                ethabi::Token::Address(token0_harness.address),
+                ethabi::Token::Address(token1_harness.address),
+            ],
+        )
+        .expect("factory did not return a token");
+    let pair_harness = load_contract(
+        pair_address.clone().into_address().expect("not an address"),
+        "demos/uniswap.fe",
+        "UniswapV2Pair",
+    );
+
+    token0_harness.test_function(
+        executor,
+        "transfer",
+        &[
+            pair_address.clone(),
+            uint_token_from_dec_str("200000000000000000000"),
+        ],
+        Some(&bool_token(true)),
+    );
+    token1_harness.test_function(
+        executor,
+        "transfer",
+        &[
+            pair_address.clone(),
+            uint_token_from_dec_str("100000000000000000000"),
+        ],
+        Some(&bool_token(true)),
+    );
+    pair_harness.test_function(
+        executor,
+        "mint",
+        &[alice],
+        Some(&uint_token_from_dec_str("141421356237309503880")),
+    );
+
+    token1_harness.set_caller(bob.clone().into_address().unwrap());
+    token1_harness.test_function(
+        executor,
+        "transfer",
+        &[pair_address, uint_token(1000)],
+        Some(&bool_token(true)),
+    );
+    pair_harness.test_function(
+        executor,
+        "swap",
+        &[uint_token(1993), uint_token(0), bob],
+        None,
+    );
+    pair_harness.test_function(
+        executor,
+        "get_reserves",
+        &[],
+        Some(&tuple_token(&[
+            uint_token_from_dec_str("199999999999999998007"),
+            uint_token_from_dec_str("100000000000000001000"),
+            uint_token_from_dec_str("0"),
+        ])),
+    );
+
+    pair_harness.gas_reporter.total_gas_used()
+}
+
+#[test]
+fn uniswap_inlining_gas() {
+    let mut baseline = executor_codegen_options();
+    baseline.optimize_mir = true;
+    assert_saves_gas(
+        "uniswap pair inlining",
+        baseline,
+        |options| options.inline_functions = true,
+        &uniswap_pair_gas,
+    );
+}
@@ -1606,10 +1606,16 @@ fn abi_decode_checks() {
             harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
 
             // place non-zero byte in padded region of u128
-            let mut tampered_data = data;
+            let mut tampered_data = data.clone();
             // 8 bytes past end of u128 (4 + 32 + 8)
             tampered_data[44] = 1;
             harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+
+            // encode `bool` as a value other than `0` or `1`
+            let mut tampered_data = data;
+            // last byte of the bool (4 + 32 + 31)
+            tampered_data[67] = 2;
+            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
         }
 
         // decode_u256_bytes_tuple_array
@@ -1721,6 +1727,12 @@ fn abi_decode_checks() {
             tampered_data.truncate(tampered_data.len() - 20);
             harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
 
+            // point the string's head at a non-canonical data offset
+            let mut tampered_data = data.clone();
+            // final byte in the string's head (4 + 31), which should hold 128
+            tampered_data[35] = 96;
+            harness.test_call_reverts(&mut executor, tampered_data, &revert_data);
+
             // set string length to value that extends beyond the next data offset
             let mut tampered_data = data.clone();
             let byte_index = 4 + head_size + 31;
@@ -1958,6 +1970,329 @@ fn ctx_init_in_call() {
     });
 }
 
+#[test]
+fn dispatcher_search() {
+    const FUNCTIONS: [&str; 16] = [
+        "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india",
+        "juliett", "kilo", "lima", "mike", "november", "oscar", "papa",
+    ];
+
+    with_executor(&|mut executor| {
+        let linear = deploy_contract(&mut executor, "dispatcher_search.fe", "Foo", &[]);
+        let search = test_utils::deploy_contract_with_codegen_options(
+            &mut executor,
+            "features/dispatcher_search.fe",
+            "Foo",
+            &[],
+            fe_driver::CodegenOptions {
+                dispatcher_search_threshold: Some(
+                    fe_driver::CodegenOptions::DEFAULT_DISPATCHER_SEARCH_THRESHOLD,
+                ),
+                ..test_utils::executor_codegen_options()
+            },
+        );
+
+        for harness in [&linear, &search] {
+            for (i, name) in FUNCTIONS.iter().enumerate() {
+                harness.test_function(
+                    &mut executor,
+                    name,
+                    &[uint_token(100)],
+                    Some(&uint_token(100 + i as u64)),
+                );
+            }
+
+            // Unknown selectors and calldata shorter than a selector fall
+            // through to the default case.
+            harness.test_call_returns(&mut executor, vec![0xff; 4], &[]);
+            harness.test_call_returns(&mut executor, vec![0xff; 3], &[]);
+        }
+
+        assert!(
+            search.gas_reporter.total_gas_used() < linear.gas_reporter.total_gas_used(),
+            "binary search dispatcher used more gas than the linear one:\nlinear:\n{}\nsearch:\n{}",
+            linear.gas_reporter,
+            search.gas_reporter,
+        );
+    });
+}
+
+#[test]
+fn reclaim_memory() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "reclaim_memory.fe", "Foo", &[]);
+        let reclaiming = test_utils::deploy_contract_with_codegen_options(
+            &mut executor,
+            "features/reclaim_memory.fe",
+            "Foo",
+            &[],
+            fe_driver::CodegenOptions {
+                reclaim_memory: true,
+                ..test_utils::executor_codegen_options()
+            },
+        );
+
+        for harness in [&harness, &reclaiming] {
+            harness.test_function(
+                &mut executor,
+                "sum",
+                &[uint_token(10)],
+                Some(&uint_token(100)),
+            );
+        }
+
+        let msize = |harness: &ContractHarness, executor: &mut Executor, name, n| {
+            harness
+                .call_function(executor, name, &[uint_token(n)])
+                .and_then(|token| token.into_uint())
+                .unwrap()
+        };
+        // Every loop iteration reuses the memory of the previous one, whether it
+        // allocates structs or `MemoryBuffer`s, and so does every call that
+        // allocates an `abi_encode` buffer.
+        for name in ["memory_size", "buffers_memory_size", "digests_memory_size"] {
+            assert_eq!(
+                msize(&reclaiming, &mut executor, name, 1),
+                msize(&reclaiming, &mut executor, name, 20),
+                "`{name}` grows memory"
+            );
+            assert!(
+                msize(&reclaiming, &mut executor, name, 20)
+                    < msize(&harness, &mut executor, name, 20)
+            );
+        }
+    });
+}
+
+#[test]
+fn mir_optimize() {
+    with_executor(&|mut executor| {
+        let harness = test_utils::deploy_contract_with_codegen_options(
+            &mut executor,
+            "features/mir_optimize.fe",
+            "Foo",
+            &[],
+            fe_driver::CodegenOptions {
+                optimize_mir: true,
+                ..test_utils::executor_codegen_options()
+            },
+        );
+
+        harness.test_function(
+            &mut executor,
+            "fold",
+            &[uint_token(1)],
+            Some(&uint_token(512)),
+        );
+        harness.test_function(&mut executor, "fold_signed", &[], Some(&int_token(-128)));
+        // Arithmetic that overflows isn't folded and still reverts.
+        harness.test_function_reverts(
+            &mut executor,
+            "fold_overflow",
+            &[],
+            &encoded_over_or_underflow(),
+        );
+    });
+}
+
+#[test]
+fn inline_functions() {
+    with_executor(&|mut executor| {
+        let harness = test_utils::deploy_contract_with_codegen_options(
+            &mut executor,
+            "features/inline.fe",
+            "Foo",
+            &[],
+            fe_driver::CodegenOptions {
+                optimize_mir: true,
+                inline_functions: true,
+                ..test_utils::executor_codegen_options()
+            },
+        );
+
+        harness.test_function(
+            &mut executor,
+            "advance",
+            &[uint_token(3)],
+            Some(&uint_token(6)),
+        );
+        harness.test_function(
+            &mut executor,
+            "double_clamped",
+            &[uint_token(250)],
+            Some(&uint_token(200)),
+        );
+        harness.test_function(
+            &mut executor,
+            "elem_at",
+            &[uint_token(1)],
+            Some(&uint_token(50)),
+        );
+        // An immediate argument doesn't disable the bounds check of the callee.
+        harness.test_function_reverts(
+            &mut executor,
+            "elem_out_of_bounds",
+            &[],
+            &encoded_panic_out_of_bounds(),
+        );
+        harness.test_function(
+            &mut executor,
+            "fact",
+            &[uint_token(5)],
+            Some(&uint_token(120)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "sub",
+            &[uint_token(1), uint_token(2)],
+            &encoded_over_or_underflow(),
+        );
+    });
+}
+
+fn storage_forward_gas(executor: &mut Executor, options: fe_driver::CodegenOptions) -> u64 {
+    let harness = test_utils::deploy_contract_with_codegen_options(
+        executor,
+        "features/storage_forward.fe",
+        "Foo",
+        &[],
+        options,
+    );
+
+    harness.test_function(
+        executor,
+        "deposit",
+        &[uint_token(1), uint_token(5)],
+        Some(&uint_token(20)),
+    );
+    harness.test_function(
+        executor,
+        "get_balance",
+        &[uint_token(1)],
+        Some(&uint_token(10)),
+    );
+    // The same entry is debited and credited.
+    harness.test_function(
+        executor,
+        "transfer",
+        &[uint_token(1), uint_token(1), uint_token(3)],
+        Some(&uint_token(10)),
+    );
+    harness.test_function(
+        executor,
+        "transfer",
+        &[uint_token(1), uint_token(2), uint_token(3)],
+        Some(&uint_token(7)),
+    );
+    harness.test_function(
+        executor,
+        "get_balance",
+        &[uint_token(2)],
+        Some(&uint_token(3)),
+    );
+    harness.test_function(
+        executor,
+        "conditional_reset",
+        &[uint_token(1), bool_token(false)],
+        Some(&uint_token(14)),
+    );
+    harness.test_function(
+        executor,
+        "conditional_reset",
+        &[uint_token(1), bool_token(true)],
+        Some(&uint_token(7)),
+    );
+    harness.test_function(
+        executor,
+        "overwrite",
+        &[uint_token(9)],
+        Some(&uint_token(9)),
+    );
+    harness.test_function(executor, "get_counter", &[], Some(&uint_token(9)));
+    // The callee writes the counter, so it's loaded again after the call.
+    harness.test_function(executor, "bump_across_call", &[], Some(&uint_token(1)));
+    // The callee reads the first store, so it isn't removed.
+    harness.test_function(
+        executor,
+        "store_read_by_call",
+        &[uint_token(4)],
+        Some(&uint_token(4)),
+    );
+    harness.test_function(executor, "get_counter", &[], Some(&uint_token(0)));
+
+    harness.gas_reporter.total_gas_used()
+}
+
+#[test]
+fn storage_forward() {
+    test_utils::assert_saves_gas(
+        "storage forwarding",
+        test_utils::executor_codegen_options(),
+        |options| options.optimize_mir = true,
+        &storage_forward_gas,
+    );
+}
+
+fn licm_gas(executor: &mut Executor, options: fe_driver::CodegenOptions) -> u64 {
+    let harness = test_utils::deploy_contract_with_codegen_options(
+        executor,
+        "features/licm.fe",
+        "Foo",
+        &[],
+        options,
+    );
+
+    harness.test_function(
+        executor,
+        "credit_times",
+        &[uint_token(7), uint_token(5), uint_token(3)],
+        Some(&uint_token(15)),
+    );
+    harness.test_function(
+        executor,
+        "credit_times",
+        &[uint_token(7), uint_token(1), uint_token(0)],
+        Some(&uint_token(15)),
+    );
+    harness.test_function(
+        executor,
+        "nested",
+        &[uint_token(6), uint_token(3), uint_token(3)],
+        Some(&uint_token(27)),
+    );
+    harness.test_function(
+        executor,
+        "masked_sum",
+        &[uint_array_token(&[1, 2, 3, 4]), uint_token(0)],
+        Some(&uint_token(2)),
+    );
+    // The checked addition isn't hoisted out of a loop that doesn't run.
+    harness.test_function(
+        executor,
+        "skipped_overflow",
+        &[ethabi::Token::Uint(U256::MAX), uint_token(0)],
+        Some(&uint_token(0)),
+    );
+    harness.test_function(
+        executor,
+        "skipped_overflow",
+        &[uint_token(2), uint_token(3)],
+        Some(&uint_token(4)),
+    );
+
+    harness.gas_reporter.total_gas_used()
+}
+
+#[test]
+fn licm() {
+    test_utils::assert_saves_gas(
+        "loop-invariant code motion",
+        test_utils::executor_codegen_options(),
+        |options| options.optimize_mir = true,
+        &licm_gas,
+    );
+}
+
 // These tests are expected to make assertions in Fe only
 #[rstest(
     fixture_file,
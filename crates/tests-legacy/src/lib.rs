@@ -1,4 +1,6 @@
 #[cfg(test)]
+mod codegen_errors;
+#[cfg(test)]
 mod crashes;
 #[cfg(test)]
 mod demo_erc20;
@@ -13,6 +15,12 @@ mod differential;
 #[cfg(test)]
 mod features;
 #[cfg(test)]
+mod native_backend;
+#[cfg(test)]
 mod solidity;
 #[cfg(test)]
+mod source_map;
+#[cfg(test)]
 mod stress;
+#[cfg(test)]
+mod yul_output;
@@ -0,0 +1,113 @@
+//! Tests that check the bytecode of the native backend behaves like the
+//! bytecode solc assembles from the same Yul.
+#![cfg(all(feature = "solc-backend", not(target_arch = "wasm32")))]
+use proptest::prelude::*;
+
+use fe_compiler_test_utils::*;
+use fe_compiler_test_utils::{self as test_utils};
+
+struct BackendHarness {
+    solc_harness: ContractHarness,
+    native_harness: ContractHarness,
+}
+
+impl BackendHarness {
+    fn from_fixture(executor: &mut Executor, fixture: &str, contract_name: &str) -> Self {
+        let deploy = |executor: &mut Executor, backend| {
+            test_utils::deploy_contract_with_codegen_options(
+                executor,
+                &format!("differential/{fixture}.fe"),
+                contract_name,
+                &[],
+                fe_driver::CodegenOptions {
+                    backend,
+                    ..executor_codegen_options()
+                },
+            )
+        };
+
+        BackendHarness {
+            solc_harness: deploy(executor, fe_driver::Backend::Solc),
+            native_harness: deploy(executor, fe_driver::Backend::Native),
+        }
+    }
+
+    /// Calls `name` on both contracts and asserts that they exit the same way
+    /// with the same output. Gas usage isn't compared, as the native backend
+    /// doesn't optimize.
+    fn assert_performed_equal(&self, executor: &mut Executor, name: &str, input: &[ethabi::Token]) {
+        let solc_capture = self.solc_harness.capture_call(executor, name, input);
+        let native_capture = self.native_harness.capture_call(executor, name, input);
+        assert_eq!(
+            solc_capture, native_capture,
+            "Called {name} with input: {input:?}"
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn native_math_u8(val in 0u8..=255, val2 in 0u8..=255) {
+        with_executor(&|mut executor| {
+            let harness = BackendHarness::from_fixture(&mut executor, "math_u8", "Foo");
+            let args = [uint_token(val.into()), uint_token(val2.into())];
+
+            for name in ["add", "subtract", "multiply", "divide", "pow", "modulo", "rightshift", "leftshift", "bit_and", "bit_or", "bit_xor", "order_of_operation"] {
+                harness.assert_performed_equal(&mut executor, name, &args);
+            }
+            for name in ["invert", "cast1", "cast2", "cast3", "sqrt"] {
+                harness.assert_performed_equal(&mut executor, name, &args[..1]);
+            }
+        });
+    }
+
+    #[test]
+    fn native_math_i8(val in -128i8..=127i8, val2 in -128i8..=127i8, val3 in 0u8..=255) {
+        with_executor(&|mut executor| {
+            let harness = BackendHarness::from_fixture(&mut executor, "math_i8", "Foo");
+            let args = [int_token(val.into()), int_token(val2.into())];
+            let shift_args = [int_token(val.into()), uint_token(val3.into())];
+
+            for name in ["add", "subtract", "multiply", "divide", "modulo"] {
+                harness.assert_performed_equal(&mut executor, name, &args);
+            }
+            for name in ["pow", "rightshift", "leftshift"] {
+                harness.assert_performed_equal(&mut executor, name, &shift_args);
+            }
+            for name in ["invert", "cast1", "cast2", "cast3", "negate"] {
+                harness.assert_performed_equal(&mut executor, name, &args[..1]);
+            }
+            harness.assert_performed_equal(&mut executor, "order_of_operation", &[int_token(val.into()), int_token(val2.into()), uint_token(val3.into())]);
+        });
+    }
+
+    #[test]
+    fn native_storage_and_memory(my_num in 0u64..=100000,
+                                 my_num2 in 0u8..=255, my_bool in any::<bool>(),
+                                 my_str in "[0-9]{20}",
+                                 my_long_string in ".{0,40}",
+                                 my_num3 in -128i8..=127i8) {
+        with_executor(&|mut executor| {
+            let harness = BackendHarness::from_fixture(&mut executor, "storage_and_memory", "Foo");
+
+            let data = ethabi::Token::Tuple(vec![
+                uint_token(my_num),
+                uint_token(my_num2.into()),
+                bool_token(my_bool),
+                address_token(&my_str),
+                int_token(my_num3.into())
+            ]);
+
+            harness.assert_performed_equal(&mut executor, "set_data", &[data]);
+            harness.assert_performed_equal(&mut executor, "get_data", &[]);
+            harness.assert_performed_equal(&mut executor, "set_item", &[uint_token(my_num2.into()), int_token(my_num3.into())]);
+            harness.assert_performed_equal(&mut executor, "get_items", &[]);
+            harness.assert_performed_equal(&mut executor, "set_string", &[string_token(&my_long_string)]);
+            harness.assert_performed_equal(&mut executor, "get_string", &[]);
+            harness.assert_performed_equal(&mut executor, "set_range", &[uint_token(my_num2.into()), uint_token(my_num)]);
+            harness.assert_performed_equal(&mut executor, "get_range", &[]);
+        });
+    }
+}
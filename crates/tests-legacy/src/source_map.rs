@@ -0,0 +1,45 @@
+//! Tests of the source maps that trace bytecode back to the Fe source.
+#![cfg(all(feature = "solc-backend", not(target_arch = "wasm32")))]
+
+use fe_driver::{CodegenDb, CodegenOptions, CompiledContract};
+
+fn compile(path: &str, source_locations: bool) -> CompiledContract {
+    let mut db = fe_driver::Db::default();
+    db.set_codegen_options(CodegenOptions {
+        source_locations,
+        ..Default::default()
+    });
+    let src = test_files::fixture(path);
+    let mut module = fe_driver::compile_single_file(&mut db, path, src, true, true, false)
+        .unwrap_or_else(|_| panic!("failed to compile `{path}`"));
+    module.contracts.remove("Foo").unwrap()
+}
+
+#[test]
+fn balances() {
+    let path = "features/balances.fe";
+    let tagged = compile(path, true);
+    let untagged = compile(path, false);
+
+    // The source markers don't make it into the Yul output or the bytecode.
+    assert_eq!(tagged.yul, untagged.yul);
+    assert_eq!(tagged.bytecode, untagged.bytecode);
+    assert_eq!(tagged.runtime_bytecode, untagged.runtime_bytecode);
+    assert!(untagged.source_map.is_empty());
+
+    let source_map: serde_json::Value = serde_json::from_str(&tagged.source_map).unwrap();
+    let sources = source_map["sources"].as_array().unwrap();
+    let file_idx = sources.iter().position(|source| source == path).unwrap() as i64;
+
+    let runtime_source_map = source_map["deployedSourceMap"].as_str().unwrap();
+    let entries = fe_yulc::source_map::decode(runtime_source_map).unwrap();
+    let src = test_files::fixture(path);
+    let mapped: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry.source == file_idx)
+        .map(|entry| &src[entry.start as usize..(entry.start + entry.length) as usize])
+        .collect();
+    assert!(mapped
+        .iter()
+        .any(|code| code.contains("balance_of(someone)")));
+}
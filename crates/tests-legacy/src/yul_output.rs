@@ -0,0 +1,47 @@
+//! Tests that inspect the Yul a module is lowered to.
+
+use fe_driver::{CodegenDb, CodegenOptions, EvmVersion};
+
+fn contract_yul(path: &str, contract: &str, options: CodegenOptions) -> String {
+    let mut db = fe_driver::Db::default();
+    db.set_codegen_options(options);
+    let module = match fe_driver::compile_single_file(
+        &mut db,
+        path,
+        test_files::fixture(path),
+        false,
+        false,
+        false,
+    ) {
+        Ok(module) => module,
+        Err(_) => panic!("failed to compile `{path}`"),
+    };
+    module.contracts[contract].yul.clone()
+}
+
+#[test]
+fn memory_copy() {
+    let path = "yul_output/memory_copy.fe";
+
+    let cancun = contract_yul(
+        path,
+        "Foo",
+        CodegenOptions {
+            evm_version: EvmVersion::Cancun,
+            ..Default::default()
+        },
+    );
+    assert!(cancun.contains("mcopy("));
+    assert!(!cancun.contains("$mcopym"));
+
+    let paris = contract_yul(
+        path,
+        "Foo",
+        CodegenOptions {
+            evm_version: EvmVersion::Paris,
+            ..Default::default()
+        },
+    );
+    assert!(paris.contains("function $mcopym("));
+    assert!(!paris.contains("mcopy("));
+}
@@ -1,24 +1,55 @@
 #![cfg(feature = "solc-backend")]
 #![allow(dead_code)]
+use std::fs;
 use std::path::Path;
 
 use dir_test::{dir_test, Fixture};
 use fe_common::diagnostics::print_diagnostics;
 use fe_common::utils::files::BuildFiles;
+use fe_driver::{Backend, CodegenDb, CodegenOptions, EvmVersion};
 use fe_test_runner::TestSink;
 
 #[dir_test(dir: "$CARGO_MANIFEST_DIR/fixtures/files", glob: "*.fe")]
 fn single_file_test_run(fixture: Fixture<&str>) {
+    run_single_file_tests(fixture.path(), fixture.content(), CodegenOptions::default())
+}
+
+/// Runs the same tests as `single_file_test_run` on the bytecode of the native
+/// backend, which must pass them just like solc's bytecode does.
+#[dir_test(dir: "$CARGO_MANIFEST_DIR/fixtures/files", glob: "*.fe")]
+fn single_file_native_test_run(fixture: Fixture<&str>) {
+    let options = CodegenOptions {
+        backend: Backend::Native,
+        ..Default::default()
+    };
+    run_single_file_tests(fixture.path(), fixture.content(), options)
+}
+
+/// Runs the tests of the fixtures in `fixtures/cancun`, which use opcodes
+/// introduced by the Cancun hardfork. The linked solc doesn't support Cancun,
+/// so they're assembled by the native backend.
+#[test]
+fn cancun_test_run() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/cancun");
+    for entry in fs::read_dir(dir).expect("failed to read the Cancun fixtures") {
+        let path = entry.expect("failed to read the Cancun fixtures").path();
+        let content = fs::read_to_string(&path).expect("failed to read a Cancun fixture");
+        let options = CodegenOptions {
+            evm_version: EvmVersion::Cancun,
+            backend: Backend::Native,
+            ..Default::default()
+        };
+        run_single_file_tests(&path.to_string_lossy(), &content, options)
+    }
+}
+
+fn run_single_file_tests(path: &str, content: &str, options: CodegenOptions) {
     let mut db = fe_driver::Db::default();
-    let tests = match fe_driver::compile_single_file_tests(
-        &mut db,
-        fixture.path(),
-        fixture.content(),
-        true,
-    ) {
+    db.set_codegen_options(options);
+    let tests = match fe_driver::compile_single_file_tests(&mut db, path, content, true) {
         Ok((_, tests)) => tests,
         Err(error) => {
-            eprintln!("Unable to compile {}.", fixture.path());
+            eprintln!("Unable to compile {path}.");
             print_diagnostics(&db, &error.0);
             panic!("failed to compile tests")
         }
@@ -0,0 +1,249 @@
+//! Compiles Yul with a solc executable, which is given the same standard JSON
+//! input as the solc that the `solc-backend` feature links in.
+
+use std::{
+    env,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Mutex,
+    thread,
+};
+
+use crate::{parse_output, standard_json_input, ContractBytecode, OptimizerSettings, YulcError};
+
+/// A solc version, as `(major, minor, patch)`.
+pub type SolcVersion = (u32, u32, u32);
+
+/// The versions of the executables that were already checked.
+static VERSIONS: Mutex<Vec<(PathBuf, SolcVersion)>> = Mutex::new(Vec::new());
+
+/// Compiles a single Yul contract to bytecode with the solc executable at
+/// `solc`, which is looked up on `PATH` if it's just a name.
+///
+/// `evm_version` is the name of the targeted hardfork as solc spells it, e.g.
+/// `"london"`.
+pub fn compile_single_contract(
+    solc: &Path,
+    name: &str,
+    yul_src: &str,
+    optimizer: &OptimizerSettings,
+    evm_version: &str,
+    verify_runtime_bytecode: bool,
+) -> Result<ContractBytecode, YulcError> {
+    let version = cached_version(solc)?;
+    check_version(&format!("`{}`", solc.display()), version, evm_version)?;
+
+    let input = standard_json_input(yul_src, optimizer, evm_version);
+    let output = run(solc, &["--standard-json"], Some(input))?;
+    parse_output(name, &output, verify_runtime_bytecode)
+}
+
+/// Fails with `YulcError::UnsupportedVersion` if `version` of the solc that
+/// `solc` describes doesn't support `evm_version`.
+pub(crate) fn check_version(
+    solc: &str,
+    version: SolcVersion,
+    evm_version: &str,
+) -> Result<(), YulcError> {
+    match required_version(evm_version) {
+        Some(required) if version < required => Err(YulcError::UnsupportedVersion(format!(
+            "{solc} is solc {}, but EVM version `{evm_version}` requires solc {} or later",
+            display_version(version),
+            display_version(required),
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the version of the solc executable at `solc`.
+pub fn version(solc: &Path) -> Result<SolcVersion, YulcError> {
+    let output = run(solc, &["--version"], None)?;
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Version: "))
+        .and_then(parse_version)
+        .ok_or_else(|| {
+            YulcError::InvalidOutput(format!(
+                "`{}` doesn't print a solc version: {output}",
+                solc.display()
+            ))
+        })
+}
+
+/// Returns the path of the `solc` executable on `PATH`, if there is one.
+pub fn solc_on_path() -> Option<PathBuf> {
+    let name = if cfg!(windows) { "solc.exe" } else { "solc" };
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn cached_version(solc: &Path) -> Result<SolcVersion, YulcError> {
+    let mut versions = VERSIONS.lock().unwrap();
+    if let Some((_, version)) = versions.iter().find(|(path, _)| path == solc) {
+        return Ok(*version);
+    }
+
+    let version = version(solc)?;
+    versions.push((solc.to_path_buf(), version));
+    Ok(version)
+}
+
+/// Runs `solc` with `args`, writing `input` to its stdin, and returns what it
+/// prints to stdout.
+fn run(solc: &Path, args: &[&str], input: Option<String>) -> Result<String, YulcError> {
+    let io_error =
+        |err: io::Error| YulcError::Io(format!("failed to run `{}`: {err}", solc.display()));
+
+    let mut child = Command::new(solc)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(io_error)?;
+
+    // The input is written by another thread, so that solc doesn't block on a
+    // full stdout pipe before it has read all of it.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = thread::spawn(move || match input {
+        Some(input) => stdin.write_all(input.as_bytes()),
+        None => Ok(()),
+    });
+    let output = child.wait_with_output().map_err(io_error)?;
+    writer
+        .join()
+        .expect("writing to solc panicked")
+        .map_err(io_error)?;
+
+    if !output.status.success() {
+        return Err(YulcError::Io(format!(
+            "`{}` failed with {}: {}",
+            solc.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout).map_err(|_| {
+        YulcError::InvalidOutput(format!("the output of `{}` isn't UTF-8", solc.display()))
+    })
+}
+
+/// Parses a version like `0.8.24+commit.e11b9ed9.Linux.g++`.
+pub(crate) fn parse_version(version: &str) -> Option<SolcVersion> {
+    let version = version.split(|c| c == '+' || c == '-').next()?;
+    let mut numbers = version.trim().split('.').map(|number| number.parse().ok());
+    let version = (numbers.next()??, numbers.next()??, numbers.next()??);
+    numbers.next().is_none().then_some(version)
+}
+
+pub fn display_version((major, minor, patch): SolcVersion) -> String {
+    format!("{major}.{minor}.{patch}")
+}
+
+/// Returns the first solc version that accepts `evm_version`.
+fn required_version(evm_version: &str) -> Option<SolcVersion> {
+    match evm_version {
+        "istanbul" => Some((0, 5, 14)),
+        "berlin" => Some((0, 8, 5)),
+        "london" => Some((0, 8, 7)),
+        "paris" => Some((0, 8, 18)),
+        "shanghai" => Some((0, 8, 20)),
+        "cancun" => Some((0, 8, 24)),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    use super::*;
+    use crate::SolcError;
+
+    /// Writes a script that stands in for solc: it prints `version` when asked
+    /// for it and `output` for any standard JSON input.
+    fn stub_solc(name: &str, version: &str, output: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("fe-yulc-{}-{name}", std::process::id()));
+        let script = format!(
+            "#!/bin/sh\n\
+             if [ \"$1\" = \"--version\" ]; then\n\
+             echo 'solc, the solidity compiler commandline interface'\n\
+             echo 'Version: {version}'\n\
+             exit 0\n\
+             fi\n\
+             cat > /dev/null\n\
+             cat <<'EOF'\n\
+             {output}\n\
+             EOF\n"
+        );
+        fs::write(&path, script).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn compile(solc: &Path, evm_version: &str) -> Result<ContractBytecode, YulcError> {
+        let optimizer = OptimizerSettings::default();
+        compile_single_contract(solc, "test", "{ stop() }", &optimizer, evm_version, true)
+    }
+
+    // The stubs are run by a single test, as executing a script while another
+    // thread is writing one can fail with `ETXTBSY`.
+    #[test]
+    fn stub_executables() {
+        let compiled = r#"{"contracts": {"input.yul": {"test": {"evm": {
+            "bytecode": {"object": "6000", "sourceMap": "0:10:0:-:0"},
+            "deployedBytecode": {"object": "00"}
+        }}}}}"#;
+        let solc = stub_solc("compiles", "0.8.24+commit.e11b9ed9.Linux.g++", compiled);
+        let bytecode = compile(&solc, "cancun").unwrap();
+        assert_eq!(bytecode.bytecode, "6000");
+        assert_eq!(bytecode.runtime_bytecode, "00");
+        assert_eq!(bytecode.source_map, "0:10:0:-:0");
+        assert_eq!(bytecode.runtime_source_map, "");
+        assert_eq!(version(&solc), Ok((0, 8, 24)));
+
+        let rejected = r#"{"errors": [
+            {"severity": "warning", "message": "unused", "formattedMessage": "Warning: unused"},
+            {"severity": "error", "message": "bad", "formattedMessage": "Error: bad",
+             "sourceLocation": {"file": "input.yul", "start": 2, "end": 8}}
+        ]}"#;
+        let solc = stub_solc("rejects", "0.8.24", rejected);
+        assert_eq!(
+            compile(&solc, "cancun").err(),
+            Some(YulcError::Solc(vec![SolcError {
+                message: "bad".into(),
+                formatted_message: "Error: bad".into(),
+                location: Some(2..8),
+            }]))
+        );
+
+        let solc = stub_solc("outdated", "0.8.17+commit.8df45f5f", compiled);
+        assert!(matches!(
+            compile(&solc, "cancun"),
+            Err(YulcError::UnsupportedVersion(_))
+        ));
+        assert!(compile(&solc, "london").is_ok());
+
+        let solc = stub_solc("garbled", "0.8.24", "not json");
+        assert!(matches!(
+            compile(&solc, "cancun"),
+            Err(YulcError::InvalidOutput(_))
+        ));
+
+        let missing = env::temp_dir().join("fe-yulc-missing-solc");
+        assert!(matches!(compile(&missing, "cancun"), Err(YulcError::Io(_))));
+    }
+
+    #[test]
+    fn versions() {
+        assert_eq!(parse_version("0.8.24+commit.e11b9ed9"), Some((0, 8, 24)));
+        assert_eq!(parse_version("0.8.26-nightly.2024.5.1"), Some((0, 8, 26)));
+        assert_eq!(parse_version("0.8"), None);
+        assert_eq!(parse_version("0.8.x"), None);
+
+        assert!(check_version("solc", (0, 8, 18), "paris").is_ok());
+        assert!(check_version("solc", (0, 8, 18), "cancun").is_err());
+    }
+}
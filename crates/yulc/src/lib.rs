@@ -1,11 +1,95 @@
+use std::{fmt, ops::Range};
+
 use indexmap::map::IndexMap;
+use serde_json::{json, Value};
+
+pub mod external;
+pub mod native;
+pub mod source_map;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YulcError {
+    /// The solc executable couldn't be run.
+    Io(String),
+    /// The solc executable doesn't support the targeted EVM version.
+    UnsupportedVersion(String),
+    /// solc's output isn't the standard JSON that was expected.
+    InvalidOutput(String),
+    /// solc rejected the Yul code.
+    Solc(Vec<SolcError>),
+    /// The native backend rejected the Yul code.
+    Native(String),
+}
+
+impl fmt::Display for YulcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            YulcError::Io(message)
+            | YulcError::UnsupportedVersion(message)
+            | YulcError::InvalidOutput(message)
+            | YulcError::Native(message) => f.write_str(message),
+            YulcError::Solc(errors) => {
+                for error in errors {
+                    writeln!(f, "{}", error.formatted_message)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
 
-#[derive(Debug)]
-pub struct YulcError(pub String);
+/// An error reported by solc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolcError {
+    pub message: String,
+    pub formatted_message: String,
+    /// The byte range of the Yul source the error is about, if solc gave one.
+    pub location: Option<Range<usize>>,
+}
+
+/// The settings of solc's optimizer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OptimizerSettings {
+    pub enabled: bool,
+    /// How many times the deployed code is expected to run. Fewer runs favor
+    /// smaller code, more runs cheaper execution. `None` leaves it to solc.
+    pub runs: Option<u32>,
+    /// The Yul optimizer step sequence, replacing solc's default one.
+    pub yul_steps: Option<String>,
+}
+
+impl OptimizerSettings {
+    /// The default optimizer settings, enabled or not.
+    pub fn enabled(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Self::default()
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut optimizer = json!({ "enabled": self.enabled });
+        if let Some(runs) = self.runs {
+            optimizer["runs"] = runs.into();
+        }
+        if let Some(steps) = &self.yul_steps {
+            optimizer["details"] = json!({
+                "yul": true,
+                "yulDetails": { "optimizerSteps": steps }
+            });
+        }
+        optimizer
+    }
+}
 
 pub struct ContractBytecode {
     pub bytecode: String,
     pub runtime_bytecode: String,
+    /// solc's source maps of the bytecode and the runtime bytecode, which
+    /// refer to the Yul source. They're empty if the compiler doesn't produce
+    /// any.
+    pub source_map: String,
+    pub runtime_source_map: String,
 }
 
 /// Compile a map of Yul contracts to a map of bytecode contracts.
@@ -13,75 +97,160 @@ pub struct ContractBytecode {
 /// Returns a `contract_name -> hex_encoded_bytecode` map.
 pub fn compile(
     contracts: impl Iterator<Item = (impl AsRef<str>, impl AsRef<str>)>,
-    optimize: bool,
+    optimizer: &OptimizerSettings,
+    evm_version: &str,
 ) -> Result<IndexMap<String, ContractBytecode>, YulcError> {
     contracts
         .map(|(name, yul_src)| {
-            compile_single_contract(name.as_ref(), yul_src.as_ref(), optimize, true)
-                .map(|bytecode| (name.as_ref().to_string(), bytecode))
+            compile_single_contract(
+                name.as_ref(),
+                yul_src.as_ref(),
+                optimizer,
+                evm_version,
+                true,
+            )
+            .map(|bytecode| (name.as_ref().to_string(), bytecode))
         })
         .collect()
 }
 
 #[cfg(feature = "solc-backend")]
 /// Compiles a single Yul contract to bytecode.
+///
+/// `evm_version` is the name of the targeted hardfork as solc spells it, e.g.
+/// `"london"`.
 pub fn compile_single_contract(
     name: &str,
     yul_src: &str,
-    optimize: bool,
+    optimizer: &OptimizerSettings,
+    evm_version: &str,
     verify_runtime_bytecode: bool,
 ) -> Result<ContractBytecode, YulcError> {
-    let solc_temp = include_str!("solc_temp.json");
-    let input = solc_temp
-        .replace("{optimizer_enabled}", &optimize.to_string())
-        .replace("{src}", yul_src);
-    let raw_output = solc::compile(&input);
-    let output: serde_json::Value = serde_json::from_str(&raw_output)
-        .map_err(|_| YulcError("JSON serialization error".into()))?;
+    external::check_version("the linked solc", solc_version()?, evm_version)?;
 
-    let bytecode = output["contracts"]["input.yul"][name]["evm"]["bytecode"]["object"]
-        .to_string()
-        .replace('"', "");
-
-    let runtime_bytecode = output["contracts"]["input.yul"][name]["evm"]["deployedBytecode"]
-        ["object"]
-        .to_string()
-        .replace('"', "");
+    let input = standard_json_input(yul_src, optimizer, evm_version);
+    parse_output(name, &solc::compile(&input), verify_runtime_bytecode)
+}
 
-    if bytecode == "null" {
-        return Err(YulcError(output.to_string()));
-    }
-    if verify_runtime_bytecode && runtime_bytecode == "null" {
-        return Err(YulcError(output.to_string()));
-    }
+#[cfg(not(feature = "solc-backend"))]
+/// Compiles a single Yul contract to bytecode with the `solc` executable on
+/// `PATH`.
+///
+/// `evm_version` is the name of the targeted hardfork as solc spells it, e.g.
+/// `"london"`.
+pub fn compile_single_contract(
+    name: &str,
+    yul_src: &str,
+    optimizer: &OptimizerSettings,
+    evm_version: &str,
+    verify_runtime_bytecode: bool,
+) -> Result<ContractBytecode, YulcError> {
+    external::compile_single_contract(
+        "solc".as_ref(),
+        name,
+        yul_src,
+        optimizer,
+        evm_version,
+        verify_runtime_bytecode,
+    )
+}
 
-    Ok(ContractBytecode {
-        bytecode,
-        runtime_bytecode,
+#[cfg(feature = "solc-backend")]
+/// Returns the version of the solc that `compile_single_contract` uses.
+pub fn solc_version() -> Result<external::SolcVersion, YulcError> {
+    let version = solc::version();
+    external::parse_version(&version).ok_or_else(|| {
+        YulcError::InvalidOutput(format!(
+            "the linked solc has an invalid version `{version}`"
+        ))
     })
 }
 
 #[cfg(not(feature = "solc-backend"))]
-/// Compiles a single Yul contract to bytecode.
-pub fn compile_single_contract(
-    _name: &str,
-    _yul_src: &str,
-    _optimize: bool,
-    _verify_runtime_bytecode: bool,
+/// Returns the version of the solc that `compile_single_contract` uses.
+pub fn solc_version() -> Result<external::SolcVersion, YulcError> {
+    external::version("solc".as_ref())
+}
+
+/// Returns the standard JSON input that has solc compile `yul_src`, which must
+/// be escaped for a JSON string.
+fn standard_json_input(yul_src: &str, optimizer: &OptimizerSettings, evm_version: &str) -> String {
+    include_str!("solc_temp.json")
+        .replace("{optimizer}", &optimizer.to_json().to_string())
+        .replace("{evm_version}", evm_version)
+        .replace("{src}", yul_src)
+}
+
+/// Returns the bytecode of the object `name` in solc's standard JSON output.
+fn parse_output(
+    name: &str,
+    raw_output: &str,
+    verify_runtime_bytecode: bool,
 ) -> Result<ContractBytecode, YulcError> {
-    // This is ugly, but required (as far as I can tell) to make
-    // `cargo test --workspace` work without solc.
-    panic!("fe-yulc requires 'solc-backend' feature")
+    let output: Value = serde_json::from_str(raw_output)
+        .map_err(|err| YulcError::InvalidOutput(format!("solc's output isn't JSON: {err}")))?;
+
+    let errors: Vec<_> = output["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|error| error["severity"] == "error")
+        .map(|error| {
+            let message = error["message"].as_str().unwrap_or_default().to_string();
+            let formatted_message = match error["formattedMessage"].as_str() {
+                Some(formatted) => formatted.replace("\\\n", "\n"),
+                None => message.clone(),
+            };
+            let location = &error["sourceLocation"];
+            let location = match (location["start"].as_u64(), location["end"].as_u64()) {
+                (Some(start), Some(end)) => Some(start as usize..end as usize),
+                _ => None,
+            };
+            SolcError {
+                message,
+                formatted_message,
+                location,
+            }
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(YulcError::Solc(errors));
+    }
+
+    let evm = &output["contracts"]["input.yul"][name]["evm"];
+    let bytecode = evm["bytecode"]["object"].as_str().ok_or_else(|| {
+        YulcError::InvalidOutput(format!("solc's output has no bytecode for `{name}`"))
+    })?;
+    let runtime_bytecode = match evm["deployedBytecode"]["object"].as_str() {
+        Some(runtime_bytecode) => runtime_bytecode,
+        None if verify_runtime_bytecode => {
+            return Err(YulcError::InvalidOutput(format!(
+                "solc's output has no runtime bytecode for `{name}`"
+            )))
+        }
+        None => "",
+    };
+
+    let source_map = |bytecode: &Value| {
+        bytecode["sourceMap"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    Ok(ContractBytecode {
+        bytecode: bytecode.to_string(),
+        runtime_bytecode: runtime_bytecode.to_string(),
+        source_map: source_map(&evm["bytecode"]),
+        runtime_source_map: source_map(&evm["deployedBytecode"]),
+    })
 }
 
 #[cfg(feature = "solc-backend")]
 #[test]
 fn test_solc_sanity() {
     let yul_src = "{ sstore(0,0) }";
-    let solc_temp = include_str!("solc_temp.json");
-    let input = solc_temp
-        .replace("{optimizer_enabled}", "false")
-        .replace("{src}", yul_src);
+    let input = standard_json_input(yul_src, &OptimizerSettings::default(), "paris");
 
     let raw_output = solc::compile(&input);
     let output: serde_json::Value = serde_json::from_str(&raw_output).unwrap();
@@ -94,3 +263,29 @@ fn test_solc_sanity() {
     // solc 0.8.7: push1 0; dup1;    sstore  "60008055"
     assert_eq!(bytecode, "60008055", "incorrect bytecode",);
 }
+
+#[test]
+fn test_optimizer_settings() {
+    let optimizer = OptimizerSettings {
+        enabled: true,
+        runs: Some(1),
+        yul_steps: Some("dhfoDgvulfnTUtnIf".into()),
+    };
+    let input = standard_json_input("{ stop() }", &optimizer, "cancun");
+    let input: Value = serde_json::from_str(&input).unwrap();
+    assert_eq!(
+        input["settings"]["optimizer"],
+        json!({
+            "enabled": true,
+            "runs": 1,
+            "details": {
+                "yul": true,
+                "yulDetails": { "optimizerSteps": "dhfoDgvulfnTUtnIf" }
+            }
+        })
+    );
+
+    let input = standard_json_input("{ stop() }", &OptimizerSettings::enabled(false), "cancun");
+    let input: Value = serde_json::from_str(&input).unwrap();
+    assert_eq!(input["settings"]["optimizer"], json!({ "enabled": false }));
+}
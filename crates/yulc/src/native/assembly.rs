@@ -0,0 +1,156 @@
+//! Assembly items and their layout into bytecode.
+
+use indexmap::IndexMap;
+
+use super::opcode::{self, JUMPDEST, PUSH0};
+use crate::YulcError;
+
+pub(super) type Label = usize;
+
+/// An item of the assembly of an object's code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Item {
+    Op(u8),
+    /// Pushes a word, given as its big endian bytes without leading zeros.
+    Push(Vec<u8>),
+    /// Pushes the code offset of a label.
+    PushLabel(Label),
+    /// Marks the position of a label with a `JUMPDEST`.
+    Label(Label),
+    /// Pushes the offset of a sub-object or data section of the object.
+    PushDataOffset(String),
+    /// Pushes the size of a sub-object or data section of the object.
+    PushDataSize(String),
+}
+
+/// The items of an object's code, and the bytes of the sub-objects and data
+/// sections appended to it.
+#[derive(Debug, Default)]
+pub(super) struct Assembly {
+    pub items: Vec<Item>,
+    pub labels: usize,
+    pub push0: bool,
+}
+
+impl Assembly {
+    pub fn new(push0: bool) -> Self {
+        Self {
+            push0,
+            ..Self::default()
+        }
+    }
+
+    pub fn new_label(&mut self) -> Label {
+        self.labels += 1;
+        self.labels - 1
+    }
+
+    pub fn op(&mut self, op: u8) {
+        self.items.push(Item::Op(op));
+    }
+
+    pub fn push(&mut self, word: &[u8; 32]) {
+        let start = word.iter().position(|byte| *byte != 0).unwrap_or(32);
+        self.items.push(Item::Push(word[start..].to_vec()));
+    }
+
+    pub fn push_label(&mut self, label: Label) {
+        self.items.push(Item::PushLabel(label));
+    }
+
+    pub fn label(&mut self, label: Label) {
+        self.items.push(Item::Label(label));
+    }
+
+    pub fn jump_to(&mut self, label: Label) {
+        self.push_label(label);
+        self.op(opcode::JUMP);
+    }
+
+    /// Lays out the code followed by `sections`, which are the sub-objects and
+    /// data sections in order, and returns the bytecode of the object named
+    /// `name`.
+    pub fn assemble(
+        &self,
+        name: &str,
+        sections: &IndexMap<String, Vec<u8>>,
+    ) -> Result<Vec<u8>, YulcError> {
+        let sections_size: usize = sections.values().map(Vec::len).sum();
+
+        // Labels and data offsets are pushed with a fixed width, so that the
+        // code size doesn't depend on them.
+        let mut width = 2;
+        let code_size = loop {
+            let code_size = self.code_size(width);
+            if code_size + sections_size < 1 << (8 * width) {
+                break code_size;
+            }
+            width += 1;
+        };
+
+        let mut labels = vec![0; self.labels];
+        let mut pc = 0;
+        for item in &self.items {
+            if let Item::Label(label) = item {
+                labels[*label] = pc;
+            }
+            pc += self.item_size(item, width);
+        }
+
+        let mut offsets = IndexMap::new();
+        let mut offset = code_size;
+        for (section, bytes) in sections {
+            offsets.insert(section.as_str(), (offset, bytes.len()));
+            offset += bytes.len();
+        }
+        offsets.insert(name, (0, offset));
+        let section = |section: &str| {
+            offsets.get(section).copied().ok_or_else(|| {
+                YulcError::Native(format!("object `{name}` has no section `{section}`"))
+            })
+        };
+
+        let mut bytecode = Vec::with_capacity(offset);
+        for item in &self.items {
+            match item {
+                Item::Op(op) => bytecode.push(*op),
+                Item::Push(bytes) if bytes.is_empty() && self.push0 => bytecode.push(PUSH0),
+                Item::Push(bytes) if bytes.is_empty() => bytecode.extend([opcode::push(1), 0]),
+                Item::Push(bytes) => {
+                    bytecode.push(opcode::push(bytes.len()));
+                    bytecode.extend(bytes);
+                }
+                Item::PushLabel(label) => push_fixed(&mut bytecode, labels[*label], width),
+                Item::Label(_) => bytecode.push(JUMPDEST),
+                Item::PushDataOffset(name) => push_fixed(&mut bytecode, section(name)?.0, width),
+                Item::PushDataSize(name) => push_fixed(&mut bytecode, section(name)?.1, width),
+            }
+        }
+        for bytes in sections.values() {
+            bytecode.extend(bytes);
+        }
+        Ok(bytecode)
+    }
+
+    fn code_size(&self, width: usize) -> usize {
+        self.items
+            .iter()
+            .map(|item| self.item_size(item, width))
+            .sum()
+    }
+
+    fn item_size(&self, item: &Item, width: usize) -> usize {
+        match item {
+            Item::Op(_) | Item::Label(_) => 1,
+            Item::Push(bytes) if bytes.is_empty() && self.push0 => 1,
+            Item::Push(bytes) if bytes.is_empty() => 2,
+            Item::Push(bytes) => 1 + bytes.len(),
+            Item::PushLabel(_) | Item::PushDataOffset(_) | Item::PushDataSize(_) => 1 + width,
+        }
+    }
+}
+
+fn push_fixed(bytecode: &mut Vec<u8>, value: usize, width: usize) {
+    bytecode.push(opcode::push(width));
+    bytecode.extend(&value.to_be_bytes()[std::mem::size_of::<usize>() - width..]);
+}
@@ -0,0 +1,309 @@
+//! A Yul to EVM assembler written in Rust, which compiles the Yul objects
+//! produced by codegen without solc.
+//!
+//! The bytecode isn't optimized: variables are kept on the stack, functions
+//! are called by jumping to them, and sub-objects and data are appended to
+//! the code of the object that contains them. See [`transform`] for how Yul
+//! code is mapped to the stack.
+
+use std::fmt;
+
+use indexmap::IndexMap;
+use yultsur::yul;
+
+use crate::{ContractBytecode, YulcError};
+
+use assembly::Assembly;
+
+mod assembly;
+mod opcode;
+mod transform;
+
+/// The hardforks before Shanghai, which didn't have `PUSH0`.
+const PRE_PUSH0_EVM_VERSIONS: [&str; 4] = ["istanbul", "berlin", "london", "paris"];
+
+/// Compiles a Yul object to bytecode.
+///
+/// Like solc, the runtime bytecode is the bytecode of the object's only
+/// sub-object. If the object has several, it's an error when
+/// `verify_runtime_bytecode` is set and the runtime bytecode is left empty
+/// otherwise.
+///
+/// `evm_version` is the name of the targeted hardfork as solc spells it, e.g.
+/// `"london"`.
+pub fn compile_object(
+    object: &yul::Object,
+    evm_version: &str,
+    verify_runtime_bytecode: bool,
+) -> Result<ContractBytecode, YulcError> {
+    let push0 = !PRE_PUSH0_EVM_VERSIONS.contains(&evm_version);
+    let sections = assemble_sections(object, push0)?;
+    let bytecode = assemble_code(object, &sections, push0)?;
+
+    let runtime_bytecode = match object.objects.as_slice() {
+        [runtime] => hex::encode(&sections[&runtime.name.identifier]),
+        _ if verify_runtime_bytecode => {
+            return Err(YulcError::Native(format!(
+                "object `{}` doesn't have a single sub-object to deploy",
+                object.name.identifier
+            )))
+        }
+        _ => String::new(),
+    };
+
+    Ok(ContractBytecode {
+        bytecode: hex::encode(bytecode),
+        runtime_bytecode,
+        source_map: String::new(),
+        runtime_source_map: String::new(),
+    })
+}
+
+fn assemble_object(object: &yul::Object, push0: bool) -> Result<Vec<u8>, YulcError> {
+    let sections = assemble_sections(object, push0)?;
+    assemble_code(object, &sections, push0)
+}
+
+/// Returns the bytes of the sub-objects and data sections of `object`, in the
+/// order they're appended to its code.
+fn assemble_sections(
+    object: &yul::Object,
+    push0: bool,
+) -> Result<IndexMap<String, Vec<u8>>, YulcError> {
+    let mut sections = IndexMap::new();
+    for sub_object in &object.objects {
+        let bytes = assemble_object(sub_object, push0)?;
+        sections.insert(sub_object.name.identifier.clone(), bytes);
+    }
+    for data in &object.data {
+        sections.insert(data.name.clone(), data_bytes(&data.value)?);
+    }
+    Ok(sections)
+}
+
+fn assemble_code(
+    object: &yul::Object,
+    sections: &IndexMap<String, Vec<u8>>,
+    push0: bool,
+) -> Result<Vec<u8>, YulcError> {
+    let mut asm = Assembly::new(push0);
+    transform::transform(&object.code, &mut asm)
+        .map_err(|err| within(err, format_args!("object `{}`", object.name.identifier)))?;
+    asm.assemble(&object.name.identifier, sections)
+}
+
+/// Adds the place an error happened in to its message.
+fn within(err: YulcError, place: fmt::Arguments) -> YulcError {
+    match err {
+        YulcError::Native(message) => YulcError::Native(format!("{message} in {place}")),
+        err => err,
+    }
+}
+
+/// Returns the bytes of a data section.
+///
+/// Codegen escapes data for the Yul source that solc reads from a JSON string,
+/// i.e. twice, so the escapes are undone twice as well.
+fn data_bytes(value: &str) -> Result<Vec<u8>, YulcError> {
+    unescape(&unescape(value.as_bytes())?)
+}
+
+/// Returns the word a Yul literal stands for.
+fn literal_word(literal: &str) -> Result<[u8; 32], YulcError> {
+    let invalid = || YulcError::Native(format!("invalid literal `{literal}`"));
+    let mut word = [0; 32];
+
+    if literal == "true" {
+        word[31] = 1;
+    } else if literal == "false" {
+    } else if let Some(string) = literal
+        .strip_prefix('"')
+        .and_then(|literal| literal.strip_suffix('"'))
+    {
+        let bytes = unescape(string.as_bytes())?;
+        if bytes.len() > 32 {
+            return Err(YulcError::Native(format!(
+                "string literal `{literal}` is longer than 32 bytes"
+            )));
+        }
+        word[..bytes.len()].copy_from_slice(&bytes);
+    } else if let Some(digits) = literal.strip_prefix("0x") {
+        let digits = if digits.len() % 2 == 1 {
+            format!("0{digits}")
+        } else {
+            digits.to_string()
+        };
+        let bytes = hex::decode(digits).map_err(|_| invalid())?;
+        let start = bytes
+            .iter()
+            .position(|byte| *byte != 0)
+            .unwrap_or(bytes.len());
+        let bytes = &bytes[start..];
+        if bytes.len() > 32 {
+            return Err(invalid());
+        }
+        word[32 - bytes.len()..].copy_from_slice(bytes);
+    } else if !literal.is_empty() && literal.bytes().all(|byte| byte.is_ascii_digit()) {
+        for digit in literal.bytes() {
+            // word = word * 10 + digit
+            let mut carry = u32::from(digit - b'0');
+            for byte in word.iter_mut().rev() {
+                let value = u32::from(*byte) * 10 + carry;
+                *byte = value as u8;
+                carry = value >> 8;
+            }
+            if carry != 0 {
+                return Err(invalid());
+            }
+        }
+    } else {
+        return Err(invalid());
+    }
+    Ok(word)
+}
+
+/// Undoes the escapes of a Yul string literal.
+fn unescape(escaped: &[u8]) -> Result<Vec<u8>, YulcError> {
+    let invalid = || {
+        YulcError::Native(format!(
+            "invalid escape sequence in `{}`",
+            String::from_utf8_lossy(escaped)
+        ))
+    };
+    let hex_digits = |digits: &[u8]| {
+        std::str::from_utf8(digits)
+            .ok()
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(invalid)
+    };
+
+    let mut bytes = Vec::with_capacity(escaped.len());
+    let mut idx = 0;
+    while idx < escaped.len() {
+        if escaped[idx] != b'\\' {
+            bytes.push(escaped[idx]);
+            idx += 1;
+            continue;
+        }
+
+        let escape = *escaped.get(idx + 1).ok_or_else(invalid)?;
+        idx += 2;
+        match escape {
+            b'\\' | b'"' | b'\'' => bytes.push(escape),
+            b'n' => bytes.push(b'\n'),
+            b'r' => bytes.push(b'\r'),
+            b't' => bytes.push(b'\t'),
+            b'x' => {
+                let digits = escaped.get(idx..idx + 2).ok_or_else(invalid)?;
+                bytes.push(hex_digits(digits)? as u8);
+                idx += 2;
+            }
+            b'u' => {
+                let digits = escaped.get(idx..idx + 4).ok_or_else(invalid)?;
+                let char = char::from_u32(hex_digits(digits)?).ok_or_else(invalid)?;
+                bytes.extend(char.encode_utf8(&mut [0; 4]).as_bytes());
+                idx += 4;
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use yultsur::*;
+
+    use super::*;
+
+    fn object(code: yul::Code, objects: Vec<yul::Object>) -> yul::Object {
+        yul::Object {
+            name: identifier! { test },
+            code,
+            objects,
+            data: vec![],
+        }
+    }
+
+    fn compile(object: &yul::Object) -> ContractBytecode {
+        compile_object(object, "cancun", false).unwrap()
+    }
+
+    #[test]
+    fn builtin_args() {
+        let object = object(code! { (sstore(0, 1)) }, vec![]);
+        assert_eq!(compile(&object).bytecode, "60015f5500");
+
+        let bytecode = compile_object(&object, "paris", false).unwrap().bytecode;
+        assert_eq!(bytecode, "600160005500");
+    }
+
+    #[test]
+    fn function_call() {
+        let x = identifier! { x };
+        let y = identifier! { y };
+        let x_expr = identifier_expression! { x };
+        let def = function_definition! {
+            function [identifier! { double }]([x]) -> [y.clone()] {
+                ([y] := add([x_expr.clone()], [x_expr]))
+            }
+        };
+        let object = object(
+            code! {
+                [yul::Statement::FunctionDefinition(def)]
+                (sstore(0, (double(21))))
+            },
+            vec![],
+        );
+
+        let call = "610009601561000d565b5f5500";
+        // jumpdest; push0; dup2; dup3; add; swap1; pop; jumpdest; swap1; pop;
+        // swap1; jump
+        let double = "5b5f81820190505b90509056";
+        assert_eq!(compile(&object).bytecode, format!("{call}{double}"));
+    }
+
+    #[test]
+    fn sub_objects() {
+        let runtime = yul::Object {
+            name: identifier! { runtime },
+            code: code! { (stop()) },
+            objects: vec![],
+            data: vec![],
+        };
+        let name = literal_expression! { ("\"runtime\"".to_string()) };
+        let object = object(
+            code! {
+                (datacopy(0, (dataoffset([name.clone()])), (datasize([name.clone()]))))
+                (return(0, (datasize([name]))))
+            },
+            vec![runtime],
+        );
+
+        let bytecode = compile(&object);
+        assert_eq!(bytecode.runtime_bytecode, "0000");
+        assert_eq!(bytecode.bytecode, "61000261000e5f396100025ff3000000");
+    }
+
+    #[test]
+    fn literals() {
+        let word = |literal| hex::encode(literal_word(literal).unwrap());
+        assert_eq!(word("true"), format!("{:0>64}", "1"));
+        assert_eq!(word("0x01ff"), format!("{:0>64}", "1ff"));
+        assert_eq!(word("256"), format!("{:0>64}", "100"));
+        assert_eq!(word("\"ab\""), format!("{:0<64}", "6162"));
+
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(word(max), "f".repeat(64));
+        let too_large =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+        assert!(literal_word(too_large).is_err());
+    }
+
+    #[test]
+    fn data_escapes() {
+        assert_eq!(data_bytes(r"a\\nb").unwrap(), b"a\nb");
+        assert_eq!(data_bytes(r"a\\\\b").unwrap(), b"a\\b");
+        assert_eq!(data_bytes(r#"\\\"a\\\""#).unwrap(), b"\"a\"");
+    }
+}
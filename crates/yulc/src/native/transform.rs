@@ -0,0 +1,741 @@
+//! Transforms Yul code into assembly.
+//!
+//! Variables live on the stack and are accessed with `DUP` and `SWAP`, so a
+//! variable more than 16 slots below the top of the stack can't be accessed.
+//! To keep the stack shallow, the slot of a variable becomes free after the
+//! last reference to it, and the next variable declared nearby takes it over.
+//!
+//! A function is called with its return label below its arguments, the first
+//! argument on top. It returns its values with the last one on top, like a
+//! builtin returning several values would.
+
+use std::{collections::HashMap, collections::VecDeque, rc::Rc};
+
+use yultsur::yul;
+
+use super::{
+    assembly::{Assembly, Item, Label},
+    literal_word,
+    opcode::{self, builtin, MAX_STACK_ACCESS},
+    within,
+};
+use crate::YulcError;
+
+/// Appends the assembly of `code`, and of the functions defined in it, to
+/// `asm`.
+pub(super) fn transform(code: &yul::Code, asm: &mut Assembly) -> Result<(), YulcError> {
+    let mut pending = VecDeque::new();
+
+    let statements = &code.block.statements;
+    CodeTransform::new(asm, &mut pending, vec![], references(statements)).block(&code.block)?;
+    asm.op(opcode::STOP);
+
+    while let Some(func) = pending.pop_front() {
+        let statements = &func.def.block.statements;
+        CodeTransform::new(
+            asm,
+            &mut pending,
+            func.functions.clone(),
+            references(statements),
+        )
+        .function(&func)?;
+    }
+    Ok(())
+}
+
+type VarId = usize;
+
+/// What a stack slot holds, at compile time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Slot {
+    /// The label the function being transformed returns to.
+    ReturnLabel,
+    Var(VarId),
+    /// An intermediate value of an expression.
+    Temp,
+    /// The slot of a variable that isn't referenced anymore, which another
+    /// variable can take over.
+    Free,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Function {
+    label: Label,
+    params: usize,
+    returns: usize,
+}
+
+type Functions = Rc<HashMap<String, Function>>;
+
+struct Scope {
+    vars: HashMap<String, VarId>,
+    functions: Functions,
+    /// The stack height the scope started at.
+    height: usize,
+}
+
+struct Var {
+    /// The number of loops the variable is declared in.
+    loop_depth: usize,
+    /// Return variables are read when the function returns, so their slot is
+    /// never freed.
+    freeable: bool,
+}
+
+struct Loop {
+    continue_label: Label,
+    break_label: Label,
+    height: usize,
+}
+
+/// A function whose code is generated after the code it's defined in, with
+/// the functions visible where it's defined.
+struct PendingFunction {
+    def: yul::FunctionDefinition,
+    label: Label,
+    functions: Vec<Functions>,
+}
+
+struct CodeTransform<'a> {
+    asm: &'a mut Assembly,
+    pending: &'a mut VecDeque<PendingFunction>,
+    stack: Vec<Slot>,
+    scopes: Vec<Scope>,
+    vars: Vec<Var>,
+    /// The number of references to each variable name that are still to be
+    /// transformed.
+    references: HashMap<String, usize>,
+    loops: Vec<Loop>,
+    /// The exit label of the function being transformed and the stack height
+    /// `leave` jumps to it with.
+    exit: Option<(Label, usize)>,
+}
+
+impl<'a> CodeTransform<'a> {
+    fn new(
+        asm: &'a mut Assembly,
+        pending: &'a mut VecDeque<PendingFunction>,
+        functions: Vec<Functions>,
+        references: HashMap<String, usize>,
+    ) -> Self {
+        let scopes = functions
+            .into_iter()
+            .map(|functions| Scope {
+                vars: HashMap::new(),
+                functions,
+                height: 0,
+            })
+            .collect();
+        Self {
+            asm,
+            pending,
+            stack: vec![],
+            scopes,
+            vars: vec![],
+            references,
+            loops: vec![],
+            exit: None,
+        }
+    }
+
+    fn function(mut self, func: &PendingFunction) -> Result<(), YulcError> {
+        let def = &func.def;
+        self.asm.label(func.label);
+        self.stack.push(Slot::ReturnLabel);
+        self.enter_scope(&[]);
+        for param in def.parameters.iter().rev() {
+            self.stack.push(Slot::Temp);
+            self.declare(&param.identifier, true);
+        }
+        let mut returns = vec![];
+        for ret in &def.returns {
+            self.push_zero();
+            let var = self.declare(&ret.identifier, false);
+            returns.push(Slot::Var(var));
+        }
+
+        let exit = self.asm.new_label();
+        self.exit = Some((exit, self.stack.len()));
+        self.block(&def.block)?;
+        self.asm.label(exit);
+
+        returns.push(Slot::ReturnLabel);
+        self.shuffle(&returns)
+            .map_err(|err| within(err, format_args!("function `{}`", def.name.identifier)))?;
+        self.asm.op(opcode::JUMP);
+        Ok(())
+    }
+
+    fn block(&mut self, block: &yul::Block) -> Result<(), YulcError> {
+        self.enter_scope(&block.statements);
+        for stmt in &block.statements {
+            self.statement(stmt)?;
+        }
+        self.exit_scope();
+        Ok(())
+    }
+
+    /// Enters the scope of a block, in which the functions defined in the
+    /// block are visible.
+    fn enter_scope(&mut self, statements: &[yul::Statement]) {
+        let defs: Vec<_> = statements
+            .iter()
+            .filter_map(|stmt| match stmt {
+                yul::Statement::FunctionDefinition(def) => Some(def),
+                _ => None,
+            })
+            .collect();
+
+        let mut functions = HashMap::new();
+        for def in &defs {
+            let func = Function {
+                label: self.asm.new_label(),
+                params: def.parameters.len(),
+                returns: def.returns.len(),
+            };
+            functions.insert(def.name.identifier.clone(), func);
+        }
+        let functions = Rc::new(functions);
+        self.scopes.push(Scope {
+            vars: HashMap::new(),
+            functions: functions.clone(),
+            height: self.stack.len(),
+        });
+
+        let visible: Vec<_> = self
+            .scopes
+            .iter()
+            .map(|scope| scope.functions.clone())
+            .collect();
+        for def in defs {
+            self.pending.push_back(PendingFunction {
+                def: def.clone(),
+                label: functions[&def.name.identifier].label,
+                functions: visible.clone(),
+            });
+        }
+    }
+
+    fn exit_scope(&mut self) {
+        let scope = self.scopes.pop().unwrap();
+        while self.stack.len() > scope.height {
+            self.pop();
+        }
+
+        // Variables of the scope can have taken over the free slots of
+        // variables of enclosing scopes.
+        for slot in &mut self.stack {
+            if matches!(slot, Slot::Var(var) if scope.vars.values().any(|v| v == var)) {
+                *slot = Slot::Free;
+            }
+        }
+    }
+
+    fn statement(&mut self, stmt: &yul::Statement) -> Result<(), YulcError> {
+        match stmt {
+            yul::Statement::Block(block) => self.block(block)?,
+
+            // Functions are transformed once the code they're defined in is.
+            yul::Statement::FunctionDefinition(_) => {}
+
+            yul::Statement::VariableDeclaration(decl) => {
+                let count = decl.identifiers.len();
+                match &decl.expression {
+                    Some(expr) => self.expression_values(expr, count)?,
+                    None => (0..count).for_each(|_| self.push_zero()),
+                }
+
+                if count == 1 {
+                    self.declare(&decl.identifiers[0].identifier, true);
+                } else {
+                    // The values stay in the slots they were returned in.
+                    let first = self.stack.len() - count;
+                    for (idx, ident) in decl.identifiers.iter().enumerate() {
+                        let var = self.new_var(&ident.identifier, true);
+                        self.stack[first + idx] = Slot::Var(var);
+                        self.free_if_unreferenced(&ident.identifier, var);
+                    }
+                }
+            }
+
+            yul::Statement::Assignment(assign) => {
+                self.expression_values(&assign.expression, assign.identifiers.len())?;
+                for ident in assign.identifiers.iter().rev() {
+                    self.assign(&ident.identifier)?;
+                }
+            }
+
+            yul::Statement::Expression(expr) => {
+                let count = self.expression(expr)?;
+                (0..count).for_each(|_| self.pop());
+            }
+
+            yul::Statement::If(if_) => {
+                let end = self.asm.new_label();
+                self.expression_values(&if_.expression, 1)?;
+                self.jump_unless(end);
+                self.block(&if_.block)?;
+                self.asm.label(end);
+            }
+
+            yul::Statement::Switch(switch) => self.switch(switch)?,
+
+            yul::Statement::ForLoop(for_loop) => self.for_loop(for_loop)?,
+
+            yul::Statement::Break | yul::Statement::Continue => {
+                let loop_ = self.loops.last().ok_or_else(|| {
+                    YulcError::Native("`break` or `continue` outside of a loop".into())
+                })?;
+                let (label, height) = if matches!(stmt, yul::Statement::Break) {
+                    (loop_.break_label, loop_.height)
+                } else {
+                    (loop_.continue_label, loop_.height)
+                };
+                self.jump_from(label, height);
+            }
+
+            yul::Statement::Leave => {
+                let (exit, height) = self
+                    .exit
+                    .ok_or_else(|| YulcError::Native("`leave` outside of a function".into()))?;
+                self.jump_from(exit, height);
+            }
+        }
+
+        // Drop the freed slots on top of the stack, as long as they belong to
+        // this scope.
+        let height = self.scopes.last().map_or(0, |scope| scope.height);
+        while self.stack.len() > height && self.stack.last() == Some(&Slot::Free) {
+            self.pop();
+        }
+        Ok(())
+    }
+
+    fn switch(&mut self, switch: &yul::Switch) -> Result<(), YulcError> {
+        self.expression_values(&switch.expression, 1)?;
+
+        let end = self.asm.new_label();
+        let mut cases = vec![];
+        let mut default = None;
+        for case in &switch.cases {
+            match &case.literal {
+                Some(literal) => {
+                    let label = self.asm.new_label();
+                    self.asm.op(opcode::dup(1));
+                    self.asm.push(&literal_word(&literal.literal)?);
+                    self.asm.op(opcode::EQ);
+                    self.asm.push_label(label);
+                    self.asm.op(opcode::JUMPI);
+                    cases.push((label, &case.block));
+                }
+                None => default = Some(&case.block),
+            }
+        }
+
+        // Each case starts by popping the value it matched.
+        self.pop();
+        if let Some(block) = default {
+            self.block(block)?;
+        }
+        self.asm.jump_to(end);
+        for (label, block) in cases {
+            self.asm.label(label);
+            self.asm.op(opcode::POP);
+            self.block(block)?;
+            self.asm.jump_to(end);
+        }
+        self.asm.label(end);
+        Ok(())
+    }
+
+    fn for_loop(&mut self, for_loop: &yul::ForLoop) -> Result<(), YulcError> {
+        // The variables declared in `pre` are visible in the whole loop.
+        self.enter_scope(&for_loop.pre.statements);
+        for stmt in &for_loop.pre.statements {
+            self.statement(stmt)?;
+        }
+
+        let cond = self.asm.new_label();
+        let post = self.asm.new_label();
+        let end = self.asm.new_label();
+        self.loops.push(Loop {
+            continue_label: post,
+            break_label: end,
+            height: self.stack.len(),
+        });
+
+        self.asm.label(cond);
+        self.expression_values(&for_loop.condition, 1)?;
+        self.jump_unless(end);
+        self.block(&for_loop.body)?;
+        self.asm.label(post);
+        self.block(&for_loop.post)?;
+        self.asm.jump_to(cond);
+        self.asm.label(end);
+
+        self.loops.pop();
+        self.exit_scope();
+        Ok(())
+    }
+
+    /// Transforms an expression, and returns the number of values it leaves
+    /// on the stack.
+    fn expression(&mut self, expr: &yul::Expression) -> Result<usize, YulcError> {
+        match expr {
+            yul::Expression::Literal(literal) => {
+                self.asm.push(&literal_word(&literal.literal)?);
+                self.stack.push(Slot::Temp);
+                Ok(1)
+            }
+            yul::Expression::Identifier(ident) => {
+                self.load(&ident.identifier)?;
+                Ok(1)
+            }
+            yul::Expression::FunctionCall(call) => self.call(call),
+        }
+    }
+
+    fn expression_values(&mut self, expr: &yul::Expression, count: usize) -> Result<(), YulcError> {
+        let values = self.expression(expr)?;
+        if values == count {
+            Ok(())
+        } else {
+            Err(YulcError::Native(format!(
+                "expected {count} values, but `{expr}` has {values}"
+            )))
+        }
+    }
+
+    fn call(&mut self, call: &yul::FunctionCall) -> Result<usize, YulcError> {
+        let name = call.identifier.identifier.as_str();
+        let args = &call.arguments;
+        let arg_count = |expected: usize| {
+            if args.len() == expected {
+                Ok(())
+            } else {
+                Err(YulcError::Native(format!(
+                    "`{name}` takes {expected} arguments, but is called with {}",
+                    args.len()
+                )))
+            }
+        };
+
+        match name {
+            "datasize" | "dataoffset" => {
+                arg_count(1)?;
+                let section = section_name(&args[0])?;
+                self.asm.items.push(if name == "datasize" {
+                    Item::PushDataSize(section)
+                } else {
+                    Item::PushDataOffset(section)
+                });
+                self.stack.push(Slot::Temp);
+                return Ok(1);
+            }
+            // Memory isn't moved around, so the guarded memory starts at the
+            // given address.
+            "memoryguard" => {
+                arg_count(1)?;
+                return self.expression(&args[0]);
+            }
+            _ => {}
+        }
+
+        if let Some(builtin) = builtin(name) {
+            arg_count(builtin.args)?;
+            for arg in args.iter().rev() {
+                self.expression_values(arg, 1)?;
+            }
+            self.asm.op(builtin.opcode);
+            self.stack.truncate(self.stack.len() - args.len());
+            self.stack
+                .extend(std::iter::repeat(Slot::Temp).take(builtin.returns));
+            return Ok(builtin.returns);
+        }
+
+        let func = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.functions.get(name).copied())
+            .ok_or_else(|| YulcError::Native(format!("function `{name}` is not defined")))?;
+        arg_count(func.params)?;
+
+        let ret = self.asm.new_label();
+        self.asm.push_label(ret);
+        self.stack.push(Slot::Temp);
+        for arg in args.iter().rev() {
+            self.expression_values(arg, 1)?;
+        }
+        self.asm.jump_to(func.label);
+        self.asm.label(ret);
+        self.stack.truncate(self.stack.len() - args.len() - 1);
+        self.stack
+            .extend(std::iter::repeat(Slot::Temp).take(func.returns));
+        Ok(func.returns)
+    }
+
+    /// Pushes the value of the variable `name`.
+    fn load(&mut self, name: &str) -> Result<(), YulcError> {
+        let var = self.lookup(name)?;
+        let depth = self.stack.len() - self.position(var);
+        if depth > MAX_STACK_ACCESS {
+            return Err(stack_too_deep(name, depth));
+        }
+        self.asm.op(opcode::dup(depth));
+        self.stack.push(Slot::Temp);
+        self.release(name, var);
+        Ok(())
+    }
+
+    /// Pops the value on top of the stack into the variable `name`.
+    fn assign(&mut self, name: &str) -> Result<(), YulcError> {
+        let var = self.lookup(name)?;
+        let pos = self.position(var);
+        if self.release(name, var) {
+            self.pop();
+            return Ok(());
+        }
+
+        let depth = self.stack.len() - 1 - pos;
+        if depth > MAX_STACK_ACCESS {
+            return Err(stack_too_deep(name, depth));
+        }
+        self.asm.op(opcode::swap(depth));
+        self.asm.op(opcode::POP);
+        self.stack.pop();
+        Ok(())
+    }
+
+    /// Declares the variable `name`, whose value is on top of the stack. The
+    /// value is moved into a free slot if there's one in reach.
+    fn declare(&mut self, name: &str, freeable: bool) -> VarId {
+        let var = self.new_var(name, freeable);
+        let top = self.stack.len() - 1;
+        let free = if freeable {
+            (1..=MAX_STACK_ACCESS.min(top)).find(|depth| self.stack[top - depth] == Slot::Free)
+        } else {
+            None
+        };
+
+        match free {
+            Some(depth) => {
+                self.asm.op(opcode::swap(depth));
+                self.asm.op(opcode::POP);
+                self.stack.pop();
+                self.stack[top - depth] = Slot::Var(var);
+            }
+            None => self.stack[top] = Slot::Var(var),
+        }
+        self.free_if_unreferenced(name, var);
+        var
+    }
+
+    fn new_var(&mut self, name: &str, freeable: bool) -> VarId {
+        let var = self.vars.len();
+        self.vars.push(Var {
+            loop_depth: self.loops.len(),
+            freeable,
+        });
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .vars
+            .insert(name.to_string(), var);
+        var
+    }
+
+    /// Counts a reference to the variable `name`, and frees its slot if it was
+    /// the last one. Returns whether the slot was freed.
+    ///
+    /// A slot is only freed outside of the loops the variable is declared in,
+    /// since the loop could reference it again in the next iteration.
+    fn release(&mut self, name: &str, var: VarId) -> bool {
+        if let Some(remaining) = self.references.get_mut(name) {
+            *remaining = remaining.saturating_sub(1);
+        }
+        self.free_if_unreferenced(name, var)
+    }
+
+    fn free_if_unreferenced(&mut self, name: &str, var: VarId) -> bool {
+        let unreferenced = self.references.get(name).copied().unwrap_or(0) == 0;
+        let info = &self.vars[var];
+        if unreferenced && info.freeable && info.loop_depth == self.loops.len() {
+            let pos = self.position(var);
+            self.stack[pos] = Slot::Free;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<VarId, YulcError> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.vars.get(name).copied())
+            .ok_or_else(|| YulcError::Native(format!("variable `{name}` is not defined")))
+    }
+
+    fn position(&self, var: VarId) -> usize {
+        self.stack
+            .iter()
+            .rposition(|slot| *slot == Slot::Var(var))
+            .expect("variable isn't on the stack")
+    }
+
+    /// Pops the stack down to `height` and jumps to `label`, leaving the
+    /// layout of the stack as it is for the unreachable code that follows.
+    fn jump_from(&mut self, label: Label, height: usize) {
+        for _ in height..self.stack.len() {
+            self.asm.op(opcode::POP);
+        }
+        self.asm.jump_to(label);
+    }
+
+    /// Pops the condition on top of the stack and jumps to `label` if it's
+    /// zero.
+    fn jump_unless(&mut self, label: Label) {
+        self.asm.op(opcode::ISZERO);
+        self.asm.push_label(label);
+        self.asm.op(opcode::JUMPI);
+        self.stack.pop();
+    }
+
+    /// Rearranges the stack into `target`, popping the slots that aren't in
+    /// it.
+    fn shuffle(&mut self, target: &[Slot]) -> Result<(), YulcError> {
+        loop {
+            let top = self.stack.len() - 1;
+            if !target.contains(&self.stack[top]) {
+                self.pop();
+                continue;
+            }
+            match (1..=MAX_STACK_ACCESS.min(top)).find(|d| !target.contains(&self.stack[top - d])) {
+                Some(depth) => {
+                    self.asm.op(opcode::swap(depth));
+                    self.stack.swap(top, top - depth);
+                }
+                None => break,
+            }
+        }
+        if self.stack.len() != target.len() {
+            return Err(YulcError::Native("stack too deep to return".into()));
+        }
+
+        while self.stack != target {
+            let top = self.stack.len() - 1;
+            let pos = target
+                .iter()
+                .position(|slot| *slot == self.stack[top])
+                .unwrap();
+            let pos = if pos == top {
+                self.stack
+                    .iter()
+                    .zip(target)
+                    .position(|(slot, target)| slot != target)
+                    .unwrap()
+            } else {
+                pos
+            };
+            let depth = top - pos;
+            if depth > MAX_STACK_ACCESS {
+                return Err(YulcError::Native("stack too deep to return".into()));
+            }
+            self.asm.op(opcode::swap(depth));
+            self.stack.swap(top, pos);
+        }
+        Ok(())
+    }
+
+    fn push_zero(&mut self) {
+        self.asm.push(&[0; 32]);
+        self.stack.push(Slot::Temp);
+    }
+
+    fn pop(&mut self) {
+        self.asm.op(opcode::POP);
+        self.stack.pop();
+    }
+}
+
+fn stack_too_deep(name: &str, depth: usize) -> YulcError {
+    YulcError::Native(format!(
+        "stack too deep: variable `{name}` is {depth} slots below the top of the stack"
+    ))
+}
+
+/// Returns the name of the sub-object or data section `datasize` or
+/// `dataoffset` is called with.
+fn section_name(expr: &yul::Expression) -> Result<String, YulcError> {
+    let name = match expr {
+        yul::Expression::Literal(literal) => &literal.literal,
+        yul::Expression::Identifier(ident) => &ident.identifier,
+        yul::Expression::FunctionCall(_) => {
+            return Err(YulcError::Native(format!("`{expr}` isn't an object name")))
+        }
+    };
+    Ok(name.trim_matches('"').to_string())
+}
+
+/// Counts the references to each variable name in `statements`, without those
+/// in the functions they define.
+fn references(statements: &[yul::Statement]) -> HashMap<String, usize> {
+    fn count_expr(expr: &yul::Expression, counts: &mut HashMap<String, usize>) {
+        match expr {
+            yul::Expression::Literal(_) => {}
+            yul::Expression::Identifier(ident) => {
+                *counts.entry(ident.identifier.clone()).or_default() += 1
+            }
+            yul::Expression::FunctionCall(call) => {
+                call.arguments
+                    .iter()
+                    .for_each(|arg| count_expr(arg, counts));
+            }
+        }
+    }
+
+    fn count_stmts(statements: &[yul::Statement], counts: &mut HashMap<String, usize>) {
+        for stmt in statements {
+            match stmt {
+                yul::Statement::Block(block) => count_stmts(&block.statements, counts),
+                yul::Statement::VariableDeclaration(decl) => {
+                    if let Some(expr) = &decl.expression {
+                        count_expr(expr, counts)
+                    }
+                }
+                yul::Statement::Assignment(assign) => {
+                    count_expr(&assign.expression, counts);
+                    for ident in &assign.identifiers {
+                        *counts.entry(ident.identifier.clone()).or_default() += 1;
+                    }
+                }
+                yul::Statement::Expression(expr) => count_expr(expr, counts),
+                yul::Statement::If(if_) => {
+                    count_expr(&if_.expression, counts);
+                    count_stmts(&if_.block.statements, counts);
+                }
+                yul::Statement::Switch(switch) => {
+                    count_expr(&switch.expression, counts);
+                    for case in &switch.cases {
+                        count_stmts(&case.block.statements, counts);
+                    }
+                }
+                yul::Statement::ForLoop(for_loop) => {
+                    count_stmts(&for_loop.pre.statements, counts);
+                    count_expr(&for_loop.condition, counts);
+                    count_stmts(&for_loop.body.statements, counts);
+                    count_stmts(&for_loop.post.statements, counts);
+                }
+                yul::Statement::FunctionDefinition(_)
+                | yul::Statement::Break
+                | yul::Statement::Continue
+                | yul::Statement::Leave => {}
+            }
+        }
+    }
+
+    let mut counts = HashMap::new();
+    count_stmts(statements, &mut counts);
+    counts
+}
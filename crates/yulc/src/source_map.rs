@@ -0,0 +1,152 @@
+//! solc's compressed source map format, which maps each instruction of the
+//! bytecode to the source range it was generated from. See
+//! <https://docs.soliditylang.org/en/latest/internals/source_mappings.html>.
+
+use crate::YulcError;
+
+/// The source range of an instruction. Ranges and sources are -1 for
+/// instructions that aren't generated from any source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMapEntry {
+    /// The byte offset the range starts at.
+    pub start: i64,
+    pub length: i64,
+    /// The index of the source file the range is in.
+    pub source: i64,
+    /// `i` for a jump into a function, `o` for a jump out of one and `-` for
+    /// anything else.
+    pub jump: char,
+    pub modifier_depth: i64,
+}
+
+impl SourceMapEntry {
+    /// The entry of an instruction that isn't generated from any source.
+    pub fn unknown(jump: char, modifier_depth: i64) -> Self {
+        Self {
+            start: -1,
+            length: -1,
+            source: -1,
+            jump,
+            modifier_depth,
+        }
+    }
+
+    fn fields(&self) -> [String; 5] {
+        [
+            self.start.to_string(),
+            self.length.to_string(),
+            self.source.to_string(),
+            self.jump.to_string(),
+            self.modifier_depth.to_string(),
+        ]
+    }
+}
+
+/// Decodes a source map, where a field that's left out repeats the field of
+/// the previous entry.
+pub fn decode(map: &str) -> Result<Vec<SourceMapEntry>, YulcError> {
+    if map.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let invalid =
+        |entry: &str| YulcError::InvalidOutput(format!("invalid source map entry `{entry}`"));
+    let number = |field: &str, entry: &str| field.parse::<i64>().map_err(|_| invalid(entry));
+
+    let mut prev = SourceMapEntry::unknown('-', 0);
+    map.split(';')
+        .map(|entry| {
+            let mut fields = entry.split(':');
+            let mut next_field = || fields.next().filter(|field| !field.is_empty());
+
+            let mut current = prev.clone();
+            if let Some(start) = next_field() {
+                current.start = number(start, entry)?;
+            }
+            if let Some(length) = next_field() {
+                current.length = number(length, entry)?;
+            }
+            if let Some(source) = next_field() {
+                current.source = number(source, entry)?;
+            }
+            if let Some(jump) = next_field() {
+                current.jump = match jump {
+                    "i" | "o" | "-" => jump.chars().next().unwrap(),
+                    _ => return Err(invalid(entry)),
+                };
+            }
+            if let Some(modifier_depth) = next_field() {
+                current.modifier_depth = number(modifier_depth, entry)?;
+            }
+
+            prev = current.clone();
+            Ok(current)
+        })
+        .collect()
+}
+
+/// Encodes a source map, leaving out the fields that repeat the previous
+/// entry.
+pub fn encode(entries: &[SourceMapEntry]) -> String {
+    let mut prev: Option<[String; 5]> = None;
+    let encoded: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let fields = entry.fields();
+            let mut changed: Vec<&str> = fields
+                .iter()
+                .enumerate()
+                .map(|(idx, field)| match &prev {
+                    Some(prev) if prev[idx] == *field => "",
+                    _ => field.as_str(),
+                })
+                .collect();
+            while changed.last() == Some(&"") {
+                changed.pop();
+            }
+            let encoded = changed.join(":");
+            prev = Some(fields);
+            encoded
+        })
+        .collect();
+    encoded.join(";")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start: i64, length: i64, source: i64, jump: char) -> SourceMapEntry {
+        SourceMapEntry {
+            start,
+            length,
+            source,
+            jump,
+            modifier_depth: 0,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let map = "0:50:0:-:0;;10:5;:3:-1:i;-1:-1::o";
+        let entries = decode(map).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                entry(0, 50, 0, '-'),
+                entry(0, 50, 0, '-'),
+                entry(10, 5, 0, '-'),
+                entry(10, 3, -1, 'i'),
+                entry(-1, -1, -1, 'o'),
+            ]
+        );
+        assert_eq!(encode(&entries), map);
+        assert_eq!(decode("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(decode("0:1:x").is_err());
+        assert!(decode("0:1:0:j").is_err());
+    }
+}